@@ -1191,66 +1191,43 @@ impl From<&[u8]> for HwCmd {
     }
 }
 
-/// The protocol implementation of AV/C vendor-dependent command specific to Apogee Ensemble.
-#[derive(Debug)]
-pub struct EnsembleOperation {
-    pub cmd: EnsembleCmd,
-    op: VendorDependent,
-}
+impl VendorDependentSchema for EnsembleCmd {
+    const COMPANY_ID: [u8; 3] = APOGEE_OUI;
 
-impl Default for EnsembleOperation {
-    fn default() -> Self {
-        Self {
-            cmd: Default::default(),
-            op: VendorDependent {
-                company_id: APOGEE_OUI,
-                data: Default::default(),
-            },
-        }
-    }
-}
-
-impl EnsembleOperation {
-    pub fn new(cmd: EnsembleCmd) -> Self {
-        Self {
-            cmd,
-            ..Default::default()
-        }
-    }
-}
-
-impl AvcOp for EnsembleOperation {
-    const OPCODE: u8 = VendorDependent::OPCODE;
-}
-
-impl AvcControl for EnsembleOperation {
-    fn build_operands(
-        &mut self,
-        addr: &AvcAddr,
-        operands: &mut Vec<u8>,
-    ) -> Result<(), AvcCmdBuildError> {
-        self.op.data = Into::<Vec<u8>>::into(&self.cmd);
+    fn build_control_data(&self, data: &mut Vec<u8>) -> Result<(), AvcCmdBuildError> {
+        data.append(&mut Into::<Vec<u8>>::into(self));
 
         // At least, 6 bytes should be required to align to 3 quadlets. Unless, the target unit is freezed.
-        while self.op.data.len() < 6 {
-            self.op.data.push(0xff);
+        while data.len() < 6 {
+            data.push(0xff);
         }
 
-        AvcControl::build_operands(&mut self.op, addr, operands)
+        Ok(())
     }
 
-    fn parse_operands(&mut self, addr: &AvcAddr, operands: &[u8]) -> Result<(), AvcRespParseError> {
-        AvcControl::parse_operands(&mut self.op, addr, operands).map(|_| {
-            // NOTE: parameters are retrieved by HwStatus command only.
-            match &mut self.cmd {
-                EnsembleCmd::HwStatusShort(buf) => buf.copy_from_slice(&self.op.data[2..]),
-                EnsembleCmd::HwStatusLong(buf) => buf.copy_from_slice(&self.op.data[2..]),
-                _ => (),
-            }
-        })
+    fn parse_control_data(&mut self, data: &[u8]) -> Result<(), AvcRespParseError> {
+        // NOTE: parameters are retrieved by HwStatus command only.
+        let buf: &mut [u8] = match self {
+            EnsembleCmd::HwStatusShort(buf) => buf,
+            EnsembleCmd::HwStatusLong(buf) => buf,
+            _ => return Ok(()),
+        };
+
+        let expected = 2 + buf.len();
+        if data.len() < expected {
+            Err(AvcRespParseError::TooShortResp(expected))
+        } else if data.len() > expected {
+            Err(AvcRespParseError::UnexpectedOperands(expected))
+        } else {
+            buf.copy_from_slice(&data[2..]);
+            Ok(())
+        }
     }
 }
 
+/// The protocol implementation of AV/C vendor-dependent command specific to Apogee Ensemble.
+pub type EnsembleOperation = VendorDependentOperation<EnsembleCmd>;
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -1368,4 +1345,32 @@ mod test {
             EnsembleCmd::from(Into::<Vec<u8>>::into(&cmd).as_slice())
         );
     }
+
+    #[test]
+    fn hw_status_data_length() {
+        let mut data = vec![EnsembleCmd::HW_STATUS, 0];
+        data.extend_from_slice(&[0x5a; METER_SHORT_FRAME_SIZE]);
+
+        let mut cmd = EnsembleCmd::HwStatusShort([0; METER_SHORT_FRAME_SIZE]);
+        assert_eq!(cmd.parse_control_data(&data), Ok(()));
+        assert_eq!(
+            cmd,
+            EnsembleCmd::HwStatusShort([0x5a; METER_SHORT_FRAME_SIZE])
+        );
+
+        let mut cmd = EnsembleCmd::HwStatusLong([0; METER_LONG_FRAME_SIZE]);
+        assert_eq!(
+            cmd.parse_control_data(&data),
+            Err(AvcRespParseError::TooShortResp(2 + METER_LONG_FRAME_SIZE))
+        );
+
+        data.push(0);
+        let mut cmd = EnsembleCmd::HwStatusShort([0; METER_SHORT_FRAME_SIZE]);
+        assert_eq!(
+            cmd.parse_control_data(&data),
+            Err(AvcRespParseError::UnexpectedOperands(
+                2 + METER_SHORT_FRAME_SIZE
+            ))
+        );
+    }
 }
@@ -0,0 +1,332 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+use {
+    firewire_bebob_protocols as protocols,
+    glib::{FileError, MainContext, MainLoop},
+    hinawa::{prelude::FwNodeExt, FwNode, FwNodeError},
+    protocols::{bridgeco::*, BebobAvc},
+    std::{sync::Arc, thread},
+    ta1394_avc_general::{general::*, *},
+};
+
+const TIMEOUT_MS: u32 = 100;
+
+// The maximum number of entries to enumerate, to avoid infinite loop against quirks.
+const MAX_FORMAT_ENTRIES: u8 = 32;
+const MAX_SUBUNIT_INFO_PAGES: u8 = 8;
+
+fn main() {
+    let code = std::env::args().nth(1)
+        .ok_or("At least one argument is required for path to special file of FireWire character device".to_string())
+        .and_then(|path| {
+            let node = FwNode::new();
+            node.open(&path)
+                .map_err(|e| {
+                    let cause = if let Some(error) = e.kind::<FileError>() {
+                        match error {
+                            FileError::Isdir => "is directory",
+                            FileError::Acces => "access permission",
+                            FileError::Noent => "not exists",
+                            _ => "unknown",
+                        }.to_string()
+                    } else if let Some(error) = e.kind::<FwNodeError>() {
+                        match error {
+                            FwNodeError::Disconnected => "disconnected",
+                            FwNodeError::Failed => "ioctl error",
+                            _ => "unknown",
+                        }.to_string()
+                    } else {
+                        e.to_string()
+                    };
+                    format!("Fail to open firewire character device {}: {} {}", path, cause, e)
+                })
+                .and_then(|_| {
+                    node.create_source()
+                        .map_err(|e| e.to_string())
+                        .map(|src| (node, src))
+                })
+        })
+        .and_then(|(node, src)| {
+            let ctx = MainContext::new();
+            let _ = src.attach(Some(&ctx));
+            let dispatcher = Arc::new(MainLoop::new(Some(&ctx), false));
+            let d = dispatcher.clone();
+            let th = thread::spawn(move || d.run());
+
+            let avc = BebobAvc::default();
+            let result = avc
+                .bind(&node)
+                .map_err(|e| e.to_string())
+                .and_then(|_| probe_unit(&avc));
+
+            dispatcher.quit();
+            th.join().unwrap();
+            result
+        })
+        .map(|_| 0)
+        .unwrap_or_else(|msg| {
+            eprintln!("{}", msg);
+            print_help();
+            1
+        });
+
+    std::process::exit(code)
+}
+
+fn print_help() {
+    print!(
+        r###"
+Usage:
+  bco-avc-probe CDEV
+
+  where:
+    CDEV:       The path to special file of firewire character device, typically '/dev/fw1'.
+
+  The report includes subunits, function blocks, plugs and stream formats of the unit, which is
+  useful to add support for the unit. Attach it to the bug report.
+"###
+    );
+}
+
+fn probe_unit(avc: &BebobAvc) -> Result<(), String> {
+    let mut op = UnitInfo::new();
+    avc.status(&AvcAddr::Unit, &mut op, TIMEOUT_MS)
+        .map_err(|e| format!("Fail to execute UNIT INFO command: {}", e))?;
+
+    println!("unit:");
+    println!("  type: {:?}", op.unit_type);
+    println!("  ID: {}", op.unit_id);
+    println!(
+        "  company ID: 0x{:02x}{:02x}{:02x}",
+        op.company_id[0], op.company_id[1], op.company_id[2]
+    );
+
+    probe_unit_plugs(avc);
+
+    let subunits = probe_subunits(avc)?;
+    subunits.iter().for_each(|entry| {
+        (0..=entry.maximum_id).for_each(|subunit_id| {
+            let subunit = AvcAddrSubunit::new(entry.subunit_type, subunit_id);
+            probe_subunit(avc, &subunit);
+        });
+    });
+
+    Ok(())
+}
+
+fn probe_unit_plugs(avc: &BebobAvc) {
+    let mut op = PlugInfo::new_for_unit_isoc_ext_plugs();
+    if let Err(e) = avc.status(&AvcAddr::Unit, &mut op, TIMEOUT_MS) {
+        println!("  plugs: not available ({})", e);
+        return;
+    }
+
+    let data = match &op {
+        PlugInfo::Unit(PlugInfoUnitData::IsocExt(data)) => data,
+        _ => unreachable!(),
+    };
+
+    println!("  plugs:");
+    [
+        (
+            "isochronous input",
+            BcoPlugDirection::Input,
+            BcoPlugAddrUnitType::Isoc,
+            data.isoc_input_plugs,
+        ),
+        (
+            "isochronous output",
+            BcoPlugDirection::Output,
+            BcoPlugAddrUnitType::Isoc,
+            data.isoc_output_plugs,
+        ),
+        (
+            "external input",
+            BcoPlugDirection::Input,
+            BcoPlugAddrUnitType::Ext,
+            data.external_input_plugs,
+        ),
+        (
+            "external output",
+            BcoPlugDirection::Output,
+            BcoPlugAddrUnitType::Ext,
+            data.external_output_plugs,
+        ),
+    ]
+    .iter()
+    .for_each(|&(label, direction, plug_type, count)| {
+        (0..count).for_each(|plug_id| {
+            println!("    {} {}:", label, plug_id);
+            let plug_addr = BcoPlugAddr::new_for_unit(direction, plug_type, plug_id);
+            print_plug_info(avc, &AvcAddr::Unit, &plug_addr, "      ");
+            if plug_type == BcoPlugAddrUnitType::Isoc {
+                print_stream_formats(avc, &AvcAddr::Unit, &plug_addr, "      ");
+            }
+        });
+    });
+}
+
+fn probe_subunits(avc: &BebobAvc) -> Result<Vec<SubunitInfoEntry>, String> {
+    let mut op = SubunitInfo::new(0, 7);
+    avc.status(&AvcAddr::Unit, &mut op, TIMEOUT_MS)
+        .map_err(|e| format!("Fail to execute SUBUNIT INFO command: {}", e))?;
+
+    println!("subunits:");
+    op.entries.iter().for_each(|entry| {
+        println!(
+            "  {:?}: maximum ID {}",
+            entry.subunit_type, entry.maximum_id
+        );
+    });
+
+    Ok(op.entries)
+}
+
+fn probe_subunit(avc: &BebobAvc, subunit: &AvcAddrSubunit) {
+    println!("subunit {:?} {}:", subunit.subunit_type, subunit.subunit_id);
+
+    let addr = AvcAddr::Subunit(*subunit);
+
+    let mut op = PlugInfo::new_for_subunit_plugs();
+    match avc.status(&addr, &mut op, TIMEOUT_MS) {
+        Ok(_) => {
+            if let PlugInfo::Subunit(data) = &op {
+                println!("  plugs:");
+                [
+                    ("destination", BcoPlugDirection::Input, data.dst_plugs),
+                    ("source", BcoPlugDirection::Output, data.src_plugs),
+                ]
+                .iter()
+                .for_each(|&(label, direction, count)| {
+                    (0..count).for_each(|plug_id| {
+                        println!("    {} {}:", label, plug_id);
+                        let plug_addr = BcoPlugAddr::new_for_subunit(direction, plug_id);
+                        print_plug_info(avc, &addr, &plug_addr, "      ");
+                    });
+                });
+            }
+        }
+        Err(e) => println!("  plugs: not available ({})", e),
+    }
+
+    println!("  function blocks:");
+    let mut entries = Vec::new();
+    for page in 0..MAX_SUBUNIT_INFO_PAGES {
+        let mut op = ExtendedSubunitInfo::new(page, 0xff);
+        if avc.status(&addr, &mut op, TIMEOUT_MS).is_err() {
+            break;
+        }
+        let count = op.entries.len();
+        entries.append(&mut op.entries);
+        // Each page has 5 entries at most.
+        if count < 5 {
+            break;
+        }
+    }
+
+    entries.iter().for_each(|entry| {
+        println!(
+            "    type 0x{:02x}, ID 0x{:02x}, purpose 0x{:02x}, {} inputs, {} outputs:",
+            entry.func_blk_type,
+            entry.func_blk_id,
+            entry.func_blk_purpose,
+            entry.input_plugs,
+            entry.output_plugs
+        );
+        [
+            ("input", BcoPlugDirection::Input, entry.input_plugs),
+            ("output", BcoPlugDirection::Output, entry.output_plugs),
+        ]
+        .iter()
+        .for_each(|&(label, direction, count)| {
+            (0..count).for_each(|plug_id| {
+                println!("      {} {}:", label, plug_id);
+                let plug_addr = BcoPlugAddr::new_for_func_blk(
+                    direction,
+                    entry.func_blk_type,
+                    entry.func_blk_id,
+                    plug_id,
+                );
+                print_plug_info(avc, &addr, &plug_addr, "        ");
+            });
+        });
+    });
+}
+
+fn print_plug_info(avc: &BebobAvc, addr: &AvcAddr, plug_addr: &BcoPlugAddr, indent: &str) {
+    let mut op = ExtendedPlugInfo::new(plug_addr, BcoPlugInfo::Type(BcoPlugType::Reserved(0xff)));
+    if avc.status(addr, &mut op, TIMEOUT_MS).is_ok() {
+        if let BcoPlugInfo::Type(plug_type) = &op.info {
+            println!("{}type: {:?}", indent, plug_type);
+        }
+    }
+
+    let mut op = ExtendedPlugInfo::new(plug_addr, BcoPlugInfo::Name("".to_string()));
+    if avc.status(addr, &mut op, TIMEOUT_MS).is_ok() {
+        if let BcoPlugInfo::Name(name) = &op.info {
+            println!("{}name: '{}'", indent, name);
+        }
+    }
+
+    let mut op = ExtendedPlugInfo::new(plug_addr, BcoPlugInfo::ChCount(0xff));
+    if avc.status(addr, &mut op, TIMEOUT_MS).is_ok() {
+        if let BcoPlugInfo::ChCount(count) = &op.info {
+            println!("{}channels: {}", indent, count);
+        }
+    }
+
+    let mut op = ExtendedPlugInfo::new(
+        plug_addr,
+        BcoPlugInfo::Input(BcoIoPlugAddr::from(&[0xff; 7])),
+    );
+    if avc.status(addr, &mut op, TIMEOUT_MS).is_ok() {
+        if let BcoPlugInfo::Input(input) = &op.info {
+            println!("{}connected from: {:?}", indent, input);
+        }
+    }
+
+    let mut op = ExtendedPlugInfo::new(plug_addr, BcoPlugInfo::Outputs(Vec::new()));
+    if avc.status(addr, &mut op, TIMEOUT_MS).is_ok() {
+        if let BcoPlugInfo::Outputs(outputs) = &op.info {
+            outputs
+                .iter()
+                .for_each(|output| println!("{}connected to: {:?}", indent, output));
+        }
+    }
+}
+
+fn print_stream_formats(avc: &BebobAvc, addr: &AvcAddr, plug_addr: &BcoPlugAddr, indent: &str) {
+    let mut op = ExtendedStreamFormatSingle::new(plug_addr);
+    match avc.status(addr, &mut op, TIMEOUT_MS) {
+        Ok(_) => println!("{}current format: {:?}", indent, op.stream_format),
+        Err(e) => println!("{}current format: not available ({})", indent, e),
+    }
+
+    println!("{}supported formats:", indent);
+    for index in 0..MAX_FORMAT_ENTRIES {
+        let mut op = ExtendedStreamFormatList::new(plug_addr, index);
+        if avc.status(addr, &mut op, TIMEOUT_MS).is_err() {
+            break;
+        }
+        match op.stream_format.as_bco_compound_am824_stream() {
+            Ok(s) => {
+                let entries: Vec<String> = s
+                    .entries
+                    .iter()
+                    .map(|entry| format!("{:?} x {}", entry.format, entry.count))
+                    .collect();
+                println!(
+                    "{}  {}: {} Hz, sync source {}, rate control {}, [{}]",
+                    indent,
+                    index,
+                    s.freq,
+                    s.sync_src,
+                    s.rate_ctl,
+                    entries.join(", ")
+                );
+            }
+            Err(_) => println!("{}  {}: {:?}", indent, index, op.stream_format),
+        }
+    }
+}
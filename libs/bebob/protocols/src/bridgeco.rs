@@ -1565,7 +1565,8 @@ impl BcoBootloaderProtocol {
         let mut size = Self::SIZE_WITH_DEBUGGER;
 
         let mut buf = vec![0; size];
-        req.transaction_sync(
+        fw_req_transaction(
+            req,
             node,
             FwTcode::ReadBlockRequest,
             DM_BCO_BOOTLOADER_INFO_OFFSET,
@@ -1575,7 +1576,8 @@ impl BcoBootloaderProtocol {
         )
         .or_else(|_| {
             size = Self::SIZE_WITHOUT_DEBUGGER;
-            req.transaction_sync(
+            fw_req_transaction(
+                req,
                 node,
                 FwTcode::ReadBlockRequest,
                 DM_BCO_BOOTLOADER_INFO_OFFSET,
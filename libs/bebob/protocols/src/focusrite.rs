@@ -367,30 +367,12 @@ pub trait SaffireStoreConfigOperation {
 /// The maximum number of offsets read/written at once.
 pub const MAXIMUM_OFFSET_COUNT: usize = 20;
 
-/// The structure of AV/C vendor-dependent command for configuration operation. The number of
-/// offsets read/written at once is 20.
-#[derive(Debug)]
-pub struct SaffireAvcOperation {
+/// The data of AV/C vendor-dependent command for configuration operation. The number of offsets
+/// read/written at once is 20.
+#[derive(Default, Debug)]
+pub struct SaffireAvcCmd {
     pub offsets: Vec<usize>,
     pub buf: Vec<u8>,
-    op: VendorDependent,
-}
-
-impl Default for SaffireAvcOperation {
-    fn default() -> Self {
-        Self {
-            offsets: Default::default(),
-            buf: Default::default(),
-            op: VendorDependent {
-                company_id: FOCUSRITE_OUI,
-                data: Default::default(),
-            },
-        }
-    }
-}
-
-impl AvcOp for SaffireAvcOperation {
-    const OPCODE: u8 = VendorDependent::OPCODE;
 }
 
 // NOTE: IEC 61883 transaction layer in ASIC is a bit heavy load, thus it's preferable not to use
@@ -398,18 +380,14 @@ impl AvcOp for SaffireAvcOperation {
 const FOCUSRITE_CONTROL_ACTION: u8 = 0x01;
 const FOCUSRITE_STATUS_ACTION: u8 = 0x03;
 
-impl AvcControl for SaffireAvcOperation {
-    fn build_operands(
-        &mut self,
-        addr: &AvcAddr,
-        operands: &mut Vec<u8>,
-    ) -> Result<(), AvcCmdBuildError> {
+impl VendorDependentSchema for SaffireAvcCmd {
+    const COMPANY_ID: [u8; 3] = FOCUSRITE_OUI;
+
+    fn build_control_data(&self, data: &mut Vec<u8>) -> Result<(), AvcCmdBuildError> {
         assert!(self.offsets.len() <= MAXIMUM_OFFSET_COUNT);
         assert_eq!(self.offsets.len() * 4, self.buf.len());
 
-        let data = &mut self.op.data;
         let buf = &self.buf;
-        data.clear();
         data.push(FOCUSRITE_CONTROL_ACTION);
         data.push(self.offsets.len() as u8);
         self.offsets.iter().enumerate().for_each(|(i, &offset)| {
@@ -418,31 +396,22 @@ impl AvcControl for SaffireAvcOperation {
             data.extend_from_slice(&idx.to_be_bytes());
             data.extend_from_slice(&buf[pos..(pos + 4)]);
         });
-        AvcControl::build_operands(&mut self.op, addr, operands)
+        Ok(())
     }
 
-    fn parse_operands(&mut self, addr: &AvcAddr, operands: &[u8]) -> Result<(), AvcRespParseError> {
-        AvcControl::parse_operands(&mut self.op, addr, operands)?;
+    fn parse_control_data(&mut self, data: &[u8]) -> Result<(), AvcRespParseError> {
         (0..self.offsets.len()).for_each(|i| {
-            let data = &self.op.data[(5 + i * 8 + 4)..(5 + i * 8 + 8)];
+            let data = &data[(5 + i * 8 + 4)..(5 + i * 8 + 8)];
             let buf = &mut self.buf[(i * 4)..(i * 4 + 4)];
             buf.copy_from_slice(data);
         });
         Ok(())
     }
-}
 
-impl AvcStatus for SaffireAvcOperation {
-    fn build_operands(
-        &mut self,
-        addr: &AvcAddr,
-        operands: &mut Vec<u8>,
-    ) -> Result<(), AvcCmdBuildError> {
+    fn build_status_data(&self, data: &mut Vec<u8>) -> Result<(), AvcCmdBuildError> {
         assert!(self.offsets.len() <= MAXIMUM_OFFSET_COUNT);
         assert_eq!(self.offsets.len() * 4, self.buf.len());
 
-        let data = &mut self.op.data;
-        data.clear();
         data.push(FOCUSRITE_STATUS_ACTION);
         data.push(self.offsets.len() as u8);
         self.offsets.iter().for_each(|&offset| {
@@ -450,13 +419,12 @@ impl AvcStatus for SaffireAvcOperation {
             data.extend_from_slice(&idx.to_be_bytes());
             data.extend_from_slice(&[0xff; 4]);
         });
-        AvcStatus::build_operands(&mut self.op, addr, operands)
+        Ok(())
     }
 
-    fn parse_operands(&mut self, addr: &AvcAddr, operands: &[u8]) -> Result<(), AvcRespParseError> {
-        AvcStatus::parse_operands(&mut self.op, addr, operands)?;
+    fn parse_status_data(&mut self, data: &[u8]) -> Result<(), AvcRespParseError> {
         (0..self.offsets.len()).for_each(|i| {
-            let data = &self.op.data[(2 + i * 8 + 4)..(2 + i * 8 + 8)];
+            let data = &data[(2 + i * 8 + 4)..(2 + i * 8 + 8)];
             let buf = &mut self.buf[(i * 4)..(i * 4 + 4)];
             buf.copy_from_slice(data);
         });
@@ -464,6 +432,9 @@ impl AvcStatus for SaffireAvcOperation {
     }
 }
 
+/// The structure of AV/C vendor-dependent command for configuration operation.
+pub type SaffireAvcOperation = VendorDependentOperation<SaffireAvcCmd>;
+
 const READ_OFFSET: u64 = 0x000100000000;
 const WRITE_OFFSET: u64 = 0x000100010000;
 
@@ -477,7 +448,8 @@ pub fn saffire_read_quadlet(
 ) -> Result<(), Error> {
     assert_eq!(buf.len(), 4);
 
-    req.transaction_sync(
+    fw_req_transaction(
+        req,
         node,
         FwTcode::ReadQuadletRequest,
         READ_OFFSET + offset as u64,
@@ -519,7 +491,8 @@ pub fn saffire_read_quadlets(
         if count == 1 {
             saffire_read_quadlet(req, node, prev_offset, frame, timeout_ms)
         } else {
-            req.transaction_sync(
+            fw_req_transaction(
+                req,
                 node,
                 FwTcode::ReadBlockRequest,
                 READ_OFFSET + prev_offset as u64,
@@ -549,7 +522,8 @@ pub fn saffire_write_quadlet(
     assert_eq!(buf.len(), 4);
 
     let mut frame = buf.to_vec();
-    req.transaction_sync(
+    fw_req_transaction(
+        req,
         node,
         FwTcode::WriteQuadletRequest,
         WRITE_OFFSET + offset as u64,
@@ -583,7 +557,8 @@ pub fn saffire_write_quadlets(
             frame
         });
 
-    req.transaction_sync(
+    fw_req_transaction(
+        req,
         node,
         FwTcode::WriteBlockRequest,
         WRITE_OFFSET,
@@ -657,11 +632,10 @@ mod test {
 
     #[test]
     fn vendor_dependent_control_operands() {
-        let mut op = SaffireAvcOperation {
+        let mut op = SaffireAvcOperation::new(SaffireAvcCmd {
             offsets: vec![0x40, 0x400],
             buf: vec![0x01, 0x23, 0x45, 0x67, 0x76, 0x54, 0x32, 0x10],
-            ..Default::default()
-        };
+        });
         let mut generated = Vec::new();
         AvcControl::build_operands(&mut op, &AvcAddr::Unit, &mut generated).unwrap();
 
@@ -675,16 +649,15 @@ mod test {
             0x09, 0xff, 0x00, 0x00, 0x13, 0x0e, 0x01, 0x02, 0x00, 0x00, 0x00, 0x10, 0x76, 0x54,
             0x32, 0x10, 0x00, 0x00, 0x01, 0x00, 0x01, 0x23, 0x45, 0x67,
         ];
-        let mut op = SaffireAvcOperation {
+        let mut op = SaffireAvcOperation::new(SaffireAvcCmd {
             offsets: vec![0x40, 0x400],
             buf: vec![0; 8],
-            ..Default::default()
-        };
+        });
         AvcControl::parse_operands(&mut op, &AvcAddr::Unit, &resp).unwrap();
-        assert_eq!(op.offsets[0], 0x40);
-        assert_eq!(&op.buf[..4], &[0x76, 0x54, 0x32, 0x10]);
-        assert_eq!(op.offsets[1], 0x400);
-        assert_eq!(&op.buf[4..], &[0x01, 0x23, 0x45, 0x67]);
+        assert_eq!(op.cmd.offsets[0], 0x40);
+        assert_eq!(&op.cmd.buf[..4], &[0x76, 0x54, 0x32, 0x10]);
+        assert_eq!(op.cmd.offsets[1], 0x400);
+        assert_eq!(&op.cmd.buf[4..], &[0x01, 0x23, 0x45, 0x67]);
     }
 
     #[test]
@@ -693,22 +666,20 @@ mod test {
             0x00, 0x13, 0x0e, 0x03, 0x02, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0xff, 0x00,
             0x00, 0x01, 0x00, 0x00, 0xff, 0x00, 0xff,
         ];
-        let mut op = SaffireAvcOperation {
+        let mut op = SaffireAvcOperation::new(SaffireAvcCmd {
             offsets: vec![0x40, 0x400],
             buf: vec![0; 8],
-            ..Default::default()
-        };
+        });
         AvcStatus::parse_operands(&mut op, &AvcAddr::Unit, &resp).unwrap();
-        assert_eq!(op.offsets[0], 0x40);
-        assert_eq!(&op.buf[..4], &[0x00, 0x00, 0x00, 0xff]);
-        assert_eq!(op.offsets[1], 0x400);
-        assert_eq!(&op.buf[4..], &[0x00, 0xff, 0x00, 0xff]);
+        assert_eq!(op.cmd.offsets[0], 0x40);
+        assert_eq!(&op.cmd.buf[..4], &[0x00, 0x00, 0x00, 0xff]);
+        assert_eq!(op.cmd.offsets[1], 0x400);
+        assert_eq!(&op.cmd.buf[4..], &[0x00, 0xff, 0x00, 0xff]);
 
-        let mut op = SaffireAvcOperation {
+        let mut op = SaffireAvcOperation::new(SaffireAvcCmd {
             offsets: vec![0x40, 0x400],
             buf: vec![0; 8],
-            ..Default::default()
-        };
+        });
         let mut generated = Vec::new();
         AvcStatus::build_operands(&mut op, &AvcAddr::Unit, &mut generated).unwrap();
 
@@ -128,15 +128,14 @@ impl MediaClockFrequencyOperation for SaffireClkProtocol {
 
     fn write_clk_freq(avc: &BebobAvc, idx: usize, timeout_ms: u32) -> Result<(), Error> {
         // 192 kHz is just available when enabled.
-        let mut op = SaffireAvcOperation {
+        let mut op = SaffireAvcOperation::new(SaffireAvcCmd {
             offsets: vec![SAFFIRE_MODE_192KHZ_OFFSET],
             buf: vec![0; 4],
-            ..Default::default()
-        };
+        });
         avc.status(&AvcAddr::Unit, &mut op, timeout_ms)?;
 
         let mut quadlet = [0; 4];
-        quadlet.copy_from_slice(&mut op.buf);
+        quadlet.copy_from_slice(&mut op.cmd.buf);
         let val = u32::from_be_bytes(quadlet);
         if (val > 0 && idx < 4) || (val == 0 && idx == 4) {
             let msg = format!("Invalid frequency of media clock: {}", Self::FREQ_LIST[idx]);
@@ -11,6 +11,7 @@ pub mod digidesign;
 pub mod esi;
 pub mod focusrite;
 pub mod icon;
+pub mod mackie;
 pub mod maudio;
 pub mod presonus;
 pub mod roland;
@@ -23,8 +24,9 @@ use {
     glib::{Error, FileError, IsA},
     hinawa::{
         prelude::{FwFcpExt, FwFcpExtManual, FwReqExtManual},
-        FwFcp, FwNode, FwReq, FwTcode,
+        FwFcp, FwFcpError, FwNode, FwReq, FwReqError, FwTcode,
     },
+    std::{cell::Cell, rc::Rc, thread, time::Duration},
     ta1394_avc_audio::{amdtp::*, *},
     ta1394_avc_general::{general::*, *},
     ta1394_avc_stream_format::*,
@@ -38,17 +40,252 @@ const DM_APPL_PARAM_OFFSET: u64 = DM_APPL_OFFSET + 0x00700000;
 const DM_BCO_OFFSET: u64 = 0xffffc8000000;
 const DM_BCO_BOOTLOADER_INFO_OFFSET: u64 = DM_BCO_OFFSET + 0x00020000;
 
+/// The policy of transaction against transient failure on busy bus.
+///
+/// Some devices connected via hubs or to bus with many nodes occasionally miss the deadline of
+/// response. The policy expands the timeout given by each operation and retries the transaction
+/// with exponential backoff when the failure is likely to be transient. The backoff blocks the
+/// thread of caller, typically the event loop of runtime, thus the number of retries and the
+/// interval are bounded; at most 5 retries with 500 milliseconds interval.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TransactionPolicy {
+    /// The minimum timeout for each transaction in milliseconds. The larger one of the value and
+    /// the timeout given by the operation is used.
+    pub min_timeout_ms: u32,
+    /// The maximum number of retries after the first failure, bounded by 5.
+    pub max_retries: u32,
+    /// The interval before the first retry in milliseconds, doubled at each retry.
+    pub backoff_ms: u32,
+}
+
+impl Default for TransactionPolicy {
+    fn default() -> Self {
+        Self {
+            min_timeout_ms: 0,
+            max_retries: 0,
+            backoff_ms: 10,
+        }
+    }
+}
+
+impl TransactionPolicy {
+    /// The upper bound of interval before retry.
+    const MAX_BACKOFF_MS: u32 = 500;
+
+    /// The upper bound of the number of retries.
+    const MAX_RETRIES: u32 = 5;
+
+    /// The timeout applied to the transaction.
+    pub fn timeout_ms(&self, timeout_ms: u32) -> u32 {
+        std::cmp::max(self.min_timeout_ms, timeout_ms)
+    }
+
+    /// The interval before the retry of given count, starting at 0.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let backoff_ms = self
+            .backoff_ms
+            .saturating_mul(1u32 << retry.min(31))
+            .min(Self::MAX_BACKOFF_MS);
+        Duration::from_millis(backoff_ms as u64)
+    }
+
+    /// Whether the error is likely to be transient and the transaction is worth retrying.
+    pub fn is_transient(error: &Error) -> bool {
+        if let Some(e) = error.kind::<FwFcpError>() {
+            e == FwFcpError::Timeout
+        } else {
+            matches!(
+                error.kind::<FwReqError>(),
+                Some(FwReqError::Busy) | Some(FwReqError::Cancelled) | Some(FwReqError::Generation)
+            )
+        }
+    }
+
+    /// Execute the transaction by the closure according to the policy. The closure is called
+    /// with the timeout in milliseconds. The transaction is retried when the failure is
+    /// transient, thus the closure should be idempotent.
+    pub fn execute<T, F>(
+        &self,
+        stats: &Cell<TransactionStats>,
+        timeout_ms: u32,
+        cb: F,
+    ) -> Result<T, Error>
+    where
+        F: FnMut(u32) -> Result<T, Error>,
+    {
+        let max_retries = self.max_retries.min(Self::MAX_RETRIES);
+        self.execute_with_retries(stats, timeout_ms, max_retries, cb)
+    }
+
+    /// Execute the transaction by the closure just once. The closure is called with the timeout
+    /// in milliseconds.
+    pub fn execute_once<T, F>(
+        &self,
+        stats: &Cell<TransactionStats>,
+        timeout_ms: u32,
+        cb: F,
+    ) -> Result<T, Error>
+    where
+        F: FnMut(u32) -> Result<T, Error>,
+    {
+        self.execute_with_retries(stats, timeout_ms, 0, cb)
+    }
+
+    fn execute_with_retries<T, F>(
+        &self,
+        stats: &Cell<TransactionStats>,
+        timeout_ms: u32,
+        max_retries: u32,
+        mut cb: F,
+    ) -> Result<T, Error>
+    where
+        F: FnMut(u32) -> Result<T, Error>,
+    {
+        let timeout_ms = self.timeout_ms(timeout_ms);
+        let mut retry = 0;
+
+        loop {
+            let mut s = stats.get();
+            s.transactions += 1;
+
+            let res = cb(timeout_ms);
+            if let Err(e) = &res {
+                if e.kind::<FwFcpError>() == Some(FwFcpError::Timeout) {
+                    s.timeouts += 1;
+                }
+            }
+            match &res {
+                Err(e) if Self::is_transient(e) && retry < max_retries => {
+                    s.retries += 1;
+                    stats.set(s);
+                    thread::sleep(self.backoff(retry));
+                    retry += 1;
+                }
+                Err(_) => {
+                    s.failures += 1;
+                    stats.set(s);
+                    break res;
+                }
+                Ok(_) => {
+                    stats.set(s);
+                    break res;
+                }
+            }
+        }
+    }
+}
+
+/// The counters of transaction for diagnostics.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TransactionStats {
+    /// The number of transactions including retries.
+    pub transactions: u64,
+    /// The number of retries after transient failure.
+    pub retries: u64,
+    /// The number of transactions without response in time.
+    pub timeouts: u64,
+    /// The number of transactions finally failed.
+    pub failures: u64,
+}
+
+/// The policy and counters of transaction shared by the runtime and all of protocol wrappers in
+/// the thread. The instance of BebobAvc and the helper functions for asynchronous transaction
+/// refer to the context of current thread, thus the runtime just configures the policy and reads
+/// the counters by the context.
+#[derive(Default, Debug, Clone)]
+pub struct TransactionContext(Rc<(Cell<TransactionPolicy>, Cell<TransactionStats>)>);
+
+thread_local! {
+    static TRANSACTION_CONTEXT: TransactionContext = Default::default();
+}
+
+impl TransactionContext {
+    /// The context of current thread.
+    pub fn current() -> Self {
+        TRANSACTION_CONTEXT.with(|ctx| ctx.clone())
+    }
+
+    /// The policy of transaction.
+    pub fn policy(&self) -> TransactionPolicy {
+        (self.0).0.get()
+    }
+
+    /// Configure the policy of transaction.
+    pub fn set_policy(&self, policy: TransactionPolicy) {
+        (self.0).0.set(policy)
+    }
+
+    /// The counters of transaction since the thread starts.
+    pub fn stats(&self) -> TransactionStats {
+        (self.0).1.get()
+    }
+
+    /// Execute the transaction by the closure with retries according to the policy.
+    pub fn execute<T, F>(&self, timeout_ms: u32, cb: F) -> Result<T, Error>
+    where
+        F: FnMut(u32) -> Result<T, Error>,
+    {
+        self.policy().execute(&(self.0).1, timeout_ms, cb)
+    }
+
+    /// Execute the transaction by the closure just once.
+    pub fn execute_once<T, F>(&self, timeout_ms: u32, cb: F) -> Result<T, Error>
+    where
+        F: FnMut(u32) -> Result<T, Error>,
+    {
+        self.policy().execute_once(&(self.0).1, timeout_ms, cb)
+    }
+}
+
+/// Perform asynchronous transaction according to the policy in the context of current thread.
+/// The read transaction is retried since it has no side effect. The write transaction is never
+/// retried since it is not necessarily idempotent; e.g. the node can apply the change but the
+/// response subaction is lost.
+pub fn fw_req_transaction(
+    req: &FwReq,
+    node: &FwNode,
+    tcode: FwTcode,
+    addr: u64,
+    length: usize,
+    frame: &mut [u8],
+    timeout_ms: u32,
+) -> Result<(), Error> {
+    let ctx = TransactionContext::current();
+    let cb = |timeout_ms| req.transaction_sync(node, tcode, addr, length, frame, timeout_ms);
+    match tcode {
+        FwTcode::ReadQuadletRequest | FwTcode::ReadBlockRequest => ctx.execute(timeout_ms, cb),
+        _ => ctx.execute_once(timeout_ms, cb),
+    }
+}
+
 /// The implementation of AV/C transaction with quirks specific to BeBoB solution.
 ///
 /// It seems a unique quirk that the status code in response frame for some AV/C commands is
 /// against AV/C general specification in control operation.
-#[derive(Default, Debug)]
-pub struct BebobAvc(FwFcp);
+///
+/// The status operation is retried according to the policy of transaction since it has no side
+/// effect. The control operation is never retried, since it is not necessarily idempotent; e.g.
+/// the unit can apply the change but the response is lost. The policy is given by the context
+/// of transaction in the thread where the instance is created.
+#[derive(Debug)]
+pub struct BebobAvc {
+    fcp: FwFcp,
+    transaction: TransactionContext,
+}
+
+impl Default for BebobAvc {
+    fn default() -> Self {
+        Self {
+            fcp: Default::default(),
+            transaction: TransactionContext::current(),
+        }
+    }
+}
 
 impl Ta1394Avc<Error> for BebobAvc {
     fn transaction(&self, command_frame: &[u8], timeout_ms: u32) -> Result<Vec<u8>, Error> {
         let mut resp = vec![0; Self::FRAME_SIZE];
-        self.0
+        self.fcp
             .avc_transaction(&command_frame, &mut resp, timeout_ms)
             .map(|len| {
                 resp.truncate(len);
@@ -96,7 +333,7 @@ impl Ta1394Avc<Error> for BebobAvc {
 
 impl BebobAvc {
     pub fn bind(&self, node: &impl IsA<FwNode>) -> Result<(), Error> {
-        self.0.bind(node)
+        self.fcp.bind(node)
     }
 
     pub fn control<O: AvcOp + AvcControl>(
@@ -105,7 +342,9 @@ impl BebobAvc {
         op: &mut O,
         timeout_ms: u32,
     ) -> Result<(), Error> {
-        Ta1394Avc::<Error>::control(self, addr, op, timeout_ms).map_err(|err| from_avc_err(err))
+        self.transaction.execute_once(timeout_ms, |timeout_ms| {
+            Ta1394Avc::<Error>::control(self, addr, op, timeout_ms).map_err(|err| from_avc_err(err))
+        })
     }
 
     pub fn status<O: AvcOp + AvcStatus>(
@@ -114,7 +353,9 @@ impl BebobAvc {
         op: &mut O,
         timeout_ms: u32,
     ) -> Result<(), Error> {
-        Ta1394Avc::<Error>::status(self, addr, op, timeout_ms).map_err(|err| from_avc_err(err))
+        self.transaction.execute(timeout_ms, |timeout_ms| {
+            Ta1394Avc::<Error>::status(self, addr, op, timeout_ms).map_err(|err| from_avc_err(err))
+        })
     }
 }
 
@@ -176,6 +417,26 @@ pub trait MediaClockFrequencyOperation {
         });
         avc.control(&AvcAddr::Unit, &mut op, timeout_ms)
     }
+
+    /// Detect mismatch between the frequency of media clock and the rate of packet streaming in
+    /// host side. The mismatch occurs when the source of sampling clock is external and its rate
+    /// is changed, then the packet streaming fails. Return the index of frequency for the rate of
+    /// packet streaming when detected.
+    fn detect_clk_freq_mismatch(
+        avc: &BebobAvc,
+        stream_rate: u32,
+        timeout_ms: u32,
+    ) -> Result<Option<usize>, Error> {
+        let idx = Self::FREQ_LIST
+            .iter()
+            .position(|&freq| freq == stream_rate)
+            .ok_or_else(|| {
+                let msg = format!("Unexpected rate of packet streaming: {}", stream_rate);
+                Error::new(FileError::Io, &msg)
+            })?;
+        let curr = Self::read_clk_freq(avc, timeout_ms)?;
+        Ok(if curr != idx { Some(idx) } else { None })
+    }
 }
 
 /// The trait of source operation for sampling clock.
@@ -399,3 +660,66 @@ pub trait AvcSelectorOperation {
         avc.control(&AUDIO_SUBUNIT_0_ADDR, &mut op, timeout_ms)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn transaction_policy_backoff() {
+        let policy = TransactionPolicy {
+            min_timeout_ms: 200,
+            max_retries: 3,
+            backoff_ms: 10,
+        };
+        assert_eq!(policy.timeout_ms(100), 200);
+        assert_eq!(policy.timeout_ms(300), 300);
+        assert_eq!(policy.backoff(0), Duration::from_millis(10));
+        assert_eq!(policy.backoff(2), Duration::from_millis(40));
+        assert_eq!(policy.backoff(31), Duration::from_millis(500));
+        assert_eq!(policy.backoff(40), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn transaction_policy_retry() {
+        let policy = TransactionPolicy {
+            min_timeout_ms: 0,
+            max_retries: 2,
+            backoff_ms: 1,
+        };
+        let stats = Cell::new(TransactionStats::default());
+
+        let mut count = 0;
+        let res = policy.execute(&stats, 100, |_| {
+            count += 1;
+            if count < 3 {
+                Err(Error::new(FwFcpError::Timeout, ""))
+            } else {
+                Ok(count)
+            }
+        });
+        assert_eq!(res.unwrap(), 3);
+        assert_eq!(
+            stats.get(),
+            TransactionStats {
+                transactions: 3,
+                retries: 2,
+                timeouts: 2,
+                failures: 0,
+            }
+        );
+
+        let res: Result<(), Error> =
+            policy.execute(&stats, 100, |_| Err(Error::new(FileError::Inval, "")));
+        assert!(res.is_err());
+        assert_eq!(stats.get().transactions, 4);
+        assert_eq!(stats.get().failures, 1);
+
+        let res: Result<(), Error> =
+            policy.execute_once(&stats, 100, |_| Err(Error::new(FwFcpError::Timeout, "")));
+        assert!(res.is_err());
+        assert_eq!(stats.get().transactions, 5);
+        assert_eq!(stats.get().retries, 2);
+        assert_eq!(stats.get().failures, 2);
+    }
+}
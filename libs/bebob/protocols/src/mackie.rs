@@ -0,0 +1,38 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Protocol implementation for Mackie Onyx FireWire option card.
+//!
+//! The module includes structure, enumeration, and trait and its implementation for protocol
+//! defined by Mackie for FireWire I/O card optionally installed to Onyx 1220, 1620, and 1640
+//! mixers.
+//!
+//! DM1000 is used for the option card.
+//!
+//! NOTE: The switch of 2 track return and the source of word clock are not supported since the
+//! function block and the plug for them are not identified yet.
+
+use super::*;
+
+/// The protocol implementation for media and sampling clock of Onyx FireWire option card.
+#[derive(Default)]
+pub struct OnyxFwClkProtocol;
+
+impl MediaClockFrequencyOperation for OnyxFwClkProtocol {
+    const FREQ_LIST: &'static [u32] = &[44100, 48000, 88200, 96000];
+}
+
+impl SamplingClockSourceOperation for OnyxFwClkProtocol {
+    const DST: SignalAddr = SignalAddr::Subunit(SignalSubunitAddr {
+        subunit: MUSIC_SUBUNIT_0,
+        plug_id: 0x01,
+    });
+
+    const SRC_LIST: &'static [SignalAddr] = &[
+        // Internal
+        SignalAddr::Subunit(SignalSubunitAddr {
+            subunit: MUSIC_SUBUNIT_0,
+            plug_id: 0x01,
+        }),
+    ];
+}
@@ -840,7 +840,8 @@ pub trait MaudioNormalMeterProtocol {
         let pos = frame.len() - 4;
         bitmap.copy_from_slice(&frame[pos..]);
 
-        req.transaction_sync(
+        fw_req_transaction(
+            req,
             node,
             FwTcode::ReadBlockRequest,
             DM_APPL_METER_OFFSET,
@@ -168,7 +168,8 @@ impl PflMeterProtocol {
     ) -> Result<(), Error> {
         let frame = &mut meter.cache;
 
-        req.transaction_sync(
+        fw_req_transaction(
+            req,
             node,
             FwTcode::ReadBlockRequest,
             DM_APPL_METER_OFFSET,
@@ -239,7 +240,8 @@ impl PflInputParametersProtocol {
         let val = params.force_smux as u32;
         cache[20..24].copy_from_slice(&val.to_be_bytes());
 
-        req.transaction_sync(
+        fw_req_transaction(
+            req,
             node,
             hinawa::FwTcode::WriteBlockRequest,
             DM_APPL_PARAM_OFFSET,
@@ -40,6 +40,14 @@
 //!  stream-input-11/12 -----------------------------------------------------> digital-output-7/8
 //! ```
 //!
+//! The state of control surface; the switch and three rotaries, is retrieved together with
+//! hardware metering. The faders and buttons of ProjectMix I/O are not reported in the way,
+//! since they are transferred as MIDI messages in isochronous packet and available via ALSA
+//! rawmidi device supported by ALSA bebob driver.
+//!
+//! The mode of optical interface (ADAT or S/PDIF) is not configurable by the protocol. Any
+//! register for it is not found in the region of parameters.
+//!
 //! The protocol implementation for M-Audio FireWire 1814 was written with firmware version
 //! below:
 //!
@@ -217,7 +225,8 @@ impl MaudioSpecialMeterProtocol {
         let mut bitmap1 = [0; 4];
         bitmap1.copy_from_slice(&frame[(METER_SIZE - 4)..]);
 
-        req.transaction_sync(
+        fw_req_transaction(
+            req,
             node,
             FwTcode::ReadBlockRequest,
             DM_APPL_METER_OFFSET,
@@ -297,6 +306,11 @@ const CACHE_SIZE: usize = 160;
 // 0x0094 - 0x0098: stream sources to mixer
 // 0x0098 - 0x009c: source of headphone pair
 // 0x009c - 0x00a0: source of analog output pair
+//
+// NOTE: The region is fully occupied by the parameters above. Any register to operate the
+// internal multiplexer without host (standalone mode), or to select its clock, is not found in
+// the region. The hardware mixer is just active while the cache is downloaded once after any
+// bus reset, regardless of packet streaming.
 const STREAM_INPUT_GAIN_POS: usize = 0x0000;
 const ANALOG_OUTPUT_VOLUME_POS: usize = 0x0008;
 const ANALOG_INPUT_GAIN_POS: usize = 0x0010;
@@ -329,7 +343,8 @@ impl Default for MaudioSpecialStateCache {
 impl MaudioSpecialStateCache {
     pub fn download(&mut self, req: &FwReq, node: &FwNode, timeout_ms: u32) -> Result<(), Error> {
         (0..CACHE_SIZE).step_by(4).try_for_each(|pos| {
-            req.transaction_sync(
+            fw_req_transaction(
+                req,
                 node,
                 FwTcode::WriteQuadletRequest,
                 DM_APPL_PARAM_OFFSET + pos as u64,
@@ -765,7 +780,8 @@ pub trait MaudioSpecialParameterProtocol<T: MaudioSpecialParameterOperation + Co
         params.write_to_cache(&mut new);
         (0..CACHE_SIZE).step_by(4).try_for_each(|pos| {
             if new[pos..(pos + 4)] != cache.0[pos..(pos + 4)] {
-                req.transaction_sync(
+                fw_req_transaction(
+                    req,
                     node,
                     FwTcode::WriteQuadletRequest,
                     DM_APPL_PARAM_OFFSET + pos as u64,
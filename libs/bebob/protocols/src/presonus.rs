@@ -5,6 +5,11 @@
 //!
 //! The module includes structure, enumeration, and trait and its implementation for protocol
 //! defined by PreSonus for some FireWire models.
+//!
+//! PreSonus FireStation is not supported. ALSA bebob driver is not bound to the model, and the
+//! identifier of model and the layout of function blocks for preamp gain and mixer are not
+//! investigated yet. The protocol can be added when the dump of configuration ROM and the result
+//! of AV/C probe for the model are available.
 
 const PRESONUS_OUI: [u8; 3] = [0x00, 0x0a, 0x92];
 
@@ -169,7 +169,8 @@ pub trait Inspire1394MeterOperation {
         timeout_ms: u32,
     ) -> Result<(), Error> {
         let frame = &mut meter.frame;
-        req.transaction_sync(
+        fw_req_transaction(
+            req,
             node,
             FwTcode::ReadBlockRequest,
             DM_APPL_METER_OFFSET,
@@ -257,7 +258,7 @@ impl PresonusSwitchOperation for Inspire1394MicPhantomProtocol {
     }
 }
 
-/// The protocol implementation of phantom powering for physical input 1/2.
+/// The protocol implementation of boost for physical input 1/2.
 #[derive(Default)]
 pub struct Inspire1394MicBoostProtocol;
 
@@ -280,7 +281,7 @@ impl PresonusSwitchOperation for Inspire1394MicBoostProtocol {
     }
 }
 
-/// The protocol implementation of phantom powering for physical input 1/2.
+/// The protocol implementation of limitter for physical input 1/2.
 #[derive(Default)]
 pub struct Inspire1394MicLimitProtocol;
 
@@ -303,6 +304,29 @@ impl PresonusSwitchOperation for Inspire1394MicLimitProtocol {
     }
 }
 
+/// The protocol implementation of stereo link for physical input 1/2 and 3/4.
+#[derive(Default)]
+pub struct Inspire1394StereoLinkProtocol;
+
+impl PresonusSwitchOperation for Inspire1394StereoLinkProtocol {
+    const CH_COUNT: usize = 2;
+
+    fn read_switch(avc: &BebobAvc, idx: usize, timeout_ms: u32) -> Result<bool, Error> {
+        let mut param = InputParameter::AnalogStereoLink(idx, false);
+        read_input_param(avc, &mut param, timeout_ms)?;
+        if let InputParameter::AnalogStereoLink(_, state) = param {
+            Ok(state)
+        } else {
+            unreachable!();
+        }
+    }
+
+    fn write_switch(avc: &BebobAvc, idx: usize, val: bool, timeout_ms: u32) -> Result<(), Error> {
+        let param = InputParameter::AnalogStereoLink(idx, val);
+        write_input_param(avc, &param, timeout_ms)
+    }
+}
+
 /// The trait for switch operation specific to Inspire 1394.
 pub trait PresonusSwitchOperation {
     const CH_COUNT: usize;
@@ -317,7 +341,6 @@ enum InputParameter {
     Analog12Phantom(usize, bool),
     Analog12Boost(usize, bool),
     Analog12Limit(usize, bool),
-    #[allow(dead_code)]
     AnalogStereoLink(usize, bool),
 }
 
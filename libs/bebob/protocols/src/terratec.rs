@@ -7,6 +7,7 @@
 //! defined by Terratec for some FireWire models.
 
 pub mod aureon;
+pub mod ews_mic;
 pub mod phase88;
 
 use super::*;
@@ -0,0 +1,21 @@
+// SPDX-License-Identifier: LGPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Protocol implementation for Terratec EWS MIC2/MIC8.
+//!
+//! The module includes structure, enumeration, and trait and its implementation for protocol
+//! defined by Terratec for EWS MIC2/MIC8.
+//!
+//! Both models have the same identifier of model in configuration ROM. The layout of function
+//! blocks for input preamp and output is not investigated yet, thus just the media clock is
+//! available.
+
+use super::*;
+
+/// The protocol implementation for media and sampling clock.
+#[derive(Default)]
+pub struct EwsMicClkProtocol;
+
+impl MediaClockFrequencyOperation for EwsMicClkProtocol {
+    const FREQ_LIST: &'static [u32] = &[44100, 48000, 88200, 96000];
+}
@@ -3,6 +3,7 @@
 
 use {
     super::{common_ctls::*, *},
+    core::knob_forward::*,
     protocols::{apogee::ensemble::*, *},
 };
 
@@ -20,6 +21,7 @@ pub struct EnsembleModel {
     route_ctl: RouteCtl,
     mixer_ctl: MixerCtl,
     stream_ctl: StreamCtl,
+    knob_forwarder: KnobForwarder,
 }
 
 #[derive(Default)]
@@ -32,6 +34,82 @@ impl SamplingClkSrcCtlOperation<EnsembleClkProtocol> for ClkCtl {
         &["Internal", "S/PDIF-coax", "Optical", "Word Clock"];
 }
 
+const KNOB_MIDI_FORWARD_NAME: &str = "knob-midi-forward";
+
+const KNOB_MIDI_CLIENT_NAME: &str = "Apogee Ensemble";
+
+// The state of knob is forwarded by MIDI control change: input target, output target, input
+// gains, and output volumes.
+const KNOB_FORWARD_PARAMS: [KnobForwardParam; 9] = [
+    KnobForwardParam {
+        ctl_num: 0x51,
+        min: 0,
+        max: 3,
+    },
+    KnobForwardParam {
+        ctl_num: 0x52,
+        min: 0,
+        max: 2,
+    },
+    KnobForwardParam {
+        ctl_num: 0x10,
+        min: EnsembleMeterProtocol::IN_KNOB_VAL_MIN as i32,
+        max: EnsembleMeterProtocol::IN_KNOB_VAL_MAX as i32,
+    },
+    KnobForwardParam {
+        ctl_num: 0x11,
+        min: EnsembleMeterProtocol::IN_KNOB_VAL_MIN as i32,
+        max: EnsembleMeterProtocol::IN_KNOB_VAL_MAX as i32,
+    },
+    KnobForwardParam {
+        ctl_num: 0x12,
+        min: EnsembleMeterProtocol::IN_KNOB_VAL_MIN as i32,
+        max: EnsembleMeterProtocol::IN_KNOB_VAL_MAX as i32,
+    },
+    KnobForwardParam {
+        ctl_num: 0x13,
+        min: EnsembleMeterProtocol::IN_KNOB_VAL_MIN as i32,
+        max: EnsembleMeterProtocol::IN_KNOB_VAL_MAX as i32,
+    },
+    KnobForwardParam {
+        ctl_num: 0x07,
+        min: EnsembleMeterProtocol::OUT_KNOB_VAL_MIN as i32,
+        max: EnsembleMeterProtocol::OUT_KNOB_VAL_MAX as i32,
+    },
+    KnobForwardParam {
+        ctl_num: 0x14,
+        min: EnsembleMeterProtocol::OUT_KNOB_VAL_MIN as i32,
+        max: EnsembleMeterProtocol::OUT_KNOB_VAL_MAX as i32,
+    },
+    KnobForwardParam {
+        ctl_num: 0x15,
+        min: EnsembleMeterProtocol::OUT_KNOB_VAL_MIN as i32,
+        max: EnsembleMeterProtocol::OUT_KNOB_VAL_MAX as i32,
+    },
+];
+
+fn forward_knob_state(model: &mut EnsembleModel) -> Result<(), Error> {
+    let m = &model.meter_ctl.0;
+    let input_target = MeterCtl::KNOB_INPUT_TARGETS
+        .iter()
+        .position(|t| t.eq(&m.knob_input_target))
+        .ok_or_else(|| {
+            let msg = format!("Unexpected knob input target: {:?}", m.knob_input_target);
+            Error::new(FileError::Io, &msg)
+        })?;
+    let output_target = MeterCtl::KNOB_OUTPUT_TARGETS
+        .iter()
+        .position(|t| t.eq(&m.knob_output_target))
+        .ok_or_else(|| {
+            let msg = format!("Unexpected knob output target: {:?}", m.knob_output_target);
+            Error::new(FileError::Io, &msg)
+        })?;
+    let mut vals = vec![input_target as i32, output_target as i32];
+    vals.extend(m.knob_input_vals.iter().map(|&val| val as i32));
+    vals.extend(m.knob_output_vals.iter().map(|&val| val as i32));
+    model.knob_forwarder.forward(&KNOB_FORWARD_PARAMS, &vals)
+}
+
 fn input_output_copy_from_meter(model: &mut EnsembleModel) {
     let m = &model.meter_ctl.0;
     model.input_ctl.0.gains.copy_from_slice(&m.knob_input_vals);
@@ -59,30 +137,24 @@ impl CtlModel<(SndUnit, FwNode)> for EnsembleModel {
             .load_src(card_cntr)
             .map(|mut elem_id_list| self.clk_ctl.0.append(&mut elem_id_list))?;
 
-        self.meter_ctl
-            .load_state(card_cntr, &mut self.avc, FCP_TIMEOUT_MS)
-            .map(|_| input_output_copy_from_meter(self))?;
+        self.meter_ctl.load_state(card_cntr)?;
+
+        self.convert_ctl.load_params(card_cntr)?;
 
-        self.convert_ctl
-            .load_params(card_cntr, &mut self.avc, FCP_TIMEOUT_MS)?;
+        self.display_ctl.load_params(card_cntr)?;
 
-        self.display_ctl
-            .load_params(card_cntr, &mut self.avc, FCP_TIMEOUT_MS)?;
+        self.input_ctl.load_params(card_cntr)?;
 
-        self.input_ctl
-            .load_params(card_cntr, &mut self.avc, FCP_TIMEOUT_MS)?;
+        self.output_ctl.load_params(card_cntr)?;
 
-        self.output_ctl
-            .load_params(card_cntr, &mut self.avc, FCP_TIMEOUT_MS)?;
+        self.route_ctl.load_params(card_cntr)?;
 
-        self.route_ctl
-            .load_params(card_cntr, &mut self.avc, FCP_TIMEOUT_MS)?;
+        self.mixer_ctl.load_params(card_cntr)?;
 
-        self.mixer_ctl
-            .load_params(card_cntr, &mut self.avc, FCP_TIMEOUT_MS)?;
+        self.stream_ctl.load_params(card_cntr)?;
 
-        self.stream_ctl
-            .load_params(card_cntr, &mut self.avc, FCP_TIMEOUT_MS)?;
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, KNOB_MIDI_FORWARD_NAME, 0);
+        card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
 
         Ok(())
     }
@@ -119,6 +191,9 @@ impl CtlModel<(SndUnit, FwNode)> for EnsembleModel {
             Ok(true)
         } else if self.stream_ctl.read_params(elem_id, elem_value)? {
             Ok(true)
+        } else if elem_id.name().as_str() == KNOB_MIDI_FORWARD_NAME {
+            elem_value.set_bool(&[self.knob_forwarder.is_enabled()]);
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -184,12 +259,46 @@ impl CtlModel<(SndUnit, FwNode)> for EnsembleModel {
             .write_params(unit, &mut self.avc, elem_id, new, FCP_TIMEOUT_MS)?
         {
             Ok(true)
+        } else if elem_id.name().as_str() == KNOB_MIDI_FORWARD_NAME {
+            if new.boolean()[0] {
+                self.knob_forwarder.enable(KNOB_MIDI_CLIENT_NAME)?;
+            } else {
+                self.knob_forwarder.disable();
+            }
+            Ok(true)
         } else {
             Ok(true)
         }
     }
 }
 
+impl CacheModel<(SndUnit, FwNode)> for EnsembleModel {
+    fn cache(&mut self, _: &mut (SndUnit, FwNode)) -> Result<(), Error> {
+        self.meter_ctl
+            .measure_state(&mut self.avc, FCP_TIMEOUT_MS)
+            .map(|_| input_output_copy_from_meter(self))?;
+
+        // NOTE: The unit has no way to retrieve the parameters, thus the initial values are
+        // sent to the unit.
+        self.avc
+            .init_params(&mut self.convert_ctl.0, FCP_TIMEOUT_MS)?;
+        self.avc
+            .init_params(&mut self.display_ctl.0, FCP_TIMEOUT_MS)?;
+        self.avc
+            .init_params(&mut self.input_ctl.0, FCP_TIMEOUT_MS)?;
+        self.avc
+            .init_params(&mut self.output_ctl.0, FCP_TIMEOUT_MS)?;
+        self.avc
+            .init_params(&mut self.route_ctl.0, FCP_TIMEOUT_MS)?;
+        self.avc
+            .init_params(&mut self.mixer_ctl.0, FCP_TIMEOUT_MS)?;
+        self.avc
+            .init_params(&mut self.stream_ctl.0, FCP_TIMEOUT_MS)?;
+
+        Ok(())
+    }
+}
+
 impl MeasureModel<(SndUnit, FwNode)> for EnsembleModel {
     fn get_measure_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
         elem_id_list.extend_from_slice(&self.meter_ctl.1);
@@ -200,7 +309,13 @@ impl MeasureModel<(SndUnit, FwNode)> for EnsembleModel {
     fn measure_states(&mut self, _: &mut (SndUnit, FwNode)) -> Result<(), Error> {
         self.meter_ctl
             .measure_state(&mut self.avc, FCP_TIMEOUT_MS)
-            .map(|_| input_output_copy_from_meter(self))
+            .map(|_| input_output_copy_from_meter(self))?;
+
+        if self.knob_forwarder.is_enabled() {
+            forward_knob_state(self)?;
+        }
+
+        Ok(())
     }
 
     fn measure_elem(
@@ -226,7 +341,13 @@ impl NotifyModel<(SndUnit, FwNode), bool> for EnsembleModel {
         elem_id_list.extend_from_slice(&self.clk_ctl.0);
     }
 
-    fn parse_notification(&mut self, _: &mut (SndUnit, FwNode), _: &bool) -> Result<(), Error> {
+    fn parse_notification(
+        &mut self,
+        unit: &mut (SndUnit, FwNode),
+        &locked: &bool,
+    ) -> Result<(), Error> {
+        self.clk_ctl
+            .correct_freq(&mut unit.0, &self.avc, locked, FCP_TIMEOUT_MS * 3)?;
         Ok(())
     }
 
@@ -330,12 +451,7 @@ impl MeterCtl {
     const LEVEL_MAX: i32 = EnsembleMeterProtocol::LEVEL_MAX as i32;
     const LEVEL_STEP: i32 = EnsembleMeterProtocol::LEVEL_STEP as i32;
 
-    fn load_state(
-        &mut self,
-        card_cntr: &mut CardCntr,
-        avc: &mut BebobAvc,
-        timeout_ms: u32,
-    ) -> Result<(), Error> {
+    fn load_state(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
         let labels: Vec<&str> = Self::KNOB_INPUT_TARGETS
             .iter()
             .map(|t| knob_input_target_to_str(t))
@@ -382,7 +498,7 @@ impl MeterCtl {
             )
             .map(|mut elem_id_list| self.1.append(&mut elem_id_list))?;
 
-        self.measure_state(avc, timeout_ms)
+        Ok(())
     }
 
     fn measure_state(&mut self, avc: &mut BebobAvc, timeout_ms: u32) -> Result<(), Error> {
@@ -408,15 +524,21 @@ impl MeterCtl {
                 Ok(true)
             }
             IN_METER_NAME => {
-                ElemValueAccessor::<i32>::set_vals(elem_value, 18, |idx| {
-                    Ok(self.0.phys_inputs[idx] as i32)
-                })?;
+                IntElem::new(
+                    18,
+                    EnsembleMeterProtocol::LEVEL_MIN,
+                    EnsembleMeterProtocol::LEVEL_MAX,
+                )
+                .set_vals(elem_value, |idx| Ok(self.0.phys_inputs[idx]))?;
                 Ok(true)
             }
             OUT_METER_NAME => {
-                ElemValueAccessor::<i32>::set_vals(elem_value, 16, |idx| {
-                    Ok(self.0.phys_outputs[idx] as i32)
-                })?;
+                IntElem::new(
+                    16,
+                    EnsembleMeterProtocol::LEVEL_MIN,
+                    EnsembleMeterProtocol::LEVEL_MAX,
+                )
+                .set_vals(elem_value, |idx| Ok(self.0.phys_outputs[idx]))?;
                 Ok(true)
             }
             _ => Ok(false),
@@ -497,12 +619,7 @@ impl ConvertCtl {
         RateConvertRate::R192000,
     ];
 
-    fn load_params(
-        &mut self,
-        card_cntr: &mut CardCntr,
-        avc: &mut BebobAvc,
-        timeout_ms: u32,
-    ) -> Result<(), Error> {
+    fn load_params(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
         let labels: Vec<&str> = Self::FORMAT_CONVERT_TARGETS
             .iter()
             .map(|t| format_convert_target_to_str(t))
@@ -527,7 +644,7 @@ impl ConvertCtl {
         let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, CD_MODE_NAME, 0);
         let _ = card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
 
-        avc.init_params(&mut self.0, timeout_ms)
+        Ok(())
     }
 
     fn read_params(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
@@ -644,12 +761,7 @@ impl DisplayCtl {
     const DISPLAY_METER_TARGETS: [DisplayMeterTarget; 2] =
         [DisplayMeterTarget::Output, DisplayMeterTarget::Input];
 
-    fn load_params(
-        &mut self,
-        card_cntr: &mut CardCntr,
-        avc: &mut BebobAvc,
-        timeout_ms: u32,
-    ) -> Result<(), Error> {
+    fn load_params(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
         let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, DISPLAY_ENABLE_NAME, 0);
         let _ = card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
 
@@ -666,7 +778,7 @@ impl DisplayCtl {
         let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, DISPLAY_OVERHOLD_NAME, 0);
         let _ = card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
 
-        avc.init_params(&mut self.0, timeout_ms)
+        Ok(())
     }
 
     fn read_params(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
@@ -747,7 +859,7 @@ const INPUT_LIMIT_NAME: &str = "input-limit";
 const INPUT_LEVEL_NAME: &str = "input-level";
 const MIC_GAIN_NAME: &str = "mic-gain";
 const MIC_PHANTOM_NAME: &str = "mic-phantom";
-const MIC_POLARITY_NAME: &str = "mic-polarity";
+const INPUT_POLARITY_NAME: &str = "input-polarity";
 const INPUT_OPT_IFACE_MODE_NAME: &str = "input-optical-mode";
 
 fn input_nominal_level_to_str(level: &InputNominalLevel) -> &str {
@@ -780,12 +892,7 @@ impl InputCtl {
         InputNominalLevel::Microphone,
     ];
 
-    fn load_params(
-        &mut self,
-        card_cntr: &mut CardCntr,
-        avc: &mut BebobAvc,
-        timeout_ms: u32,
-    ) -> Result<(), Error> {
+    fn load_params(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
         let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, INPUT_LIMIT_NAME, 0);
         let _ = card_cntr.add_bool_elems(&elem_id, 1, Self::INPUT_LABELS.len(), true)?;
 
@@ -814,7 +921,7 @@ impl InputCtl {
         let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, MIC_PHANTOM_NAME, 0);
         let _ = card_cntr.add_bool_elems(&elem_id, 1, Self::MIC_LABELS.len(), true)?;
 
-        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, MIC_POLARITY_NAME, 0);
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, INPUT_POLARITY_NAME, 0);
         let _ = card_cntr.add_bool_elems(&elem_id, 1, Self::MIC_LABELS.len(), true)?;
 
         let labels: Vec<&str> = OPT_IFACE_MODES
@@ -824,7 +931,7 @@ impl InputCtl {
         let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, INPUT_OPT_IFACE_MODE_NAME, 0);
         let _ = card_cntr.add_enum_elems(&elem_id, 1, 1, &labels, None, true)?;
 
-        avc.init_params(&mut self.0, timeout_ms)
+        Ok(())
     }
 
     fn read_params(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
@@ -833,16 +940,15 @@ impl InputCtl {
                 elem_value.set_bool(&self.0.limits);
                 Ok(true)
             }
-            INPUT_LEVEL_NAME => {
-                ElemValueAccessor::<u32>::set_vals(elem_value, Self::INPUT_LABELS.len(), |idx| {
+            INPUT_LEVEL_NAME => EnumElem::new(Self::INPUT_LABELS.len(), Self::NOMINAL_LEVELS.len())
+                .set_vals(elem_value, |idx| {
                     let pos = Self::NOMINAL_LEVELS
                         .iter()
                         .position(|l| l.eq(&self.0.levels[idx]))
                         .unwrap();
-                    Ok(pos as u32)
+                    Ok(pos)
                 })
-                .map(|_| true)
-            }
+                .map(|_| true),
             MIC_GAIN_NAME => {
                 let vals: Vec<i32> = self.0.gains.iter().map(|&val| val as i32).collect();
                 elem_value.set_int(&vals);
@@ -852,7 +958,7 @@ impl InputCtl {
                 elem_value.set_bool(&self.0.phantoms);
                 Ok(true)
             }
-            MIC_POLARITY_NAME => {
+            INPUT_POLARITY_NAME => {
                 elem_value.set_bool(&self.0.polarities);
                 Ok(true)
             }
@@ -927,7 +1033,7 @@ impl InputCtl {
                 avc.update_params(&params, &mut self.0, timeout_ms)
                     .map(|_| true)
             }
-            MIC_POLARITY_NAME => {
+            INPUT_POLARITY_NAME => {
                 let mut params = self.0.clone();
                 params
                     .polarities
@@ -981,12 +1087,7 @@ impl<'a> OutputCtl {
         OutputNominalLevel::Consumer,
     ];
 
-    fn load_params(
-        &mut self,
-        card_cntr: &mut CardCntr,
-        avc: &mut BebobAvc,
-        timeout_ms: u32,
-    ) -> Result<(), Error> {
+    fn load_params(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
         let labels: Vec<&str> = Self::NOMINAL_LEVELS
             .iter()
             .map(|l| output_nominal_level_to_str(l))
@@ -1031,21 +1132,20 @@ impl<'a> OutputCtl {
             ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, OUTPUT_OPT_IFACE_MODE_NAME, 0);
         let _ = card_cntr.add_enum_elems(&elem_id, 1, 1, &labels, None, true)?;
 
-        avc.init_params(&mut self.0, timeout_ms)
+        Ok(())
     }
 
     fn read_params(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            OUTPUT_LEVEL_NAME => {
-                ElemValueAccessor::<u32>::set_vals(elem_value, Self::OUT_LABELS.len(), |i| {
+            OUTPUT_LEVEL_NAME => EnumElem::new(Self::OUT_LABELS.len(), Self::NOMINAL_LEVELS.len())
+                .set_vals(elem_value, |i| {
                     let pos = Self::NOMINAL_LEVELS
                         .iter()
                         .position(|l| l.eq(&self.0.levels[i]))
                         .unwrap();
-                    Ok(pos as u32)
+                    Ok(pos)
                 })
-                .map(|_| true)
-            }
+                .map(|_| true),
             OUTPUT_VOL_NAME => {
                 elem_value.set_int(&[self.0.vol as i32]);
                 Ok(true)
@@ -1259,12 +1359,7 @@ impl RouteCtl {
         "none",
     ];
 
-    fn load_params(
-        &mut self,
-        card_cntr: &mut CardCntr,
-        avc: &mut BebobAvc,
-        timeout_ms: u32,
-    ) -> Result<(), Error> {
+    fn load_params(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
         let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, OUT_SRC_NAME, 0);
         let _ = card_cntr.add_enum_elems(
             &elem_id,
@@ -1295,7 +1390,7 @@ impl RouteCtl {
             true,
         )?;
 
-        avc.init_params(&mut self.0, timeout_ms)
+        Ok(())
     }
 
     fn read_params(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
@@ -1439,12 +1534,7 @@ impl MixerCtl {
         mute_avail: true,
     };
 
-    fn load_params(
-        &mut self,
-        card_cntr: &mut CardCntr,
-        avc: &mut BebobAvc,
-        timeout_ms: u32,
-    ) -> Result<(), Error> {
+    fn load_params(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
         let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, MIXER_SRC_GAIN_NAME, 0);
         let _ = card_cntr.add_int_elems(
             &elem_id,
@@ -1457,7 +1547,7 @@ impl MixerCtl {
             true,
         )?;
 
-        avc.init_params(&mut self.0, timeout_ms)
+        Ok(())
     }
 
     fn read_params(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
@@ -1521,12 +1611,7 @@ impl StreamCtl {
         StreamMode::Format8x8,
     ];
 
-    fn load_params(
-        &mut self,
-        card_cntr: &mut CardCntr,
-        avc: &mut BebobAvc,
-        timeout_ms: u32,
-    ) -> Result<(), Error> {
+    fn load_params(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
         let labels: Vec<&str> = Self::STREAM_MODES
             .iter()
             .map(|m| stream_mode_to_str(m))
@@ -1535,7 +1620,7 @@ impl StreamCtl {
             alsactl::ElemId::new_by_name(alsactl::ElemIfaceType::Card, 0, 0, STREAM_MODE_NAME, 0);
         let _ = card_cntr.add_enum_elems(&elem_id, 1, 1, &labels, None, true)?;
 
-        avc.init_params(&mut self.0, timeout_ms)
+        Ok(())
     }
 
     fn read_params(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
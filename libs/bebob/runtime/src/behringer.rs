@@ -99,7 +99,13 @@ impl NotifyModel<(SndUnit, FwNode), bool> for Fca610Model {
         elem_id_list.extend_from_slice(&self.clk_ctl.0);
     }
 
-    fn parse_notification(&mut self, _: &mut (SndUnit, FwNode), _: &bool) -> Result<(), Error> {
+    fn parse_notification(
+        &mut self,
+        unit: &mut (SndUnit, FwNode),
+        &locked: &bool,
+    ) -> Result<(), Error> {
+        self.clk_ctl
+            .correct_freq(&mut unit.0, &self.avc, locked, FCP_TIMEOUT_MS * 3)?;
         Ok(())
     }
 
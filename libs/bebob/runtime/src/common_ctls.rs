@@ -1,7 +1,26 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 // Copyright (c) 2020 Takashi Sakamoto
 
-use {super::*, protocols::*};
+use {super::*, protocols::*, std::sync::Mutex};
+
+/// The tapers of fader for elements of level.
+static FADER_TAPERS: Mutex<Vec<(String, FaderTaper)>> = Mutex::new(Vec::new());
+
+/// Configure the tapers of fader for elements of level by the name of element. The elements
+/// without taper map the value to the level linearly.
+pub fn set_fader_tapers(tapers: Vec<(String, FaderTaper)>) {
+    *FADER_TAPERS.lock().unwrap() = tapers;
+}
+
+fn fader_taper(name: &str) -> FaderTaper {
+    FADER_TAPERS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|&(_, taper)| taper)
+        .unwrap_or_default()
+}
 
 pub trait MediaClkFreqCtlOperation<T: MediaClockFrequencyOperation> {
     fn load_freq(&mut self, card_cntr: &mut CardCntr) -> Result<Vec<ElemId>, Error> {
@@ -19,10 +38,11 @@ pub trait MediaClkFreqCtlOperation<T: MediaClockFrequencyOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            CLK_RATE_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
-                T::read_clk_freq(avc, timeout_ms).map(|idx| idx as u32)
-            })
-            .map(|_| true),
+            CLK_RATE_NAME => {
+                let elem = EnumElem::new(1, T::FREQ_LIST.len());
+                T::read_clk_freq(avc, timeout_ms).map(|idx| elem.set_val(elem_value, idx))?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -38,17 +58,45 @@ pub trait MediaClkFreqCtlOperation<T: MediaClockFrequencyOperation> {
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
             CLK_RATE_NAME => {
+                let val = EnumElem::new(1, T::FREQ_LIST.len()).get_val(new)?;
                 unit.lock()?;
-                let res = ElemValueAccessor::<u32>::get_val(new, |val| {
-                    T::write_clk_freq(avc, val as usize, timeout_ms)
-                })
-                .map(|_| true);
+                let res = T::write_clk_freq(avc, val, timeout_ms).map(|_| true);
                 let _ = unit.unlock();
                 res
             }
             _ => Ok(false),
         }
     }
+
+    /// Re-apply the rate of PCM substreams to media clock when they differ at the notification
+    /// that packet streaming stops, typically due to the change of external clock rate. Return
+    /// true when corrected.
+    fn correct_freq(
+        &self,
+        unit: &mut SndUnit,
+        avc: &BebobAvc,
+        locked: bool,
+        timeout_ms: u32,
+    ) -> Result<bool, Error> {
+        if locked {
+            return Ok(false);
+        }
+
+        let stream_rate = match configured_pcm_rates(unit.card_id()).first() {
+            Some(&rate) => rate,
+            None => return Ok(false),
+        };
+
+        match T::detect_clk_freq_mismatch(avc, stream_rate, timeout_ms)? {
+            Some(idx) => {
+                unit.lock()?;
+                let res = T::write_clk_freq(avc, idx, timeout_ms).map(|_| true);
+                let _ = unit.unlock();
+                res
+            }
+            None => Ok(false),
+        }
+    }
 }
 
 pub trait SamplingClkSrcCtlOperation<T: SamplingClockSourceOperation> {
@@ -81,10 +129,11 @@ pub trait SamplingClkSrcCtlOperation<T: SamplingClockSourceOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            CLK_SRC_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
-                T::read_clk_src(avc, timeout_ms).map(|idx| idx as u32)
-            })
-            .map(|_| true),
+            CLK_SRC_NAME => {
+                let elem = EnumElem::new(1, T::SRC_LIST.len());
+                T::read_clk_src(avc, timeout_ms).map(|idx| elem.set_val(elem_value, idx))?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -100,11 +149,9 @@ pub trait SamplingClkSrcCtlOperation<T: SamplingClockSourceOperation> {
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
             CLK_SRC_NAME => {
+                let val = EnumElem::new(1, T::SRC_LIST.len()).get_val(new)?;
                 unit.lock()?;
-                let res = ElemValueAccessor::<u32>::get_val(new, |val| {
-                    T::write_clk_src(avc, val as usize, timeout_ms)
-                })
-                .map(|_| true);
+                let res = T::write_clk_src(avc, val, timeout_ms).map(|_| true);
                 let _ = unit.unlock();
                 res
             }
@@ -136,6 +183,13 @@ pub trait AvcLevelCtlOperation<T: AvcLevelOperation> {
             Self::LEVEL_NAME
         );
 
+        // The value is not linear to dB when the taper is configured.
+        let tlv: Vec<u32> = Self::LEVEL_TLV.into();
+        let tlv = match fader_taper(Self::LEVEL_NAME) {
+            FaderTaper::Linear => Some(&tlv[..]),
+            _ => None,
+        };
+
         let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, Self::LEVEL_NAME, 0);
         card_cntr
             .add_int_elems(
@@ -145,10 +199,10 @@ pub trait AvcLevelCtlOperation<T: AvcLevelOperation> {
                 Self::LEVEL_MAX,
                 Self::LEVEL_STEP,
                 T::ENTRIES.len(),
-                Some(&Into::<Vec<u32>>::into(Self::LEVEL_TLV)),
+                tlv,
                 true,
             )
-            .map(|_| ())
+            .map(|elem_id_list| card_cntr.add_ramp_targets(&elem_id_list, Self::LEVEL_STEP))
     }
 
     fn read_level(
@@ -159,8 +213,11 @@ pub trait AvcLevelCtlOperation<T: AvcLevelOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::LEVEL_NAME {
-            ElemValueAccessor::<i32>::set_vals(elem_value, T::ENTRIES.len(), |idx| {
-                T::read_level(avc, idx, timeout_ms).map(|level| level as i32)
+            let taper = fader_taper(Self::LEVEL_NAME);
+            let elem = IntElem::new(T::ENTRIES.len(), Self::LEVEL_MIN, Self::LEVEL_MAX);
+            elem.set_vals(elem_value, |idx| {
+                T::read_level(avc, idx, timeout_ms)
+                    .map(|level| taper.to_pos(level as i32, Self::LEVEL_MIN, Self::LEVEL_MAX))
             })
             .map(|_| true)
         } else {
@@ -177,10 +234,15 @@ pub trait AvcLevelCtlOperation<T: AvcLevelOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::LEVEL_NAME {
-            ElemValueAccessor::<i32>::get_vals(new, old, T::ENTRIES.len(), |idx, val| {
-                T::write_level(avc, idx, val as i16, timeout_ms)
-            })
-            .map(|_| true)
+            let taper = fader_taper(Self::LEVEL_NAME);
+            let to_level = |pos| taper.to_level(pos, Self::LEVEL_MIN, Self::LEVEL_MAX);
+            let elem = IntElem::new(T::ENTRIES.len(), Self::LEVEL_MIN, Self::LEVEL_MAX);
+            elem.changes(new, old)?
+                .iter()
+                .try_for_each(|&(idx, val)| {
+                    T::write_level(avc, idx, to_level(val) as i16, timeout_ms)
+                })
+                .map(|_| true)
         } else {
             Ok(false)
         }
@@ -220,7 +282,8 @@ pub trait AvcLrBalanceCtlOperation<T: AvcLevelOperation + AvcLrBalanceOperation>
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::BALANCE_NAME {
-            ElemValueAccessor::<i32>::set_vals(elem_value, T::ENTRIES.len(), |idx| {
+            let elem = IntElem::new(T::ENTRIES.len(), Self::BALANCE_MIN, Self::BALANCE_MAX);
+            elem.set_vals(elem_value, |idx| {
                 T::read_lr_balance(avc, idx, timeout_ms).map(|balance| balance as i32)
             })
             .map(|_| true)
@@ -238,10 +301,11 @@ pub trait AvcLrBalanceCtlOperation<T: AvcLevelOperation + AvcLrBalanceOperation>
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::BALANCE_NAME {
-            ElemValueAccessor::<i32>::get_vals(new, old, T::ENTRIES.len(), |idx, val| {
-                T::write_lr_balance(avc, idx, val as i16, timeout_ms)
-            })
-            .map(|_| true)
+            let elem = IntElem::new(T::ENTRIES.len(), Self::BALANCE_MIN, Self::BALANCE_MAX);
+            elem.changes(new, old)?
+                .iter()
+                .try_for_each(|&(idx, val)| T::write_lr_balance(avc, idx, val as i16, timeout_ms))
+                .map(|_| true)
         } else {
             Ok(false)
         }
@@ -268,10 +332,9 @@ pub trait AvcMuteCtlOperation<T: AvcLevelOperation + AvcMuteOperation>:
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::MUTE_NAME {
-            ElemValueAccessor::<bool>::set_vals(elem_value, T::ENTRIES.len(), |idx| {
-                T::read_mute(avc, idx, timeout_ms)
-            })
-            .map(|_| true)
+            BoolElem::new(T::ENTRIES.len())
+                .set_vals(elem_value, |idx| T::read_mute(avc, idx, timeout_ms))
+                .map(|_| true)
         } else {
             Ok(false)
         }
@@ -286,10 +349,11 @@ pub trait AvcMuteCtlOperation<T: AvcLevelOperation + AvcMuteOperation>:
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::MUTE_NAME {
-            ElemValueAccessor::<bool>::get_vals(new, old, T::ENTRIES.len(), |idx, val| {
-                T::write_mute(avc, idx, val, timeout_ms)
-            })
-            .map(|_| true)
+            BoolElem::new(T::ENTRIES.len())
+                .changes(new, old)?
+                .iter()
+                .try_for_each(|&(idx, val)| T::write_mute(avc, idx, val, timeout_ms))
+                .map(|_| true)
         } else {
             Ok(false)
         }
@@ -332,10 +396,9 @@ pub trait AvcSelectorCtlOperation<T: AvcSelectorOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::SELECTOR_NAME {
-            ElemValueAccessor::<u32>::set_vals(elem_value, Self::CH_COUNT, |idx| {
-                T::read_selector(avc, idx, timeout_ms).map(|val| val as u32)
-            })
-            .map(|_| true)
+            EnumElem::new(Self::CH_COUNT, T::INPUT_PLUG_ID_LIST.len())
+                .set_vals(elem_value, |idx| T::read_selector(avc, idx, timeout_ms))
+                .map(|_| true)
         } else {
             Ok(false)
         }
@@ -350,10 +413,11 @@ pub trait AvcSelectorCtlOperation<T: AvcSelectorOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::SELECTOR_NAME {
-            ElemValueAccessor::<u32>::get_vals(new, old, Self::CH_COUNT, |idx, val| {
-                T::write_selector(avc, idx, val as usize, timeout_ms)
-            })
-            .map(|_| true)
+            EnumElem::new(Self::CH_COUNT, T::INPUT_PLUG_ID_LIST.len())
+                .changes(new, old)?
+                .iter()
+                .try_for_each(|&(idx, val)| T::write_selector(avc, idx, val, timeout_ms))
+                .map(|_| true)
         } else {
             Ok(false)
         }
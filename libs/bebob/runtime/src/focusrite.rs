@@ -32,10 +32,12 @@ trait SaffireProMediaClkFreqCtlOperation<T: SaffireProioMediaClockFrequencyOpera
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            CLK_RATE_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
-                T::read_clk_freq(req, &unit.1, timeout_ms).map(|idx| idx as u32)
-            })
-            .map(|_| true),
+            CLK_RATE_NAME => {
+                let elem = EnumElem::new(1, T::FREQ_LIST.len());
+                T::read_clk_freq(req, &unit.1, timeout_ms)
+                    .map(|idx| elem.set_val(elem_value, idx))?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -50,11 +52,9 @@ trait SaffireProMediaClkFreqCtlOperation<T: SaffireProioMediaClockFrequencyOpera
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
             CLK_RATE_NAME => {
+                let val = EnumElem::new(1, T::FREQ_LIST.len()).get_val(elem_value)?;
                 unit.0.lock()?;
-                let res = ElemValueAccessor::<u32>::get_val(elem_value, |val| {
-                    T::write_clk_freq(req, &unit.1, val as usize, timeout_ms)
-                })
-                .map(|_| true);
+                let res = T::write_clk_freq(req, &unit.1, val, timeout_ms).map(|_| true);
                 let _ = unit.0.unlock();
                 res
             }
@@ -98,10 +98,12 @@ trait SaffireProSamplingClkSrcCtlOperation<T: SaffireProioSamplingClockSourceOpe
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            CLK_SRC_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
-                T::read_clk_src(req, &unit.1, timeout_ms).map(|idx| idx as u32)
-            })
-            .map(|_| true),
+            CLK_SRC_NAME => {
+                let elem = EnumElem::new(1, T::SRC_LIST.len());
+                T::read_clk_src(req, &unit.1, timeout_ms)
+                    .map(|idx| elem.set_val(elem_value, idx))?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -116,11 +118,9 @@ trait SaffireProSamplingClkSrcCtlOperation<T: SaffireProioSamplingClockSourceOpe
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
             CLK_SRC_NAME => {
+                let val = EnumElem::new(1, T::SRC_LIST.len()).get_val(elem_value)?;
                 unit.0.lock()?;
-                let res = ElemValueAccessor::<u32>::get_val(elem_value, |val| {
-                    T::write_clk_src(req, &unit.1, val as usize, timeout_ms)
-                })
-                .map(|_| true);
+                let res = T::write_clk_src(req, &unit.1, val, timeout_ms).map(|_| true);
                 let _ = unit.0.unlock();
                 res
             }
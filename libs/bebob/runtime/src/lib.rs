@@ -11,6 +11,7 @@ mod digidesign;
 mod esi;
 mod focusrite;
 mod icon;
+mod mackie;
 mod maudio;
 mod presonus;
 mod roland;
@@ -21,8 +22,13 @@ mod yamaha_terratec;
 use {
     alsa_ctl_tlv_codec::DbInterval,
     alsactl::{prelude::*, *},
-    core::{card_cntr::*, dispatcher::*, elem_value_accessor::*, RuntimeOperation},
-    firewire_bebob_protocols as protocols,
+    core::{
+        card_cntr::*, diagnostics::*, dispatcher::*, elem_alias::*, elem_locale::*, fader_taper::*,
+        feature_report::*, level_ramp::*, meter_trigger::*, metrics::*, midi_activity::*,
+        mirror::*, panic_ctl::*, ref_level::*, scrub::*, stream_guard::*, stream_stats::*,
+        typed_elem::*, RuntimeOperation,
+    },
+    firewire_bebob_protocols::{self as protocols, TransactionContext},
     glib::{source, Error, FileError},
     hinawa::{
         prelude::{FwNodeExt, FwNodeExtManual},
@@ -32,7 +38,7 @@ use {
     ieee1212_config_rom::ConfigRom,
     model::*,
     nix::sys::signal,
-    std::{convert::TryFrom, sync::mpsc},
+    std::{convert::TryFrom, sync::mpsc, time::Instant},
     ta1394_avc_general::config_rom::*,
 };
 
@@ -43,6 +49,8 @@ enum Event {
     Elem(ElemId, ElemEventMask),
     Timer,
     StreamLock(bool),
+    Scrub,
+    Ramp,
 }
 
 pub struct BebobRuntime {
@@ -53,6 +61,25 @@ pub struct BebobRuntime {
     tx: mpsc::SyncSender<Event>,
     dispatchers: Vec<Dispatcher>,
     timer: Option<Dispatcher>,
+    ramp_timer: Option<Dispatcher>,
+    card_id: u32,
+    metrics: UnitMetrics,
+    published_metrics: (UnitMetrics, Instant),
+    metrics_exporter: Option<MetricsExporter>,
+    debouncer: EventDebouncer<ElemId>,
+    ref_level: RefLevel,
+    scrubber: Scrubber,
+    mirror_ctl: MirrorCtl,
+    model_ids: (u32, u32),
+    midi_activity: MidiActivity,
+    stream_stats: StreamStats,
+    feature_report: FeatureReport,
+    panic_ctl: PanicCtl,
+    meter_trigger: MeterTrigger,
+    stream_guard: StreamGuard,
+    level_ramp_duration: std::time::Duration,
+    level_ramps: Vec<(ElemId, LevelRamp)>,
+    transaction: TransactionContext,
 }
 
 impl Drop for BebobRuntime {
@@ -72,7 +99,7 @@ impl Drop for BebobRuntime {
 
 impl RuntimeOperation<u32> for BebobRuntime {
     fn new(card_id: u32) -> Result<Self, Error> {
-        let path = format!("/dev/snd/hwC{}D0", card_id);
+        let path = Self::devnode_path(&format!("hwC{}D0", card_id));
         let unit = SndUnit::new();
         unit.open(&path, 0)?;
 
@@ -81,7 +108,7 @@ impl RuntimeOperation<u32> for BebobRuntime {
             return Err(Error::new(FileError::Inval, label));
         }
 
-        let path = format!("/dev/{}", unit.node_device().unwrap());
+        let path = Self::devnode_path(&unit.node_device().unwrap());
         let node = FwNode::new();
         node.open(&path)?;
 
@@ -100,6 +127,7 @@ impl RuntimeOperation<u32> for BebobRuntime {
                 "Configuration ROM is not for 1394TA standard",
             ))?;
 
+        let model_ids = (vendor.vendor_id, model.model_id);
         let model = BebobModel::new(vendor.vendor_id, model.model_id, model.model_name)?;
 
         let card_cntr = CardCntr::default();
@@ -116,6 +144,26 @@ impl RuntimeOperation<u32> for BebobRuntime {
             tx,
             dispatchers: Vec::new(),
             timer: None,
+            ramp_timer: None,
+            card_id,
+            metrics: Default::default(),
+            published_metrics: (Default::default(), Instant::now()),
+            metrics_exporter: None,
+            debouncer: EventDebouncer::new(Self::ELEM_EVENT_DEBOUNCE_WINDOW),
+            ref_level: Default::default(),
+            scrubber: Default::default(),
+            mirror_ctl: Default::default(),
+            model_ids,
+            midi_activity: Default::default(),
+            stream_stats: Default::default(),
+            feature_report: Default::default(),
+            panic_ctl: Default::default(),
+            meter_trigger: Default::default(),
+            stream_guard: Default::default(),
+            level_ramp_duration: Default::default(),
+            level_ramps: Default::default(),
+            // The model created above in the same thread shares the context.
+            transaction: TransactionContext::current(),
         })
     }
 
@@ -123,80 +171,368 @@ impl RuntimeOperation<u32> for BebobRuntime {
         self.launch_node_event_dispatcher()?;
         self.launch_system_event_dispatcher()?;
 
+        setup_label_translations(&mut self.card_cntr);
+
+        // Tapers are optional, thus any failure is not fatal. They should be configured before
+        // adding elements.
+        match load_fader_tapers_from_env() {
+            Ok(tapers) => {
+                if !tapers.is_empty() {
+                    report_feature(FADER_TAPER_FEATURE_NAME, &tapers.len().to_string());
+                }
+                common_ctls::set_fader_tapers(tapers)
+            }
+            Err(e) => report_feature(FADER_TAPER_FEATURE_NAME, &e.to_string()),
+        }
+
+        // NOTE: The transactions to cache the state of unit are not issued in parallel since
+        // the firmware of BeBoB handles AV/C command one by one and the other FCP requests are
+        // rejected during the transaction. The duration is measured and exported by metrics.
+        let now = Instant::now();
         self.model.load(&mut self.unit, &mut self.card_cntr)?;
+        // Some models cache the state of unit after adding the elements with initial values, then
+        // update the elements. It waits for the transactions to finish before handling events.
+        self.model.cache(&mut self.unit, &mut self.card_cntr)?;
+        self.metrics.record_load(now.elapsed());
 
-        if self.model.measure_elem_list.len() > 0 {
+        self.midi_activity.load(&mut self.card_cntr, self.card_id)?;
+
+        if self.model.measure_elem_list.len() > 0 || self.midi_activity.has_ports() {
             let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, Self::TIMER_NAME, 0);
             let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
         }
 
+        self.ref_level
+            .load(&mut self.card_cntr, &self.model.measure_elem_list)?;
+
+        self.stream_guard
+            .load(&mut self.card_cntr, self.card_id, &[CLK_RATE_NAME])?;
+
+        self.panic_ctl
+            .load(&mut self.card_cntr, &Self::PANIC_TARGETS)?;
+
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, Self::LEVEL_RAMP_NAME, 0);
+        let _ = self.card_cntr.add_int_elems(
+            &elem_id,
+            1,
+            Self::LEVEL_RAMP_MIN,
+            Self::LEVEL_RAMP_MAX,
+            Self::LEVEL_RAMP_STEP,
+            1,
+            None,
+            true,
+        )?;
+
+        let policy = self.transaction.policy();
+
+        let elem_id =
+            ElemId::new_by_name(ElemIfaceType::Card, 0, 0, Self::TRANSACTION_TIMEOUT_NAME, 0);
+        let elem_id_list = self.card_cntr.add_int_elems(
+            &elem_id,
+            1,
+            Self::TRANSACTION_TIMEOUT_MIN,
+            Self::TRANSACTION_TIMEOUT_MAX,
+            Self::TRANSACTION_TIMEOUT_STEP,
+            1,
+            None,
+            true,
+        )?;
+        let elem_value = ElemValue::new();
+        elem_value.set_int(&[policy.min_timeout_ms as i32]);
+        self.card_cntr
+            .card
+            .write_elem_value(&elem_id_list[0], &elem_value)?;
+
+        let elem_id =
+            ElemId::new_by_name(ElemIfaceType::Card, 0, 0, Self::TRANSACTION_RETRIES_NAME, 0);
+        let elem_id_list = self.card_cntr.add_int_elems(
+            &elem_id,
+            1,
+            Self::TRANSACTION_RETRIES_MIN,
+            Self::TRANSACTION_RETRIES_MAX,
+            1,
+            1,
+            None,
+            true,
+        )?;
+        let elem_value = ElemValue::new();
+        elem_value.set_int(&[policy.max_retries as i32]);
+        self.card_cntr
+            .card
+            .write_elem_value(&elem_id_list[0], &elem_value)?;
+
+        // The element is not unlocked so that the other processes can not change it.
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, Self::METRICS_NAME, 0);
+        let _ = self
+            .card_cntr
+            .add_bytes_elems(&elem_id, 1, UnitMetrics::SIZE, None, false)?;
+
+        self.stream_stats.load(&mut self.card_cntr, self.card_id)?;
+        self.feature_report.load(&mut self.card_cntr)?;
+
+        if let Some(res) = MetricsExporter::from_env(Self::SERVICE_NAME, self.card_id) {
+            match res {
+                Ok(exporter) => self.metrics_exporter = Some(exporter),
+                Err(e) => report_feature(METRICS_EXPORTER_FEATURE_NAME, &e.to_string()),
+            }
+        }
+
+        self.meter_trigger
+            .setup(&self.card_cntr, Self::METER_TRIGGER_CLIENT_NAME);
+
+        setup_aliases(&mut self.card_cntr);
+
+        // The verification is optional, thus any failure is not fatal.
+        match scrub_interval_from_env(Self::FAMILY_NAME) {
+            Ok(Some(interval)) => self.launch_scrub_dispatcher(interval)?,
+            Ok(None) => (),
+            Err(e) => report_feature(SCRUB_FEATURE_NAME, &e.to_string()),
+        }
+
+        // The mirroring is optional, thus any failure is not fatal. It should be started after
+        // adding elements.
+        if let Err(e) = self.start_mirroring() {
+            report_feature(MIRROR_FEATURE_NAME, &e.to_string());
+        }
+
+        self.feature_report.update(&mut self.card_cntr)?;
+
         Ok(())
     }
 
     fn run(&mut self) -> Result<(), Error> {
         loop {
-            let ev = match self.rx.recv() {
-                Ok(ev) => ev,
-                Err(_) => continue,
+            let now = Instant::now();
+            let timeout = match (self.debouncer.timeout(now), self.metrics_timeout(now)) {
+                (Some(a), Some(b)) => Some(std::cmp::min(a, b)),
+                (a, b) => a.or(b),
+            };
+            let ev = match timeout {
+                Some(timeout) => self.rx.recv_timeout(timeout).ok(),
+                None => self.rx.recv().ok(),
             };
 
-            match ev {
-                Event::Shutdown => break,
-                Event::Disconnected => break,
-                Event::BusReset(generation) => {
-                    println!("IEEE 1394 bus is updated: {}", generation);
-                }
-                Event::Elem(elem_id, events) => {
-                    if elem_id.name() != Self::TIMER_NAME {
-                        let _ = self.model.dispatch_elem_event(
-                            &mut self.unit,
+            if let Some(ev) = ev {
+                match ev {
+                    Event::Shutdown => break,
+                    Event::Disconnected => break,
+                    Event::BusReset(generation) => {
+                        println!("IEEE 1394 bus is updated: {}", generation);
+                        self.metrics.notifications += 1;
+                        self.stream_stats.record_bus_reset();
+                    }
+                    Event::Elem(elem_id, events) => {
+                        if self
+                            .ref_level
+                            .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                        {
+                            let _ = self.feature_report.update(&mut self.card_cntr);
+                            continue;
+                        } else if self.panic_ctl.handle_elem_event(
                             &mut self.card_cntr,
                             &elem_id,
                             &events,
-                        );
-                    } else {
-                        let mut elem_value = ElemValue::new();
-                        if self
-                            .card_cntr
-                            .card
-                            .read_elem_value(&elem_id, &mut elem_value)
-                            .is_ok()
+                        ) {
+                            continue;
+                        } else if self.stream_guard.handle_elem_event(
+                            &mut self.card_cntr,
+                            &elem_id,
+                            &events,
+                        ) {
+                            let _ = self.feature_report.update(&mut self.card_cntr);
+                            continue;
+                        } else if elem_id.name() == Self::METRICS_NAME
+                            || self.stream_stats.has_elem(&elem_id)
+                            || self.feature_report.has_elem(&elem_id)
+                        {
+                            // The change of value is by the runtime itself.
+                            continue;
+                        } else if elem_id.name() == Self::TRANSACTION_TIMEOUT_NAME
+                            || elem_id.name() == Self::TRANSACTION_RETRIES_NAME
                         {
-                            let val = elem_value.boolean()[0];
-                            if val {
-                                let _ = self.start_interval_timer();
+                            self.update_transaction_policy();
+                        } else if elem_id.name() == Self::LEVEL_RAMP_NAME {
+                            let mut elem_value = ElemValue::new();
+                            if self
+                                .card_cntr
+                                .card
+                                .read_elem_value(&elem_id, &mut elem_value)
+                                .is_ok()
+                            {
+                                let val = elem_value.int()[0];
+                                self.set_level_ramp_duration(val as u64);
+                            }
+                        } else if elem_id.name() != Self::TIMER_NAME {
+                            if events == ElemEventMask::VALUE
+                                && self.model.debounced_elem_list.contains(&elem_id)
+                            {
+                                self.debouncer.push(elem_id, Instant::now());
                             } else {
-                                self.stop_interval_timer();
+                                let _ = self.debouncer.remove(&elem_id);
+                                self.dispatch_elem_event(&elem_id, &events);
+                            }
+                        } else {
+                            let mut elem_value = ElemValue::new();
+                            if self
+                                .card_cntr
+                                .card
+                                .read_elem_value(&elem_id, &mut elem_value)
+                                .is_ok()
+                            {
+                                let val = elem_value.boolean()[0];
+                                if val {
+                                    let _ = self.start_interval_timer();
+                                } else {
+                                    self.stop_interval_timer();
+                                }
                             }
                         }
                     }
-                }
-                Event::Timer => {
-                    let _ = self
-                        .model
-                        .measure_elems(&mut self.unit, &mut self.card_cntr);
-                }
-                Event::StreamLock(locked) => {
-                    let _ = self.model.dispatch_stream_lock(
-                        &mut self.unit,
-                        &mut self.card_cntr,
-                        locked,
-                    );
+                    Event::Timer => {
+                        let now = Instant::now();
+                        let _ = self
+                            .model
+                            .measure_elems(&mut self.unit, &mut self.card_cntr);
+                        let _ = self.midi_activity.measure(&mut self.card_cntr);
+                        self.meter_trigger
+                            .check_and_report(&mut self.card_cntr, &self.feature_report);
+                        self.stream_stats.poll();
+                        self.metrics
+                            .record_timer(now.elapsed(), Self::TIMER_INTERVAL);
+                    }
+                    Event::StreamLock(locked) => {
+                        let _ = self.model.dispatch_stream_lock(
+                            &mut self.unit,
+                            &mut self.card_cntr,
+                            locked,
+                        );
+                        self.metrics.notifications += 1;
+                        self.stream_stats.record_stream_lock(locked);
+                        self.stream_stats.poll();
+                    }
+                    Event::Scrub => self.scrub_elems(),
+                    Event::Ramp => self.advance_level_ramps(),
                 }
             }
+
+            self.debouncer
+                .pop_expired(Instant::now())
+                .iter()
+                .for_each(|elem_id| self.dispatch_elem_event(elem_id, &ElemEventMask::VALUE));
+
+            self.update_metrics();
         }
         Ok(())
     }
 }
 
+impl DiagnosticsOperation<u32> for BebobRuntime {
+    fn collect_diagnostics(card_id: &u32) -> Result<Vec<DiagnosticsEntry>, Error> {
+        collect_common_diagnostics(*card_id)
+    }
+}
+
 impl<'a> BebobRuntime {
     const NODE_DISPATCHER_NAME: &'a str = "node event dispatcher";
     const SYSTEM_DISPATCHER_NAME: &'a str = "system event dispatcher";
     const TIMER_DISPATCHER_NAME: &'a str = "interval timer dispatcher";
 
     const TIMER_NAME: &'a str = "metering";
+    const METER_TRIGGER_CLIENT_NAME: &'static str = "BeBoB meter trigger";
     const TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
 
+    // The elements reset by panic switch.
+    const PANIC_TARGETS: [PanicTarget; 15] = [
+        PanicTarget {
+            name: "mixer-output-mute",
+            state: Some(true),
+        },
+        PanicTarget {
+            name: "phys-output-mute",
+            state: Some(true),
+        },
+        PanicTarget {
+            name: "analog-output-mute",
+            state: Some(true),
+        },
+        PanicTarget {
+            name: "output-mute",
+            state: Some(true),
+        },
+        PanicTarget {
+            name: "headphone-mute",
+            state: Some(true),
+        },
+        PanicTarget {
+            name: "mixer-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "mixer-phys-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "mixer-stream-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "aux-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "mixer-output-volume",
+            state: None,
+        },
+        PanicTarget {
+            name: "output-volume",
+            state: None,
+        },
+        PanicTarget {
+            name: "analog-output-volume",
+            state: None,
+        },
+        PanicTarget {
+            name: "phys-output-volume",
+            state: None,
+        },
+        PanicTarget {
+            name: "aux-output-volume",
+            state: None,
+        },
+        PanicTarget {
+            name: "headphone-volume",
+            state: None,
+        },
+    ];
+
+    // The window to coalesce bursts of events for the same element operated by encoders.
+    const ELEM_EVENT_DEBOUNCE_WINDOW: std::time::Duration = std::time::Duration::from_millis(20);
+
+    // The duration to ramp level in milliseconds. Zero means no ramping.
+    const LEVEL_RAMP_NAME: &'a str = "level-ramp-duration";
+    const LEVEL_RAMP_MIN: i32 = 0;
+    const LEVEL_RAMP_MAX: i32 = 500;
+    const LEVEL_RAMP_STEP: i32 = 10;
+
+    // The policy of AV/C transaction. The timeout is the minimum in milliseconds, and the retry
+    // is done just for status operation.
+    const TRANSACTION_TIMEOUT_NAME: &'a str = "transaction-min-timeout";
+    const TRANSACTION_TIMEOUT_MIN: i32 = 0;
+    const TRANSACTION_TIMEOUT_MAX: i32 = 1000;
+    const TRANSACTION_TIMEOUT_STEP: i32 = 10;
+    const TRANSACTION_RETRIES_NAME: &'a str = "transaction-max-retries";
+    const TRANSACTION_RETRIES_MIN: i32 = 0;
+    const TRANSACTION_RETRIES_MAX: i32 = 5;
+
+    const SERVICE_NAME: &'a str = "snd-bebob-ctl-service";
+    const FAMILY_NAME: &'a str = "bebob";
+
+    const SCRUB_DISPATCHER_NAME: &'a str = "scrub dispatcher";
+    const RAMP_DISPATCHER_NAME: &'a str = "level ramp dispatcher";
+    const METRICS_NAME: &'a str = "metrics";
+    // The minimum interval to publish the metrics.
+    const METRICS_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
     fn launch_node_event_dispatcher(&mut self) -> Result<(), Error> {
         let name = Self::NODE_DISPATCHER_NAME.to_string();
         let mut dispatcher = Dispatcher::run(name)?;
@@ -258,6 +594,45 @@ impl<'a> BebobRuntime {
         Ok(())
     }
 
+    fn launch_scrub_dispatcher(&mut self, interval: std::time::Duration) -> Result<(), Error> {
+        let name = Self::SCRUB_DISPATCHER_NAME.to_string();
+        let mut dispatcher = Dispatcher::run(name)?;
+
+        let tx = self.tx.clone();
+        dispatcher.attach_interval_handler(interval, move || {
+            let _ = tx.send(Event::Scrub);
+            source::Continue(true)
+        });
+
+        self.dispatchers.push(dispatcher);
+
+        Ok(())
+    }
+
+    // Read the parameters from hardware again for the subset of elements. The model updates the
+    // element when the value differs from the cached one, as it does for the event to add
+    // element. The elements for meters are excluded since they are updated by the timer.
+    fn scrub_elems(&mut self) {
+        let elem_id_list: Vec<ElemId> = self
+            .card_cntr
+            .registered_elem_id_list()
+            .into_iter()
+            .filter(|elem_id| !self.model.measure_elem_list.contains(elem_id))
+            .collect();
+
+        self.scrubber
+            .next_batch(&elem_id_list)
+            .iter()
+            .for_each(|elem_id| {
+                let _ = self.model.dispatch_elem_event(
+                    &mut self.unit,
+                    &mut self.card_cntr,
+                    elem_id,
+                    &ElemEventMask::ADD,
+                );
+            });
+    }
+
     fn start_interval_timer(&mut self) -> Result<(), Error> {
         let mut dispatcher = Dispatcher::run(Self::TIMER_DISPATCHER_NAME.to_string())?;
         let tx = self.tx.clone();
@@ -271,6 +646,219 @@ impl<'a> BebobRuntime {
         Ok(())
     }
 
+    fn dispatch_elem_event(&mut self, elem_id: &ElemId, events: &ElemEventMask) {
+        let now = Instant::now();
+        let res = if *events == ElemEventMask::VALUE && self.start_level_ramp(elem_id, now) {
+            Ok(())
+        } else {
+            self.model
+                .dispatch_elem_event(&mut self.unit, &mut self.card_cntr, elem_id, events)
+        };
+        self.metrics.record_dispatch(now.elapsed());
+
+        // The change applied to the unit is replicated to the secondary unit.
+        if res.is_ok() && events.contains(ElemEventMask::VALUE) {
+            self.mirror_ctl.forward(&self.card_cntr, elem_id);
+        }
+    }
+
+    fn start_mirroring(&mut self) -> Result<(), Error> {
+        if let Some(config) = mirror_config_from_env()? {
+            // The elements for the runtime itself are not mirrored.
+            let excluded = [
+                Self::TIMER_NAME,
+                Self::METRICS_NAME,
+                STREAM_XRUN_COUNT_NAME,
+                STREAM_UNLOCK_COUNT_NAME,
+                BUS_RESET_COUNT_NAME,
+                STREAM_GLITCH_RATE_NAME,
+                FEATURE_REPORT_NAME,
+                MIDI_IN_DETECT_NAME,
+                MIDI_OUT_DETECT_NAME,
+            ];
+            self.mirror_ctl.start(
+                &self.card_cntr,
+                self.card_id,
+                self.model_ids,
+                config,
+                &excluded,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn update_transaction_policy(&mut self) {
+        let mut policy = self.transaction.policy();
+
+        let mut elem_value = ElemValue::new();
+        let elem_id =
+            ElemId::new_by_name(ElemIfaceType::Card, 0, 0, Self::TRANSACTION_TIMEOUT_NAME, 0);
+        if self
+            .card_cntr
+            .card
+            .read_elem_value(&elem_id, &mut elem_value)
+            .is_ok()
+        {
+            policy.min_timeout_ms = elem_value.int()[0] as u32;
+        }
+
+        let elem_id =
+            ElemId::new_by_name(ElemIfaceType::Card, 0, 0, Self::TRANSACTION_RETRIES_NAME, 0);
+        if self
+            .card_cntr
+            .card
+            .read_elem_value(&elem_id, &mut elem_value)
+            .is_ok()
+        {
+            policy.max_retries = elem_value.int()[0] as u32;
+        }
+
+        self.transaction.set_policy(policy);
+    }
+
+    // The duration till the changed metrics is published.
+    fn metrics_timeout(&self, now: Instant) -> Option<std::time::Duration> {
+        let (published, at) = &self.published_metrics;
+        if self.metrics != *published {
+            Some((*at + Self::METRICS_INTERVAL).saturating_duration_since(now))
+        } else {
+            None
+        }
+    }
+
+    fn update_metrics(&mut self) {
+        let stats = self.transaction.stats();
+        self.metrics.transactions = stats.transactions;
+        self.metrics.retries = stats.retries;
+        self.metrics.timeouts = stats.timeouts;
+        self.metrics.failures = stats.failures;
+        self.stream_stats.update_metrics(&mut self.metrics);
+        let _ = self.stream_stats.update(&mut self.card_cntr);
+        let _ = self.feature_report.update(&mut self.card_cntr);
+
+        // The metrics is published at the change, but not so often.
+        let now = Instant::now();
+        match self.metrics_timeout(now) {
+            Some(timeout) if timeout.is_zero() => self.published_metrics = (self.metrics, now),
+            _ => return,
+        }
+
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, Self::METRICS_NAME, 0);
+        let elem_value = ElemValue::new();
+        elem_value.set_bytes(&self.metrics.to_raw());
+        let _ = self.card_cntr.card.write_elem_value(&elem_id, &elem_value);
+
+        if let Some(exporter) = &self.metrics_exporter {
+            exporter.update(&self.metrics);
+        }
+    }
+
+    fn set_level_ramp_duration(&mut self, duration_ms: u64) {
+        let duration = std::time::Duration::from_millis(duration_ms);
+        self.level_ramp_duration = duration;
+        self.level_ramps
+            .iter_mut()
+            .for_each(|(_, ramp)| ramp.set_duration(duration));
+    }
+
+    // Start ramp from the value applied to the unit to the new value of element. Return false
+    // when the element is not for ramp or the new value should be applied at once.
+    fn start_level_ramp(&mut self, elem_id: &ElemId, now: Instant) -> bool {
+        if self.card_cntr.ramp_step(elem_id).is_none() {
+            return false;
+        }
+
+        let old = match self.card_cntr.cached_int_vals(elem_id) {
+            Some(vals) => vals,
+            None => return false,
+        };
+
+        let mut elem_value = ElemValue::new();
+        if self
+            .card_cntr
+            .card
+            .read_elem_value(elem_id, &mut elem_value)
+            .is_err()
+        {
+            return false;
+        }
+
+        let pos = match self.level_ramps.iter().position(|(eid, _)| eid == elem_id) {
+            Some(pos) => pos,
+            None => {
+                let mut ramp = LevelRamp::default();
+                ramp.set_duration(self.level_ramp_duration);
+                self.level_ramps.push((elem_id.clone(), ramp));
+                self.level_ramps.len() - 1
+            }
+        };
+        let ramp = &mut self.level_ramps[pos].1;
+
+        let mut started = false;
+        old.iter()
+            .zip(elem_value.int())
+            .enumerate()
+            .for_each(|(ch, (&from, &to))| started |= ramp.start(ch, from, to, now));
+
+        if !ramp.is_active() {
+            let _ = self.level_ramps.remove(pos);
+        } else if self.ramp_timer.is_none() && self.start_ramp_timer().is_err() {
+            // Apply the new value at once instead.
+            let _ = self.level_ramps.remove(pos);
+            return false;
+        }
+
+        started
+    }
+
+    // Apply the intermediate values of ramp at each tick of timer.
+    fn advance_level_ramps(&mut self) {
+        let now = Instant::now();
+        let mut ramps = std::mem::take(&mut self.level_ramps);
+
+        ramps.iter_mut().for_each(|(elem_id, ramp)| {
+            let step = self.card_cntr.ramp_step(elem_id).unwrap_or(1);
+            let mut vals = match self.card_cntr.cached_int_vals(elem_id) {
+                Some(vals) => vals,
+                None => {
+                    let _ = ramp.finish();
+                    return;
+                }
+            };
+            ramp.advance(now, step)
+                .iter()
+                .for_each(|&(ch, val)| vals[ch] = val);
+
+            let res =
+                self.model
+                    .dispatch_elem_value(&mut self.unit, &mut self.card_cntr, elem_id, &vals);
+            if res.is_err() {
+                // The element is back to the value applied to the unit.
+                let _ = ramp.finish();
+            }
+        });
+
+        ramps.retain(|(_, ramp)| ramp.is_active());
+        self.level_ramps = ramps;
+
+        if self.level_ramps.is_empty() {
+            self.ramp_timer = None;
+        }
+    }
+
+    fn start_ramp_timer(&mut self) -> Result<(), Error> {
+        let mut dispatcher = Dispatcher::run(Self::RAMP_DISPATCHER_NAME.to_string())?;
+        let tx = self.tx.clone();
+        dispatcher.attach_interval_handler(LevelRamp::INTERVAL, move || {
+            let _ = tx.send(Event::Ramp);
+            source::Continue(true)
+        });
+
+        self.ramp_timer = Some(dispatcher);
+
+        Ok(())
+    }
+
     fn stop_interval_timer(&mut self) {
         if let Some(dispatcher) = &self.timer {
             drop(dispatcher);
@@ -272,21 +272,12 @@ where
             SWITCH_NAME => {
                 if O::HAS_SWITCH {
                     if let Some(switch) = &mut self.as_mut().switch {
-                        ElemValueAccessor::<u32>::get_val(new, |val| {
-                            let state = SWITCH_LIST
-                                .iter()
-                                .nth(val as usize)
-                                .ok_or_else(|| {
-                                    let msg = format!("Invalid index for LED switch: {}", val);
-                                    Error::new(FileError::Inval, &msg)
-                                })
-                                .map(|s| *s)?;
-                            let mut op = AudiophileLedSwitch::new(state);
-                            avc.control(&AvcAddr::Unit, &mut op, timeout_ms)?;
-                            *switch = state;
-                            Ok(())
-                        })
-                        .map(|_| true)
+                        let val = EnumElem::new(1, SWITCH_LIST.len()).get_val(new)?;
+                        let state = SWITCH_LIST[val];
+                        let mut op = AudiophileLedSwitch::new(state);
+                        avc.control(&AvcAddr::Unit, &mut op, timeout_ms)?;
+                        *switch = state;
+                        Ok(true)
                     } else {
                         Ok(false)
                     }
@@ -352,10 +343,11 @@ pub trait MaudioNormalMixerCtlOperation<O: MaudioNormalMixerOperation> {
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::MIXER_NAME {
             let dst_idx = elem_id.index() as usize;
-            ElemValueAccessor::<bool>::set_vals(elem_value, Self::SRC_COUNT, |src_idx| {
-                O::read_mixer_src(avc, dst_idx, src_idx, timeout_ms)
-            })
-            .map(|_| true)
+            BoolElem::new(Self::SRC_COUNT)
+                .set_vals(elem_value, |src_idx| {
+                    O::read_mixer_src(avc, dst_idx, src_idx, timeout_ms)
+                })
+                .map(|_| true)
         } else {
             Ok(false)
         }
@@ -371,10 +363,13 @@ pub trait MaudioNormalMixerCtlOperation<O: MaudioNormalMixerOperation> {
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::MIXER_NAME {
             let dst_idx = elem_id.index() as usize;
-            ElemValueAccessor::<bool>::get_vals(new, old, Self::SRC_COUNT, |src_idx, val| {
-                O::write_mixer_src(avc, dst_idx, src_idx, val, timeout_ms)
-            })
-            .map(|_| true)
+            BoolElem::new(Self::SRC_COUNT)
+                .changes(new, old)?
+                .iter()
+                .try_for_each(|&(src_idx, val)| {
+                    O::write_mixer_src(avc, dst_idx, src_idx, val, timeout_ms)
+                })
+                .map(|_| true)
         } else {
             Ok(false)
         }
@@ -151,7 +151,13 @@ impl NotifyModel<(SndUnit, FwNode), bool> for PflModel {
         elem_id_list.extend_from_slice(&self.clk_ctl.0);
     }
 
-    fn parse_notification(&mut self, _: &mut (SndUnit, FwNode), _: &bool) -> Result<(), Error> {
+    fn parse_notification(
+        &mut self,
+        unit: &mut (SndUnit, FwNode),
+        &locked: &bool,
+    ) -> Result<(), Error> {
+        self.clk_ctl
+            .correct_freq(&mut unit.0, &self.avc, locked, FCP_TIMEOUT_MS * 3)?;
         Ok(())
     }
 
@@ -296,17 +302,16 @@ impl InputParamsCtl {
 
     fn read_params(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            ADAT_MUTE_NAME => {
-                ElemValueAccessor::<bool>::set_vals(elem_value, 4, |idx| Ok(self.0.adat_mute[idx]))
-                    .map(|_| true)
-            }
+            ADAT_MUTE_NAME => BoolElem::new(4)
+                .set_vals(elem_value, |idx| Ok(self.0.adat_mute[idx]))
+                .map(|_| true),
             SPDIF_MUTE_NAME => {
-                ElemValueAccessor::<bool>::set_val(elem_value, || Ok(self.0.spdif_mute))
-                    .map(|_| true)
+                BoolElem::new(1).set_val(elem_value, self.0.spdif_mute);
+                Ok(true)
             }
             FORCE_SMUX_NAME => {
-                ElemValueAccessor::<bool>::set_val(elem_value, || Ok(self.0.force_smux))
-                    .map(|_| true)
+                BoolElem::new(1).set_val(elem_value, self.0.force_smux);
+                Ok(true)
             }
             _ => Ok(false),
         }
@@ -329,19 +334,19 @@ impl InputParamsCtl {
 
                 let mut params = self.0.clone();
 
-                ElemValueAccessor::<bool>::get_vals(new, old, 4, |idx, val| {
-                    params.adat_mute[idx] = val;
-                    Ok(())
-                })
-                .and_then(|_| {
-                    PflInputParametersProtocol::write_input_parameters(
-                        req,
-                        &unit.1,
-                        &mut params,
-                        timeout_ms,
-                    )?;
+                BoolElem::new(4)
+                    .changes(new, old)?
+                    .iter()
+                    .for_each(|&(idx, val)| params.adat_mute[idx] = val);
+                PflInputParametersProtocol::write_input_parameters(
+                    req,
+                    &unit.1,
+                    &mut params,
+                    timeout_ms,
+                )
+                .map(|_| {
                     self.0 = params;
-                    Ok(true)
+                    true
                 })
             }
             SPDIF_MUTE_NAME => {
@@ -351,37 +356,31 @@ impl InputParamsCtl {
 
                 let mut params = self.0.clone();
 
-                ElemValueAccessor::<bool>::get_val(new, |val| {
-                    params.spdif_mute = val;
-                    Ok(())
-                })
-                .and_then(|_| {
-                    PflInputParametersProtocol::write_input_parameters(
-                        req,
-                        &unit.1,
-                        &mut params,
-                        timeout_ms,
-                    )?;
+                params.spdif_mute = BoolElem::new(1).get_val(new)?;
+                PflInputParametersProtocol::write_input_parameters(
+                    req,
+                    &unit.1,
+                    &mut params,
+                    timeout_ms,
+                )
+                .map(|_| {
                     self.0 = params;
-                    Ok(true)
+                    true
                 })
             }
             FORCE_SMUX_NAME => {
                 let mut params = self.0.clone();
 
-                ElemValueAccessor::<bool>::get_val(new, |val| {
-                    params.force_smux = val;
-                    Ok(())
-                })
-                .and_then(|_| {
-                    PflInputParametersProtocol::write_input_parameters(
-                        req,
-                        &unit.1,
-                        &mut params,
-                        timeout_ms,
-                    )?;
+                params.force_smux = BoolElem::new(1).get_val(new)?;
+                PflInputParametersProtocol::write_input_parameters(
+                    req,
+                    &unit.1,
+                    &mut params,
+                    timeout_ms,
+                )
+                .map(|_| {
                     self.0 = params;
-                    Ok(true)
+                    true
                 })
             }
             _ => Ok(false),
@@ -3,6 +3,7 @@
 
 use {
     super::*,
+    core::knob_forward::*,
     protocols::{maudio::special::*, *},
 };
 
@@ -20,6 +21,7 @@ pub struct SpecialModel<T: MediaClockFrequencyOperation + Default> {
     output_ctl: OutputCtl,
     aux_ctl: AuxCtl,
     mixer_ctl: MixerCtl,
+    knob_forwarder: KnobForwarder,
 }
 
 const FCP_TIMEOUT_MS: u32 = 200;
@@ -33,6 +35,45 @@ impl<T: MediaClockFrequencyOperation + Default> MediaClkFreqCtlOperation<T> for
 #[derive(Default)]
 struct MeterCtl(MaudioSpecialMeterState, Vec<ElemId>);
 
+const KNOB_MIDI_FORWARD_NAME: &str = "knob-midi-forward";
+
+const KNOB_MIDI_CLIENT_NAME: &str = "M-Audio FireWire 1814/ProjectMix I/O";
+
+// The state of control surface is forwarded by MIDI control change: switch and rotaries. The
+// faders of ProjectMix I/O are not included since they are transferred as MIDI messages in
+// isochronous packet and available in ALSA rawmidi device already.
+const KNOB_FORWARD_PARAMS: [KnobForwardParam; 4] = [
+    KnobForwardParam {
+        ctl_num: 0x50,
+        min: 0,
+        max: 1,
+    },
+    KnobForwardParam {
+        ctl_num: 0x10,
+        min: MaudioSpecialMeterProtocol::ROTARY_MIN as i32,
+        max: MaudioSpecialMeterProtocol::ROTARY_MAX as i32,
+    },
+    KnobForwardParam {
+        ctl_num: 0x11,
+        min: MaudioSpecialMeterProtocol::ROTARY_MIN as i32,
+        max: MaudioSpecialMeterProtocol::ROTARY_MAX as i32,
+    },
+    KnobForwardParam {
+        ctl_num: 0x12,
+        min: MaudioSpecialMeterProtocol::ROTARY_MIN as i32,
+        max: MaudioSpecialMeterProtocol::ROTARY_MAX as i32,
+    },
+];
+
+fn forward_knob_state<T: MediaClockFrequencyOperation + Default>(
+    model: &mut SpecialModel<T>,
+) -> Result<(), Error> {
+    let m = &model.meter_ctl.0;
+    let mut vals = vec![m.switch as i32];
+    vals.extend(m.rotaries.iter().map(|&val| val as i32));
+    model.knob_forwarder.forward(&KNOB_FORWARD_PARAMS, &vals)
+}
+
 impl<T: MediaClockFrequencyOperation + Default> CtlModel<(SndUnit, FwNode)> for SpecialModel<T> {
     fn load(
         &mut self,
@@ -56,6 +97,10 @@ impl<T: MediaClockFrequencyOperation + Default> CtlModel<(SndUnit, FwNode)> for
 
         self.mixer_ctl.load_params(card_cntr, &mut self.cache)?;
 
+        // NOTE: the state of control surface is forwarded as long as metering timer is enabled.
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, KNOB_MIDI_FORWARD_NAME, 0);
+        card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
+
         self.cache.download(&self.req, &unit.1, TIMEOUT_MS)?;
 
         Ok(())
@@ -80,6 +125,9 @@ impl<T: MediaClockFrequencyOperation + Default> CtlModel<(SndUnit, FwNode)> for
             Ok(true)
         } else if self.mixer_ctl.read_params(elem_id, elem_value)? {
             Ok(true)
+        } else if elem_id.name().as_str() == KNOB_MIDI_FORWARD_NAME {
+            elem_value.set_bool(&[self.knob_forwarder.is_enabled()]);
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -137,6 +185,13 @@ impl<T: MediaClockFrequencyOperation + Default> CtlModel<(SndUnit, FwNode)> for
             TIMEOUT_MS,
         )? {
             Ok(true)
+        } else if elem_id.name().as_str() == KNOB_MIDI_FORWARD_NAME {
+            if new.boolean()[0] {
+                self.knob_forwarder.enable(KNOB_MIDI_CLIENT_NAME)?;
+            } else {
+                self.knob_forwarder.disable();
+            }
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -163,6 +218,10 @@ impl<T: MediaClockFrequencyOperation + Default> MeasureModel<(SndUnit, FwNode)>
             self.avc.control(&AvcAddr::Unit, &mut op, FCP_TIMEOUT_MS)?;
         }
 
+        if self.knob_forwarder.is_enabled() {
+            forward_knob_state(self)?;
+        }
+
         // Compute in 32 bit storage.
         let val_min = MaudioSpecialOutputProtocol::VOLUME_MIN as i32;
         let val_max = MaudioSpecialOutputProtocol::VOLUME_MAX as i32;
@@ -240,7 +299,13 @@ impl<T: MediaClockFrequencyOperation + Default> NotifyModel<(SndUnit, FwNode), b
         elem_id_list.extend_from_slice(&self.clk_ctl.0);
     }
 
-    fn parse_notification(&mut self, _: &mut (SndUnit, FwNode), _: &bool) -> Result<(), Error> {
+    fn parse_notification(
+        &mut self,
+        unit: &mut (SndUnit, FwNode),
+        &locked: &bool,
+    ) -> Result<(), Error> {
+        self.clk_ctl
+            .correct_freq(&mut unit.0, &self.avc, locked, FCP_TIMEOUT_MS * 3)?;
         Ok(())
     }
 
@@ -317,6 +382,12 @@ const HEADPHONE_LABELS: [&'static str; 4] =
 const AUX_OUTPUT_LABELS: [&'static str; 2] = ["aux-output-1", "aux-output-2"];
 
 impl MeterCtl {
+    const ROTARY_ELEM: IntElem<i16> = IntElem::new(
+        3,
+        MaudioSpecialMeterProtocol::ROTARY_MIN,
+        MaudioSpecialMeterProtocol::ROTARY_MAX,
+    );
+
     const METER_TLV: DbInterval = DbInterval {
         min: -14400,
         max: 0,
@@ -396,60 +467,30 @@ impl MeterCtl {
         MaudioSpecialMeterProtocol::read_state(req, node, &mut self.0, timeout_ms)
     }
 
+    fn read_meter(elem_value: &mut ElemValue, levels: &[i16]) -> Result<bool, Error> {
+        IntElem::new(
+            levels.len(),
+            MaudioSpecialMeterProtocol::LEVEL_MIN,
+            MaudioSpecialMeterProtocol::LEVEL_MAX,
+        )
+        .set_vals(elem_value, |idx| Ok(levels[idx]))
+        .map(|_| true)
+    }
+
     fn read_state(&self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            ANALOG_INPUT_METER_NAME => {
-                ElemValueAccessor::set_vals(elem_value, ANALOG_INPUT_LABELS.len(), |idx| {
-                    Ok(self.0.analog_inputs[idx] as i32)
-                })
-                .map(|_| true)
-            }
-            SPDIF_INPUT_METER_NAME => {
-                ElemValueAccessor::set_vals(elem_value, SPDIF_INPUT_LABELS.len(), |idx| {
-                    Ok(self.0.spdif_inputs[idx] as i32)
-                })
-                .map(|_| true)
-            }
-            ADAT_INPUT_METER_NAME => {
-                ElemValueAccessor::set_vals(elem_value, ADAT_INPUT_LABELS.len(), |idx| {
-                    Ok(self.0.adat_inputs[idx] as i32)
-                })
-                .map(|_| true)
-            }
-            ANALOG_OUTPUT_METER_NAME => {
-                ElemValueAccessor::set_vals(elem_value, ANALOG_OUTPUT_LABELS.len(), |idx| {
-                    Ok(self.0.analog_outputs[idx] as i32)
-                })
-                .map(|_| true)
-            }
-            SPDIF_OUTPUT_METER_NAME => {
-                ElemValueAccessor::set_vals(elem_value, SPDIF_OUTPUT_LABELS.len(), |idx| {
-                    Ok(self.0.spdif_outputs[idx] as i32)
-                })
-                .map(|_| true)
-            }
-            ADAT_OUTPUT_METER_NAME => {
-                ElemValueAccessor::set_vals(elem_value, ADAT_OUTPUT_LABELS.len(), |idx| {
-                    Ok(self.0.adat_outputs[idx] as i32)
-                })
-                .map(|_| true)
-            }
-            HP_METER_NAME => {
-                ElemValueAccessor::set_vals(elem_value, HEADPHONE_LABELS.len(), |idx| {
-                    Ok(self.0.headphone[idx] as i32)
-                })
-                .map(|_| true)
-            }
-            AUX_OUT_METER_NAME => {
-                ElemValueAccessor::set_vals(elem_value, AUX_OUTPUT_LABELS.len(), |idx| {
-                    Ok(self.0.aux_outputs[idx] as i32)
-                })
-                .map(|_| true)
+            ANALOG_INPUT_METER_NAME => Self::read_meter(elem_value, &self.0.analog_inputs),
+            SPDIF_INPUT_METER_NAME => Self::read_meter(elem_value, &self.0.spdif_inputs),
+            ADAT_INPUT_METER_NAME => Self::read_meter(elem_value, &self.0.adat_inputs),
+            ANALOG_OUTPUT_METER_NAME => Self::read_meter(elem_value, &self.0.analog_outputs),
+            SPDIF_OUTPUT_METER_NAME => Self::read_meter(elem_value, &self.0.spdif_outputs),
+            ADAT_OUTPUT_METER_NAME => Self::read_meter(elem_value, &self.0.adat_outputs),
+            HP_METER_NAME => Self::read_meter(elem_value, &self.0.headphone),
+            AUX_OUT_METER_NAME => Self::read_meter(elem_value, &self.0.aux_outputs),
+            ROTARY_NAME => {
+                Self::ROTARY_ELEM.set_vals(elem_value, |idx| Ok(self.0.rotaries[idx]))?;
+                Ok(true)
             }
-            ROTARY_NAME => ElemValueAccessor::<i32>::set_vals(elem_value, 3, |idx| {
-                Ok(self.0.rotaries[idx] as i32)
-            })
-            .map(|_| true),
             SWITCH_NAME => {
                 elem_value.set_bool(&[self.0.switch]);
                 Ok(true)
@@ -557,8 +598,13 @@ impl InputCtl {
     }
 
     fn read_int(elem_value: &mut ElemValue, gains: &[i16]) -> Result<bool, Error> {
-        ElemValueAccessor::<i32>::set_vals(elem_value, gains.len(), |idx| Ok(gains[idx] as i32))
-            .map(|_| true)
+        IntElem::new(
+            gains.len(),
+            MaudioSpecialInputProtocol::GAIN_MIN,
+            MaudioSpecialInputProtocol::GAIN_MAX,
+        )
+        .set_vals(elem_value, |idx| Ok(gains[idx]))
+        .map(|_| true)
     }
 
     fn read_params(&self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
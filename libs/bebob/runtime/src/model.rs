@@ -11,6 +11,7 @@ use super::{
     focusrite::saffirepro10io_model::*,
     focusrite::saffirepro26io_model::*,
     icon::FirexonModel,
+    mackie::OnyxFwModel,
     maudio::audiophile_model::AudiophileModel,
     maudio::fw410_model::Fw410Model,
     maudio::ozonic_model::OzonicModel,
@@ -23,6 +24,7 @@ use super::{
     roland::*,
     stanton::ScratchampModel,
     terratec::aureon_model::*,
+    terratec::ews_mic_model::*,
     terratec::phase88_model::*,
     yamaha_terratec::{GoPhase24CoaxModel, GoPhase24OptModel},
     *,
@@ -32,6 +34,7 @@ pub struct BebobModel {
     ctl_model: Model,
     pub measure_elem_list: Vec<alsactl::ElemId>,
     pub notified_elem_list: Vec<alsactl::ElemId>,
+    pub debounced_elem_list: Vec<alsactl::ElemId>,
 }
 
 enum Model {
@@ -44,6 +47,7 @@ enum Model {
     FocusriteSaffire(SaffireModel),
     FocusriteSaffireLe(SaffireLeModel),
     IconFirexon(FirexonModel),
+    MackieOnyxFw(OnyxFwModel),
     MaudioOzonic(OzonicModel),
     MaudioSolo(SoloModel),
     MaudioAudiophile(AudiophileModel),
@@ -58,6 +62,7 @@ enum Model {
     RolandFa66(Fa66Model),
     StantonScratchamp(ScratchampModel),
     TerratecAureon(AureonModel),
+    TerratecEwsMic(EwsMicModel),
     TerratecPhase24(GoPhase24CoaxModel),
     TerratecPhaseX24(GoPhase24OptModel),
     TerratecPhase88(Phase88Model),
@@ -83,6 +88,7 @@ impl BebobModel {
                 }
             }
             (0x001a9e, 0x000001) => Model::IconFirexon(Default::default()),
+            (0x000ff2, 0x010065) => Model::MackieOnyxFw(Default::default()),
             (0x000d6c, 0x00000a) => Model::MaudioOzonic(Default::default()),
             (0x000d6c, 0x010062) => Model::MaudioSolo(Default::default()),
             (0x000d6c, 0x010060) => Model::MaudioAudiophile(Default::default()),
@@ -97,13 +103,20 @@ impl BebobModel {
             (0x0040ab, 0x010049) => Model::RolandFa66(Default::default()),
             (0x001260, 0x000001) => Model::StantonScratchamp(Default::default()),
             (0x000aac, 0x000002) => Model::TerratecAureon(Default::default()),
+            (0x000aac, 0x000005) => Model::TerratecEwsMic(Default::default()),
             (0x000aac, 0x000004) => Model::TerratecPhase24(Default::default()),
             (0x000aac, 0x000007) => Model::TerratecPhaseX24(Default::default()),
             (0x000aac, 0x000003) => Model::TerratecPhase88(Default::default()),
             (0x00a0de, 0x10000b) => Model::YamahaGo44(Default::default()),
             (0x00a0de, 0x10000c) => Model::YamahaGo46(Default::default()),
             _ => {
-                return Err(Error::new(FileError::Noent, "Not supported"));
+                // NOTE: the unit in the state of bootloader is not bound to sound card, thus never
+                // reaches here. See README.md of firewire-bebob-protocols crate.
+                let msg = format!(
+                    "Not supported: vendor 0x{:06x}, model 0x{:06x}",
+                    vendor_id, model_id
+                );
+                return Err(Error::new(FileError::Noent, &msg));
             }
         };
 
@@ -111,6 +124,7 @@ impl BebobModel {
             ctl_model,
             measure_elem_list: Vec::new(),
             notified_elem_list: Vec::new(),
+            debounced_elem_list: Vec::new(),
         };
 
         Ok(model)
@@ -131,6 +145,7 @@ impl BebobModel {
             Model::FocusriteSaffire(m) => m.load(unit, card_cntr),
             Model::FocusriteSaffireLe(m) => m.load(unit, card_cntr),
             Model::IconFirexon(m) => m.load(unit, card_cntr),
+            Model::MackieOnyxFw(m) => m.load(unit, card_cntr),
             Model::MaudioOzonic(m) => m.load(unit, card_cntr),
             Model::MaudioSolo(m) => m.load(unit, card_cntr),
             Model::MaudioAudiophile(m) => m.load(unit, card_cntr),
@@ -145,6 +160,7 @@ impl BebobModel {
             Model::RolandFa66(m) => m.load(unit, card_cntr),
             Model::StantonScratchamp(m) => m.load(unit, card_cntr),
             Model::TerratecAureon(m) => m.load(unit, card_cntr),
+            Model::TerratecEwsMic(m) => m.load(unit, card_cntr),
             Model::TerratecPhase24(m) => m.load(unit, card_cntr),
             Model::TerratecPhaseX24(m) => m.load(unit, card_cntr),
             Model::TerratecPhase88(m) => m.load(unit, card_cntr),
@@ -187,6 +203,7 @@ impl BebobModel {
             Model::FocusriteSaffire(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
             Model::FocusriteSaffireLe(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
             Model::IconFirexon(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
+            Model::MackieOnyxFw(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
             Model::MaudioOzonic(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
             Model::MaudioSolo(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
             Model::MaudioAudiophile(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
@@ -198,6 +215,7 @@ impl BebobModel {
             Model::PresonusInspire1394(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
             Model::StantonScratchamp(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
             Model::TerratecAureon(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
+            Model::TerratecEwsMic(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
             Model::TerratecPhase88(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
             Model::TerratecPhase24(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
             Model::TerratecPhaseX24(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
@@ -206,9 +224,24 @@ impl BebobModel {
             _ => (),
         }
 
+        if let Model::StantonScratchamp(m) = &self.ctl_model {
+            m.get_debounced_elem_list(&mut self.debounced_elem_list);
+        }
+
         Ok(())
     }
 
+    pub fn cache(
+        &mut self,
+        unit: &mut (SndUnit, FwNode),
+        card_cntr: &mut CardCntr,
+    ) -> Result<(), Error> {
+        match &mut self.ctl_model {
+            Model::ApogeeEnsemble(m) => card_cntr.dispatch_cache(unit, m),
+            _ => Ok(()),
+        }
+    }
+
     pub fn dispatch_elem_event(
         &mut self,
         unit: &mut (SndUnit, FwNode),
@@ -234,6 +267,7 @@ impl BebobModel {
                 card_cntr.dispatch_elem_event(unit, &elem_id, &events, m)
             }
             Model::IconFirexon(m) => card_cntr.dispatch_elem_event(unit, &elem_id, &events, m),
+            Model::MackieOnyxFw(m) => card_cntr.dispatch_elem_event(unit, &elem_id, &events, m),
             Model::MaudioOzonic(m) => card_cntr.dispatch_elem_event(unit, &elem_id, &events, m),
             Model::MaudioSolo(m) => card_cntr.dispatch_elem_event(unit, &elem_id, &events, m),
             Model::MaudioAudiophile(m) => card_cntr.dispatch_elem_event(unit, &elem_id, &events, m),
@@ -252,6 +286,7 @@ impl BebobModel {
                 card_cntr.dispatch_elem_event(unit, &elem_id, &events, m)
             }
             Model::TerratecAureon(m) => card_cntr.dispatch_elem_event(unit, &elem_id, &events, m),
+            Model::TerratecEwsMic(m) => card_cntr.dispatch_elem_event(unit, &elem_id, &events, m),
             Model::TerratecPhase24(m) => card_cntr.dispatch_elem_event(unit, &elem_id, &events, m),
             Model::TerratecPhaseX24(m) => card_cntr.dispatch_elem_event(unit, &elem_id, &events, m),
             Model::TerratecPhase88(m) => card_cntr.dispatch_elem_event(unit, &elem_id, &events, m),
@@ -260,6 +295,52 @@ impl BebobModel {
         }
     }
 
+    /// Dispatch the intermediate values of ramp for the element.
+    pub fn dispatch_elem_value(
+        &mut self,
+        unit: &mut (SndUnit, FwNode),
+        card_cntr: &mut CardCntr,
+        elem_id: &alsactl::ElemId,
+        vals: &[i32],
+    ) -> Result<(), Error> {
+        match &mut self.ctl_model {
+            Model::ApogeeEnsemble(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::BehringerFca610(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::DigidesignMbox2pro(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::EsiQuatafire610(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::FocusriteSaffirePro26io(m) => {
+                card_cntr.dispatch_elem_value(unit, elem_id, vals, m)
+            }
+            Model::FocusriteSaffirePro10io(m) => {
+                card_cntr.dispatch_elem_value(unit, elem_id, vals, m)
+            }
+            Model::FocusriteSaffire(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::FocusriteSaffireLe(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::IconFirexon(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::MackieOnyxFw(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::MaudioOzonic(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::MaudioSolo(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::MaudioAudiophile(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::MaudioFw410(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::MaudioPfl(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::MaudioFw1814(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::MaudioProjectMix(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::PresonusFp10(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::PresonusFirebox(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::PresonusInspire1394(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::RolandFa101(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::RolandFa66(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::StantonScratchamp(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::TerratecAureon(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::TerratecEwsMic(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::TerratecPhase24(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::TerratecPhaseX24(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::TerratecPhase88(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::YamahaGo44(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+            Model::YamahaGo46(m) => card_cntr.dispatch_elem_value(unit, elem_id, vals, m),
+        }
+    }
+
     pub fn measure_elems(
         &mut self,
         unit: &mut (SndUnit, FwNode),
@@ -325,6 +406,9 @@ impl BebobModel {
             Model::IconFirexon(m) => {
                 card_cntr.dispatch_notification(unit, &notice, &self.notified_elem_list, m)
             }
+            Model::MackieOnyxFw(m) => {
+                card_cntr.dispatch_notification(unit, &notice, &self.notified_elem_list, m)
+            }
             Model::MaudioOzonic(m) => {
                 card_cntr.dispatch_notification(unit, &notice, &self.notified_elem_list, m)
             }
@@ -358,6 +442,9 @@ impl BebobModel {
             Model::TerratecAureon(m) => {
                 card_cntr.dispatch_notification(unit, &notice, &self.notified_elem_list, m)
             }
+            Model::TerratecEwsMic(m) => {
+                card_cntr.dispatch_notification(unit, &notice, &self.notified_elem_list, m)
+            }
             Model::TerratecPhase88(m) => {
                 card_cntr.dispatch_notification(unit, &notice, &self.notified_elem_list, m)
             }
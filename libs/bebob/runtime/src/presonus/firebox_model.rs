@@ -439,7 +439,13 @@ impl NotifyModel<(SndUnit, FwNode), bool> for FireboxModel {
         elem_id_list.extend_from_slice(&self.clk_ctl.0);
     }
 
-    fn parse_notification(&mut self, _: &mut (SndUnit, FwNode), _: &bool) -> Result<(), Error> {
+    fn parse_notification(
+        &mut self,
+        unit: &mut (SndUnit, FwNode),
+        &locked: &bool,
+    ) -> Result<(), Error> {
+        self.clk_ctl
+            .correct_freq(&mut unit.0, &self.avc, locked, FCP_TIMEOUT_MS * 3)?;
         Ok(())
     }
 
@@ -474,10 +480,11 @@ trait SwitchCtlOperation<T: AvcSelectorOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::SWITCH_NAME {
-            ElemValueAccessor::<bool>::set_vals(elem_value, Self::CH_COUNT, |idx| {
-                T::read_selector(avc, idx, timeout_ms).map(|val| val > 0)
-            })
-            .map(|_| true)
+            BoolElem::new(Self::CH_COUNT)
+                .set_vals(elem_value, |idx| {
+                    T::read_selector(avc, idx, timeout_ms).map(|val| val > 0)
+                })
+                .map(|_| true)
         } else {
             Ok(false)
         }
@@ -492,10 +499,11 @@ trait SwitchCtlOperation<T: AvcSelectorOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::SWITCH_NAME {
-            ElemValueAccessor::<bool>::get_vals(new, old, Self::CH_COUNT, |idx, val| {
-                T::write_selector(avc, idx, val as usize, timeout_ms)
-            })
-            .map(|_| true)
+            BoolElem::new(Self::CH_COUNT)
+                .changes(new, old)?
+                .iter()
+                .try_for_each(|&(idx, val)| T::write_selector(avc, idx, val as usize, timeout_ms))
+                .map(|_| true)
         } else {
             Ok(false)
         }
@@ -85,6 +85,11 @@ impl SwitchCtlOperation<Inspire1394PhonoProtocol> for PhysInputCtl {
     const SWITCH_LABELS: &'static [&'static str] = &["analog-input-3/4"];
 }
 
+impl SwitchCtlOperation<Inspire1394StereoLinkProtocol> for PhysInputCtl {
+    const SWITCH_NAME: &'static str = "analog-input-stereo-link";
+    const SWITCH_LABELS: &'static [&'static str] = &["analog-input-1/2", "analog-input-3/4"];
+}
+
 #[derive(Default)]
 struct PhysOutputCtl;
 
@@ -183,6 +188,10 @@ impl CtlModel<(SndUnit, FwNode)> for Inspire1394Model {
             card_cntr,
         )?;
         SwitchCtlOperation::<Inspire1394PhonoProtocol>::load_switch(&self.phys_in_ctl, card_cntr)?;
+        SwitchCtlOperation::<Inspire1394StereoLinkProtocol>::load_switch(
+            &self.phys_in_ctl,
+            card_cntr,
+        )?;
         self.phys_out_ctl.load_level(card_cntr)?;
         self.phys_out_ctl.load_mute(card_cntr)?;
         self.phys_out_ctl.load_selector(card_cntr)?;
@@ -255,6 +264,14 @@ impl CtlModel<(SndUnit, FwNode)> for Inspire1394Model {
             FCP_TIMEOUT_MS,
         )? {
             Ok(true)
+        } else if SwitchCtlOperation::<Inspire1394StereoLinkProtocol>::read_switch(
+            &self.phys_in_ctl,
+            &self.avc,
+            elem_id,
+            elem_value,
+            FCP_TIMEOUT_MS,
+        )? {
+            Ok(true)
         } else if self
             .phys_out_ctl
             .read_level(&self.avc, elem_id, elem_value, FCP_TIMEOUT_MS)?
@@ -391,6 +408,15 @@ impl CtlModel<(SndUnit, FwNode)> for Inspire1394Model {
             FCP_TIMEOUT_MS,
         )? {
             Ok(true)
+        } else if SwitchCtlOperation::<Inspire1394StereoLinkProtocol>::write_switch(
+            &self.phys_in_ctl,
+            &self.avc,
+            elem_id,
+            old,
+            new,
+            FCP_TIMEOUT_MS,
+        )? {
+            Ok(true)
         } else if self
             .phys_out_ctl
             .write_level(&self.avc, elem_id, old, new, FCP_TIMEOUT_MS)?
@@ -467,7 +493,13 @@ impl NotifyModel<(SndUnit, FwNode), bool> for Inspire1394Model {
         elem_id_list.extend_from_slice(&self.clk_ctl.0);
     }
 
-    fn parse_notification(&mut self, _: &mut (SndUnit, FwNode), _: &bool) -> Result<(), Error> {
+    fn parse_notification(
+        &mut self,
+        unit: &mut (SndUnit, FwNode),
+        &locked: &bool,
+    ) -> Result<(), Error> {
+        self.clk_ctl
+            .correct_freq(&mut unit.0, &self.avc, locked, FCP_TIMEOUT_MS * 3)?;
         Ok(())
     }
 
@@ -616,10 +648,9 @@ trait SwitchCtlOperation<T: PresonusSwitchOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::SWITCH_NAME {
-            ElemValueAccessor::<bool>::set_vals(elem_value, T::CH_COUNT, |idx| {
-                T::read_switch(avc, idx, timeout_ms)
-            })
-            .map(|_| true)
+            BoolElem::new(T::CH_COUNT)
+                .set_vals(elem_value, |idx| T::read_switch(avc, idx, timeout_ms))
+                .map(|_| true)
         } else {
             Ok(false)
         }
@@ -634,10 +665,11 @@ trait SwitchCtlOperation<T: PresonusSwitchOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         if elem_id.name().as_str() == Self::SWITCH_NAME {
-            ElemValueAccessor::<bool>::get_vals(new, old, T::CH_COUNT, |idx, val| {
-                T::write_switch(avc, idx, val, timeout_ms)
-            })
-            .map(|_| true)
+            BoolElem::new(T::CH_COUNT)
+                .changes(new, old)?
+                .iter()
+                .try_for_each(|&(idx, val)| T::write_switch(avc, idx, val, timeout_ms))
+                .map(|_| true)
         } else {
             Ok(false)
         }
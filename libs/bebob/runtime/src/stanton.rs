@@ -8,6 +8,8 @@ use {
 
 const FCP_TIMEOUT_MS: u32 = 100;
 
+const HP_VOL_NAME: &str = "headphone-volume";
+
 #[derive(Default)]
 pub struct ScratchampModel {
     avc: BebobAvc,
@@ -42,10 +44,20 @@ impl AvcLevelCtlOperation<ScratchampOutputProtocol> for ScratchampOutputCtl {
 struct ScratchampHeadphoneCtl;
 
 impl AvcLevelCtlOperation<ScratchampHeadphoneProtocol> for ScratchampHeadphoneCtl {
-    const LEVEL_NAME: &'static str = "headphone-volume";
+    const LEVEL_NAME: &'static str = HP_VOL_NAME;
     const PORT_LABELS: &'static [&'static str] = &["headphone-1", "headphone-2"];
 }
 
+impl ScratchampModel {
+    /// The volumes are typically operated by encoders in DJ surface, thus the events of elements
+    /// come in bursts.
+    pub fn get_debounced_elem_list(&self, elem_id_list: &mut Vec<ElemId>) {
+        [OUT_VOL_NAME, HP_VOL_NAME].iter().for_each(|name| {
+            elem_id_list.push(ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, name, 0));
+        });
+    }
+}
+
 impl CtlModel<(SndUnit, FwNode)> for ScratchampModel {
     fn load(
         &mut self,
@@ -145,7 +157,13 @@ impl NotifyModel<(SndUnit, FwNode), bool> for ScratchampModel {
         elem_id_list.extend_from_slice(&self.clk_ctl.0);
     }
 
-    fn parse_notification(&mut self, _: &mut (SndUnit, FwNode), _: &bool) -> Result<(), Error> {
+    fn parse_notification(
+        &mut self,
+        unit: &mut (SndUnit, FwNode),
+        &locked: &bool,
+    ) -> Result<(), Error> {
+        self.clk_ctl
+            .correct_freq(&mut unit.0, &self.avc, locked, FCP_TIMEOUT_MS * 3)?;
         Ok(())
     }
 
@@ -2,6 +2,7 @@
 // Copyright (c) 2021 Takashi Sakamoto
 
 pub mod aureon_model;
+pub mod ews_mic_model;
 pub mod phase88_model;
 
 use super::*;
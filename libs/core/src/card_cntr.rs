@@ -7,6 +7,53 @@ use {super::*, alsactl::{prelude::*, *}, glib::FileError};
 pub struct CardCntr {
     pub card: Card,
     entries: Vec<ElemValue>,
+    // The pair of original element and its alias.
+    aliases: Vec<(ElemId, ElemId)>,
+    // The element to tag the ownership of elements by the process.
+    owner: Option<ElemId>,
+    // Whether to reclaim the elements left by the process already exited.
+    reclaim: bool,
+    // The pair of label for item of enumerated element and its translation.
+    translations: Vec<(String, String)>,
+    // The pair of element and its TLV data given at registration.
+    tlvs: Vec<(ElemId, Vec<u32>)>,
+    // The pair of element of which value is changed by ramp and the step of value.
+    ramp_targets: Vec<(ElemId, i32)>,
+}
+
+/// The name of read-only element for the identifier and the start time of process which owns the
+/// elements added by service.
+pub const OWNER_PID_NAME: &str = "owner-pid";
+
+// The start time of process after system boot in clock ticks, to distinguish the process from
+// the other process which reuses the identifier.
+fn process_start_time(pid: u32) -> Option<u64> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The name of command in the second field can include white spaces and parentheses, thus
+    // the fields are parsed after the last parenthesis. The start time is the 22nd field.
+    let pos = stat.rfind(')')?;
+    stat[(pos + 1)..]
+        .split_whitespace()
+        .nth(19)
+        .and_then(|field| field.parse().ok())
+}
+
+// The tag consists of the identifier and the start time of process.
+fn owner_tag(pid: u32) -> Option<String> {
+    process_start_time(pid).map(|start_time| format!("{}:{}", pid, start_time))
+}
+
+fn is_owner_alive(tag: &str) -> bool {
+    let mut fields = tag.splitn(2, ':');
+    match (fields.next(), fields.next()) {
+        (Some(pid), Some(_)) => pid
+            .parse()
+            .ok()
+            .and_then(owner_tag)
+            .map(|t| t == tag)
+            .unwrap_or(false),
+        _ => false,
+    }
 }
 
 pub trait CtlModel<O: Sized> {
@@ -48,6 +95,12 @@ pub trait NotifyModel<O: Sized, N> {
     ) -> Result<bool, Error>;
 }
 
+/// The model to cache the state of hardware after adding elements with initial values. The
+/// elements are updated when the cached state differs from the initial values.
+pub trait CacheModel<O: Sized> {
+    fn cache(&mut self, unit: &mut O) -> Result<(), Error>;
+}
+
 impl Drop for CardCntr {
     fn drop(&mut self) {
         self.entries
@@ -56,6 +109,10 @@ impl Drop for CardCntr {
             .for_each(|elem_id| {
                 let _ = self.card.remove_elems(&elem_id);
             });
+
+        if let Some(elem_id) = &self.owner {
+            let _ = self.card.remove_elems(elem_id);
+        }
     }
 }
 
@@ -90,7 +147,7 @@ impl CardCntr {
     {
         let entries = labels
             .iter()
-            .map(|entry| entry.as_ref())
+            .map(|entry| self.translate_label(entry.as_ref()))
             .collect::<Vec<&str>>();
 
         let elem_info = ElemInfoEnumerated::new();
@@ -166,6 +223,285 @@ impl CardCntr {
         Ok(elem_id_list.remove(0))
     }
 
+    /// Replace the labels for items of enumerated element. The labels can not be changed after
+    /// the element is added, thus the element is removed and added again, then user space
+    /// applications receive the events for the removal and the addition. The identifiers of
+    /// elements added again are returned.
+    pub fn update_enum_labels<O>(
+        &mut self,
+        elem_id: &ElemId,
+        labels: &[O],
+    ) -> Result<Vec<ElemId>, Error>
+    where
+        O: AsRef<str>,
+    {
+        let elem_info = self.card.elem_info(elem_id)?;
+        let value_count = match &elem_info {
+            ElemInfo::Enumerated(info) => Ok(info.value_count() as usize),
+            _ => {
+                let msg = format!("{} is not enumerated element", elem_id.name());
+                Err(Error::new(FileError::Inval, &msg))
+            }
+        }?;
+        let unlock = !elem_info.as_ref().access().contains(ElemAccessFlag::LOCK);
+        let tlv = self.elem_tlv(elem_id).map(|tlv| tlv.to_vec());
+
+        let elem_id_list: Vec<ElemId> = self
+            .registered_elem_id_list()
+            .into_iter()
+            .filter(|eid| {
+                eid.name() == elem_id.name()
+                    && eid.device_id() == elem_id.device_id()
+                    && eid.subdevice_id() == elem_id.subdevice_id()
+                    && eid.iface() == elem_id.iface()
+            })
+            .collect();
+        if elem_id_list.len() == 0 {
+            let msg = format!("{} is not added by runtime", elem_id.name());
+            return Err(Error::new(FileError::Noent, &msg));
+        }
+
+        // The removal of one element removes the others in set.
+        self.card.remove_elems(elem_id)?;
+        self.entries.retain(|v| match v.elem_id() {
+            Some(e) => !elem_id_list.iter().any(|eid| eid.eq(&e)),
+            None => true,
+        });
+        self.tlvs
+            .retain(|(e, _)| !elem_id_list.iter().any(|eid| eid.eq(e)));
+
+        let elem_id = ElemId::new_by_name(
+            elem_id.iface(),
+            elem_id.device_id(),
+            elem_id.subdevice_id(),
+            &elem_id.name(),
+            elem_id_list[0].index(),
+        );
+        self.add_enum_elems(
+            &elem_id,
+            elem_id_list.len(),
+            value_count,
+            labels,
+            tlv.as_ref().map(|tlv| &tlv[..]),
+            unlock,
+        )
+    }
+
+    /// Configure translations of labels for items of enumerated element. The translations are
+    /// applied to the enumerated elements added after the call.
+    pub fn set_label_translations(&mut self, translations: &[(String, String)]) {
+        self.translations = translations.to_vec();
+    }
+
+    fn translate_label<'a>(&'a self, label: &'a str) -> &'a str {
+        self.translations
+            .iter()
+            .find(|(orig, _)| orig.as_str() == label)
+            .map(|(_, translation)| translation.as_str())
+            .unwrap_or(label)
+    }
+
+    /// The list of identifiers for elements registered by the runtime.
+    pub fn registered_elem_id_list(&self) -> Vec<ElemId> {
+        self.entries.iter().filter_map(|v| v.elem_id()).collect()
+    }
+
+    /// The TLV data given at registration of the element.
+    pub fn elem_tlv(&self, elem_id: &ElemId) -> Option<&[u32]> {
+        self.tlvs
+            .iter()
+            .find(|(eid, _)| eid == elem_id)
+            .map(|(_, tlv)| &tlv[..])
+    }
+
+    /// Register the elements of which value can be changed by ramp, with the step of value.
+    pub fn add_ramp_targets(&mut self, elem_id_list: &[ElemId], step: i32) {
+        elem_id_list.iter().for_each(|elem_id| {
+            self.ramp_targets.retain(|(eid, _)| eid != elem_id);
+            self.ramp_targets.push((elem_id.clone(), step));
+        });
+    }
+
+    /// The step of value for the element registered as target of ramp.
+    pub fn ramp_step(&self, elem_id: &ElemId) -> Option<i32> {
+        self.ramp_targets
+            .iter()
+            .find(|(eid, _)| eid == elem_id)
+            .map(|(_, step)| *step)
+    }
+
+    /// The integer values of element cached at the last dispatch to the model.
+    pub fn cached_int_vals(&self, elem_id: &ElemId) -> Option<Vec<i32>> {
+        self.entries
+            .iter()
+            .find(|v| v.elem_id().as_ref() == Some(elem_id))
+            .map(|v| v.int().to_vec())
+    }
+
+    /// Add the element with the other name as alias of the given element. The alias has the same
+    /// information and value as the original element, and the change of value in either of them
+    /// is propagated to the other.
+    pub fn add_alias_elem(&mut self, elem_id: &ElemId, name: &str) -> Result<ElemId, Error> {
+        let elem_info = self.card.elem_info(elem_id)?;
+        let orig_id = elem_info.as_ref().elem_id().ok_or_else(|| {
+            let label = "Unexpected result to detect element id";
+            Error::new(FileError::Io, label)
+        })?;
+
+        let alias_id = ElemId::new_by_name(
+            orig_id.iface(),
+            orig_id.device_id(),
+            orig_id.subdevice_id(),
+            name,
+            orig_id.index(),
+        );
+        let mut elem_id_list = self.register_elems(&alias_id, 1, &elem_info, None, true)?;
+        let alias_id = elem_id_list.remove(0);
+
+        let mut elem_value = ElemValue::new();
+        self.card.read_elem_value(&orig_id, &mut elem_value)?;
+        self.card.write_elem_value(&alias_id, &elem_value)?;
+
+        self.aliases.push((orig_id, alias_id.clone()));
+
+        Ok(alias_id)
+    }
+
+    /// Add aliases according to the pairs of original name and alias name. Any element which has
+    /// the original name is aliased. The original names unavailable in the card are skipped, and
+    /// returned.
+    pub fn add_aliases(&mut self, aliases: &[(String, String)]) -> Result<Vec<String>, Error> {
+        let mut unavailable = Vec::new();
+
+        aliases
+            .iter()
+            .try_for_each(|(orig, alias)| {
+                let elem_id_list: Vec<ElemId> = self
+                    .entries
+                    .iter()
+                    .filter_map(|v| v.elem_id())
+                    .filter(|elem_id| elem_id.name() == orig.as_str())
+                    .collect();
+
+                if elem_id_list.len() == 0 {
+                    unavailable.push(orig.to_string());
+                    Ok(())
+                } else {
+                    elem_id_list
+                        .iter()
+                        .try_for_each(|elem_id| self.add_alias_elem(elem_id, alias).map(|_| ()))
+                }
+            })
+            .map(|_| unavailable)
+    }
+
+    fn update_aliases(
+        card: &Card,
+        aliases: &[(ElemId, ElemId)],
+        elem_id: &ElemId,
+        elem_value: &ElemValue,
+    ) -> Result<(), Error> {
+        aliases
+            .iter()
+            .filter(|(orig, _)| orig.eq(elem_id))
+            .try_for_each(|(_, alias)| card.write_elem_value(alias, elem_value))
+    }
+
+    /// Tag the sound card with the identifier and the start time of current process as the owner
+    /// of elements added by service. The tag is the label of read-only element, and the element
+    /// is locked till the process exits so that the other process can not remove it. When the
+    /// tag is left by the process already exited, the elements left by the process are regarded
+    /// as stale, and removed when they can not be reused.
+    fn claim_ownership(&mut self) -> Result<(), Error> {
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, OWNER_PID_NAME, 0);
+        let tag = owner_tag(std::process::id()).ok_or_else(|| {
+            let label = "Fail to detect the start time of current process";
+            Error::new(FileError::Io, label)
+        })?;
+
+        let elem_id_list = self.card.elem_id_list()?;
+        if elem_id_list.iter().any(|eid| eid.eq(&elem_id)) {
+            let elem_info = self.card.elem_info(&elem_id)?;
+            let prev = match &elem_info {
+                ElemInfo::Enumerated(info) => info.labels().first().map(|label| label.to_string()),
+                _ => None,
+            };
+            if let Some(prev) = prev.filter(|prev| is_owner_alive(prev)) {
+                let label = format!("The elements are owned by the other process: {}", prev);
+                return Err(Error::new(FileError::Exist, &label));
+            }
+
+            // The previous process crashed without removing its elements. The tag is replaced.
+            self.card.remove_elems(&elem_id)?;
+            self.reclaim = true;
+        }
+
+        let elem_info = ElemInfoEnumerated::new();
+        elem_info.set_value_count(1);
+        elem_info.set_labels(&[tag.as_str()]);
+        elem_info.set_access(ElemAccessFlag::READ);
+        let _ = self.card.add_elems(&elem_id, 1, &elem_info)?;
+
+        // The element is locked as long as the process runs, so that the other process can not
+        // remove it. The lock is released by system when the process exits.
+        self.card.lock_elem(&elem_id, true)?;
+
+        self.owner = Some(elem_id);
+
+        Ok(())
+    }
+
+    /// Remove the stale elements with the same name which can not be reused due to the count or
+    /// the type. The elements locked by the other process, or added by the other than user space
+    /// are not removed.
+    fn reclaim_stale_elems<O: AsRef<ElemInfoCommon>>(
+        &mut self,
+        elem_id: &ElemId,
+        elem_count: usize,
+        elem_info: &O,
+    ) -> Result<(), Error> {
+        let elem_id_list: Vec<ElemId> = self
+            .card
+            .elem_id_list()?
+            .into_iter()
+            .filter(|eid| {
+                eid.name() == elem_id.name()
+                    && eid.device_id() == elem_id.device_id()
+                    && eid.subdevice_id() == elem_id.subdevice_id()
+                    && eid.iface() == elem_id.iface()
+            })
+            .collect();
+
+        if elem_id_list.len() == 0 {
+            return Ok(());
+        }
+
+        let mut reusable = elem_id_list.len() == elem_count;
+        for eid in &elem_id_list {
+            let info = self.card.elem_info(eid)?;
+            let access = info.as_ref().access();
+            if !access.contains(ElemAccessFlag::USER)
+                || access.contains(ElemAccessFlag::OWNER)
+                || access.contains(ElemAccessFlag::LOCK)
+            {
+                return Ok(());
+            }
+            if info.as_ref().elem_type() != elem_info.as_ref().elem_type() {
+                reusable = false;
+            }
+        }
+
+        if !reusable {
+            // The stale elements left by exited process are removed. Any error is ignored since
+            // the removal of one element removes the others in set.
+            elem_id_list.iter().for_each(|eid| {
+                let _ = self.card.remove_elems(eid);
+            });
+        }
+
+        Ok(())
+    }
+
     fn register_elems<O: AsRef<ElemInfoCommon>>(
         &mut self,
         elem_id: &ElemId,
@@ -174,6 +510,14 @@ impl CardCntr {
         tlv: Option<&[u32]>,
         unlock: bool,
     ) -> Result<Vec<ElemId>, Error> {
+        if self.owner.is_none() {
+            self.claim_ownership()?;
+        }
+
+        if self.reclaim {
+            self.reclaim_stale_elems(elem_id, elem_count, elem_info)?;
+        }
+
         // If already registered, reuse them if possible.
         let elem_id_list = self.card.elem_id_list()?;
         let elem_id_list = match elem_id_list.iter().position(|eid| eid.eq(elem_id)) {
@@ -259,6 +603,10 @@ impl CardCntr {
             elem_id_list
                 .iter()
                 .try_for_each(|elem_id| self.card.write_elem_tlv(&elem_id, &cntr))?;
+            elem_id_list.iter().for_each(|elem_id| {
+                self.tlvs.retain(|(eid, _)| eid != elem_id);
+                self.tlvs.push((elem_id.clone(), cntr.to_vec()));
+            });
         }
 
         if unlock {
@@ -287,6 +635,24 @@ impl CardCntr {
                 Some(e) => e != *elem_id,
                 None => true,
             });
+            self.aliases
+                .retain(|(orig, alias)| orig != elem_id && alias != elem_id);
+            return Ok(());
+        }
+
+        let orig = self
+            .aliases
+            .iter()
+            .find(|(_, alias)| alias.eq(elem_id))
+            .map(|(orig, _)| orig.clone());
+        if let Some(orig) = orig {
+            if events.contains(ElemEventMask::VALUE) {
+                // Forward the value to the original element, then the event for the original
+                // element is dispatched to the model.
+                let mut val = ElemValue::new();
+                self.card.read_elem_value(elem_id, &mut val)?;
+                self.card.write_elem_value(&orig, &val)?;
+            }
             return Ok(());
         }
 
@@ -316,6 +682,8 @@ impl CardCntr {
                         continue;
                     }
 
+                    let _ = Self::update_aliases(&self.card, &self.aliases, &e, &val);
+
                     *v = val;
                 }
             }
@@ -345,6 +713,7 @@ impl CardCntr {
                 match ctl_model.write(unit, &e, v, &val) {
                     Ok(res) => {
                         if res {
+                            Self::update_aliases(&self.card, &self.aliases, &e, &val)?;
                             *v = val;
                             return Ok(());
                         }
@@ -352,6 +721,7 @@ impl CardCntr {
                     Err(err) => {
                         // Back to old values.
                         self.card.write_elem_value(&e, v)?;
+                        Self::update_aliases(&self.card, &self.aliases, &e, v)?;
                         return Err(err);
                     }
                 }
@@ -361,6 +731,67 @@ impl CardCntr {
         Ok(())
     }
 
+    /// Write the value cached at the last dispatch to the model back to the element, so that the
+    /// change of value by applications is discarded.
+    pub fn restore_elem_value(&mut self, elem_id: &ElemId) -> Result<(), Error> {
+        match self
+            .entries
+            .iter()
+            .find(|v| v.elem_id().as_ref() == Some(elem_id))
+        {
+            Some(v) => {
+                self.card.write_elem_value(elem_id, v)?;
+                Self::update_aliases(&self.card, &self.aliases, elem_id, v)
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Dispatch the integer values to the model as if the element had them, without any change of
+    /// the value in the element. It is used to apply intermediate values of ramp while the element
+    /// has the target value. At failure, the element is back to the cached value.
+    pub fn dispatch_elem_value<O, T>(
+        &mut self,
+        unit: &mut O,
+        elem_id: &ElemId,
+        vals: &[i32],
+        ctl_model: &mut T,
+    ) -> Result<(), Error>
+    where
+        O: Sized,
+        T: CtlModel<O>,
+    {
+        let v = match self
+            .entries
+            .iter_mut()
+            .find(|v| v.elem_id().as_ref() == Some(elem_id))
+        {
+            Some(v) => v,
+            None => return Ok(()),
+        };
+
+        let val = ElemValue::new();
+        val.set_int(vals);
+
+        if v.equal(&val) {
+            return Ok(());
+        }
+
+        match ctl_model.write(unit, elem_id, v, &val) {
+            Ok(res) => {
+                if res {
+                    *v = val;
+                }
+                Ok(())
+            }
+            Err(err) => {
+                self.card.write_elem_value(elem_id, v)?;
+                Self::update_aliases(&self.card, &self.aliases, elem_id, v)?;
+                Err(err)
+            }
+        }
+    }
+
     pub fn measure_elems<O, T>(
         &mut self,
         unit: &mut O,
@@ -373,6 +804,7 @@ impl CardCntr {
     {
         let card = &self.card;
         let entries = &mut self.entries;
+        let aliases = &self.aliases;
 
         ctl_model.measure_states(unit)?;
 
@@ -386,6 +818,7 @@ impl CardCntr {
                 .try_for_each(|elem_value| {
                     if ctl_model.measure_elem(unit, elem_id, elem_value)? {
                         card.write_elem_value(elem_id, elem_value)?;
+                        Self::update_aliases(card, aliases, elem_id, elem_value)?;
                     }
 
                     Ok(())
@@ -393,6 +826,37 @@ impl CardCntr {
         })
     }
 
+    pub fn dispatch_cache<O, T>(&mut self, unit: &mut O, ctl_model: &mut T) -> Result<(), Error>
+    where
+        O: Sized,
+        T: CtlModel<O> + CacheModel<O>,
+    {
+        ctl_model.cache(unit)?;
+
+        // Update the elements of which value differs from the initial value.
+        for v in &mut self.entries {
+            let e = match v.elem_id() {
+                Some(e) => e,
+                None => continue,
+            };
+
+            let mut val = ElemValue::new();
+            if !ctl_model.read(unit, &e, &mut val)? {
+                continue;
+            }
+
+            if v.equal(&val) {
+                continue;
+            }
+
+            self.card.write_elem_value(&e, &val)?;
+            Self::update_aliases(&self.card, &self.aliases, &e, &val)?;
+            *v = val;
+        }
+
+        Ok(())
+    }
+
     pub fn dispatch_notification<O, N, T>(
         &mut self,
         unit: &mut O,
@@ -406,6 +870,7 @@ impl CardCntr {
     {
         let card = &self.card;
         let entries = &mut self.entries;
+        let aliases = &self.aliases;
 
         ctl_model.parse_notification(unit, notification)?;
 
@@ -419,6 +884,7 @@ impl CardCntr {
                 .try_for_each(|elem_value| {
                     if ctl_model.read_notified_elem(unit, elem_id, elem_value)? {
                         card.write_elem_value(elem_id, elem_value)?;
+                        Self::update_aliases(card, aliases, elem_id, elem_value)?;
                     }
 
                     Ok(())
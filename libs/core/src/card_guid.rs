@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Resolver of sound card by GUID of node.
+//!
+//! The numeric ID of sound card is assigned in order of device probing, thus it is not
+//! necessarily the same between boots. The module locates the sound card by comparing GUID in
+//! bus information block of configuration ROM for the node to which ALSA HwDep character device
+//! is bound, so that service programs can be started with stable identifier.
+
+use {
+    super::{node_devices::*, passed_fds::*, *},
+    glib::FileError,
+    hinawa::{
+        prelude::{FwNodeExt, FwNodeExtManual},
+        FwNode,
+    },
+    hitaki::{prelude::*, SndUnit},
+};
+
+/// Parse GUID expressed in hexadecimal, with or without '0x' prefix.
+pub fn parse_guid(arg: &str) -> Option<u64> {
+    let literal = arg
+        .strip_prefix("0x")
+        .or_else(|| arg.strip_prefix("0X"))
+        .unwrap_or(arg);
+    u64::from_str_radix(literal, 16).ok()
+}
+
+/// Retrieve GUID from the content of configuration ROM.
+pub fn guid_from_config_rom(raw: &[u8]) -> Option<u64> {
+    if raw.len() < 20 {
+        return None;
+    }
+
+    let mut quadlet = [0; 4];
+    quadlet.copy_from_slice(&raw[12..16]);
+    let guid_hi = u32::from_be_bytes(quadlet);
+    quadlet.copy_from_slice(&raw[16..20]);
+    let guid_lo = u32::from_be_bytes(quadlet);
+
+    Some(((guid_hi as u64) << 32) | (guid_lo as u64))
+}
+
+/// Read the content of configuration ROM for the node to which the sound card is bound.
+pub fn read_card_config_rom(card_id: u32) -> Result<Vec<u8>, Error> {
+    let unit = SndUnit::new();
+    open_card_hwdep(card_id, |devnode| unit.open(devnode, 0))?;
+
+    let node_device = unit
+        .node_device()
+        .ok_or_else(|| Error::new(FileError::Nxio, "No node device is bound"))?;
+    let node = FwNode::new();
+    node.open(&devnode_path(&node_device))?;
+
+    node.config_rom().map(|raw| raw.to_vec())
+}
+
+fn read_card_guid(card_id: u32) -> Result<u64, Error> {
+    let raw = read_card_config_rom(card_id)?;
+    guid_from_config_rom(&raw)
+        .ok_or_else(|| Error::new(FileError::Nxio, "Configuration ROM is too short"))
+}
+
+/// Find the numeric ID of sound card for the node with the GUID. The ALSA HwDep character
+/// devices inaccessible or not for units on IEEE 1394 bus are just skipped.
+pub fn resolve_card_by_guid(guid: u64) -> Result<u32, Error> {
+    let mut card_ids: Vec<u32> = std::fs::read_dir("/dev/snd")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter_map(|name| {
+                    name.strip_prefix("hwC")
+                        .and_then(|name| name.strip_suffix("D0"))
+                        .and_then(|card_id| card_id.parse().ok())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    card_ids.sort();
+
+    card_ids
+        .into_iter()
+        .find(|&card_id| {
+            read_card_guid(card_id)
+                .map(|val| val == guid)
+                .unwrap_or_default()
+        })
+        .ok_or_else(|| {
+            let msg = format!("No sound card is found for the node: 0x{:016x}", guid);
+            Error::new(FileError::Noent, &msg)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn guid_parse() {
+        assert_eq!(parse_guid("0x000d6c040000ab12"), Some(0x000d6c040000ab12));
+        assert_eq!(parse_guid("000D6C040000AB12"), Some(0x000d6c040000ab12));
+        assert_eq!(parse_guid("0xg"), None);
+        assert_eq!(parse_guid(""), None);
+
+        let raw = [
+            0x04, 0x04, 0x00, 0x00, 0x31, 0x33, 0x39, 0x34, 0x00, 0x00, 0xa0, 0x02, 0x00, 0x0d,
+            0x6c, 0x04, 0x00, 0x00, 0xab, 0x12,
+        ];
+        assert_eq!(guid_from_config_rom(&raw), Some(0x000d6c040000ab12));
+        assert_eq!(guid_from_config_rom(&raw[..16]), None);
+    }
+}
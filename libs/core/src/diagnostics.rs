@@ -0,0 +1,229 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Collection of diagnostics for bug reports.
+//!
+//! The service programs collect the content of configuration ROM, the dump of registers specific
+//! to the family of device, and the current state of control elements, then archive them into
+//! a tarball so that users can attach it to bug reports at once. The data which can identify
+//! the user or the device is redacted; the chip ID in GUID, the nickname of device, and the
+//! values of elements for bytes, which are used for nickname and raw vendor command.
+
+use {
+    super::{card_guid::*, *},
+    alsactl::{prelude::*, *},
+    glib::FileError,
+    std::io::Write,
+};
+
+/// The entry of diagnostics, expressed as file in tarball.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticsEntry {
+    /// The name of file.
+    pub name: String,
+    /// The content of file.
+    pub data: Vec<u8>,
+}
+
+impl DiagnosticsEntry {
+    pub fn new(name: &str, data: Vec<u8>) -> Self {
+        Self {
+            name: name.to_string(),
+            data,
+        }
+    }
+}
+
+/// The trait for the collection of diagnostics specific to the family of device.
+pub trait DiagnosticsOperation<T> {
+    /// Collect the entries of diagnostics for the device given by the argument.
+    fn collect_diagnostics(arg: &T) -> Result<Vec<DiagnosticsEntry>, Error>;
+}
+
+/// Zero the chip ID in GUID of bus information block, while the OUI is left as is.
+pub fn redact_config_rom(raw: &mut [u8]) {
+    if raw.len() >= 20 {
+        raw[15..20].iter_mut().for_each(|b| *b = 0);
+    }
+}
+
+fn format_elem_values(card: &Card, elem_id: &ElemId) -> Result<String, Error> {
+    let elem_info = card.elem_info(elem_id)?;
+    let mut elem_value = ElemValue::new();
+    card.read_elem_value(elem_id, &mut elem_value)?;
+
+    let literal = match &elem_info {
+        ElemInfo::Boolean(info) => {
+            let count = info.value_count() as usize;
+            format!("{:?}", &elem_value.boolean()[..count])
+        }
+        ElemInfo::Integer(info) => {
+            let count = info.value_count() as usize;
+            format!("{:?}", &elem_value.int()[..count])
+        }
+        ElemInfo::Enumerated(info) => {
+            let count = info.value_count() as usize;
+            format!("{:?}", &elem_value.enumerated()[..count])
+        }
+        ElemInfo::Integer64(info) => {
+            let count = info.value_count() as usize;
+            format!("{:?}", &elem_value.int64()[..count])
+        }
+        ElemInfo::Bytes(info) => format!("<redacted {} bytes>", info.value_count()),
+        _ => "<unsupported>".to_string(),
+    };
+
+    Ok(literal)
+}
+
+/// Dump the current values of all elements in the sound card as text, one element per line.
+pub fn dump_card_state(card_id: u32) -> Result<String, Error> {
+    let card = Card::new();
+    card.open(card_id, 0)?;
+
+    let mut elem_id_list = card.elem_id_list()?;
+    elem_id_list.sort_by(|a, b| {
+        a.name()
+            .cmp(&b.name())
+            .then_with(|| a.index().cmp(&b.index()))
+    });
+
+    let dump = elem_id_list
+        .iter()
+        .fold(String::new(), |mut dump, elem_id| {
+            let literal = format_elem_values(&card, elem_id).unwrap_or_else(|e| format!("<{}>", e));
+            dump.push_str(&format!(
+                "{}[{}]: {}\n",
+                elem_id.name(),
+                elem_id.index(),
+                literal
+            ));
+            dump
+        });
+
+    Ok(dump)
+}
+
+/// Collect the entries of diagnostics common to all of families; the content of configuration
+/// ROM and the current state of elements.
+pub fn collect_common_diagnostics(card_id: u32) -> Result<Vec<DiagnosticsEntry>, Error> {
+    let mut raw = read_card_config_rom(card_id)?;
+    redact_config_rom(&mut raw);
+    let state = dump_card_state(card_id)?;
+
+    Ok(vec![
+        DiagnosticsEntry::new("config-rom.bin", raw),
+        DiagnosticsEntry::new("ctl-state.txt", state.into_bytes()),
+    ])
+}
+
+const TAR_BLOCK_SIZE: usize = 512;
+
+fn write_octal(field: &mut [u8], val: u64) {
+    // The last byte is for terminator.
+    let literal = format!("{:0width$o}", val, width = field.len() - 1);
+    field[..literal.len()].copy_from_slice(literal.as_bytes());
+}
+
+fn build_tar_header(name: &str, size: usize, mtime: u64) -> [u8; TAR_BLOCK_SIZE] {
+    let mut header = [0; TAR_BLOCK_SIZE];
+
+    let name = name.as_bytes();
+    let len = std::cmp::min(name.len(), 100);
+    header[..len].copy_from_slice(&name[..len]);
+    write_octal(&mut header[100..108], 0o644);
+    write_octal(&mut header[108..116], 0);
+    write_octal(&mut header[116..124], 0);
+    write_octal(&mut header[124..136], size as u64);
+    write_octal(&mut header[136..148], mtime);
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+
+    // The checksum is computed with the field filled by spaces.
+    header[148..156].iter_mut().for_each(|b| *b = b' ');
+    let sum: u32 = header.iter().map(|&b| b as u32).sum();
+    write_octal(&mut header[148..155], sum as u64);
+    header[154] = 0;
+
+    header
+}
+
+/// Archive the entries into tarball in POSIX ustar format under the directory.
+pub fn build_tarball(dir: &str, entries: &[DiagnosticsEntry], mtime: u64) -> Vec<u8> {
+    let mut tarball = Vec::new();
+
+    entries.iter().for_each(|entry| {
+        let name = format!("{}/{}", dir, entry.name);
+        tarball.extend_from_slice(&build_tar_header(&name, entry.data.len(), mtime));
+        tarball.extend_from_slice(&entry.data);
+        let padding = (TAR_BLOCK_SIZE - entry.data.len() % TAR_BLOCK_SIZE) % TAR_BLOCK_SIZE;
+        tarball.resize(tarball.len() + padding, 0);
+    });
+
+    // Two blocks filled with zero for the end of archive.
+    tarball.resize(tarball.len() + TAR_BLOCK_SIZE * 2, 0);
+
+    tarball
+}
+
+/// Write the tarball of entries to the file in the path.
+pub fn write_diagnostics(path: &str, dir: &str, entries: &[DiagnosticsEntry]) -> Result<(), Error> {
+    let mtime = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    let tarball = build_tarball(dir, entries, mtime);
+
+    std::fs::File::create(path)
+        .and_then(|mut file| file.write_all(&tarball))
+        .map_err(|e| {
+            let msg = format!("Fail to write {}: {}", path, e);
+            Error::new(FileError::Io, &msg)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn config_rom_redaction() {
+        let mut raw = vec![0xff; 24];
+        redact_config_rom(&mut raw);
+        assert_eq!(&raw[12..15], &[0xff; 3]);
+        assert_eq!(&raw[15..20], &[0x00; 5]);
+        assert_eq!(&raw[20..], &[0xff; 4]);
+    }
+
+    #[test]
+    fn tarball_layout() {
+        let entries = [
+            DiagnosticsEntry::new("a.bin", vec![0x01; 4]),
+            DiagnosticsEntry::new("b.txt", vec![0x02; 513]),
+        ];
+        let tarball = build_tarball("dir", &entries, 0);
+        assert_eq!(tarball.len(), TAR_BLOCK_SIZE * (1 + 1 + 1 + 2 + 2));
+
+        let header = &tarball[..TAR_BLOCK_SIZE];
+        assert_eq!(&header[..9], b"dir/a.bin");
+        assert_eq!(&header[124..136], b"00000000004\0");
+        assert_eq!(&header[257..263], b"ustar\0");
+
+        let sum: u32 = header[..148]
+            .iter()
+            .chain(&[b' '; 8])
+            .chain(&header[156..])
+            .map(|&b| b as u32)
+            .sum();
+        assert_eq!(&header[148..155], format!("{:06o}\0", sum).as_bytes());
+
+        let header = &tarball[TAR_BLOCK_SIZE * 2..TAR_BLOCK_SIZE * 3];
+        assert_eq!(&header[..9], b"dir/b.txt");
+        assert_eq!(&header[124..136], b"00000001001\0");
+        assert_eq!(
+            &tarball[TAR_BLOCK_SIZE * 3..TAR_BLOCK_SIZE * 3 + 513],
+            &[0x02; 513][..]
+        );
+    }
+}
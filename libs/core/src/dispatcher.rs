@@ -9,7 +9,11 @@ use {
     hinawa::{FwNode, prelude::FwNodeExt},
     hitaki::{AlsaFirewire, prelude::AlsaFirewireExt},
     nix::sys::signal,
-    std::{sync::Arc, thread, time::Duration},
+    std::{
+        sync::Arc,
+        thread,
+        time::{Duration, Instant},
+    },
 };
 
 pub struct Dispatcher {
@@ -135,3 +139,89 @@ impl Dispatcher {
         self.attach_src_to_ctx(&src);
     }
 }
+
+/// The helper to coalesce bursts of events for the same target, like repeated events for the
+/// same element from noisy encoders in control surface. The events queued within the window are
+/// merged into one, and it is retrieved when the window expires.
+#[derive(Debug)]
+pub struct EventDebouncer<T: PartialEq> {
+    window: Duration,
+    pending: Vec<(T, Instant)>,
+}
+
+impl<T: PartialEq> EventDebouncer<T> {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Queue the event for the target. The event is merged into the one already queued.
+    pub fn push(&mut self, target: T, now: Instant) {
+        if self.pending.iter().find(|(t, _)| target.eq(t)).is_none() {
+            self.pending.push((target, now + self.window));
+        }
+    }
+
+    /// Discard the queued event for the target. Return true if it is queued.
+    pub fn remove(&mut self, target: &T) -> bool {
+        let len = self.pending.len();
+        self.pending.retain(|(t, _)| !target.eq(t));
+        self.pending.len() != len
+    }
+
+    /// The duration until the earliest window expires, if any event is queued.
+    pub fn timeout(&self, now: Instant) -> Option<Duration> {
+        self.pending
+            .iter()
+            .map(|(_, deadline)| deadline.saturating_duration_since(now))
+            .min()
+    }
+
+    /// Retrieve the queued events of which window expires, in the order of queueing.
+    pub fn pop_expired(&mut self, now: Instant) -> Vec<T> {
+        let mut expired = Vec::new();
+        let mut i = 0;
+        while i < self.pending.len() {
+            if self.pending[i].1 <= now {
+                expired.push(self.pending.remove(i).0);
+            } else {
+                i += 1;
+            }
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn event_debouncer() {
+        let window = Duration::from_millis(20);
+        let mut debouncer = EventDebouncer::new(window);
+        let now = Instant::now();
+        assert_eq!(debouncer.timeout(now), None);
+
+        debouncer.push(1, now);
+        debouncer.push(2, now + Duration::from_millis(5));
+        debouncer.push(1, now + Duration::from_millis(10));
+        assert_eq!(debouncer.timeout(now), Some(window));
+
+        assert_eq!(
+            debouncer.pop_expired(now + Duration::from_millis(19)),
+            Vec::<i32>::new()
+        );
+        assert_eq!(debouncer.pop_expired(now + window), vec![1]);
+        assert_eq!(
+            debouncer.timeout(now + window),
+            Some(Duration::from_millis(5))
+        );
+
+        assert!(debouncer.remove(&2));
+        assert!(!debouncer.remove(&2));
+        assert_eq!(debouncer.timeout(now + window), None);
+    }
+}
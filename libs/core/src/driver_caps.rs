@@ -0,0 +1,83 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Capabilities of drivers in Linux kernel.
+//!
+//! Some ioctl requests for ALSA HwDep character device are added in later version of Linux
+//! kernel than the one of ALSA drivers for the units, while the drivers report no version of
+//! protocol. The helper probes the availability of the request by the request itself, so that
+//! the runtime can skip the function unavailable instead of failing. The request unknown to the
+//! driver fails with ENOTTY, or ENOSYS in some layers. The former is not mapped to any code of
+//! FileError by GLib, thus it is reported as FileError::Failed. The unavailable feature is
+//! recorded in the report of features.
+
+use {
+    super::{feature_report::*, *},
+    glib::FileError,
+};
+
+/// The feature of driver added in later version of Linux kernel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DriverFeature {
+    /// The image of parameters in register DSP of ALSA firewire-motu driver.
+    MotuRegisterDspParameter,
+    /// The image of meter in register DSP of ALSA firewire-motu driver.
+    MotuRegisterDspMeter,
+    /// The image of meter in command DSP of ALSA firewire-motu driver.
+    MotuCommandDspMeter,
+}
+
+impl DriverFeature {
+    /// The name of feature in the report of features.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::MotuRegisterDspParameter => "register-dsp-parameter",
+            Self::MotuRegisterDspMeter => "register-dsp-meter",
+            Self::MotuCommandDspMeter => "command-dsp-meter",
+        }
+    }
+}
+
+/// Whether the error of ioctl request means that the driver does not support the request.
+pub fn is_unsupported_request(error: &Error) -> bool {
+    match error.kind::<FileError>() {
+        Some(FileError::Nosys) | Some(FileError::Failed) => true,
+        _ => false,
+    }
+}
+
+/// Probe the feature by the ioctl request. Ok(false) is returned when the driver does not support
+/// the request, and the feature is recorded as unavailable. The other error is returned as is.
+pub fn probe_driver_feature<F>(feature: DriverFeature, request: F) -> Result<bool, Error>
+where
+    F: FnOnce() -> Result<(), Error>,
+{
+    match request() {
+        Ok(_) => Ok(true),
+        Err(e) if is_unsupported_request(&e) => {
+            report_feature(feature.name(), "unavailable");
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn driver_feature_probe() {
+        let feature = DriverFeature::MotuRegisterDspParameter;
+        assert_eq!(probe_driver_feature(feature, || Ok(())).unwrap(), true);
+
+        let res = probe_driver_feature(feature, || Err(Error::new(FileError::Nosys, "")));
+        assert_eq!(res.unwrap(), false);
+
+        let res = probe_driver_feature(feature, || Err(Error::new(FileError::Failed, "")));
+        assert_eq!(res.unwrap(), false);
+
+        let res = probe_driver_feature(feature, || Err(Error::new(FileError::Io, "")));
+        assert_eq!(res.unwrap_err().kind::<FileError>(), Some(FileError::Io));
+    }
+}
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Optional aliases of control elements.
+//!
+//! The module includes the loader of the map between the name of element and its alias. It is
+//! useful for users migrated from FFADO to keep compatibility of their scripts and the state of
+//! control elements stored by alsactl, by exposing the elements with the names they used.
+//!
+//! The map is a text file given by the environment variable. Each line consists of the name of
+//! element, equal sign, and the name of alias. The line begins with hash sign is comment.
+//!
+//! ```text
+//! # original name = alias name
+//! mixer-source-gain = Mixer Source Gain
+//! ```
+//!
+//! The name of element unavailable in the runtime is skipped, thus a map is available for the
+//! runtimes of all families. The skipped names are recorded in the report of features. The map
+//! for the names of controls common to all devices in FFADO is in `docs/ffado-alias-map.txt`.
+
+use {
+    super::{card_cntr::*, feature_report::*, *},
+    glib::FileError,
+};
+
+/// The environment variable for the path to the file of alias map.
+pub const ALIAS_MAP_ENV: &str = "SND_FIREWIRE_CTL_ALIAS_MAP";
+
+/// The name of feature in the report of features for the alias map.
+pub const ALIAS_MAP_FEATURE_NAME: &str = "alias-map";
+
+/// Parse the text of alias map into the pairs of original name and alias name.
+pub fn parse_alias_map(text: &str) -> Result<Vec<(String, String)>, Error> {
+    parse_name_map(text, "alias map")
+}
+
+// Parse the lines of pair of names separated by equal sign.
+pub(crate) fn parse_name_map(text: &str, kind: &str) -> Result<Vec<(String, String)>, Error> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            line.split_once('=')
+                .map(|(orig, alias)| (orig.trim(), alias.trim()))
+                .filter(|(orig, alias)| !orig.is_empty() && !alias.is_empty())
+                .map(|(orig, alias)| (orig.to_string(), alias.to_string()))
+                .ok_or_else(|| {
+                    let msg = format!("Invalid entry of {} at line {}: {}", kind, i + 1, line);
+                    Error::new(FileError::Inval, &msg)
+                })
+        })
+        .collect()
+}
+
+/// Add aliases when the environment variable for the file of alias map is given.
+pub fn add_aliases_from_env(card_cntr: &mut CardCntr) -> Result<(), Error> {
+    match std::env::var_os(ALIAS_MAP_ENV) {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path).map_err(|e| {
+                let msg = format!("Fail to read alias map {:?}: {}", path, e);
+                Error::new(FileError::Io, &msg)
+            })?;
+            let aliases = parse_alias_map(&text)?;
+            let unavailable = card_cntr.add_aliases(&aliases)?;
+            if unavailable.len() > 0 {
+                let msg = format!("unavailable: {}", unavailable.join(", "));
+                report_feature(ALIAS_MAP_FEATURE_NAME, &msg);
+            }
+            Ok(())
+        }
+        None => Ok(()),
+    }
+}
+
+/// Add aliases when the environment variable for the file of alias map is given. The aliases are
+/// optional, thus the failure is not returned but recorded in the report of features. It should
+/// be called after adding elements.
+pub fn setup_aliases(card_cntr: &mut CardCntr) {
+    if let Err(e) = add_aliases_from_env(card_cntr) {
+        report_feature(ALIAS_MAP_FEATURE_NAME, &e.to_string());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn alias_map_parse() {
+        let text = "
+            # comment
+            mixer-source-gain = Mixer Source Gain
+
+            clock-source=Clock Source
+        ";
+        let aliases = parse_alias_map(text).unwrap();
+        assert_eq!(
+            aliases,
+            vec![
+                (
+                    "mixer-source-gain".to_string(),
+                    "Mixer Source Gain".to_string()
+                ),
+                ("clock-source".to_string(), "Clock Source".to_string()),
+            ]
+        );
+
+        assert!(parse_alias_map("clock-source").is_err());
+        assert!(parse_alias_map("clock-source = ").is_err());
+    }
+}
@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Batch assignment of values to elements selected by pattern of name.
+//!
+//! The elements are selected by the pattern of name, in which asterisk matches any sequence of
+//! characters and question mark matches any single character, then the values are written to
+//! them via ALSA control character device. The write operation is dispatched to the same handler
+//! in the service program as the other applications do, thus the values are validated by the
+//! service program. All of the values are parsed and checked against the information of element
+//! before writing, so that no element is changed when any of them is invalid for the access, the
+//! range, or the step. However the assignment is not atomic; when the service program rejects the
+//! write to one of elements, the elements written before it keep the new values.
+//!
+//! The single value is assigned to all of the values in the element, or the same number of
+//! values as the element has are given. The value of boolean element is one of 'true', 'false',
+//! 'on', 'off', '1', and '0'. The value of enumerated element is either the label of item or the
+//! index of item.
+
+use {
+    super::*,
+    alsactl::{prelude::*, *},
+    glib::FileError,
+};
+
+/// Whether the name matches the pattern. The asterisk in the pattern matches any sequence of
+/// characters, and the question mark matches any single character.
+pub fn match_elem_name(pattern: &str, name: &str) -> bool {
+    fn match_chars(pattern: &[char], name: &[char]) -> bool {
+        match pattern.split_first() {
+            None => name.is_empty(),
+            Some(('*', rest)) => (0..=name.len()).any(|pos| match_chars(rest, &name[pos..])),
+            Some(('?', rest)) => !name.is_empty() && match_chars(rest, &name[1..]),
+            Some((c, rest)) => name.first() == Some(c) && match_chars(rest, &name[1..]),
+        }
+    }
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    match_chars(&pattern, &name)
+}
+
+/// Parse the literal of boolean value.
+pub fn parse_bool_literal(literal: &str) -> Option<bool> {
+    match literal {
+        "true" | "on" | "1" => Some(true),
+        "false" | "off" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// Whether the value of integer is within the range and aligned to the step. The step of zero
+/// means any value in the range.
+pub fn check_int_value(val: i32, min: i32, max: i32, step: i32) -> bool {
+    (min..=max).contains(&val) && (step <= 0 || (val as i64 - min as i64) % step as i64 == 0)
+}
+
+/// Parse the literals into the values as many as the count. The single literal is expanded to
+/// all of the values.
+pub fn expand_literals<T, F>(literals: &[String], count: usize, parse: F) -> Option<Vec<T>>
+where
+    T: Copy,
+    F: Fn(&str) -> Option<T>,
+{
+    if literals.len() == 1 {
+        parse(&literals[0]).map(|val| vec![val; count])
+    } else if literals.len() == count {
+        literals.iter().map(|literal| parse(literal)).collect()
+    } else {
+        None
+    }
+}
+
+fn build_elem_value(
+    card: &Card,
+    elem_id: &ElemId,
+    literals: &[String],
+) -> Result<ElemValue, Error> {
+    let elem_info = card.elem_info(elem_id)?;
+    let name = elem_id.name();
+
+    let access = elem_info.as_ref().access();
+    if !access.contains(ElemAccessFlag::WRITE) || access.contains(ElemAccessFlag::LOCK) {
+        let msg = format!("{} is read-only", name);
+        return Err(Error::new(FileError::Perm, &msg));
+    }
+
+    let elem_value = ElemValue::new();
+    let parsed = match &elem_info {
+        ElemInfo::Boolean(info) => {
+            let count = info.value_count() as usize;
+            expand_literals(literals, count, parse_bool_literal)
+                .map(|vals| elem_value.set_bool(&vals))
+        }
+        ElemInfo::Integer(info) => {
+            let count = info.value_count() as usize;
+            let (min, max, step) = (info.value_min(), info.value_max(), info.value_step());
+            expand_literals(literals, count, |literal| {
+                literal
+                    .parse::<i32>()
+                    .ok()
+                    .filter(|&val| check_int_value(val, min, max, step))
+            })
+            .map(|vals| elem_value.set_int(&vals))
+        }
+        ElemInfo::Enumerated(info) => {
+            let count = info.value_count() as usize;
+            let labels = info.labels();
+            expand_literals(literals, count, |literal| {
+                labels
+                    .iter()
+                    .position(|label| label.as_str() == literal)
+                    .or_else(|| literal.parse::<usize>().ok())
+                    .filter(|&pos| pos < labels.len())
+                    .map(|pos| pos as u32)
+            })
+            .map(|vals| elem_value.set_enum(&vals))
+        }
+        _ => {
+            let msg = format!("{} is not available for batch assignment", name);
+            return Err(Error::new(FileError::Inval, &msg));
+        }
+    };
+
+    parsed.map(|_| elem_value).ok_or_else(|| {
+        let msg = format!("Invalid values for {}: {}", name, literals.join(" "));
+        Error::new(FileError::Inval, &msg)
+    })
+}
+
+/// Write the values to the elements in the sound card whose name matches the pattern, then
+/// return the list of written elements.
+pub fn set_elems_by_pattern(
+    card_id: u32,
+    pattern: &str,
+    literals: &[String],
+) -> Result<Vec<ElemId>, Error> {
+    let card = Card::new();
+    card.open(card_id, 0)?;
+
+    let mut elem_id_list: Vec<ElemId> = card
+        .elem_id_list()?
+        .into_iter()
+        .filter(|elem_id| match_elem_name(pattern, &elem_id.name()))
+        .collect();
+    if elem_id_list.is_empty() {
+        let msg = format!("No element matches the pattern: {}", pattern);
+        return Err(Error::new(FileError::Noent, &msg));
+    }
+    elem_id_list.sort_by(|a, b| {
+        a.name()
+            .cmp(&b.name())
+            .then_with(|| a.index().cmp(&b.index()))
+    });
+
+    let entries = elem_id_list
+        .iter()
+        .map(|elem_id| build_elem_value(&card, elem_id, literals).map(|v| (elem_id, v)))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    entries.iter().try_for_each(|(elem_id, elem_value)| {
+        card.write_elem_value(elem_id, elem_value).map_err(|e| {
+            let msg = format!(
+                "Fail to write {}[{}]: {}",
+                elem_id.name(),
+                elem_id.index(),
+                e
+            );
+            Error::new(FileError::Io, &msg)
+        })
+    })?;
+
+    Ok(elem_id_list)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn elem_name_match() {
+        assert!(match_elem_name("mixer-*-gain", "mixer-source-gain"));
+        assert!(match_elem_name("mixer-*-gain", "mixer--gain"));
+        assert!(!match_elem_name("mixer-*-gain", "mixer-source-balance"));
+        assert!(match_elem_name("output-?-volume", "output-1-volume"));
+        assert!(!match_elem_name("output-?-volume", "output-10-volume"));
+        assert!(match_elem_name("*", "clock-rate"));
+        assert!(match_elem_name("clock-rate", "clock-rate"));
+        assert!(!match_elem_name("clock-rate", "clock-rate-source"));
+    }
+
+    #[test]
+    fn literals_expand() {
+        let literals = vec!["on".to_string()];
+        assert_eq!(
+            expand_literals(&literals, 3, parse_bool_literal),
+            Some(vec![true, true, true])
+        );
+
+        let literals = vec!["1".to_string(), "off".to_string()];
+        assert_eq!(
+            expand_literals(&literals, 2, parse_bool_literal),
+            Some(vec![true, false])
+        );
+        assert_eq!(expand_literals(&literals, 3, parse_bool_literal), None);
+
+        let literals = vec!["yes".to_string()];
+        assert_eq!(expand_literals(&literals, 1, parse_bool_literal), None);
+    }
+
+    #[test]
+    fn int_value_check() {
+        assert!(check_int_value(0, -10, 10, 0));
+        assert!(!check_int_value(11, -10, 10, 0));
+        assert!(check_int_value(-4, -10, 10, 3));
+        assert!(!check_int_value(-5, -10, 10, 3));
+        assert!(check_int_value(i32::MAX, i32::MIN, i32::MAX, 1));
+    }
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Optional localization of labels for enumerated elements.
+//!
+//! The module includes the loader of the map between the label of item in enumerated element and
+//! its translation. The translation is applied when the enumerated element is added, thus the map
+//! should be loaded before adding elements. alsactl stores the value of enumerated element by the
+//! label of item, therefore the state stored under one map is not restored under the other map.
+//! The state should be restored before changing the map, then stored again.
+//!
+//! The runtimes of all families load the map. The runtime for TASCAM FE-8 adds no element, thus
+//! it is out of the scope. The failure to load the map is not fatal, and is recorded in the report
+//! of features.
+//!
+//! The name of element is not translated since it is used to identify the element by service
+//! program and the other programs. The alias map in [`super::elem_alias`] is available for it.
+//!
+//! The map is a text file given by the environment variable. Each line consists of the label,
+//! equal sign, and the translation. The line begins with hash sign is comment.
+//!
+//! ```text
+//! # label = translation
+//! Internal = Interne
+//! ```
+
+use {
+    super::{card_cntr::*, elem_alias::parse_name_map, feature_report::*, *},
+    glib::FileError,
+};
+
+/// The environment variable for the path to the file of locale map.
+pub const LOCALE_MAP_ENV: &str = "SND_FIREWIRE_CTL_LOCALE_MAP";
+
+/// The name of feature in the report of features for failure to load the locale map.
+pub const LOCALE_MAP_FEATURE_NAME: &str = "locale-map";
+
+/// Parse the text of locale map into the pairs of label and translation.
+pub fn parse_locale_map(text: &str) -> Result<Vec<(String, String)>, Error> {
+    parse_name_map(text, "locale map")
+}
+
+/// Configure translations of labels when the environment variable for the file of locale map is
+/// given.
+pub fn set_label_translations_from_env(card_cntr: &mut CardCntr) -> Result<(), Error> {
+    match std::env::var_os(LOCALE_MAP_ENV) {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path).map_err(|e| {
+                let msg = format!("Fail to read locale map {:?}: {}", path, e);
+                Error::new(FileError::Io, &msg)
+            })?;
+            let translations = parse_locale_map(&text)?;
+            card_cntr.set_label_translations(&translations);
+            Ok(())
+        }
+        None => Ok(()),
+    }
+}
+
+/// Configure translations of labels when the environment variable for the file of locale map is
+/// given. The translations are optional, thus the failure is not returned but recorded in the
+/// report of features. It should be called before adding elements.
+pub fn setup_label_translations(card_cntr: &mut CardCntr) {
+    if let Err(e) = set_label_translations_from_env(card_cntr) {
+        report_feature(LOCALE_MAP_FEATURE_NAME, &e.to_string());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn locale_map_parse() {
+        let text = "
+            # comment
+            Internal = Interne
+            Word clock=Horloge de mot
+        ";
+        let translations = parse_locale_map(text).unwrap();
+        assert_eq!(
+            translations,
+            vec![
+                ("Internal".to_string(), "Interne".to_string()),
+                ("Word clock".to_string(), "Horloge de mot".to_string()),
+            ]
+        );
+
+        assert!(parse_locale_map("Internal").is_err());
+    }
+}
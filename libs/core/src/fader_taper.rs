@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Optional taper of faders for elements of level.
+//!
+//! The value of element for level is mapped linearly to the level in dB of device by default,
+//! thus the most of travel of fader is spent for low level which is rarely used. The audio taper
+//! maps the position of fader to the level in piecewise linear curve similar to the one of
+//! faders in mixing console, so that the half of travel covers the upper quarter of range.
+//!
+//! The map is a text file given by the environment variable. Each line consists of the name of
+//! element, equal sign, and the name of taper. The line begins with hash sign is comment.
+//!
+//! ```text
+//! # name of element = taper
+//! output-volume = audio
+//! ```
+
+use {
+    super::{elem_alias::parse_name_map, *},
+    glib::FileError,
+};
+
+/// The environment variable for the path to the file of fader taper map.
+pub const FADER_TAPER_ENV: &str = "SND_FIREWIRE_CTL_FADER_TAPER";
+
+/// The name of feature in the report for fader taper.
+pub const FADER_TAPER_FEATURE_NAME: &str = "fader-tapers";
+
+/// The taper of fader.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FaderTaper {
+    /// The position is the level.
+    Linear,
+    /// The level changes finely around the upper part of range.
+    Audio,
+}
+
+impl Default for FaderTaper {
+    fn default() -> Self {
+        Self::Linear
+    }
+}
+
+impl FaderTaper {
+    // The pairs of position and level in thousandth of range.
+    const AUDIO_POINTS: [(i64, i64); 5] =
+        [(0, 0), (250, 500), (500, 750), (750, 900), (1000, 1000)];
+
+    fn map(points: &[(i64, i64)], val: i32, min: i32, max: i32) -> i32 {
+        let range = (max as i64) - (min as i64);
+        if range <= 0 {
+            return min;
+        }
+        let ratio = ((val as i64) - (min as i64)).clamp(0, range) * 1000;
+
+        let mapped = points
+            .windows(2)
+            .find(|pair| ratio <= pair[1].0 * range)
+            .map(|pair| {
+                let (x0, y0) = pair[0];
+                let (x1, y1) = pair[1];
+                y0 * range + (ratio - x0 * range) * (y1 - y0) / (x1 - x0)
+            })
+            .unwrap_or(ratio);
+
+        ((min as i64) + mapped / 1000) as i32
+    }
+
+    /// Compute the level from the position of fader.
+    pub fn to_level(&self, pos: i32, min: i32, max: i32) -> i32 {
+        match self {
+            Self::Linear => pos,
+            Self::Audio => Self::map(&Self::AUDIO_POINTS, pos, min, max),
+        }
+    }
+
+    /// Compute the position of fader from the level.
+    pub fn to_pos(&self, level: i32, min: i32, max: i32) -> i32 {
+        match self {
+            Self::Linear => level,
+            Self::Audio => {
+                let points: Vec<(i64, i64)> =
+                    Self::AUDIO_POINTS.iter().map(|&(x, y)| (y, x)).collect();
+                Self::map(&points, level, min, max)
+            }
+        }
+    }
+}
+
+fn parse_fader_taper(name: &str) -> Option<FaderTaper> {
+    match name {
+        "linear" => Some(FaderTaper::Linear),
+        "audio" => Some(FaderTaper::Audio),
+        _ => None,
+    }
+}
+
+/// Parse the text of fader taper map into the pairs of name of element and taper.
+pub fn parse_fader_taper_map(text: &str) -> Result<Vec<(String, FaderTaper)>, Error> {
+    parse_name_map(text, "fader taper map")?
+        .into_iter()
+        .map(|(name, taper)| {
+            parse_fader_taper(&taper)
+                .map(|taper| (name, taper))
+                .ok_or_else(|| {
+                    let msg = format!("Invalid name of fader taper: {}", taper);
+                    Error::new(FileError::Inval, &msg)
+                })
+        })
+        .collect()
+}
+
+/// Load fader taper map when the environment variable for the file is given.
+pub fn load_fader_tapers_from_env() -> Result<Vec<(String, FaderTaper)>, Error> {
+    match std::env::var_os(FADER_TAPER_ENV) {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path).map_err(|e| {
+                let msg = format!("Fail to read fader taper map {:?}: {}", path, e);
+                Error::new(FileError::Io, &msg)
+            })?;
+            parse_fader_taper_map(&text)
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fader_taper_map_parse() {
+        let text = "
+            # comment
+            output-volume = audio
+            phys-input-gain=linear
+        ";
+        let tapers = parse_fader_taper_map(text).unwrap();
+        assert_eq!(
+            tapers,
+            vec![
+                ("output-volume".to_string(), FaderTaper::Audio),
+                ("phys-input-gain".to_string(), FaderTaper::Linear),
+            ]
+        );
+
+        assert!(parse_fader_taper_map("output-volume = log").is_err());
+    }
+
+    #[test]
+    fn audio_taper() {
+        let taper = FaderTaper::Audio;
+        let (min, max) = (-32768, 0);
+
+        assert_eq!(taper.to_level(min, min, max), min);
+        assert_eq!(taper.to_level(max, min, max), max);
+        // The half of travel covers the upper quarter of range.
+        assert_eq!(taper.to_level(-16384, min, max), -8192);
+        assert_eq!(taper.to_pos(-8192, min, max), -16384);
+
+        // The position is kept by the round trip.
+        (min..=max).step_by(256).for_each(|pos| {
+            let level = taper.to_level(pos, min, max);
+            assert!((taper.to_pos(level, min, max) - pos).abs() <= 2);
+        });
+
+        assert_eq!(FaderTaper::Linear.to_level(-100, min, max), -100);
+    }
+}
@@ -0,0 +1,129 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Report of optional features engaged by the runtime for the unit.
+//!
+//! The runtime engages optional features according to the configuration, the quirks of node, and
+//! the capabilities of drivers, thus two units of the same model can behave differently. The
+//! features are recorded at any point of runtime, then exposed by read-only bytes element so that
+//! the difference can be investigated remotely. The content of element is text, each line of
+//! which consists of the name of feature, equal sign, and the value.
+//!
+//! ```text
+//! link-quirk-payload=256
+//! register-dsp-meter=unavailable
+//! ```
+
+use {
+    super::{card_cntr::*, *},
+    alsactl::{prelude::*, *},
+    std::sync::Mutex,
+};
+
+/// The name of element for the report of features.
+pub const FEATURE_REPORT_NAME: &str = "feature-report";
+
+/// The size of element for the report of features in bytes.
+pub const FEATURE_REPORT_SIZE: usize = 512;
+
+/// The features recorded by the runtime.
+static FEATURES: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Record the feature engaged by the runtime. The value of the feature recorded already is
+/// replaced.
+pub fn report_feature(name: &str, value: &str) {
+    let mut features = FEATURES.lock().unwrap();
+    match features.iter_mut().find(|(n, _)| n == name) {
+        Some((_, v)) => *v = value.to_string(),
+        None => features.push((name.to_string(), value.to_string())),
+    }
+}
+
+/// Format the features into the content of element. The line not fitting in the size is
+/// omitted.
+pub fn format_features(features: &[(String, String)], size: usize) -> Vec<u8> {
+    let mut raw = Vec::with_capacity(size);
+    for (name, value) in features {
+        let line = format!("{}={}\n", name, value);
+        if raw.len() + line.len() > size {
+            break;
+        }
+        raw.extend_from_slice(line.as_bytes());
+    }
+    raw.resize(size, 0);
+    raw
+}
+
+/// The element for the report of features.
+#[derive(Default, Debug)]
+pub struct FeatureReport(Option<ElemId>);
+
+impl FeatureReport {
+    /// Add the element.
+    pub fn load(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
+        // The element is not unlocked so that the other processes can not change it.
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, FEATURE_REPORT_NAME, 0);
+        card_cntr
+            .add_bytes_elems(&elem_id, 1, FEATURE_REPORT_SIZE, None, false)
+            .map(|mut elem_id_list| self.0 = elem_id_list.pop())
+    }
+
+    /// Whether the element is for the report.
+    pub fn has_elem(&self, elem_id: &ElemId) -> bool {
+        self.0.as_ref().map(|e| e.eq(elem_id)).unwrap_or_default()
+    }
+
+    /// Update the element when the features differ.
+    pub fn update(&self, card_cntr: &mut CardCntr) -> Result<(), Error> {
+        if let Some(elem_id) = &self.0 {
+            let raw = format_features(&FEATURES.lock().unwrap(), FEATURE_REPORT_SIZE);
+
+            let mut elem_value = ElemValue::new();
+            card_cntr.card.read_elem_value(elem_id, &mut elem_value)?;
+            if elem_value.bytes()[..FEATURE_REPORT_SIZE] != raw[..] {
+                elem_value.set_bytes(&raw);
+                card_cntr.card.write_elem_value(elem_id, &elem_value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn features_format() {
+        let features = vec![
+            ("link-quirk-payload".to_string(), "256".to_string()),
+            ("register-dsp-meter".to_string(), "unavailable".to_string()),
+        ];
+        let raw = format_features(&features, 64);
+        assert_eq!(raw.len(), 64);
+        let text = "link-quirk-payload=256\nregister-dsp-meter=unavailable\n";
+        assert_eq!(&raw[..text.len()], text.as_bytes());
+        assert!(raw[text.len()..].iter().all(|&b| b == 0));
+
+        // The line not fitting in the size is omitted.
+        let raw = format_features(&features, 32);
+        let text = "link-quirk-payload=256\n";
+        assert_eq!(&raw[..text.len()], text.as_bytes());
+        assert!(raw[text.len()..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn feature_report() {
+        report_feature("test-feature", "a");
+        report_feature("test-feature", "b");
+        let features = FEATURES.lock().unwrap();
+        let entries: Vec<&(String, String)> = features
+            .iter()
+            .filter(|(name, _)| name == "test-feature")
+            .collect();
+        assert_eq!(
+            entries,
+            vec![&("test-feature".to_string(), "b".to_string())]
+        );
+    }
+}
@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Helper to align gain of inputs so that the peak level detected by meters hits target level.
+//!
+//! The helper watches the meters of inputs during some periods of measurement, then proposes the
+//! gain of inputs according to the maximum peak level in the periods. The input without any
+//! signal is left as is.
+//!
+//! The range of gain can differ between inputs, e.g. microphone and line inputs of Fireface 400.
+
+use super::{card_cntr::*, *};
+
+/// The range of gain for input.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct InputGainRange {
+    /// The minimum value of gain.
+    pub min: i32,
+    /// The maximum value of gain.
+    pub max: i32,
+    /// The amount of dB per step of the value.
+    pub db_per_step: f64,
+}
+
+/// The state of gain staging.
+#[derive(Default, Debug)]
+pub struct GainStaging {
+    /// The target of peak level in dBFS.
+    pub target_db: f64,
+    peaks: Vec<f64>,
+    remaining: usize,
+}
+
+impl GainStaging {
+    /// The default target of peak level, which leaves headroom for transient.
+    pub const DEFAULT_TARGET_DB: f64 = -12.0;
+
+    /// Start to watch the meters of inputs for the given count of measurement.
+    pub fn start(&mut self, input_count: usize, measure_count: usize) {
+        if self.target_db == 0.0 {
+            self.target_db = Self::DEFAULT_TARGET_DB;
+        }
+        self.peaks = vec![0.0; input_count];
+        self.remaining = measure_count;
+    }
+
+    /// Stop to watch the meters of inputs.
+    pub fn stop(&mut self) {
+        self.remaining = 0;
+    }
+
+    /// Whether to watch the meters of inputs.
+    pub fn is_running(&self) -> bool {
+        self.remaining > 0
+    }
+
+    /// Record the peak levels of inputs in linear scale between 0.0 and 1.0. Return true at the
+    /// last measurement.
+    pub fn observe(&mut self, peaks: &[f64]) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+
+        self.peaks.iter_mut().zip(peaks).for_each(|(peak, &level)| {
+            if level > *peak {
+                *peak = level;
+            }
+        });
+
+        self.remaining -= 1;
+        self.remaining == 0
+    }
+
+    /// Propose the gain of inputs according to the recorded peak levels. The gain is expressed
+    /// in the value of control element within the range of each input.
+    pub fn propose(&self, gains: &[i32], ranges: &[InputGainRange]) -> Vec<i32> {
+        gains
+            .iter()
+            .zip(ranges)
+            .zip(&self.peaks)
+            .map(|((&gain, range), &peak)| {
+                if peak <= 0.0 || range.db_per_step <= 0.0 {
+                    gain
+                } else {
+                    let diff_db = self.target_db - 20.0 * peak.min(1.0).log10();
+                    // Round toward lower gain to avoid clipping.
+                    let diff = (diff_db / range.db_per_step).floor() as i32;
+                    (gain + diff).clamp(range.min, range.max)
+                }
+            })
+            .collect()
+    }
+}
+
+/// The trait for model with meters and adjustable gain for inputs.
+pub trait GainStagingOperation<O: Sized>: MeasureModel<O> {
+    /// The range of gain for each input.
+    const INPUT_GAIN_RANGES: &'static [InputGainRange];
+
+    fn gain_staging(&mut self) -> &mut GainStaging;
+
+    /// The latest peak levels of inputs in linear scale between 0.0 and 1.0.
+    fn input_peaks(&self) -> Vec<f64>;
+
+    /// The current value of input gains.
+    fn input_gains(&self) -> Vec<i32>;
+
+    fn write_input_gains(&mut self, unit: &mut O, gains: &[i32]) -> Result<(), Error>;
+
+    /// Feed the latest peak levels after measurement, then apply proposed gains at the end of
+    /// watching. Return true when the gains are applied.
+    fn update_gain_staging(&mut self, unit: &mut O) -> Result<bool, Error> {
+        let peaks = self.input_peaks();
+        if !self.gain_staging().observe(&peaks) {
+            return Ok(false);
+        }
+
+        let gains = self.input_gains();
+        let proposal = self.gain_staging().propose(&gains, Self::INPUT_GAIN_RANGES);
+        self.write_input_gains(unit, &proposal).map(|_| true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn gain_staging_proposal() {
+        let mut staging = GainStaging::default();
+        staging.start(3, 2);
+        assert!(staging.is_running());
+
+        assert!(!staging.observe(&[0.1, 0.0, 1.0]));
+        assert!(staging.observe(&[0.05, 0.0, 0.5]));
+        assert!(!staging.is_running());
+
+        let range = InputGainRange {
+            min: 10,
+            max: 75,
+            db_per_step: 1.0,
+        };
+
+        // -20 dBFS requires 8 dB more, silence is left as is, and full scale requires 12 dB less.
+        let gains = staging.propose(&[20, 30, 40], &[range; 3]);
+        assert_eq!(gains, vec![28, 30, 28]);
+
+        // Clamped.
+        let gains = staging.propose(&[70, 30, 15], &[range; 3]);
+        assert_eq!(gains, vec![75, 30, 10]);
+
+        // Coarse step.
+        let range = InputGainRange {
+            min: 0,
+            max: 100,
+            db_per_step: 3.0,
+        };
+        let gains = staging.propose(&[20, 30, 40], &[range; 3]);
+        assert_eq!(gains, vec![22, 30, 36]);
+
+        // Different range for each input.
+        let ranges = [
+            InputGainRange {
+                min: 0,
+                max: 36,
+                db_per_step: 0.5,
+            },
+            range,
+            range,
+        ];
+        let gains = staging.propose(&[10, 30, 40], &ranges);
+        assert_eq!(gains, vec![26, 30, 36]);
+    }
+}
@@ -0,0 +1,158 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Exclusive lock of service program for the node.
+//!
+//! Neither ALSA HwDep character device nor ALSA control character device is opened exclusively,
+//! thus two instances of service program can be launched for the same node by accident. Then
+//! their transactions are interleaved and the state of unit is corrupted. The module acquires
+//! advisory lock of file named after GUID of node, so that the second instance detects the first
+//! one and exits. When GUID of node is not available, the file is named after the sound card or
+//! the node instead. The lock is released by system when the process finishes, even if it is
+//! aborted. The files are put into the directory given by the environment variable, the runtime
+//! directory of user, or '/run'. The file is opened without following symbolic link nor truncating
+//! its content, since the service program usually runs with privilege of root.
+
+use {
+    super::*,
+    glib::FileError,
+    nix::{
+        errno::Errno,
+        fcntl::{flock, FlockArg},
+        libc,
+    },
+    std::{
+        fs::{File, OpenOptions},
+        os::unix::{fs::OpenOptionsExt, io::AsRawFd},
+        path::{Path, PathBuf},
+    },
+};
+
+/// The environment variable for the directory to put lock files.
+pub const LOCK_DIR_ENV: &str = "SND_FIREWIRE_CTL_LOCK_DIR";
+
+/// The directory to put lock files when neither the environment variable for it nor the runtime
+/// directory of user is available.
+pub const DEFAULT_LOCK_DIR: &str = "/run";
+
+/// The directory to put lock files.
+pub fn instance_lock_dir() -> PathBuf {
+    std::env::var_os(LOCK_DIR_ENV)
+        .or_else(|| std::env::var_os("XDG_RUNTIME_DIR"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_LOCK_DIR))
+}
+
+/// The path of lock file for the node with the GUID.
+pub fn instance_lock_path(dir: &Path, guid: u64) -> PathBuf {
+    dir.join(format!("snd-firewire-ctl-services-{:016x}.lock", guid))
+}
+
+/// The path of lock file for the device with the name, like 'card1' or 'fw1', used when GUID of
+/// node is not available.
+pub fn instance_lock_path_by_device(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("snd-firewire-ctl-services-{}.lock", name))
+}
+
+/// The advisory lock for the node, held till dropped.
+#[derive(Debug)]
+pub struct InstanceLock {
+    // The lock is released when the file is closed.
+    _file: File,
+}
+
+impl InstanceLock {
+    /// Acquire the lock for the node with the GUID. FileError::Again is returned when the other
+    /// instance holds the lock.
+    pub fn acquire(guid: u64) -> Result<Self, Error> {
+        Self::acquire_in(&instance_lock_dir(), guid)
+    }
+
+    /// Acquire the lock for the node with the GUID in the given directory.
+    pub fn acquire_in(dir: &Path, guid: u64) -> Result<Self, Error> {
+        let path = instance_lock_path(dir, guid);
+        Self::lock_file(&path, &format!("the node 0x{:016x}", guid))
+    }
+
+    /// Acquire the lock for the device with the name, like 'card1' or 'fw1'. FileError::Again is
+    /// returned when the other instance holds the lock.
+    pub fn acquire_by_device(name: &str) -> Result<Self, Error> {
+        Self::acquire_by_device_in(&instance_lock_dir(), name)
+    }
+
+    /// Acquire the lock for the device with the name in the given directory.
+    pub fn acquire_by_device_in(dir: &Path, name: &str) -> Result<Self, Error> {
+        let path = instance_lock_path_by_device(dir, name);
+        Self::lock_file(&path, name)
+    }
+
+    fn lock_file(path: &Path, target: &str) -> Result<Self, Error> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .mode(0o600)
+            .custom_flags(libc::O_NOFOLLOW)
+            .open(path)
+            .map_err(|e| {
+                let msg = format!("Fail to open lock file {:?}: {}", path, e);
+                Error::new(FileError::Io, &msg)
+            })?;
+
+        flock(file.as_raw_fd(), FlockArg::LockExclusiveNonblock).map_err(|e| {
+            if e.as_errno() == Some(Errno::EAGAIN) {
+                let msg = format!(
+                    "The other instance of service program is running for {}",
+                    target
+                );
+                Error::new(FileError::Again, &msg)
+            } else {
+                let msg = format!("Fail to lock file {:?}: {}", path, e);
+                Error::new(FileError::Io, &msg)
+            }
+        })?;
+
+        Ok(Self { _file: file })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn instance_lock() {
+        let guid = 0x000d6c040000ab12;
+        let dir = std::env::temp_dir().join(format!("instance-lock-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        assert_eq!(
+            instance_lock_path(&dir, guid),
+            dir.join("snd-firewire-ctl-services-000d6c040000ab12.lock")
+        );
+
+        let lock = InstanceLock::acquire_in(&dir, guid).unwrap();
+        let err = InstanceLock::acquire_in(&dir, guid).unwrap_err();
+        assert_eq!(err.kind::<FileError>(), Some(FileError::Again));
+
+        drop(lock);
+        assert!(InstanceLock::acquire_in(&dir, guid).is_ok());
+
+        assert_eq!(
+            instance_lock_path_by_device(&dir, "card1"),
+            dir.join("snd-firewire-ctl-services-card1.lock")
+        );
+        let lock = InstanceLock::acquire_by_device_in(&dir, "card1").unwrap();
+        let err = InstanceLock::acquire_by_device_in(&dir, "card1").unwrap_err();
+        assert_eq!(err.kind::<FileError>(), Some(FileError::Again));
+        drop(lock);
+
+        // The symbolic link is not followed.
+        let link_guid = 0x000d6c040000ab13;
+        let target = dir.join("target");
+        std::os::unix::fs::symlink(&target, instance_lock_path(&dir, link_guid)).unwrap();
+        assert!(InstanceLock::acquire_in(&dir, link_guid).is_err());
+        assert!(!target.exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Helper to forward operation of knob and button on top panel to ALSA Sequencer.
+//!
+//! Some models report the state of knob and button on top panel, which is retrieved by polling.
+//! The helper compares the state with the previous one, then delivers the change as MIDI control
+//! change message via the port of ALSA Sequencer client, so that sequencer applications can
+//! follow the operation of hardware as well as software mixers via control elements. The state
+//! is retrieved by the metering timer, thus it is forwarded as long as the timer is enabled.
+
+use {
+    super::*,
+    alsaseq::{prelude::*, *},
+    glib::FileError,
+};
+
+/// The parameter of value forwarded as MIDI control change message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct KnobForwardParam {
+    /// The number of MIDI control change.
+    pub ctl_num: u32,
+    /// The minimum value of control.
+    pub min: i32,
+    /// The maximum value of control.
+    pub max: i32,
+}
+
+/// Scale the value of control to the range of MIDI control change message.
+pub fn scale_to_midi_value(val: i32, min: i32, max: i32) -> i32 {
+    if max <= min {
+        0
+    } else {
+        (val.clamp(min, max) - min) * 127 / (max - min)
+    }
+}
+
+#[derive(Debug)]
+struct KnobForwardPort {
+    client: UserClient,
+    port_id: u8,
+    event: Event,
+}
+
+impl Drop for KnobForwardPort {
+    fn drop(&mut self) {
+        let _ = self.client.delete_port(self.port_id);
+    }
+}
+
+/// The state of forwarding.
+#[derive(Default, Debug)]
+pub struct KnobForwarder {
+    port: Option<KnobForwardPort>,
+    prev: Vec<i32>,
+}
+
+impl KnobForwarder {
+    const SEQ_PORT_NAME: &'static str = "Knob";
+
+    /// Whether to forward the change.
+    pub fn is_enabled(&self) -> bool {
+        self.port.is_some()
+    }
+
+    /// Add ALSA Sequencer client and port to start forwarding.
+    pub fn enable(&mut self, client_name: &str) -> Result<(), Error> {
+        if self.port.is_some() {
+            return Ok(());
+        }
+
+        let client = UserClient::new();
+        client.open(0)?;
+
+        let info = ClientInfo::new();
+        info.set_name(Some(client_name));
+        client.set_info(&info)?;
+
+        let mut info = PortInfo::new();
+        info.set_attrs(PortAttrFlag::MIDI_GENERIC | PortAttrFlag::HARDWARE);
+        info.set_caps(PortCapFlag::READ | PortCapFlag::SUBS_READ);
+        info.set_name(Some(Self::SEQ_PORT_NAME));
+        client.create_port(&mut info)?;
+        let port_id = info
+            .addr()
+            .map(|addr| addr.port_id())
+            .ok_or_else(|| Error::new(FileError::Io, "Fail to get address for added port."))?;
+
+        let mut event = Event::new(EventType::Controller);
+        event.set_queue_id(SpecificAddress::Subscribers.into());
+
+        // The whole state is delivered at first.
+        self.prev.clear();
+        self.port = Some(KnobForwardPort {
+            client,
+            port_id,
+            event,
+        });
+
+        Ok(())
+    }
+
+    /// Remove ALSA Sequencer client to stop forwarding.
+    pub fn disable(&mut self) {
+        self.port = None;
+    }
+
+    /// Deliver the change of values as MIDI control change messages.
+    pub fn forward(&mut self, params: &[KnobForwardParam], vals: &[i32]) -> Result<(), Error> {
+        let changes = self.detect_changes(vals);
+
+        if let Some(port) = &mut self.port {
+            changes.iter().try_for_each(|&(i, val)| {
+                let param = &params[i];
+                let mut data = port.event.ctl_data()?;
+                data.set_channel(0);
+                data.set_param(param.ctl_num);
+                data.set_value(scale_to_midi_value(val, param.min, param.max));
+                port.event.set_ctl_data(&data)?;
+                port.client.schedule_event(&port.event)
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    fn detect_changes(&mut self, vals: &[i32]) -> Vec<(usize, i32)> {
+        let changes = vals
+            .iter()
+            .enumerate()
+            .filter(|&(i, val)| self.prev.get(i) != Some(val))
+            .map(|(i, &val)| (i, val))
+            .collect();
+        self.prev = vals.to_vec();
+        changes
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn knob_forward_changes() {
+        assert_eq!(scale_to_midi_value(0, 0, 64), 0);
+        assert_eq!(scale_to_midi_value(64, 0, 64), 127);
+        assert_eq!(scale_to_midi_value(32, 0, 64), 63);
+        assert_eq!(scale_to_midi_value(80, 10, 75), 127);
+        assert_eq!(scale_to_midi_value(1, 1, 1), 0);
+
+        let mut forwarder = KnobForwarder::default();
+        assert_eq!(
+            forwarder.detect_changes(&[1, 2, 3]),
+            vec![(0, 1), (1, 2), (2, 3)]
+        );
+        assert_eq!(forwarder.detect_changes(&[1, 4, 3]), vec![(1, 4)]);
+        assert_eq!(forwarder.detect_changes(&[1, 4, 3]), vec![]);
+    }
+}
@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Ramp of level for channels by intermediate values.
+//!
+//! Some devices change level in coarse steps, and zipper noise or pops are audible when the level
+//! is changed at once. The helper interpolates between the current level and the target level
+//! over the configured duration, so that the runtime writes the intermediate levels at each tick
+//! of timer instead of waiting for them in the event loop.
+
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RampEntry {
+    ch: usize,
+    from: i32,
+    to: i32,
+    started: Instant,
+}
+
+/// The state of ramp for channels.
+#[derive(Default, Debug, Clone)]
+pub struct LevelRamp {
+    duration: Duration,
+    entries: Vec<RampEntry>,
+}
+
+impl LevelRamp {
+    /// The interval of timer to advance ramp.
+    pub const INTERVAL: Duration = Duration::from_millis(10);
+
+    /// Configure the duration of ramp. Zero disables ramp.
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    /// The duration of ramp.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Whether any channel is under ramp.
+    pub fn is_active(&self) -> bool {
+        !self.entries.is_empty()
+    }
+
+    /// The target level of channel under ramp.
+    pub fn target(&self, ch: usize) -> Option<i32> {
+        self.entries.iter().find(|e| e.ch == ch).map(|e| e.to)
+    }
+
+    /// Start ramp of channel from the current level to the target level. The ramp in progress
+    /// for the channel is replaced. Return false when ramp is not required, thus the target level
+    /// should be applied at once.
+    pub fn start(&mut self, ch: usize, from: i32, to: i32, now: Instant) -> bool {
+        self.entries.retain(|e| e.ch != ch);
+
+        if self.duration.is_zero() || from == to {
+            false
+        } else {
+            self.entries.push(RampEntry {
+                ch,
+                from,
+                to,
+                started: now,
+            });
+            true
+        }
+    }
+
+    /// Finish ramp for all channels at once. Return the pairs of channel and target level.
+    pub fn finish(&mut self) -> Vec<(usize, i32)> {
+        self.entries.drain(..).map(|e| (e.ch, e.to)).collect()
+    }
+
+    /// Compute the levels of channels under ramp at the time. Each intermediate level is aligned
+    /// to the step. The channel reaching the target level is removed from the ramp.
+    pub fn advance(&mut self, now: Instant, step: i32) -> Vec<(usize, i32)> {
+        let duration = self.duration;
+        let levels = self
+            .entries
+            .iter()
+            .map(|e| {
+                let elapsed = now.saturating_duration_since(e.started);
+                (e.ch, interpolate(e.from, e.to, step, elapsed, duration))
+            })
+            .collect();
+        self.entries
+            .retain(|e| now.saturating_duration_since(e.started) < duration);
+        levels
+    }
+}
+
+fn interpolate(from: i32, to: i32, step: i32, elapsed: Duration, duration: Duration) -> i32 {
+    if elapsed >= duration {
+        to
+    } else {
+        let step = std::cmp::max(step, 1) as i64;
+        let distance = (to - from) as i64;
+        let delta = distance * elapsed.as_micros() as i64 / duration.as_micros() as i64;
+        from + (delta / step * step) as i32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn level_ramp_interpolation() {
+        let duration = Duration::from_millis(40);
+        assert_eq!(interpolate(0, 0x400, 0x100, Duration::ZERO, duration), 0);
+        assert_eq!(
+            interpolate(0, 0x400, 0x100, Duration::from_millis(10), duration),
+            0x100
+        );
+        assert_eq!(
+            interpolate(0, 0x400, 0x100, Duration::from_millis(25), duration),
+            0x200
+        );
+        assert_eq!(
+            interpolate(0x400, 0, 0x100, Duration::from_millis(10), duration),
+            0x300
+        );
+        assert_eq!(
+            interpolate(0, 0x400, 0x100, Duration::from_millis(50), duration),
+            0x400
+        );
+    }
+
+    #[test]
+    fn level_ramp_advance() {
+        let now = Instant::now();
+        let mut ramp = LevelRamp::default();
+        assert!(!ramp.start(0, 0, 0x400, now));
+        assert!(!ramp.is_active());
+
+        ramp.set_duration(Duration::from_millis(40));
+        assert!(!ramp.start(0, 0x400, 0x400, now));
+        assert!(ramp.start(0, 0, 0x400, now));
+        assert!(ramp.start(1, 0x400, 0, now));
+        assert_eq!(ramp.target(1), Some(0));
+
+        let levels = ramp.advance(now + Duration::from_millis(20), 0x100);
+        assert_eq!(levels, vec![(0, 0x200), (1, 0x200)]);
+        assert!(ramp.is_active());
+
+        let levels = ramp.advance(now + Duration::from_millis(40), 0x100);
+        assert_eq!(levels, vec![(0, 0x400), (1, 0)]);
+        assert!(!ramp.is_active());
+
+        assert!(ramp.start(0, 0, 0x400, now));
+        assert_eq!(ramp.finish(), vec![(0, 0x400)]);
+        assert!(!ramp.is_active());
+    }
+}
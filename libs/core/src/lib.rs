@@ -1,8 +1,39 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 // Copyright (c) 2020 Takashi Sakamoto
 pub mod card_cntr;
+pub mod card_guid;
+pub mod diagnostics;
 pub mod dispatcher;
+pub mod driver_caps;
+pub mod elem_alias;
+pub mod elem_batch;
+pub mod elem_locale;
 pub mod elem_value_accessor;
+pub mod fader_taper;
+pub mod feature_report;
+pub mod gain_staging;
+pub mod instance_lock;
+pub mod knob_forward;
+pub mod level_ramp;
+pub mod link_quirk;
+pub mod meter_trigger;
+pub mod metrics;
+pub mod midi_activity;
+pub mod midi_map;
+pub mod mirror;
+pub mod mono_fold;
+pub mod node_devices;
+pub mod panic_ctl;
+pub mod passed_fds;
+pub mod ref_level;
+pub mod sandbox;
+pub mod scrub;
+pub mod solo_bus;
+pub mod stream_guard;
+pub mod stream_stats;
+pub mod typed_elem;
+#[cfg(feature = "vendor-cmd-console")]
+pub mod vendor_cmd;
 
 use glib::Error;
 
@@ -10,4 +41,10 @@ pub trait RuntimeOperation<T>: Sized {
     fn new(arg: T) -> Result<Self, Error>;
     fn listen(&mut self) -> Result<(), Error>;
     fn run(&mut self) -> Result<(), Error>;
+
+    /// The path to open the character device for the name; e.g. hwC1D0 and fw1. The file
+    /// descriptor passed by the launcher is used instead of the node in /dev if available.
+    fn devnode_path(name: &str) -> String {
+        passed_fds::devnode_path(name)
+    }
 }
@@ -0,0 +1,186 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Expert option to restrict asynchronous transactions for specific nodes.
+//!
+//! Some devices fail transactions with large payload in long cable runs or with flaky
+//! connectors. The module includes the loader of the table of quirks for nodes, given by the
+//! environment variable as a text file. Each line consists of GUID of node in hexadecimal and
+//! the list of options. The line begins with hash sign is comment.
+//!
+//! ```text
+//! # GUID             options
+//! 0x00130e0401400045 speed=S100
+//! 0x000d6c040000ab12 payload=256
+//! ```
+//!
+//! The speed of transaction is decided by Linux FireWire subsystem according to the topology of
+//! bus, and it can not be forced by userspace applications. The speed option restricts the
+//! payload of block transactions to the maximum for the speed instead, which is effective for
+//! the most of cases. The payload option restricts it further.
+//!
+//! The quirk is applied to the runtime for DICE family only, in which the content of register
+//! space is transferred by block transactions split into frames of arbitrary size. The protocols
+//! of the other families define the layout of each transaction; e.g. AV/C command in FCP frame,
+//! therefore the payload can not be split without verification by the actual device. Like the
+//! other optional configurations, the table is given by the environment variable, since the
+//! runtime has no configuration file.
+
+use {super::*, glib::FileError, std::collections::HashMap};
+
+/// The environment variable for the path to the file of link quirks.
+pub const LINK_QUIRK_ENV: &str = "SND_FIREWIRE_CTL_LINK_QUIRK";
+
+/// The speed of link.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LinkSpeed {
+    S100,
+    S200,
+    S400,
+    S800,
+}
+
+impl LinkSpeed {
+    /// The maximum size of payload for asynchronous packet in the speed.
+    pub fn max_payload(&self) -> usize {
+        match self {
+            Self::S100 => 512,
+            Self::S200 => 1024,
+            Self::S400 => 2048,
+            Self::S800 => 4096,
+        }
+    }
+}
+
+fn parse_link_speed(arg: &str) -> Option<LinkSpeed> {
+    match arg {
+        "S100" | "s100" => Some(LinkSpeed::S100),
+        "S200" | "s200" => Some(LinkSpeed::S200),
+        "S400" | "s400" => Some(LinkSpeed::S400),
+        "S800" | "s800" => Some(LinkSpeed::S800),
+        _ => None,
+    }
+}
+
+/// The quirk of link for the node.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct LinkQuirk {
+    /// The speed to which payload is restricted.
+    pub speed: Option<LinkSpeed>,
+    /// The size of payload in bytes.
+    pub payload: Option<usize>,
+}
+
+impl LinkQuirk {
+    /// The maximum size of payload in bytes for block transactions, aligned to quadlet.
+    pub fn max_payload(&self) -> Option<usize> {
+        let speed = self.speed.map(|speed| speed.max_payload());
+        let size = match (speed, self.payload) {
+            (Some(s), Some(p)) => Some(std::cmp::min(s, p)),
+            (s, p) => s.or(p),
+        };
+        size.map(|size| std::cmp::max(size / 4 * 4, 4))
+    }
+
+    /// Find the quirk for the node when the environment variable for the file is given.
+    pub fn from_env(guid: u64) -> Option<Result<Self, Error>> {
+        std::env::var_os(LINK_QUIRK_ENV).and_then(|path| {
+            let res = std::fs::read_to_string(&path)
+                .map_err(|e| {
+                    let msg = format!("Fail to read link quirks {:?}: {}", path, e);
+                    Error::new(FileError::Io, &msg)
+                })
+                .and_then(|text| parse_link_quirks(&text));
+            match res {
+                Ok(mut quirks) => quirks.remove(&guid).map(Ok),
+                Err(e) => Some(Err(e)),
+            }
+        })
+    }
+}
+
+fn parse_link_quirk_option(quirk: &mut LinkQuirk, option: &str) -> Option<()> {
+    let (key, val) = option.split_once('=')?;
+    match key {
+        "speed" => quirk.speed = Some(parse_link_speed(val)?),
+        "payload" => quirk.payload = Some(val.parse().ok().filter(|&size| size >= 4)?),
+        _ => return None,
+    }
+    Some(())
+}
+
+/// Parse the text of link quirks into the table of GUID and quirk.
+pub fn parse_link_quirks(text: &str) -> Result<HashMap<u64, LinkQuirk>, Error> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            let mut fields = line.split_whitespace();
+            fields
+                .next()
+                .and_then(card_guid::parse_guid)
+                .and_then(|guid| {
+                    let mut quirk = LinkQuirk::default();
+                    fields
+                        .try_for_each(|option| parse_link_quirk_option(&mut quirk, option))
+                        .map(|_| (guid, quirk))
+                })
+                .ok_or_else(|| {
+                    let msg = format!("Invalid entry of link quirks at line {}: {}", i + 1, line);
+                    Error::new(FileError::Inval, &msg)
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn link_quirks_parse() {
+        let text = "
+            # comment
+            0x00130e0401400045 speed=S100
+            000d6c040000ab12 payload=256 speed=s200
+        ";
+        let quirks = parse_link_quirks(text).unwrap();
+        assert_eq!(
+            quirks.get(&0x00130e0401400045),
+            Some(&LinkQuirk {
+                speed: Some(LinkSpeed::S100),
+                payload: None,
+            })
+        );
+        assert_eq!(
+            quirks.get(&0x000d6c040000ab12),
+            Some(&LinkQuirk {
+                speed: Some(LinkSpeed::S200),
+                payload: Some(256),
+            })
+        );
+
+        assert!(parse_link_quirks("0x0g speed=S100").is_err());
+        assert!(parse_link_quirks("0x1 speed=S1600").is_err());
+        assert!(parse_link_quirks("0x1 payload=2").is_err());
+        assert!(parse_link_quirks("0x1 retries=3").is_err());
+    }
+
+    #[test]
+    fn link_quirk_payload() {
+        let quirk = LinkQuirk {
+            speed: Some(LinkSpeed::S100),
+            payload: None,
+        };
+        assert_eq!(quirk.max_payload(), Some(512));
+
+        let quirk = LinkQuirk {
+            speed: Some(LinkSpeed::S200),
+            payload: Some(258),
+        };
+        assert_eq!(quirk.max_payload(), Some(256));
+
+        assert_eq!(LinkQuirk::default().max_payload(), None);
+    }
+}
@@ -0,0 +1,387 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Trigger of MIDI messages by level of meter.
+//!
+//! The helper compares the value in meter elements with threshold after measurement, then delivers
+//! MIDI note or control change message via the port of ALSA Sequencer client when the value rises
+//! to the threshold, and when it falls below the threshold minus hysteresis. Sequencer
+//! applications can start recording or lighting cues by the presence of signal.
+//!
+//! The configuration is a text file given by the environment variable. Each line consists of the
+//! name of element, the index of element, the position of value in the element, equal sign, the
+//! threshold, the hysteresis, MIDI channel (1-16), the type of message ('note' or 'cc'), and the
+//! number of note or control. The note on message or the control change message with value 127
+//! is delivered at rising, and the note off message or the control change message with value 0 at
+//! falling. The line begins with hash sign is comment. The trigger is optional, thus the failure
+//! is recorded in the report of features instead of stopping the runtime. The trigger is disabled
+//! after the failure at measurement, so that the same failure is not repeated at each measurement.
+//!
+//! ```text
+//! # name index position = threshold hysteresis channel type number
+//! analog-output-meter 0 0 = 1000000 100000 1 note 60
+//! analog-input-meter 1 0 = 1000000 100000 1 cc 20
+//! ```
+
+use {
+    super::{card_cntr::*, feature_report::*, *},
+    alsactl::{prelude::*, *},
+    alsaseq::{
+        prelude::*, ClientInfo, Event, EventType, PortAttrFlag, PortCapFlag, PortInfo,
+        SpecificAddress, UserClient,
+    },
+    glib::FileError,
+};
+
+/// The environment variable for the path to the file of meter trigger.
+pub const METER_TRIGGER_ENV: &str = "SND_FIREWIRE_CTL_METER_TRIGGER";
+
+/// The name of feature in the report for meter trigger.
+pub const METER_TRIGGER_FEATURE_NAME: &str = "meter-trigger";
+
+/// The type of MIDI message delivered by the trigger.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MeterTriggerMsg {
+    /// Note on and off message with the number of note.
+    Note(u8),
+    /// Control change message with the number of control.
+    Ctl(u32),
+}
+
+/// The entry of meter trigger.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MeterTriggerEntry {
+    /// The name of element.
+    pub name: String,
+    /// The index of element.
+    pub index: u32,
+    /// The position of value in the element.
+    pub pos: usize,
+    /// The value to rise the trigger.
+    pub threshold: i32,
+    /// The margin below the threshold to fall the trigger.
+    pub hysteresis: i32,
+    /// The MIDI channel between 0 and 15.
+    pub channel: u8,
+    /// The type of message.
+    pub msg: MeterTriggerMsg,
+}
+
+fn parse_meter_trigger_entry(line: &str) -> Option<MeterTriggerEntry> {
+    let (elem, trigger) = line.split_once('=')?;
+
+    let mut fields = elem.split_whitespace();
+    let name = fields.next()?.to_string();
+    let index = fields.next()?.parse().ok()?;
+    let pos = fields.next()?.parse().ok()?;
+    if fields.next().is_some() {
+        return None;
+    }
+
+    let mut fields = trigger.split_whitespace();
+    let threshold = fields.next()?.parse().ok()?;
+    let hysteresis = fields.next()?.parse().ok().filter(|&val: &i32| val >= 0)?;
+    let channel = fields
+        .next()?
+        .parse::<u8>()
+        .ok()
+        .filter(|ch| (1..=16).contains(ch))?;
+    let msg = match fields.next()? {
+        "note" => fields
+            .next()?
+            .parse::<u8>()
+            .ok()
+            .filter(|&num| num < 128)
+            .map(MeterTriggerMsg::Note)?,
+        "cc" => fields
+            .next()?
+            .parse::<u32>()
+            .ok()
+            .filter(|&num| num < 128)
+            .map(MeterTriggerMsg::Ctl)?,
+        _ => return None,
+    };
+    if fields.next().is_some() {
+        return None;
+    }
+
+    Some(MeterTriggerEntry {
+        name,
+        index,
+        pos,
+        threshold,
+        hysteresis,
+        channel: channel - 1,
+        msg,
+    })
+}
+
+/// Parse the text of meter trigger into the entries.
+pub fn parse_meter_trigger(text: &str) -> Result<Vec<MeterTriggerEntry>, Error> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            parse_meter_trigger_entry(line).ok_or_else(|| {
+                let msg = format!("Invalid entry of meter trigger at line {}: {}", i + 1, line);
+                Error::new(FileError::Inval, &msg)
+            })
+        })
+        .collect()
+}
+
+/// Detect the transition of trigger. The new state is returned when the trigger rises or falls.
+pub fn detect_trigger(active: bool, val: i32, threshold: i32, hysteresis: i32) -> Option<bool> {
+    if !active && val >= threshold {
+        Some(true)
+    } else if active && val < threshold.saturating_sub(hysteresis) {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[derive(Debug)]
+struct MeterTriggerBinding {
+    entry: MeterTriggerEntry,
+    elem_id: ElemId,
+    active: bool,
+}
+
+#[derive(Debug)]
+struct MeterTriggerPort {
+    client: UserClient,
+    port_id: u8,
+}
+
+impl Drop for MeterTriggerPort {
+    fn drop(&mut self) {
+        let _ = self.client.delete_port(self.port_id);
+    }
+}
+
+impl MeterTriggerPort {
+    fn deliver(&self, entry: &MeterTriggerEntry, active: bool) -> Result<(), Error> {
+        let val = if active { 127 } else { 0 };
+
+        match entry.msg {
+            MeterTriggerMsg::Note(note) => {
+                let ev_type = if active {
+                    EventType::Noteon
+                } else {
+                    EventType::Noteoff
+                };
+                let mut event = Event::new(ev_type);
+                event.set_queue_id(SpecificAddress::Subscribers.into());
+                let mut data = event.note_data()?;
+                data.set_channel(entry.channel);
+                data.set_note(note);
+                data.set_velocity(val as u8);
+                event.set_note_data(&data)?;
+                self.client.schedule_event(&event)
+            }
+            MeterTriggerMsg::Ctl(ctl_num) => {
+                let mut event = Event::new(EventType::Controller);
+                event.set_queue_id(SpecificAddress::Subscribers.into());
+                let mut data = event.ctl_data()?;
+                data.set_channel(entry.channel);
+                data.set_param(ctl_num);
+                data.set_value(val);
+                event.set_ctl_data(&data)?;
+                self.client.schedule_event(&event)
+            }
+        }
+    }
+}
+
+/// The state of meter trigger.
+#[derive(Default, Debug)]
+pub struct MeterTrigger {
+    port: Option<MeterTriggerPort>,
+    bindings: Vec<MeterTriggerBinding>,
+}
+
+impl MeterTrigger {
+    const SEQ_PORT_NAME: &'static str = "Meter Trigger";
+
+    /// Bind the meter elements in the configuration given by the environment variable, then add
+    /// ALSA Sequencer client and port to deliver messages. Nothing is added unless the variable
+    /// is given.
+    pub fn load(&mut self, card_cntr: &CardCntr, client_name: &str) -> Result<(), Error> {
+        let path = match std::env::var_os(METER_TRIGGER_ENV) {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let text = std::fs::read_to_string(&path).map_err(|e| {
+            let msg = format!("Fail to read meter trigger {:?}: {}", path, e);
+            Error::new(FileError::Io, &msg)
+        })?;
+        let entries = parse_meter_trigger(&text)?;
+
+        let elem_id_list = card_cntr.card.elem_id_list()?;
+        let mut bindings = Vec::new();
+        entries.into_iter().try_for_each(|entry| {
+            let elem_id = elem_id_list
+                .iter()
+                .find(|elem_id| elem_id.name() == entry.name && elem_id.index() == entry.index)
+                .cloned()
+                .ok_or_else(|| {
+                    let msg = format!("{}[{}] is not found", entry.name, entry.index);
+                    Error::new(FileError::Noent, &msg)
+                })?;
+            let elem_info = card_cntr.card.elem_info(&elem_id)?;
+            let count = match &elem_info {
+                ElemInfo::Integer(info) => Ok(info.value_count() as usize),
+                _ => {
+                    let msg = format!("{} is not available for meter trigger", entry.name);
+                    Err(Error::new(FileError::Inval, &msg))
+                }
+            }?;
+            if entry.pos >= count {
+                let msg = format!("Invalid position for {}: {}", entry.name, entry.pos);
+                return Err(Error::new(FileError::Inval, &msg));
+            }
+            bindings.push(MeterTriggerBinding {
+                entry,
+                elem_id,
+                active: false,
+            });
+            Ok(())
+        })?;
+
+        let client = UserClient::new();
+        client.open(0)?;
+
+        let info = ClientInfo::new();
+        info.set_name(Some(client_name));
+        client.set_info(&info)?;
+
+        let mut info = PortInfo::new();
+        info.set_attrs(PortAttrFlag::MIDI_GENERIC | PortAttrFlag::APPLICATION);
+        info.set_caps(PortCapFlag::READ | PortCapFlag::SUBS_READ);
+        info.set_name(Some(Self::SEQ_PORT_NAME));
+        client.create_port(&mut info)?;
+        let port_id = info
+            .addr()
+            .map(|addr| addr.port_id())
+            .ok_or_else(|| Error::new(FileError::Io, "Fail to get address for added port."))?;
+
+        self.bindings = bindings;
+        self.port = Some(MeterTriggerPort { client, port_id });
+
+        Ok(())
+    }
+
+    /// Whether the configuration is loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.port.is_some()
+    }
+
+    /// Compare the value in the meter elements with the thresholds, then deliver messages for the
+    /// transition. It should be called after measurement. The trigger is disabled after failure.
+    pub fn check(&mut self, card_cntr: &CardCntr) -> Result<(), Error> {
+        let port = match &self.port {
+            Some(port) => port,
+            None => return Ok(()),
+        };
+
+        let res = self.bindings.iter_mut().try_for_each(|binding| {
+            let mut elem_value = ElemValue::new();
+            card_cntr
+                .card
+                .read_elem_value(&binding.elem_id, &mut elem_value)?;
+            let val = elem_value.int()[binding.entry.pos];
+
+            match detect_trigger(
+                binding.active,
+                val,
+                binding.entry.threshold,
+                binding.entry.hysteresis,
+            ) {
+                Some(active) => port
+                    .deliver(&binding.entry, active)
+                    .map(|_| binding.active = active),
+                None => Ok(()),
+            }
+        });
+
+        if res.is_err() {
+            self.port = None;
+        }
+
+        res
+    }
+
+    /// Load the configuration, and record the failure in the report of features. It should be
+    /// called after adding elements.
+    pub fn setup(&mut self, card_cntr: &CardCntr, client_name: &str) {
+        if let Err(e) = self.load(card_cntr, client_name) {
+            report_feature(METER_TRIGGER_FEATURE_NAME, &e.to_string());
+        }
+    }
+
+    /// Check the meter elements, and record the failure in the report of features, then update
+    /// the element for the report.
+    pub fn check_and_report(&mut self, card_cntr: &mut CardCntr, feature_report: &FeatureReport) {
+        if let Err(e) = self.check(card_cntr) {
+            report_feature(METER_TRIGGER_FEATURE_NAME, &e.to_string());
+            let _ = feature_report.update(card_cntr);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn meter_trigger_parse() {
+        let text = "
+            # comment
+            output-meter 0 0 = 1000000 100000 1 note 60
+
+            input-meter 1 2=-40 3 16 cc 20
+        ";
+        let entries = parse_meter_trigger(text).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                MeterTriggerEntry {
+                    name: "output-meter".to_string(),
+                    index: 0,
+                    pos: 0,
+                    threshold: 1000000,
+                    hysteresis: 100000,
+                    channel: 0,
+                    msg: MeterTriggerMsg::Note(60),
+                },
+                MeterTriggerEntry {
+                    name: "input-meter".to_string(),
+                    index: 1,
+                    pos: 2,
+                    threshold: -40,
+                    hysteresis: 3,
+                    channel: 15,
+                    msg: MeterTriggerMsg::Ctl(20),
+                },
+            ]
+        );
+
+        assert!(parse_meter_trigger("output-meter 0 0 = 100 10 17 note 60").is_err());
+        assert!(parse_meter_trigger("output-meter 0 0 = 100 -1 1 note 60").is_err());
+        assert!(parse_meter_trigger("output-meter 0 0 = 100 10 1 pc 60").is_err());
+        assert!(parse_meter_trigger("output-meter 0 0 = 100 10 1 cc 128").is_err());
+        assert!(parse_meter_trigger("output-meter 0 = 100 10 1 cc 20").is_err());
+    }
+
+    #[test]
+    fn meter_trigger_detect() {
+        assert_eq!(detect_trigger(false, 99, 100, 10), None);
+        assert_eq!(detect_trigger(false, 100, 100, 10), Some(true));
+        assert_eq!(detect_trigger(true, 95, 100, 10), None);
+        assert_eq!(detect_trigger(true, 90, 100, 10), None);
+        assert_eq!(detect_trigger(true, 89, 100, 10), Some(false));
+        assert_eq!(detect_trigger(false, 89, 100, 10), None);
+    }
+}
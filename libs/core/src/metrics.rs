@@ -0,0 +1,348 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Metrics of the unit for monitoring.
+//!
+//! The module includes counters of transactions, notifications, and timer for the unit handled by
+//! service program. They are exported by read-only bytes element, and optionally by HTTP/1.0
+//! response with text in Prometheus exposition format via UNIX domain socket when the environment
+//! variable for the directory of socket is given. Prometheus scrapes targets over TCP only, thus
+//! the socket is expected to be forwarded by the other program, or to be read by HTTP client
+//! supporting UNIX domain socket; e.g. `curl --unix-socket`.
+
+use {
+    super::*,
+    glib::FileError,
+    std::{
+        io::{BufRead, BufReader, Write},
+        os::unix::{
+            fs::FileTypeExt,
+            net::{UnixListener, UnixStream},
+        },
+        path::PathBuf,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex,
+        },
+        thread,
+        time::Duration,
+    },
+};
+
+/// The environment variable for the directory to put UNIX domain socket for metrics.
+pub const METRICS_DIR_ENV: &str = "SND_FIREWIRE_CTL_METRICS_DIR";
+
+/// The name of feature in the report for export of metrics.
+pub const METRICS_EXPORTER_FEATURE_NAME: &str = "metrics-exporter";
+
+/// The counters for the unit.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UnitMetrics {
+    /// The number of transactions including retries.
+    pub transactions: u64,
+    /// The number of retries after transient failure.
+    pub retries: u64,
+    /// The number of transactions without response in time.
+    pub timeouts: u64,
+    /// The number of transactions finally failed.
+    pub failures: u64,
+    /// The number of notifications from the unit.
+    pub notifications: u64,
+    /// The number of timer events which took longer than the interval of timer.
+    pub timer_overruns: u64,
+    /// The number of dispatched events for control elements.
+    pub dispatches: u64,
+    /// The total latency to dispatch the events in microseconds.
+    pub dispatch_latency_total_us: u64,
+    /// The maximum latency to dispatch the events in microseconds.
+    pub dispatch_latency_max_us: u64,
+    /// The duration to load the model and cache the state of unit in microseconds.
+    pub load_duration_us: u64,
+    /// The number of transitions to XRUN state in PCM substreams.
+    pub stream_xruns: u64,
+    /// The number of loss of stream lock.
+    pub stream_unlocks: u64,
+    /// The number of bus reset.
+    pub bus_resets: u64,
+}
+
+impl UnitMetrics {
+    /// The size of raw data for bytes element.
+    pub const SIZE: usize = 104;
+
+    /// Record the latency to dispatch event for control element.
+    pub fn record_dispatch(&mut self, latency: Duration) {
+        let latency_us = latency.as_micros() as u64;
+        self.dispatches += 1;
+        self.dispatch_latency_total_us += latency_us;
+        if latency_us > self.dispatch_latency_max_us {
+            self.dispatch_latency_max_us = latency_us;
+        }
+    }
+
+    /// Record the duration to load the model.
+    pub fn record_load(&mut self, elapsed: Duration) {
+        self.load_duration_us = elapsed.as_micros() as u64;
+    }
+
+    /// Record the duration of timer event.
+    pub fn record_timer(&mut self, elapsed: Duration, interval: Duration) {
+        if elapsed > interval {
+            self.timer_overruns += 1;
+        }
+    }
+
+    fn entries(&self) -> [(&'static str, &'static str, u64); 13] {
+        [
+            (
+                "transactions_total",
+                "The number of transactions including retries.",
+                self.transactions,
+            ),
+            (
+                "retries_total",
+                "The number of retries after transient failure.",
+                self.retries,
+            ),
+            (
+                "timeouts_total",
+                "The number of transactions without response in time.",
+                self.timeouts,
+            ),
+            (
+                "failures_total",
+                "The number of transactions finally failed.",
+                self.failures,
+            ),
+            (
+                "notifications_total",
+                "The number of notifications from the unit.",
+                self.notifications,
+            ),
+            (
+                "timer_overruns_total",
+                "The number of timer events longer than the interval.",
+                self.timer_overruns,
+            ),
+            (
+                "dispatches_total",
+                "The number of dispatched events for control elements.",
+                self.dispatches,
+            ),
+            (
+                "dispatch_latency_microseconds_total",
+                "The total latency to dispatch the events.",
+                self.dispatch_latency_total_us,
+            ),
+            (
+                "dispatch_latency_microseconds_max",
+                "The maximum latency to dispatch the events.",
+                self.dispatch_latency_max_us,
+            ),
+            (
+                "load_duration_microseconds",
+                "The duration to load the model and cache the state of unit.",
+                self.load_duration_us,
+            ),
+            (
+                "stream_xruns_total",
+                "The number of transitions to XRUN state in PCM substreams.",
+                self.stream_xruns,
+            ),
+            (
+                "stream_unlocks_total",
+                "The number of loss of stream lock.",
+                self.stream_unlocks,
+            ),
+            (
+                "bus_resets_total",
+                "The number of bus reset.",
+                self.bus_resets,
+            ),
+        ]
+    }
+
+    /// Serialize the counters in big endian order for bytes element.
+    pub fn to_raw(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(Self::SIZE);
+        self.entries()
+            .iter()
+            .for_each(|(_, _, val)| raw.extend_from_slice(&val.to_be_bytes()));
+        raw
+    }
+
+    /// Generate text in Prometheus exposition format.
+    pub fn to_prometheus(&self, service: &str, card_id: u32) -> String {
+        let mut text = String::new();
+        self.entries().iter().for_each(|(name, help, val)| {
+            text.push_str(&format!("# HELP snd_firewire_{} {}\n", name, help));
+            let kind = if name.ends_with("_total") {
+                "counter"
+            } else {
+                "gauge"
+            };
+            text.push_str(&format!("# TYPE snd_firewire_{} {}\n", name, kind));
+            text.push_str(&format!(
+                "snd_firewire_{}{{service=\"{}\",card=\"{}\"}} {}\n",
+                name, service, card_id, val
+            ));
+        });
+        text
+    }
+
+    /// Generate HTTP/1.0 response with the text in Prometheus exposition format.
+    pub fn to_http_response(&self, service: &str, card_id: u32) -> String {
+        let body = self.to_prometheus(service, card_id);
+        format!(
+            "HTTP/1.0 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    }
+}
+
+/// The exporter of metrics via UNIX domain socket. The HTTP/1.0 response is written to each
+/// connection after reading the header of request, then the connection is closed.
+pub struct MetricsExporter {
+    path: PathBuf,
+    metrics: Arc<Mutex<UnitMetrics>>,
+    running: Arc<AtomicBool>,
+    th: Option<thread::JoinHandle<()>>,
+}
+
+impl MetricsExporter {
+    // The client which sends no request in the duration is disconnected.
+    const REQUEST_TIMEOUT: Duration = Duration::from_millis(500);
+
+    // The header of request is not parsed, just skipped.
+    fn skip_request(stream: &UnixStream) {
+        let _ = stream.set_read_timeout(Some(Self::REQUEST_TIMEOUT));
+        let mut reader = BufReader::new(stream);
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match reader.read_line(&mut line) {
+                Ok(len) if len > 0 && !line.trim_end().is_empty() => (),
+                _ => break,
+            }
+        }
+    }
+
+    /// Start exporter when the environment variable for the directory of socket is given.
+    pub fn from_env(service: &str, card_id: u32) -> Option<Result<Self, Error>> {
+        std::env::var_os(METRICS_DIR_ENV).map(|dir| {
+            let path = PathBuf::from(dir).join(format!("{}-card{}.sock", service, card_id));
+            Self::start(path, service, card_id)
+        })
+    }
+
+    /// Start exporter with the path of socket.
+    pub fn start(path: PathBuf, service: &str, card_id: u32) -> Result<Self, Error> {
+        // Remove stale socket left by the previous process. The other type of file is not removed.
+        if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+            if metadata.file_type().is_socket() {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+
+        let listener = UnixListener::bind(&path).map_err(|e| {
+            let msg = format!("Fail to bind socket {}: {}", path.display(), e);
+            Error::new(FileError::Io, &msg)
+        })?;
+
+        let metrics = Arc::new(Mutex::new(UnitMetrics::default()));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let m = metrics.clone();
+        let r = running.clone();
+        let service = service.to_string();
+        // The thread blocks in accept(2) and is woken up by the connection from itself to stop.
+        let th = thread::spawn(move || {
+            for res in listener.incoming() {
+                if !r.load(Ordering::Relaxed) {
+                    break;
+                }
+                if let Ok(mut stream) = res {
+                    Self::skip_request(&stream);
+                    let text = m.lock().unwrap().to_http_response(&service, card_id);
+                    let _ = stream.write_all(text.as_bytes());
+                }
+            }
+        });
+
+        Ok(Self {
+            path,
+            metrics,
+            running,
+            th: Some(th),
+        })
+    }
+
+    /// Update the metrics to be exported.
+    pub fn update(&self, metrics: &UnitMetrics) {
+        *self.metrics.lock().unwrap() = *metrics;
+    }
+}
+
+impl Drop for MetricsExporter {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(th) = self.th.take() {
+            // The thread is not joined when failing to wake it up.
+            if UnixStream::connect(&self.path).is_ok() {
+                let _ = th.join();
+            }
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn unit_metrics_serialization() {
+        let mut metrics = UnitMetrics {
+            transactions: 10,
+            retries: 2,
+            timeouts: 1,
+            stream_xruns: 3,
+            ..Default::default()
+        };
+        metrics.record_dispatch(Duration::from_micros(300));
+        metrics.record_dispatch(Duration::from_micros(100));
+        metrics.record_timer(Duration::from_millis(60), Duration::from_millis(50));
+        metrics.record_timer(Duration::from_millis(10), Duration::from_millis(50));
+        metrics.record_load(Duration::from_millis(1500));
+
+        assert_eq!(metrics.dispatches, 2);
+        assert_eq!(metrics.dispatch_latency_total_us, 400);
+        assert_eq!(metrics.dispatch_latency_max_us, 300);
+        assert_eq!(metrics.timer_overruns, 1);
+        assert_eq!(metrics.load_duration_us, 1500000);
+
+        let raw = metrics.to_raw();
+        assert_eq!(raw.len(), UnitMetrics::SIZE);
+        assert_eq!(&raw[..8], &10u64.to_be_bytes());
+        assert_eq!(&raw[64..72], &300u64.to_be_bytes());
+        assert_eq!(&raw[72..80], &1500000u64.to_be_bytes());
+        assert_eq!(&raw[80..88], &3u64.to_be_bytes());
+
+        let text = metrics.to_prometheus("snd-bebob-ctl-service", 1);
+        assert!(text.contains(
+            "snd_firewire_transactions_total{service=\"snd-bebob-ctl-service\",card=\"1\"} 10\n"
+        ));
+        assert!(text.contains("# TYPE snd_firewire_dispatch_latency_microseconds_max gauge\n"));
+        assert!(text.contains("# TYPE snd_firewire_load_duration_microseconds gauge\n"));
+        assert!(text.contains("# TYPE snd_firewire_stream_xruns_total counter\n"));
+
+        let resp = metrics.to_http_response("snd-bebob-ctl-service", 1);
+        let expected = format!(
+            "HTTP/1.0 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n",
+            text.len()
+        );
+        assert!(resp.starts_with(&expected));
+        assert!(resp.ends_with(&text));
+    }
+}
@@ -0,0 +1,163 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Indicators of activity in MIDI ports.
+//!
+//! Some devices have no mechanism to report activity of MIDI ports. The helper snoops the byte
+//! counters of ALSA rawmidi substreams in procfs at each period of timer for metering, then the
+//! read-only elements blink while any byte is transferred in the period, so that users can
+//! confirm MIDI wiring from the mixer UI. The names of elements are the same as the ones
+//! for the ports reported by Fireworks devices.
+
+use {
+    super::{card_cntr::*, *},
+    alsactl::{prelude::*, *},
+};
+
+/// The name of element for activity of MIDI input ports.
+pub const MIDI_IN_DETECT_NAME: &str = "midi-in-detect";
+/// The name of element for activity of MIDI output ports.
+pub const MIDI_OUT_DETECT_NAME: &str = "midi-out-detect";
+
+/// Parse the content of rawmidi file in procfs, then retrieve the byte counters of substreams
+/// for input and output.
+pub fn parse_rawmidi_proc(text: &str) -> (Vec<u64>, Vec<u64>) {
+    let mut inputs = Vec::new();
+    let mut outputs = Vec::new();
+
+    text.lines().for_each(|line| {
+        let mut fields = line.splitn(2, ':');
+        let (key, val) = match (fields.next(), fields.next()) {
+            (Some(key), Some(val)) => (key.trim(), val.trim()),
+            _ => return,
+        };
+        if let Ok(count) = val.parse::<u64>() {
+            match key {
+                "Rx bytes" => inputs.push(count),
+                "Tx bytes" => outputs.push(count),
+                _ => (),
+            }
+        }
+    });
+
+    (inputs, outputs)
+}
+
+/// Detect activity by comparing the counters against the ones in the last period.
+pub fn detect_midi_activity(prev: &[u64], curr: &[u64]) -> Vec<bool> {
+    curr.iter()
+        .enumerate()
+        .map(|(i, &count)| prev.get(i).map(|&p| p != count).unwrap_or(false))
+        .collect()
+}
+
+/// The state of indicators for MIDI ports.
+#[derive(Default, Debug)]
+pub struct MidiActivity {
+    path: String,
+    inputs: Vec<u64>,
+    outputs: Vec<u64>,
+    elem_id_list: Vec<ElemId>,
+}
+
+impl MidiActivity {
+    fn read_counters(&self) -> Option<(Vec<u64>, Vec<u64>)> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .map(|text| parse_rawmidi_proc(&text))
+    }
+
+    /// Add the elements for the ports of rawmidi device in the sound card. Nothing is added
+    /// when the sound card has no rawmidi device.
+    pub fn load(&mut self, card_cntr: &mut CardCntr, card_id: u32) -> Result<(), Error> {
+        self.path = format!("/proc/asound/card{}/midi0", card_id);
+        let (inputs, outputs) = match self.read_counters() {
+            Some(counters) => counters,
+            None => return Ok(()),
+        };
+
+        if inputs.len() > 0 {
+            let elem_id = ElemId::new_by_name(ElemIfaceType::Rawmidi, 0, 0, MIDI_IN_DETECT_NAME, 0);
+            let mut elem_id_list = card_cntr.add_bool_elems(&elem_id, 1, inputs.len(), false)?;
+            self.elem_id_list.append(&mut elem_id_list);
+        }
+
+        if outputs.len() > 0 {
+            let elem_id =
+                ElemId::new_by_name(ElemIfaceType::Rawmidi, 0, 0, MIDI_OUT_DETECT_NAME, 0);
+            let mut elem_id_list = card_cntr.add_bool_elems(&elem_id, 1, outputs.len(), false)?;
+            self.elem_id_list.append(&mut elem_id_list);
+        }
+
+        self.inputs = inputs;
+        self.outputs = outputs;
+
+        Ok(())
+    }
+
+    /// Whether any element is added.
+    pub fn has_ports(&self) -> bool {
+        self.elem_id_list.len() > 0
+    }
+
+    /// Read the counters again, then update the elements.
+    pub fn measure(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
+        if !self.has_ports() {
+            return Ok(());
+        }
+
+        let (inputs, outputs) = match self.read_counters() {
+            Some(counters) => counters,
+            None => return Ok(()),
+        };
+
+        let in_detected = detect_midi_activity(&self.inputs, &inputs);
+        let out_detected = detect_midi_activity(&self.outputs, &outputs);
+        self.inputs = inputs;
+        self.outputs = outputs;
+
+        self.elem_id_list.iter().try_for_each(|elem_id| {
+            let detected = match elem_id.name().as_str() {
+                MIDI_IN_DETECT_NAME => &in_detected,
+                MIDI_OUT_DETECT_NAME => &out_detected,
+                _ => return Ok(()),
+            };
+
+            let mut elem_value = ElemValue::new();
+            card_cntr.card.read_elem_value(elem_id, &mut elem_value)?;
+            let count = detected.len();
+            if elem_value.boolean()[..count] != detected[..] {
+                elem_value.set_bool(detected);
+                card_cntr.card.write_elem_value(elem_id, &elem_value)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rawmidi_proc_parse() {
+        let text = "BeBoB MIDI\n\
+                    \n\
+                    Output 0\n  Tx bytes     : 120\n\
+                    Output 1\n  Tx bytes     : 0\n  Owner PID    : 1234\n  Mode         : native\n\
+                    Input 0\n  Rx bytes     : 3456\n";
+        let (inputs, outputs) = parse_rawmidi_proc(text);
+        assert_eq!(inputs, vec![3456]);
+        assert_eq!(outputs, vec![120, 0]);
+    }
+
+    #[test]
+    fn midi_activity_detection() {
+        assert_eq!(
+            detect_midi_activity(&[10, 20, 30], &[10, 25, 30]),
+            vec![false, true, false]
+        );
+        // The substream added after loading is not detected.
+        assert_eq!(detect_midi_activity(&[10], &[11, 5]), vec![true, false]);
+    }
+}
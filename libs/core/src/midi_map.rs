@@ -0,0 +1,467 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Mapping of MIDI control change messages to elements.
+//!
+//! The helper adds the port of ALSA Sequencer client to receive MIDI control change messages, then
+//! scales the value of message to the range of element bound to the control, so that users can
+//! operate mixers in the device by any MIDI fader box without external middleware. The value is
+//! written to the element, thus the change is propagated to the device by the model as the change
+//! of value in the element.
+//!
+//! The map is a configuration file of which path is given by the environment variable, since the
+//! runtime has no configuration file of its own. The map is optional, thus the runtime is expected
+//! to record the failure to load it in the report of features and continue. Each line consists of
+//! MIDI channel (1-16) and the number of control, equal sign, the name of element, the index of
+//! element, the position of value in the element, and optional mode. In 'pickup' mode, the
+//! message is ignored until the position of fader reaches the current value of element, to avoid
+//! sudden jump of value. The line begins with hash sign is comment.
+//!
+//! ```text
+//! # channel control = name index position [pickup]
+//! 1 7 = mixer-output-volume 0 0 pickup
+//! 1 10 = mixer-source-pan 0 2
+//! ```
+
+use {
+    super::{card_cntr::*, dispatcher::*, feature_report::*, knob_forward::scale_to_midi_value, *},
+    alsactl::{prelude::*, *},
+    alsaseq::{
+        prelude::*, ClientInfo, Event, EventType, PortAttrFlag, PortCapFlag, PortInfo, UserClient,
+    },
+    glib::FileError,
+};
+
+/// The environment variable for the path to the file of MIDI map.
+pub const MIDI_MAP_ENV: &str = "SND_FIREWIRE_CTL_MIDI_MAP";
+
+/// The name of feature in the report for MIDI map.
+pub const MIDI_MAP_FEATURE_NAME: &str = "midi-map";
+
+/// The events received from ALSA Sequencer, delivered to the thread of runtime.
+pub type MidiMapEvents = Vec<Event>;
+
+/// The mode to apply the value of message.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MidiMapMode {
+    /// The value is applied immediately.
+    Jump,
+    /// The value is applied after the position of fader reaches the current value.
+    Pickup,
+}
+
+impl Default for MidiMapMode {
+    fn default() -> Self {
+        Self::Jump
+    }
+}
+
+/// The entry of MIDI map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MidiMapEntry {
+    /// The MIDI channel between 0 and 15.
+    pub channel: u8,
+    /// The number of MIDI control change.
+    pub ctl_num: u32,
+    /// The name of element.
+    pub name: String,
+    /// The index of element.
+    pub index: u32,
+    /// The position of value in the element.
+    pub pos: usize,
+    /// The mode to apply the value.
+    pub mode: MidiMapMode,
+}
+
+fn parse_midi_map_entry(line: &str) -> Option<MidiMapEntry> {
+    let (ctl, elem) = line.split_once('=')?;
+
+    let mut fields = ctl.split_whitespace();
+    let channel = fields
+        .next()?
+        .parse::<u8>()
+        .ok()
+        .filter(|&ch| ch >= 1 && ch <= 16)?;
+    let ctl_num = fields
+        .next()?
+        .parse::<u32>()
+        .ok()
+        .filter(|&num| num < 128)?;
+    if fields.next().is_some() {
+        return None;
+    }
+
+    let mut fields = elem.split_whitespace();
+    let name = fields.next()?.to_string();
+    let index = fields.next()?.parse().ok()?;
+    let pos = fields.next()?.parse().ok()?;
+    let mode = match fields.next() {
+        None => MidiMapMode::Jump,
+        Some("pickup") => MidiMapMode::Pickup,
+        Some("jump") => MidiMapMode::Jump,
+        Some(_) => return None,
+    };
+    if fields.next().is_some() {
+        return None;
+    }
+
+    Some(MidiMapEntry {
+        channel: channel - 1,
+        ctl_num,
+        name,
+        index,
+        pos,
+        mode,
+    })
+}
+
+/// Parse the text of MIDI map into the entries.
+pub fn parse_midi_map(text: &str) -> Result<Vec<MidiMapEntry>, Error> {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            parse_midi_map_entry(line).ok_or_else(|| {
+                let msg = format!("Invalid entry of MIDI map at line {}: {}", i + 1, line);
+                Error::new(FileError::Inval, &msg)
+            })
+        })
+        .collect()
+}
+
+/// Scale the value of MIDI control change message to the range of element.
+pub fn scale_from_midi_value(val: i32, min: i32, max: i32) -> i32 {
+    if max <= min {
+        min
+    } else {
+        min + (val.clamp(0, 127) * (max - min) + 63) / 127
+    }
+}
+
+/// The state of pickup for the entry.
+#[derive(Default, Debug)]
+pub struct MidiPickup {
+    picked: bool,
+    prev: Option<i32>,
+    applied: Option<i32>,
+}
+
+impl MidiPickup {
+    /// Whether to apply the value of message. The position of fader should reach or cross the
+    /// current value at first, and again after the value is changed by the others.
+    pub fn check(&mut self, val: i32, curr: i32, min: i32, max: i32) -> bool {
+        if self.applied != Some(curr) {
+            self.picked = false;
+        }
+
+        if !self.picked {
+            let target = scale_to_midi_value(curr, min, max);
+            self.picked = (val - target).abs() <= 1
+                || self
+                    .prev
+                    .map(|prev| (prev - target).signum() * (val - target).signum() < 0)
+                    .unwrap_or_default();
+        }
+        self.prev = Some(val);
+
+        self.picked
+    }
+
+    /// Record the value applied to the element.
+    pub fn applied(&mut self, val: i32) {
+        self.applied = Some(val);
+    }
+}
+
+#[derive(Debug)]
+struct MidiMapBinding {
+    entry: MidiMapEntry,
+    elem_id: ElemId,
+    min: i32,
+    max: i32,
+    pickup: MidiPickup,
+}
+
+#[derive(Debug)]
+struct MidiMapPort {
+    client: UserClient,
+    port_id: u8,
+}
+
+impl Drop for MidiMapPort {
+    fn drop(&mut self) {
+        let _ = self.client.delete_port(self.port_id);
+    }
+}
+
+/// The state of MIDI map.
+#[derive(Default, Debug)]
+pub struct MidiMap {
+    port: Option<MidiMapPort>,
+    bindings: Vec<MidiMapBinding>,
+}
+
+// Retrieve the number of values, the minimum and maximum value.
+fn read_elem_range(card: &Card, elem_id: &ElemId) -> Result<(usize, i32, i32), Error> {
+    let elem_info = card.elem_info(elem_id)?;
+
+    match &elem_info {
+        ElemInfo::Boolean(info) => Ok((info.value_count() as usize, 0, 1)),
+        ElemInfo::Integer(info) => Ok((
+            info.value_count() as usize,
+            info.value_min(),
+            info.value_max(),
+        )),
+        ElemInfo::Enumerated(info) => Ok((
+            info.value_count() as usize,
+            0,
+            info.labels().len() as i32 - 1,
+        )),
+        _ => {
+            let msg = format!("{} is not available for MIDI map", elem_id.name());
+            Err(Error::new(FileError::Inval, &msg))
+        }
+    }
+}
+
+fn read_elem_val(card: &Card, elem_id: &ElemId, pos: usize) -> Result<(ElemValue, i32), Error> {
+    let elem_info = card.elem_info(elem_id)?;
+    let mut elem_value = ElemValue::new();
+    card.read_elem_value(elem_id, &mut elem_value)?;
+
+    let val = match &elem_info {
+        ElemInfo::Boolean(_) => elem_value.boolean().get(pos).map(|&val| val as i32),
+        ElemInfo::Integer(_) => elem_value.int().get(pos).copied(),
+        _ => elem_value.enumerated().get(pos).map(|&val| val as i32),
+    };
+
+    val.map(|val| (elem_value, val)).ok_or_else(|| {
+        let msg = format!("Invalid position for {}: {}", elem_id.name(), pos);
+        Error::new(FileError::Inval, &msg)
+    })
+}
+
+fn write_elem_val(card: &Card, elem_id: &ElemId, pos: usize, val: i32) -> Result<(), Error> {
+    let elem_info = card.elem_info(elem_id)?;
+    let (elem_value, _) = read_elem_val(card, elem_id, pos)?;
+
+    match &elem_info {
+        ElemInfo::Boolean(_) => {
+            let mut vals = elem_value.boolean().to_vec();
+            vals[pos] = val > 0;
+            elem_value.set_bool(&vals);
+        }
+        ElemInfo::Integer(_) => {
+            let mut vals = elem_value.int().to_vec();
+            vals[pos] = val;
+            elem_value.set_int(&vals);
+        }
+        _ => {
+            let mut vals = elem_value.enumerated().to_vec();
+            vals[pos] = val as u32;
+            elem_value.set_enum(&vals);
+        }
+    }
+
+    // The change is dispatched to the model by the event of element.
+    card.write_elem_value(elem_id, &elem_value)
+}
+
+impl MidiMap {
+    const SEQ_PORT_NAME: &'static str = "Mixer Map";
+
+    /// Bind the elements in the map given by the environment variable, then add ALSA Sequencer
+    /// client and port to receive messages. Nothing is added unless the variable is given.
+    pub fn load(&mut self, card_cntr: &CardCntr, client_name: &str) -> Result<(), Error> {
+        let path = match std::env::var_os(MIDI_MAP_ENV) {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let text = std::fs::read_to_string(&path).map_err(|e| {
+            let msg = format!("Fail to read MIDI map {:?}: {}", path, e);
+            Error::new(FileError::Io, &msg)
+        })?;
+        let entries = parse_midi_map(&text)?;
+
+        let elem_id_list = card_cntr.card.elem_id_list()?;
+        let mut bindings = Vec::new();
+        entries.into_iter().try_for_each(|entry| {
+            let elem_id = elem_id_list
+                .iter()
+                .find(|elem_id| elem_id.name() == entry.name && elem_id.index() == entry.index)
+                .cloned()
+                .ok_or_else(|| {
+                    let msg = format!("{}[{}] is not found", entry.name, entry.index);
+                    Error::new(FileError::Noent, &msg)
+                })?;
+            let (count, min, max) = read_elem_range(&card_cntr.card, &elem_id)?;
+            if entry.pos >= count {
+                let msg = format!("Invalid position for {}: {}", entry.name, entry.pos);
+                return Err(Error::new(FileError::Inval, &msg));
+            }
+            bindings.push(MidiMapBinding {
+                entry,
+                elem_id,
+                min,
+                max,
+                pickup: Default::default(),
+            });
+            Ok(())
+        })?;
+
+        let client = UserClient::new();
+        client.open(0)?;
+
+        let info = ClientInfo::new();
+        info.set_name(Some(client_name));
+        client.set_info(&info)?;
+
+        let mut info = PortInfo::new();
+        info.set_attrs(PortAttrFlag::MIDI_GENERIC | PortAttrFlag::APPLICATION);
+        info.set_caps(PortCapFlag::WRITE | PortCapFlag::SUBS_WRITE);
+        info.set_name(Some(Self::SEQ_PORT_NAME));
+        client.create_port(&mut info)?;
+        let port_id = info
+            .addr()
+            .map(|addr| addr.port_id())
+            .ok_or_else(|| Error::new(FileError::Io, "Fail to get address for added port."))?;
+
+        self.bindings = bindings;
+        self.port = Some(MidiMapPort { client, port_id });
+
+        Ok(())
+    }
+
+    /// Whether the map is loaded.
+    pub fn is_loaded(&self) -> bool {
+        self.port.is_some()
+    }
+
+    /// Attach the client of ALSA Sequencer to the dispatcher. The callback is called with the
+    /// received events, which should be passed to the thread of runtime.
+    pub fn attach_dispatcher<F>(&self, dispatcher: &mut Dispatcher, cb: F) -> Result<(), Error>
+    where
+        F: Fn(MidiMapEvents) + Send + 'static,
+    {
+        if let Some(port) = &self.port {
+            dispatcher.attach_snd_seq(&port.client)?;
+            port.client
+                .connect_handle_event(move |_, ev_cntr| cb(ev_cntr.deserialize()));
+        }
+        Ok(())
+    }
+
+    /// Handle the events from ALSA Sequencer, then write the scaled value to the elements. The
+    /// failure is recorded in the report of features.
+    pub fn handle_seq_events(&mut self, card_cntr: &mut CardCntr, events: &[Event]) {
+        events
+            .iter()
+            .filter(|ev| EventType::Controller == ev.event_type())
+            .filter_map(|ev| ev.ctl_data().ok())
+            .for_each(|data| {
+                let channel = data.channel();
+                let ctl_num = data.param();
+                let val = data.value();
+
+                self.bindings
+                    .iter_mut()
+                    .filter(|b| b.entry.channel == channel && b.entry.ctl_num == ctl_num)
+                    .for_each(|binding| {
+                        if let Err(e) = apply_binding(&card_cntr.card, binding, val) {
+                            let msg = format!("{}: {}", binding.entry.name, e);
+                            report_feature(MIDI_MAP_FEATURE_NAME, &msg);
+                        }
+                    });
+            });
+    }
+}
+
+fn apply_binding(card: &Card, binding: &mut MidiMapBinding, val: i32) -> Result<(), Error> {
+    let pos = binding.entry.pos;
+    let (_, curr) = read_elem_val(card, &binding.elem_id, pos)?;
+
+    if binding.entry.mode == MidiMapMode::Pickup
+        && !binding.pickup.check(val, curr, binding.min, binding.max)
+    {
+        return Ok(());
+    }
+
+    let target = scale_from_midi_value(val, binding.min, binding.max);
+    binding.pickup.applied(target);
+    if target != curr {
+        write_elem_val(card, &binding.elem_id, pos, target)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn midi_map_parse() {
+        let text = "
+            # comment
+            1 7 = mixer-output-volume 0 0 pickup
+
+            16 10=mixer-source-pan 1 2
+        ";
+        let entries = parse_midi_map(text).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                MidiMapEntry {
+                    channel: 0,
+                    ctl_num: 7,
+                    name: "mixer-output-volume".to_string(),
+                    index: 0,
+                    pos: 0,
+                    mode: MidiMapMode::Pickup,
+                },
+                MidiMapEntry {
+                    channel: 15,
+                    ctl_num: 10,
+                    name: "mixer-source-pan".to_string(),
+                    index: 1,
+                    pos: 2,
+                    mode: MidiMapMode::Jump,
+                },
+            ]
+        );
+
+        assert!(parse_midi_map("0 7 = mixer-output-volume 0 0").is_err());
+        assert!(parse_midi_map("1 128 = mixer-output-volume 0 0").is_err());
+        assert!(parse_midi_map("1 7 = mixer-output-volume 0").is_err());
+        assert!(parse_midi_map("1 7 = mixer-output-volume 0 0 soft").is_err());
+    }
+
+    #[test]
+    fn midi_value_scale() {
+        assert_eq!(scale_from_midi_value(0, -128, 127), -128);
+        assert_eq!(scale_from_midi_value(127, -128, 127), 127);
+        assert_eq!(scale_from_midi_value(64, 0, 1), 1);
+        assert_eq!(scale_from_midi_value(63, 0, 1), 0);
+        assert_eq!(scale_from_midi_value(200, 0, 100), 100);
+        assert_eq!(scale_from_midi_value(10, 5, 5), 5);
+    }
+
+    #[test]
+    fn midi_pickup() {
+        let mut pickup = MidiPickup::default();
+
+        // The fader below the current value.
+        assert!(!pickup.check(10, 64, 0, 127));
+        assert!(!pickup.check(30, 64, 0, 127));
+        // Crossing the current value.
+        assert!(pickup.check(70, 64, 0, 127));
+        pickup.applied(70);
+        assert!(pickup.check(80, 70, 0, 127));
+        pickup.applied(80);
+
+        // The value is changed by the others.
+        assert!(!pickup.check(90, 20, 0, 127));
+        assert!(pickup.check(21, 20, 0, 127));
+    }
+}
@@ -0,0 +1,483 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Mirrored control of two identical devices.
+//!
+//! For redundant rigs, the change of value in elements applied to the primary unit is replicated
+//! to the secondary unit of the same model, so that both units are configured identically. The
+//! runtimes for them are separate processes which coordinate via UNIX domain socket of datagram
+//! type, given by the environment variable with the role, like 'primary:/run/fw-mirror.sock'
+//! for the one and 'secondary:/run/fw-mirror.sock' for the other. The runtime for the secondary
+//! unit writes the received value to the element with the same name and index, thus the change
+//! is propagated to the device by the model as the change of value in the element. The message
+//! includes the vendor ID and model ID parsed from configuration ROM, thus the message from the
+//! unit of the other model is ignored.
+//!
+//! The socket is created with permission only for the owner, and the message from the process of
+//! the other user is ignored according to the credential of peer. Any failure in the runtime for
+//! the secondary is recorded in the report of features.
+
+use {
+    super::{card_cntr::*, feature_report::*, *},
+    alsactl::{prelude::*, *},
+    glib::FileError,
+    nix::{
+        sys::{
+            socket::{
+                recvmsg, setsockopt, sockopt::PassCred, ControlMessageOwned, MsgFlags,
+                UnixCredentials,
+            },
+            uio::IoVec,
+        },
+        unistd::geteuid,
+    },
+    std::{
+        fs::Permissions,
+        os::unix::{
+            fs::{FileTypeExt, PermissionsExt},
+            io::AsRawFd,
+            net::UnixDatagram,
+        },
+        path::PathBuf,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    },
+};
+
+/// The environment variable for the role and the path of socket, delimited by colon.
+pub const MIRROR_ENV: &str = "SND_FIREWIRE_CTL_MIRROR";
+
+/// The role of runtime in mirroring.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MirrorRole {
+    /// The change of value in elements is sent to the secondary.
+    Primary,
+    /// The change of value in elements is received from the primary.
+    Secondary,
+}
+
+/// The configuration of mirroring.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MirrorConfig {
+    pub role: MirrorRole,
+    pub path: PathBuf,
+}
+
+/// Parse the role and the path of socket, like 'primary:/run/fw-mirror.sock'.
+pub fn parse_mirror_config(arg: &str) -> Result<MirrorConfig, Error> {
+    let (role, path) = arg
+        .trim()
+        .split_once(':')
+        .filter(|(_, path)| path.len() > 0)
+        .ok_or_else(|| {
+            let msg = format!("Invalid configuration of mirroring: {}", arg);
+            Error::new(FileError::Inval, &msg)
+        })?;
+
+    let role = match role {
+        "primary" => MirrorRole::Primary,
+        "secondary" => MirrorRole::Secondary,
+        _ => {
+            let msg = format!("Invalid role of mirroring: {}", role);
+            return Err(Error::new(FileError::Inval, &msg));
+        }
+    };
+
+    Ok(MirrorConfig {
+        role,
+        path: PathBuf::from(path),
+    })
+}
+
+/// Retrieve the configuration of mirroring from the environment variable.
+pub fn mirror_config_from_env() -> Result<Option<MirrorConfig>, Error> {
+    match std::env::var(MIRROR_ENV) {
+        Ok(arg) => parse_mirror_config(&arg).map(Some),
+        Err(_) => Ok(None),
+    }
+}
+
+/// The name of feature in the report for mirroring.
+pub const MIRROR_FEATURE_NAME: &str = "mirroring";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum MirrorValues {
+    Bool(Vec<bool>),
+    Int(Vec<i32>),
+    Enum(Vec<u32>),
+    Int64(Vec<i64>),
+    Bytes(Vec<u8>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MirrorMessage {
+    vendor_id: u32,
+    model_id: u32,
+    name: String,
+    index: u32,
+    values: MirrorValues,
+}
+
+fn join_vals<T: ToString>(vals: &[T]) -> String {
+    vals.iter()
+        .map(|val| val.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn split_vals<T: std::str::FromStr>(literal: &str) -> Option<Vec<T>> {
+    literal
+        .split_whitespace()
+        .map(|val| val.parse().ok())
+        .collect()
+}
+
+impl MirrorMessage {
+    // The message consists of lines for vendor and model IDs, name, index, type, and values.
+    fn serialize(&self) -> String {
+        let (kind, vals) = match &self.values {
+            MirrorValues::Bool(vals) => ("bool", join_vals(vals)),
+            MirrorValues::Int(vals) => ("int", join_vals(vals)),
+            MirrorValues::Enum(vals) => ("enum", join_vals(vals)),
+            MirrorValues::Int64(vals) => ("int64", join_vals(vals)),
+            MirrorValues::Bytes(vals) => ("bytes", join_vals(vals)),
+        };
+        format!(
+            "{:06x}:{:06x}\n{}\n{}\n{}\n{}\n",
+            self.vendor_id, self.model_id, self.name, self.index, kind, vals
+        )
+    }
+
+    fn parse(literal: &str) -> Option<Self> {
+        let mut lines = literal.lines();
+        let (vendor_id, model_id) = lines.next()?.split_once(':')?;
+        let vendor_id = u32::from_str_radix(vendor_id, 16).ok()?;
+        let model_id = u32::from_str_radix(model_id, 16).ok()?;
+        let name = lines.next()?.to_string();
+        let index = lines.next()?.parse().ok()?;
+        let kind = lines.next()?;
+        let vals = lines.next().unwrap_or("");
+        let values = match kind {
+            "bool" => MirrorValues::Bool(split_vals(vals)?),
+            "int" => MirrorValues::Int(split_vals(vals)?),
+            "enum" => MirrorValues::Enum(split_vals(vals)?),
+            "int64" => MirrorValues::Int64(split_vals(vals)?),
+            "bytes" => MirrorValues::Bytes(split_vals(vals)?),
+            _ => return None,
+        };
+        Some(Self {
+            vendor_id,
+            model_id,
+            name,
+            index,
+            values,
+        })
+    }
+}
+
+fn read_values(card: &Card, elem_id: &ElemId) -> Result<MirrorValues, Error> {
+    let elem_info = card.elem_info(elem_id)?;
+    let mut elem_value = ElemValue::new();
+    card.read_elem_value(elem_id, &mut elem_value)?;
+
+    match &elem_info {
+        ElemInfo::Boolean(info) => {
+            let count = info.value_count() as usize;
+            Ok(MirrorValues::Bool(elem_value.boolean()[..count].to_vec()))
+        }
+        ElemInfo::Integer(info) => {
+            let count = info.value_count() as usize;
+            Ok(MirrorValues::Int(elem_value.int()[..count].to_vec()))
+        }
+        ElemInfo::Enumerated(info) => {
+            let count = info.value_count() as usize;
+            Ok(MirrorValues::Enum(
+                elem_value.enumerated()[..count].to_vec(),
+            ))
+        }
+        ElemInfo::Integer64(info) => {
+            let count = info.value_count() as usize;
+            Ok(MirrorValues::Int64(elem_value.int64()[..count].to_vec()))
+        }
+        ElemInfo::Bytes(info) => {
+            let count = info.value_count() as usize;
+            Ok(MirrorValues::Bytes(elem_value.bytes()[..count].to_vec()))
+        }
+        _ => {
+            let msg = format!("{} is not supported for mirroring", elem_id.name());
+            Err(Error::new(FileError::Inval, &msg))
+        }
+    }
+}
+
+fn write_values(card: &Card, msg: &MirrorMessage) -> Result<(), Error> {
+    let elem_id = card
+        .elem_id_list()?
+        .into_iter()
+        .find(|elem_id| elem_id.name().as_str() == msg.name && elem_id.index() == msg.index)
+        .ok_or_else(|| {
+            let label = format!("{} is not found for mirroring", msg.name);
+            Error::new(FileError::Noent, &label)
+        })?;
+
+    let mut elem_value = ElemValue::new();
+    card.read_elem_value(&elem_id, &mut elem_value)?;
+    match &msg.values {
+        MirrorValues::Bool(vals) => elem_value.set_bool(vals),
+        MirrorValues::Int(vals) => elem_value.set_int(vals),
+        MirrorValues::Enum(vals) => elem_value.set_enum(vals),
+        MirrorValues::Int64(vals) => elem_value.set_int64(vals),
+        MirrorValues::Bytes(vals) => elem_value.set_bytes(vals),
+    }
+    // The change is dispatched to the model by the event of element.
+    card.write_elem_value(&elem_id, &elem_value)
+}
+
+struct MirrorReceiver {
+    path: PathBuf,
+    running: Arc<AtomicBool>,
+    th: Option<thread::JoinHandle<()>>,
+}
+
+impl Drop for MirrorReceiver {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(th) = self.th.take() {
+            let _ = th.join();
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// The state of mirroring.
+#[derive(Default)]
+pub struct MirrorCtl {
+    vendor_id: u32,
+    model_id: u32,
+    sender: Option<(UnixDatagram, PathBuf)>,
+    targets: Vec<ElemId>,
+    receiver: Option<MirrorReceiver>,
+}
+
+impl MirrorCtl {
+    const POLL_INTERVAL: Duration = Duration::from_millis(100);
+    const MAX_MESSAGE_SIZE: usize = 4096;
+
+    /// Start mirroring according to the configuration. The elements registered by the runtime
+    /// with write access are the targets of mirroring, except for the ones with the given names.
+    /// The vendor ID and model ID are parsed from configuration ROM by the runtime.
+    pub fn start(
+        &mut self,
+        card_cntr: &CardCntr,
+        card_id: u32,
+        (vendor_id, model_id): (u32, u32),
+        config: MirrorConfig,
+        excluded: &[&str],
+    ) -> Result<(), Error> {
+        self.vendor_id = vendor_id;
+        self.model_id = model_id;
+
+        match config.role {
+            MirrorRole::Primary => {
+                self.targets = card_cntr
+                    .registered_elem_id_list()
+                    .into_iter()
+                    .filter(|elem_id| !excluded.iter().any(|name| elem_id.name() == *name))
+                    .filter(|elem_id| {
+                        card_cntr
+                            .card
+                            .elem_info(elem_id)
+                            .map(|info| info.as_ref().access().contains(ElemAccessFlag::WRITE))
+                            .unwrap_or(false)
+                    })
+                    .collect();
+
+                let socket = UnixDatagram::unbound().map_err(|e| {
+                    let msg = format!("Fail to open socket for mirroring: {}", e);
+                    Error::new(FileError::Io, &msg)
+                })?;
+                self.sender = Some((socket, config.path));
+            }
+            MirrorRole::Secondary => {
+                let ids = (self.vendor_id, self.model_id);
+                self.receiver = Some(Self::start_receiver(config.path, card_id, ids)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn start_receiver(
+        path: PathBuf,
+        card_id: u32,
+        ids: (u32, u32),
+    ) -> Result<MirrorReceiver, Error> {
+        // Remove stale socket left by the previous process. The other type of file is not removed.
+        if let Ok(metadata) = std::fs::symlink_metadata(&path) {
+            if metadata.file_type().is_socket() {
+                let _ = std::fs::remove_file(&path);
+            }
+        }
+
+        // The permission of socket is restricted to the owner after binding, instead of changing
+        // the mask of file mode for the whole process.
+        let socket = UnixDatagram::bind(&path)
+            .and_then(|socket| {
+                std::fs::set_permissions(&path, Permissions::from_mode(0o600)).map(|_| socket)
+            })
+            .and_then(|socket| {
+                socket
+                    .set_read_timeout(Some(Self::POLL_INTERVAL))
+                    .map(|_| socket)
+            })
+            .map_err(|e| {
+                let msg = format!("Fail to bind socket {}: {}", path.display(), e);
+                Error::new(FileError::Io, &msg)
+            })?;
+
+        // The credential of peer is delivered with each message.
+        setsockopt(socket.as_raw_fd(), PassCred, &true).map_err(|e| {
+            let msg = format!("Fail to request credential of peer: {}", e);
+            Error::new(FileError::Io, &msg)
+        })?;
+
+        let running = Arc::new(AtomicBool::new(true));
+
+        let r = running.clone();
+        let th = thread::spawn(move || {
+            let card = Card::new();
+            if let Err(e) = card.open(card_id, 0) {
+                let msg = format!("failed to open sound card: {}", e);
+                report_feature(MIRROR_FEATURE_NAME, &msg);
+                return;
+            }
+
+            let uid = geteuid().as_raw();
+            let mut buf = vec![0; Self::MAX_MESSAGE_SIZE];
+            while r.load(Ordering::Relaxed) {
+                let (len, peer) = match Self::recv_with_credential(&socket, &mut buf) {
+                    Some(res) => res,
+                    None => continue,
+                };
+                // The message from the process of the other user is ignored.
+                if peer != uid && peer != 0 {
+                    continue;
+                }
+                let msg = match std::str::from_utf8(&buf[..len])
+                    .ok()
+                    .and_then(MirrorMessage::parse)
+                {
+                    Some(msg) => msg,
+                    None => continue,
+                };
+                // The message from the unit of the other model is ignored.
+                if (msg.vendor_id, msg.model_id) != ids {
+                    continue;
+                }
+                if let Err(e) = write_values(&card, &msg) {
+                    let msg = format!("failed to write {}: {}", msg.name, e);
+                    report_feature(MIRROR_FEATURE_NAME, &msg);
+                }
+            }
+        });
+
+        Ok(MirrorReceiver {
+            path,
+            running,
+            th: Some(th),
+        })
+    }
+
+    // Receive the message and the user ID of peer.
+    fn recv_with_credential(socket: &UnixDatagram, buf: &mut [u8]) -> Option<(usize, u32)> {
+        let iov = [IoVec::from_mut_slice(buf)];
+        let mut cmsg_buf = nix::cmsg_space!(UnixCredentials);
+        let msg = recvmsg(
+            socket.as_raw_fd(),
+            &iov,
+            Some(&mut cmsg_buf),
+            MsgFlags::empty(),
+        )
+        .ok()?;
+        let uid = msg.cmsgs().find_map(|cmsg| match cmsg {
+            ControlMessageOwned::ScmCredentials(cred) => Some(cred.uid()),
+            _ => None,
+        })?;
+        Some((msg.bytes, uid))
+    }
+
+    /// Send the value of element to the secondary when the runtime is for the primary. Any
+    /// failure is not fatal since the secondary can be absent.
+    pub fn forward(&self, card_cntr: &CardCntr, elem_id: &ElemId) {
+        if let Some((socket, path)) = &self.sender {
+            if !self.targets.contains(elem_id) {
+                return;
+            }
+
+            if let Ok(values) = read_values(&card_cntr.card, elem_id) {
+                let msg = MirrorMessage {
+                    vendor_id: self.vendor_id,
+                    model_id: self.model_id,
+                    name: elem_id.name().to_string(),
+                    index: elem_id.index(),
+                    values,
+                };
+                let _ = socket.send_to(msg.serialize().as_bytes(), path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn mirror_config_parse() {
+        let config = parse_mirror_config("primary:/run/fw-mirror.sock").unwrap();
+        assert_eq!(config.role, MirrorRole::Primary);
+        assert_eq!(config.path, PathBuf::from("/run/fw-mirror.sock"));
+
+        let config = parse_mirror_config(" secondary:/tmp/a:b\n").unwrap();
+        assert_eq!(config.role, MirrorRole::Secondary);
+        assert_eq!(config.path, PathBuf::from("/tmp/a:b"));
+
+        assert!(parse_mirror_config("tertiary:/run/fw-mirror.sock").is_err());
+        assert!(parse_mirror_config("primary:").is_err());
+        assert!(parse_mirror_config("primary").is_err());
+    }
+
+    #[test]
+    fn mirror_message_serialization() {
+        let msg = MirrorMessage {
+            vendor_id: 0x0007f5,
+            model_id: 0x010062,
+            name: "mixer-source-gain".to_string(),
+            index: 2,
+            values: MirrorValues::Int(vec![-10, 0, 0x7fff]),
+        };
+        let literal = msg.serialize();
+        assert_eq!(
+            literal,
+            "0007f5:010062\nmixer-source-gain\n2\nint\n-10 0 32767\n"
+        );
+        assert_eq!(MirrorMessage::parse(&literal), Some(msg));
+
+        let msg = MirrorMessage {
+            vendor_id: 1,
+            model_id: 2,
+            name: "phantom-powering".to_string(),
+            index: 0,
+            values: MirrorValues::Bool(vec![true, false]),
+        };
+        assert_eq!(MirrorMessage::parse(&msg.serialize()), Some(msg));
+
+        assert_eq!(MirrorMessage::parse("1:2\nname\n0\nfloat\n0.5\n"), None);
+        assert_eq!(MirrorMessage::parse("1:2\nname\n0\nbool\ntrue yes\n"), None);
+        assert_eq!(MirrorMessage::parse("1\nname\n0\nbool\ntrue\n"), None);
+    }
+}
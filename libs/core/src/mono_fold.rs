@@ -0,0 +1,239 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Fold-down of output pair to mono orchestrated by software.
+//!
+//! Enabling the fold-down for a mixer with stereo output moves the pan (and the balance and the
+//! width of stereo sources) of all sources to the center, thus the left and right channels are
+//! summed into both outputs. The level of summed channels is decided by the pan law at center of
+//! DSP in the device, which is -3 dB for the typical constant-power law. Disabling the fold-down
+//! restores the original positions. It is useful to check mono compatibility of mix.
+//!
+//! The fold-down is implemented over the elements for pan, thus the change is propagated to the
+//! device by the model as the change of value in the elements. The position changed by the others
+//! during fold-down is respected at release.
+
+use {
+    super::{card_cntr::*, feature_report::*, *},
+    alsactl::{prelude::*, *},
+    glib::FileError,
+};
+
+/// The state of fold-down for an element of pan.
+#[derive(Default, Debug)]
+pub struct MonoFold {
+    // The pair of original position and the position applied by fold-down.
+    saved: Vec<Option<(i32, i32)>>,
+}
+
+impl MonoFold {
+    /// Compute the positions according to the state of fold-down. The original positions are kept
+    /// while folded, then they are returned at release.
+    pub fn update(&mut self, enabled: bool, vals: &[i32], center: i32) -> Vec<i32> {
+        self.saved.resize(vals.len(), None);
+
+        vals.iter()
+            .zip(self.saved.iter_mut())
+            .map(|(&val, saved)| {
+                // The position changed by the others since the last application is respected.
+                if let Some((_, applied)) = *saved {
+                    if applied != val {
+                        *saved = None;
+                    }
+                }
+                let orig = saved.map(|(orig, _)| orig).unwrap_or(val);
+
+                if enabled {
+                    *saved = Some((orig, center));
+                    center
+                } else {
+                    *saved = None;
+                    orig
+                }
+            })
+            .collect()
+    }
+}
+
+/// The elements for mixers to which fold-down is added.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MonoFoldTarget {
+    /// The names of element for positions of sources in the mixers, and the value for center.
+    /// The middle of range in the element is used for center if None.
+    pub pans: &'static [(&'static str, Option<i32>)],
+    /// The name of element for fold-down of mixers, added by the helper.
+    pub fold: &'static str,
+}
+
+#[derive(Debug)]
+struct MonoFoldPan {
+    elem_id: ElemId,
+    value_count: usize,
+    center: i32,
+    fold: MonoFold,
+}
+
+#[derive(Debug)]
+struct MonoFoldEntry {
+    fold: ElemId,
+    pans: Vec<MonoFoldPan>,
+}
+
+/// The state of fold-down for mixers.
+#[derive(Default, Debug)]
+pub struct MonoFoldCtl {
+    entries: Vec<MonoFoldEntry>,
+}
+
+fn find_elem_id_list(elem_id_list: &[ElemId], name: &str) -> Vec<ElemId> {
+    let mut list: Vec<ElemId> = elem_id_list
+        .iter()
+        .filter(|elem_id| elem_id.name() == name)
+        .cloned()
+        .collect();
+    list.sort_by_key(|elem_id| elem_id.index());
+    list
+}
+
+fn read_int_shape(card: &Card, elem_id: &ElemId) -> Result<(usize, i32, i32), Error> {
+    let elem_info = card.elem_info(elem_id)?;
+    match &elem_info {
+        ElemInfo::Integer(info) => Ok((
+            info.value_count() as usize,
+            info.value_min(),
+            info.value_max(),
+        )),
+        _ => {
+            let msg = format!("{} is not integer element", elem_id.name());
+            Err(Error::new(FileError::Inval, &msg))
+        }
+    }
+}
+
+impl MonoFoldCtl {
+    /// Add the elements for fold-down to the mixers found in the given targets.
+    pub fn load(
+        &mut self,
+        card_cntr: &mut CardCntr,
+        targets: &[MonoFoldTarget],
+    ) -> Result<(), Error> {
+        let elem_id_list = card_cntr.card.elem_id_list()?;
+
+        targets.iter().try_for_each(|target| {
+            // The elements with the same number as the first one are available.
+            let mut pan_lists: Vec<(Vec<ElemId>, Option<i32>)> = Vec::new();
+            target.pans.iter().for_each(|&(name, center)| {
+                let list = find_elem_id_list(&elem_id_list, name);
+                let count = pan_lists
+                    .first()
+                    .map(|(l, _)| l.len())
+                    .unwrap_or(list.len());
+                if list.len() > 0 && list.len() == count {
+                    pan_lists.push((list, center));
+                }
+            });
+
+            let mixer_count = match pan_lists.first() {
+                Some((list, _)) => list.len(),
+                None => return Ok(()),
+            };
+
+            let elem_id = ElemId::new_by_name(pan_lists[0].0[0].iface(), 0, 0, target.fold, 0);
+            let folds = card_cntr.add_bool_elems(&elem_id, mixer_count, 1, true)?;
+
+            // Any fold-down is not kept across restart of runtime since the original positions
+            // are lost.
+            let elem_value = ElemValue::new();
+            elem_value.set_bool(&[false]);
+            folds
+                .iter()
+                .try_for_each(|elem_id| card_cntr.card.write_elem_value(elem_id, &elem_value))?;
+
+            folds.into_iter().enumerate().try_for_each(|(i, fold)| {
+                let pans = pan_lists
+                    .iter()
+                    .map(|(list, center)| {
+                        let elem_id = list[i].clone();
+                        read_int_shape(&card_cntr.card, &elem_id).map(|(value_count, min, max)| {
+                            MonoFoldPan {
+                                elem_id,
+                                value_count,
+                                center: center.unwrap_or(min + (max - min) / 2),
+                                fold: Default::default(),
+                            }
+                        })
+                    })
+                    .collect::<Result<Vec<MonoFoldPan>, Error>>()?;
+                self.entries.push(MonoFoldEntry { fold, pans });
+                Ok(())
+            })
+        })
+    }
+
+    /// Handle the event of element. True is returned when the element is for fold-down.
+    pub fn handle_elem_event(
+        &mut self,
+        card_cntr: &mut CardCntr,
+        elem_id: &ElemId,
+        events: &ElemEventMask,
+    ) -> bool {
+        let entry = match self.entries.iter_mut().find(|entry| entry.fold.eq(elem_id)) {
+            Some(entry) => entry,
+            None => return false,
+        };
+
+        if events.contains(ElemEventMask::VALUE) {
+            if let Err(e) = apply_entry(&card_cntr.card, entry) {
+                report_feature(&elem_id.name(), &format!("failed: {}", e));
+            }
+        }
+
+        true
+    }
+}
+
+fn apply_entry(card: &Card, entry: &mut MonoFoldEntry) -> Result<(), Error> {
+    let mut elem_value = ElemValue::new();
+    card.read_elem_value(&entry.fold, &mut elem_value)?;
+    let enabled = elem_value.boolean()[0];
+
+    entry.pans.iter_mut().try_for_each(|pan| {
+        let count = pan.value_count;
+
+        let mut pan_value = ElemValue::new();
+        card.read_elem_value(&pan.elem_id, &mut pan_value)?;
+        let positions = pan_value.int()[..count].to_vec();
+
+        let vals = pan.fold.update(enabled, &positions, pan.center);
+        if vals != positions {
+            // The change is dispatched to the model by the event of element.
+            pan_value.set_int(&vals);
+            card.write_elem_value(&pan.elem_id, &pan_value)
+        } else {
+            Ok(())
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn mono_fold_update() {
+        let mut fold = MonoFold::default();
+
+        let vals = fold.update(true, &[0, 128, 255], 128);
+        assert_eq!(vals, vec![128, 128, 128]);
+
+        // Released.
+        let vals = fold.update(false, &vals, 128);
+        assert_eq!(vals, vec![0, 128, 255]);
+
+        // The position changed by the others during fold-down is respected at release.
+        let vals = fold.update(true, &vals, 128);
+        assert_eq!(vals, vec![128, 128, 128]);
+        let vals = fold.update(false, &[128, 64, 128], 128);
+        assert_eq!(vals, vec![0, 64, 255]);
+    }
+}
@@ -0,0 +1,153 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Discovery of ALSA devices produced by one node.
+//!
+//! Some devices expose several ALSA HwDep character devices for one node; e.g. one for audio
+//! function and another for console function, and in the case the devices can be distributed to
+//! several sound cards. The module enumerates the ALSA HwDep character devices in a sound card
+//! and the sound cards bound to the same node, so that runtime can find the device for its
+//! function instead of assuming the first device of the sound card, and so that just one
+//! process serves the node.
+
+use {
+    super::{passed_fds::*, *},
+    glib::FileError,
+    hitaki::{prelude::*, SndUnit},
+};
+
+/// Parse the name of ALSA HwDep character device, then retrieve the numeric ID of sound card
+/// and device.
+pub fn parse_hwdep_devnode(name: &str) -> Option<(u32, u32)> {
+    let mut fields = name.strip_prefix("hwC")?.splitn(2, 'D');
+    let card_id = fields.next()?.parse().ok()?;
+    let device_id = fields.next()?.parse().ok()?;
+    Some((card_id, device_id))
+}
+
+fn list_hwdep_devnodes() -> Vec<(u32, u32)> {
+    let mut list: Vec<(u32, u32)> = std::fs::read_dir("/dev/snd")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter_map(|name| parse_hwdep_devnode(&name))
+                .collect()
+        })
+        .unwrap_or_default();
+    // The devices passed by the launcher are available without the access to /dev directory.
+    passed_fds()
+        .iter()
+        .filter_map(|(name, _)| parse_hwdep_devnode(name))
+        .for_each(|entry| list.push(entry));
+    list.sort();
+    list.dedup();
+    list
+}
+
+/// List the path of ALSA HwDep character devices in the sound card, in order of device number.
+pub fn card_hwdep_devnodes(card_id: u32) -> Vec<String> {
+    list_hwdep_devnodes()
+        .into_iter()
+        .filter(|&(id, _)| id == card_id)
+        .map(|(id, device_id)| devnode_path(&format!("hwC{}D{}", id, device_id)))
+        .collect()
+}
+
+/// Open the first ALSA HwDep character device in the sound card accepted by the given closure,
+/// then return the path of it. The devices rejected by the closure are just skipped, since the
+/// device for the other function of node, or for the other type of unit can be in the sound card.
+pub fn open_card_hwdep<F>(card_id: u32, mut open: F) -> Result<String, Error>
+where
+    F: FnMut(&str) -> Result<(), Error>,
+{
+    let mut last_err = None;
+
+    card_hwdep_devnodes(card_id)
+        .into_iter()
+        .find(|devnode| match open(devnode) {
+            Ok(()) => true,
+            Err(e) => {
+                last_err = Some(e);
+                false
+            }
+        })
+        .ok_or_else(|| {
+            last_err.unwrap_or_else(|| {
+                let msg = format!("No HwDep device is found in the sound card: {}", card_id);
+                Error::new(FileError::Noent, &msg)
+            })
+        })
+}
+
+fn read_card_node_device(card_id: u32) -> Result<String, Error> {
+    let unit = SndUnit::new();
+    open_card_hwdep(card_id, |devnode| unit.open(devnode, 0))?;
+    unit.node_device()
+        .map(|node_device| node_device.to_string())
+        .ok_or_else(|| Error::new(FileError::Nxio, "No node device is bound"))
+}
+
+/// The set of sound cards bound to one node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeDevices {
+    /// The name of node device; e.g. fw1.
+    pub node_device: String,
+    /// The numeric ID of sound cards bound to the node, in ascending order.
+    pub card_ids: Vec<u32>,
+}
+
+impl NodeDevices {
+    /// Enumerate the sound cards bound to the same node as the given sound card. The sound cards
+    /// inaccessible or not for units on IEEE 1394 bus are just skipped.
+    pub fn discover(card_id: u32) -> Result<Self, Error> {
+        let node_device = read_card_node_device(card_id)?;
+
+        let mut card_ids: Vec<u32> = list_hwdep_devnodes()
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        card_ids.dedup();
+        card_ids.retain(|&id| {
+            id == card_id
+                || read_card_node_device(id)
+                    .map(|name| name == node_device)
+                    .unwrap_or_default()
+        });
+
+        Ok(Self {
+            node_device,
+            card_ids,
+        })
+    }
+
+    /// The sound card of which runtime serves the node. The runtimes for the other sound cards
+    /// are expected to exit.
+    pub fn primary_card(&self) -> Option<u32> {
+        self.card_ids.first().copied()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hwdep_devnode_parse() {
+        assert_eq!(parse_hwdep_devnode("hwC0D0"), Some((0, 0)));
+        assert_eq!(parse_hwdep_devnode("hwC12D3"), Some((12, 3)));
+        assert_eq!(parse_hwdep_devnode("hwC1"), None);
+        assert_eq!(parse_hwdep_devnode("hwCD0"), None);
+        assert_eq!(parse_hwdep_devnode("controlC0"), None);
+        assert_eq!(parse_hwdep_devnode("hwC1D0p"), None);
+    }
+
+    #[test]
+    fn primary_card() {
+        let devices = NodeDevices {
+            node_device: "fw1".to_string(),
+            card_ids: vec![2, 3],
+        };
+        assert_eq!(devices.primary_card(), Some(2));
+    }
+}
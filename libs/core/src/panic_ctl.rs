@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Panic switch to reset the state of DSP to safe defaults at once.
+//!
+//! When the switch is turned on, the elements given by the runtime are reset; e.g. outputs are
+//! muted, the gains of mixer sources are set to minimum, the solo of mixer sources is cleared,
+//! and the volumes of outputs for monitor are set to minimum. The reset is implemented over the
+//! elements for them, thus the change is propagated to the device by the model as the change of
+//! value in the elements, one write operation per element. The switch is turned off after the
+//! reset. The failure of reset is recorded in the report of features.
+
+use {
+    super::{card_cntr::*, feature_report::*, *},
+    alsactl::{prelude::*, *},
+};
+
+/// The name of element for panic switch.
+pub const PANIC_NAME: &str = "panic";
+
+/// The element to be reset by the panic switch.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PanicTarget {
+    /// The name of element.
+    pub name: &'static str,
+    /// The value for boolean element. The minimum value is used for integer element if None.
+    pub state: Option<bool>,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum SafeValue {
+    Bool(bool),
+    Int(i32),
+}
+
+/// The panic switch.
+#[derive(Default, Debug)]
+pub struct PanicCtl {
+    targets: Vec<(ElemId, SafeValue)>,
+    switch: Option<ElemId>,
+}
+
+// The element of which type is not expected by the target is skipped.
+fn safe_value(
+    card: &Card,
+    elem_id: &ElemId,
+    state: Option<bool>,
+) -> Result<Option<SafeValue>, Error> {
+    let elem_info = card.elem_info(elem_id)?;
+    let val = match (state, &elem_info) {
+        (Some(state), ElemInfo::Boolean(_)) => Some(SafeValue::Bool(state)),
+        (None, ElemInfo::Integer(info)) => Some(SafeValue::Int(info.value_min())),
+        _ => None,
+    };
+    Ok(val)
+}
+
+// Fill the values with the safe one. True is returned when any of them is changed.
+fn fill_safe_values<T: Copy + PartialEq>(vals: &mut [T], safe: T) -> bool {
+    let changed = vals.iter().any(|v| *v != safe);
+    vals.iter_mut().for_each(|v| *v = safe);
+    changed
+}
+
+impl PanicCtl {
+    /// Add the element for panic switch when any of the given targets is found.
+    pub fn load(&mut self, card_cntr: &mut CardCntr, targets: &[PanicTarget]) -> Result<(), Error> {
+        let elem_id_list = card_cntr.card.elem_id_list()?;
+
+        targets.iter().try_for_each(|target| {
+            elem_id_list
+                .iter()
+                .filter(|elem_id| elem_id.name() == target.name)
+                .try_for_each(|elem_id| {
+                    safe_value(&card_cntr.card, elem_id, target.state).map(|val| {
+                        if let Some(val) = val {
+                            self.targets.push((elem_id.clone(), val));
+                        }
+                    })
+                })
+        })?;
+
+        if self.targets.len() == 0 {
+            return Ok(());
+        }
+
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, PANIC_NAME, 0);
+        let elem_id_list = card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
+
+        let elem_value = ElemValue::new();
+        elem_value.set_bool(&[false]);
+        card_cntr
+            .card
+            .write_elem_value(&elem_id_list[0], &elem_value)?;
+        self.switch = Some(elem_id_list[0].clone());
+
+        Ok(())
+    }
+
+    /// Handle the event of element. True is returned when the element is for panic.
+    pub fn handle_elem_event(
+        &mut self,
+        card_cntr: &mut CardCntr,
+        elem_id: &ElemId,
+        events: &ElemEventMask,
+    ) -> bool {
+        if self.switch.as_ref() != Some(elem_id) {
+            return false;
+        }
+
+        if events.contains(ElemEventMask::VALUE) {
+            if let Err(e) = self.apply_switch(&card_cntr.card, elem_id) {
+                report_feature(PANIC_NAME, &format!("failed: {}", e));
+            }
+        }
+
+        true
+    }
+
+    fn apply_switch(&self, card: &Card, elem_id: &ElemId) -> Result<(), Error> {
+        let mut elem_value = ElemValue::new();
+        card.read_elem_value(elem_id, &mut elem_value)?;
+        if !elem_value.boolean()[0] {
+            return Ok(());
+        }
+
+        // Reset all of targets even if any of them fails.
+        let res = self.targets.iter().fold(Ok(()), |res, (target, val)| {
+            let r = Self::reset_target(card, target, val);
+            res.and(r)
+        });
+
+        elem_value.set_bool(&[false]);
+        card.write_elem_value(elem_id, &elem_value)?;
+
+        res
+    }
+
+    fn reset_target(card: &Card, target: &ElemId, val: &SafeValue) -> Result<(), Error> {
+        let mut target_value = ElemValue::new();
+        card.read_elem_value(target, &mut target_value)?;
+
+        let changed = match val {
+            SafeValue::Bool(state) => {
+                let mut vals = target_value.boolean().to_vec();
+                let changed = fill_safe_values(&mut vals, *state);
+                target_value.set_bool(&vals);
+                changed
+            }
+            SafeValue::Int(min) => {
+                let mut vals = target_value.int().to_vec();
+                let changed = fill_safe_values(&mut vals, *min);
+                target_value.set_int(&vals);
+                changed
+            }
+        };
+
+        // The change is dispatched to the model by the event of element.
+        if changed {
+            card.write_elem_value(target, &target_value)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn panic_safe_values() {
+        let mut vals = [true, false, true];
+        assert_eq!(fill_safe_values(&mut vals, true), true);
+        assert_eq!(vals, [true, true, true]);
+        assert_eq!(fill_safe_values(&mut vals, true), false);
+
+        let mut vals = [-100, -100];
+        assert_eq!(fill_safe_values(&mut vals, -100), false);
+        assert_eq!(vals, [-100, -100]);
+    }
+}
@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Character devices passed by the launcher as file descriptors.
+//!
+//! The runtime opens ALSA HwDep character device and Linux FireWire character device in the
+//! `/dev` directory by default, thus the user of process requires the access to them. When the
+//! process is launched by the service manager such as systemd, or by privileged launcher, the
+//! launcher can open the character devices and pass the file descriptors to the process instead,
+//! so that the process can run in sandbox without the access to `/dev` directory.
+//!
+//! The file descriptors are passed by the same protocol as socket activation of systemd. The
+//! `LISTEN_PID` environment variable is the process ID, the `LISTEN_FDS` environment variable is
+//! the number of file descriptors starting from 3, and the `LISTEN_FDNAMES` environment variable
+//! is the list of names separated by colon. The name is the one of character device; e.g.
+//! `hwC1D0` and `fw1`. The character device is opened again via `/proc/self/fd` since the
+//! underlying libraries accept the path only.
+
+use std::os::unix::io::RawFd;
+
+/// The first file descriptor passed by the launcher.
+pub const LISTEN_FDS_START: RawFd = 3;
+
+/// Parse the values of environment variables for the passed file descriptors, then retrieve the
+/// pairs of name and file descriptor. Nothing is retrieved unless the process ID matches.
+pub fn parse_passed_fds(
+    listen_pid: Option<&str>,
+    pid: u32,
+    listen_fds: Option<&str>,
+    listen_fdnames: Option<&str>,
+) -> Vec<(String, RawFd)> {
+    if listen_pid.and_then(|literal| literal.parse::<u32>().ok()) != Some(pid) {
+        return Vec::new();
+    }
+
+    let count = listen_fds
+        .and_then(|literal| literal.parse::<RawFd>().ok())
+        .unwrap_or_default();
+
+    listen_fdnames
+        .unwrap_or_default()
+        .split(':')
+        .zip(LISTEN_FDS_START..(LISTEN_FDS_START + count))
+        .filter(|(name, _)| !name.is_empty())
+        .map(|(name, fd)| (name.to_string(), fd))
+        .collect()
+}
+
+/// Retrieve the pairs of name and file descriptor passed to the process.
+pub fn passed_fds() -> Vec<(String, RawFd)> {
+    let listen_pid = std::env::var("LISTEN_PID").ok();
+    let listen_fds = std::env::var("LISTEN_FDS").ok();
+    let listen_fdnames = std::env::var("LISTEN_FDNAMES").ok();
+    parse_passed_fds(
+        listen_pid.as_deref(),
+        std::process::id(),
+        listen_fds.as_deref(),
+        listen_fdnames.as_deref(),
+    )
+}
+
+/// The path of character device in `/dev` directory for the name.
+pub fn default_devnode_path(name: &str) -> String {
+    if name.starts_with("hwC") {
+        format!("/dev/snd/{}", name)
+    } else {
+        format!("/dev/{}", name)
+    }
+}
+
+/// The path to open the character device for the name; e.g. `hwC1D0` and `fw1`. The path via
+/// `/proc/self/fd` is used if the file descriptor is passed for the name.
+pub fn devnode_path(name: &str) -> String {
+    passed_fds()
+        .into_iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, fd)| format!("/proc/self/fd/{}", fd))
+        .unwrap_or_else(|| default_devnode_path(name))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn passed_fds_parse() {
+        assert_eq!(
+            parse_passed_fds(Some("100"), 100, Some("2"), Some("hwC1D0:fw1")),
+            vec![("hwC1D0".to_string(), 3), ("fw1".to_string(), 4)]
+        );
+
+        // The names more than the file descriptors are ignored.
+        assert_eq!(
+            parse_passed_fds(Some("100"), 100, Some("1"), Some("hwC1D0:fw1")),
+            vec![("hwC1D0".to_string(), 3)]
+        );
+
+        // The file descriptors for the other process.
+        assert_eq!(
+            parse_passed_fds(Some("101"), 100, Some("2"), Some("hwC1D0:fw1")),
+            vec![]
+        );
+
+        assert_eq!(parse_passed_fds(None, 100, None, None), vec![]);
+    }
+
+    #[test]
+    fn devnode_path_default() {
+        assert_eq!(default_devnode_path("hwC1D0"), "/dev/snd/hwC1D0");
+        assert_eq!(default_devnode_path("fw1"), "/dev/fw1");
+    }
+}
@@ -0,0 +1,196 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Digital reference level for scaling of meters.
+//!
+//! In broadcast practice, 0 VU is aligned to digital reference level such as -18 dBFS or
+//! -20 dBFS. The element for reference level allows users to choose it per sound card, then the
+//! TLV data of meter elements is rewritten so that the dB value is relative to the reference
+//! level. The choice is preserved by 'alsactl store/restore', or given by the environment
+//! variable so that the fleet of devices is configured consistently.
+//!
+//! The switches of nominal level in the models are not changed, since they are for analog level
+//! (e.g. +4 dBu or -10 dBV) and no model supports digital reference level in its hardware.
+
+use {
+    super::{card_cntr::*, feature_report::*, *},
+    alsactl::{prelude::*, *},
+    glib::FileError,
+};
+
+/// The environment variable for the reference level in dBFS, like '-18'.
+pub const REF_LEVEL_ENV: &str = "SND_FIREWIRE_CTL_REF_LEVEL";
+
+/// The name of element for reference level.
+pub const REF_LEVEL_NAME: &str = "reference-level";
+
+// The pair of reference level in 0.01 dB and its label.
+const REF_LEVELS: [(i32, &str); 4] = [
+    (0, "0dBFS"),
+    (-1400, "-14dBFS"),
+    (-1800, "-18dBFS"),
+    (-2000, "-20dBFS"),
+];
+
+// The types of TLV defined in 'include/uapi/sound/tlv.h'.
+const SNDRV_CTL_TLVT_DB_SCALE: u32 = 1;
+const SNDRV_CTL_TLVT_DB_LINEAR: u32 = 2;
+const SNDRV_CTL_TLVT_DB_MINMAX: u32 = 4;
+const SNDRV_CTL_TLVT_DB_MINMAX_MUTE: u32 = 5;
+
+/// Parse the reference level in dBFS, with or without 'dBFS' suffix, into the index of
+/// supported levels.
+pub fn parse_ref_level(arg: &str) -> Option<usize> {
+    let literal = arg.trim();
+    let literal = literal.strip_suffix("dBFS").unwrap_or(literal).trim();
+    literal
+        .parse::<i32>()
+        .ok()
+        .and_then(|val| REF_LEVELS.iter().position(|&(level, _)| level == val * 100))
+}
+
+/// Shift the range of TLV data for dB so that the value is relative to the reference level in
+/// 0.01 dB. None is returned for TLV data of the other types.
+pub fn shift_db_tlv(raw: &[u32], ref_level: i32) -> Option<Vec<u32>> {
+    if raw.len() < 4 {
+        return None;
+    }
+
+    let mut tlv = raw.to_vec();
+    match raw[0] {
+        SNDRV_CTL_TLVT_DB_SCALE => {
+            tlv[2] = (raw[2] as i32 - ref_level) as u32;
+        }
+        SNDRV_CTL_TLVT_DB_LINEAR | SNDRV_CTL_TLVT_DB_MINMAX | SNDRV_CTL_TLVT_DB_MINMAX_MUTE => {
+            tlv[2] = (raw[2] as i32 - ref_level) as u32;
+            tlv[3] = (raw[3] as i32 - ref_level) as u32;
+        }
+        _ => return None,
+    }
+    Some(tlv)
+}
+
+/// The state of reference level.
+#[derive(Default, Debug)]
+pub struct RefLevel {
+    elem_id: Option<ElemId>,
+    meters: Vec<ElemId>,
+}
+
+impl RefLevel {
+    /// Add the element for reference level when any meter element with TLV data for dB is found
+    /// in the given list.
+    pub fn load(&mut self, card_cntr: &mut CardCntr, elem_id_list: &[ElemId]) -> Result<(), Error> {
+        self.meters = elem_id_list
+            .iter()
+            .filter(|elem_id| elem_id.name().contains("meter"))
+            .filter(|elem_id| {
+                card_cntr
+                    .elem_tlv(elem_id)
+                    .and_then(|tlv| shift_db_tlv(tlv, 0))
+                    .is_some()
+            })
+            .cloned()
+            .collect();
+
+        if self.meters.len() == 0 {
+            return Ok(());
+        }
+
+        let labels: Vec<&str> = REF_LEVELS.iter().map(|&(_, label)| label).collect();
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, REF_LEVEL_NAME, 0);
+        let elem_id_list = card_cntr.add_enum_elems(&elem_id, 1, 1, &labels, None, true)?;
+        let elem_id = elem_id_list[0].clone();
+
+        let mut elem_value = ElemValue::new();
+        if let Some(arg) = std::env::var_os(REF_LEVEL_ENV) {
+            let arg = arg.to_string_lossy();
+            let pos = parse_ref_level(&arg).ok_or_else(|| {
+                let msg = format!("Unsupported reference level: {}", arg);
+                Error::new(FileError::Inval, &msg)
+            })?;
+            elem_value.set_enum(&[pos as u32]);
+            card_cntr.card.write_elem_value(&elem_id, &elem_value)?;
+        } else {
+            // The element can be reused, thus the level is restored from it.
+            card_cntr.card.read_elem_value(&elem_id, &mut elem_value)?;
+        }
+        self.elem_id = Some(elem_id);
+
+        self.apply(card_cntr, elem_value.enumerated()[0] as usize)
+    }
+
+    /// Handle the event of element. True is returned when the element is for reference level.
+    pub fn handle_elem_event(
+        &mut self,
+        card_cntr: &mut CardCntr,
+        elem_id: &ElemId,
+        events: &ElemEventMask,
+    ) -> bool {
+        if self.elem_id.as_ref() != Some(elem_id) {
+            return false;
+        }
+
+        if events.contains(ElemEventMask::VALUE) {
+            let mut elem_value = ElemValue::new();
+            let res = card_cntr
+                .card
+                .read_elem_value(elem_id, &mut elem_value)
+                .and_then(|_| self.apply(card_cntr, elem_value.enumerated()[0] as usize));
+            if let Err(e) = res {
+                report_feature(REF_LEVEL_NAME, &format!("failed: {}", e));
+            }
+        }
+
+        true
+    }
+
+    fn apply(&self, card_cntr: &mut CardCntr, pos: usize) -> Result<(), Error> {
+        let &(ref_level, _) = REF_LEVELS.iter().nth(pos).ok_or_else(|| {
+            let msg = format!("Invalid index of reference level: {}", pos);
+            Error::new(FileError::Inval, &msg)
+        })?;
+
+        self.meters.iter().try_for_each(|elem_id| {
+            match card_cntr
+                .elem_tlv(elem_id)
+                .and_then(|tlv| shift_db_tlv(tlv, ref_level))
+            {
+                Some(tlv) => card_cntr.card.write_elem_tlv(elem_id, &tlv),
+                None => Ok(()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ref_level_parse() {
+        assert_eq!(parse_ref_level("-18"), Some(2));
+        assert_eq!(parse_ref_level("-20dBFS"), Some(3));
+        assert_eq!(parse_ref_level(" -14 dBFS"), Some(1));
+        assert_eq!(parse_ref_level("0"), Some(0));
+        assert_eq!(parse_ref_level("-16"), None);
+        assert_eq!(parse_ref_level("dBFS"), None);
+    }
+
+    #[test]
+    fn ref_level_shift() {
+        let raw = [SNDRV_CTL_TLVT_DB_MINMAX, 8, (-4800i32) as u32, 0];
+        let tlv = shift_db_tlv(&raw, -1800).unwrap();
+        assert_eq!(tlv[2] as i32, -3000);
+        assert_eq!(tlv[3] as i32, 1800);
+
+        let raw = [SNDRV_CTL_TLVT_DB_SCALE, 8, (-9600i32) as u32, 100];
+        let tlv = shift_db_tlv(&raw, -2000).unwrap();
+        assert_eq!(tlv[2] as i32, -7600);
+        assert_eq!(tlv[3], 100);
+
+        let raw = [3, 8, 0, 0];
+        assert_eq!(shift_db_tlv(&raw, -1800), None);
+        assert_eq!(shift_db_tlv(&raw[..2], -1800), None);
+    }
+}
@@ -0,0 +1,556 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Optional sandbox of service program.
+//!
+//! The service programs run long with the access to character devices, thus the sandbox is
+//! available for defense in depth. When the environment variable is given, the service program
+//! engages the sandbox after opening the character devices for the unit, and before launching
+//! the threads for dispatchers, so that all of threads in the process are restricted. The
+//! sandbox consists of two parts:
+//!
+//!  * Landlock ruleset, which prohibits any access to file system except for the paths the
+//!    runtime requires later; procfs for the state of PCM substreams, MIDI ports, and process,
+//!    ALSA Sequencer character device, the files of configuration, and the directories of
+//!    sockets given by the environment variables.
+//!  * Seccomp filter to allow the system calls used by the runtime, GLib, and the underlying
+//!    libraries. The list of system calls is not exercised enough in all of supported models yet,
+//!    thus the other system calls are allowed as well, but logged by kernel so that the list can
+//!    be completed by the logs before failing them.
+//!
+//! The part not supported by the kernel or architecture is skipped, and recorded in the report
+//! of features.
+
+use {
+    super::{
+        elem_alias::*, elem_locale::*, fader_taper::*, feature_report::*, link_quirk::*,
+        meter_trigger::*, metrics::*, midi_map::*, mirror::*, *,
+    },
+    glib::FileError,
+    nix::libc,
+    std::{
+        ffi::{CString, OsString},
+        io,
+        mem::size_of,
+        os::unix::ffi::OsStrExt,
+        path::{Path, PathBuf},
+    },
+};
+
+/// The environment variable to engage the sandbox.
+pub const SANDBOX_ENV: &str = "SND_FIREWIRE_CTL_SANDBOX";
+
+// The system calls for landlock have the same number in all of architectures.
+const SYS_LANDLOCK_CREATE_RULESET: libc::c_long = 444;
+const SYS_LANDLOCK_ADD_RULE: libc::c_long = 445;
+const SYS_LANDLOCK_RESTRICT_SELF: libc::c_long = 446;
+
+const LANDLOCK_RULE_PATH_BENEATH: libc::c_int = 1;
+
+// The access rights to file system in the first ABI of landlock.
+const LANDLOCK_ACCESS_FS_ALL: u64 = (1 << 13) - 1;
+
+/// The access right to write file.
+pub const LANDLOCK_ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+/// The access right to read file.
+pub const LANDLOCK_ACCESS_FS_READ_FILE: u64 = 1 << 2;
+/// The access right to read directory.
+pub const LANDLOCK_ACCESS_FS_READ_DIR: u64 = 1 << 3;
+/// The access right to remove file.
+pub const LANDLOCK_ACCESS_FS_REMOVE_FILE: u64 = 1 << 5;
+/// The access right to create socket.
+pub const LANDLOCK_ACCESS_FS_MAKE_SOCK: u64 = 1 << 9;
+
+#[repr(C)]
+struct LandlockRulesetAttr {
+    handled_access_fs: u64,
+}
+
+#[repr(C, packed)]
+struct LandlockPathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: i32,
+}
+
+/// The path and the access rights beneath it allowed in the sandbox.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SandboxRule {
+    pub path: PathBuf,
+    pub access: u64,
+}
+
+impl SandboxRule {
+    fn new<P: AsRef<Path>>(path: P, access: u64) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            access,
+        }
+    }
+}
+
+/// Collect the rules for the paths which the runtime requires after engaging the sandbox. The
+/// function to retrieve environment variable is given.
+pub fn collect_sandbox_rules<F>(var_os: F) -> Vec<SandboxRule>
+where
+    F: Fn(&str) -> Option<OsString>,
+{
+    let mut rules = vec![
+        // The state of PCM substreams and MIDI ports of sound card, and the process owning the
+        // element.
+        SandboxRule::new(
+            "/proc",
+            LANDLOCK_ACCESS_FS_READ_FILE | LANDLOCK_ACCESS_FS_READ_DIR,
+        ),
+        // ALSA Sequencer clients for the features of MIDI.
+        SandboxRule::new(
+            "/dev/snd/seq",
+            LANDLOCK_ACCESS_FS_READ_FILE | LANDLOCK_ACCESS_FS_WRITE_FILE,
+        ),
+    ];
+
+    // The files of configuration are read when adding elements.
+    [
+        ALIAS_MAP_ENV,
+        LOCALE_MAP_ENV,
+        FADER_TAPER_ENV,
+        LINK_QUIRK_ENV,
+        METER_TRIGGER_ENV,
+        MIDI_MAP_ENV,
+    ]
+    .iter()
+    .filter_map(|&name| var_os(name))
+    .for_each(|path| rules.push(SandboxRule::new(path, LANDLOCK_ACCESS_FS_READ_FILE)));
+
+    // The sockets are created, and removed at finishing.
+    let sock_access = LANDLOCK_ACCESS_FS_MAKE_SOCK | LANDLOCK_ACCESS_FS_REMOVE_FILE;
+    if let Some(dir) = var_os(METRICS_DIR_ENV) {
+        rules.push(SandboxRule::new(dir, sock_access));
+    }
+    if let Some(config) = var_os(MIRROR_ENV)
+        .and_then(|arg| arg.into_string().ok())
+        .and_then(|arg| parse_mirror_config(&arg).ok())
+    {
+        if let Some(dir) = config.path.parent() {
+            rules.push(SandboxRule::new(dir, sock_access));
+        }
+    }
+
+    rules
+}
+
+// Add the rule to the ruleset. The path not existing is skipped since the runtime reports the
+// failure to access it.
+fn add_landlock_rule(ruleset_fd: libc::c_int, rule: &SandboxRule) -> Result<(), Error> {
+    let path = match CString::new(rule.path.as_os_str().as_bytes()) {
+        Ok(path) => path,
+        Err(_) => return Ok(()),
+    };
+    let fd = unsafe { libc::open(path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC) };
+    if fd < 0 {
+        return Ok(());
+    }
+
+    // The access rights for directory are not available for file.
+    let mut access = rule.access;
+    if !rule.path.is_dir() {
+        access &= LANDLOCK_ACCESS_FS_READ_FILE | LANDLOCK_ACCESS_FS_WRITE_FILE;
+    }
+
+    let attr = LandlockPathBeneathAttr {
+        allowed_access: access,
+        parent_fd: fd,
+    };
+    let res = unsafe {
+        libc::syscall(
+            SYS_LANDLOCK_ADD_RULE,
+            ruleset_fd,
+            LANDLOCK_RULE_PATH_BENEATH,
+            &attr as *const LandlockPathBeneathAttr,
+            0,
+        )
+    };
+    let err = io::Error::last_os_error();
+
+    unsafe { libc::close(fd) };
+
+    if res < 0 {
+        let label = format!("Fail to add landlock rule for {}", rule.path.display());
+        Err(io_error(&label, err))
+    } else {
+        Ok(())
+    }
+}
+
+fn io_error(label: &str, err: io::Error) -> Error {
+    let msg = format!("{}: {}", label, err);
+    Error::new(FileError::Io, &msg)
+}
+
+fn set_no_new_privs() -> Result<(), Error> {
+    let res = unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) };
+    if res < 0 {
+        Err(io_error(
+            "Fail to set no_new_privs",
+            io::Error::last_os_error(),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Prohibit any access to file system later except for the rules. False is returned when
+/// landlock is not supported.
+pub fn restrict_file_system(rules: &[SandboxRule]) -> Result<bool, Error> {
+    let attr = LandlockRulesetAttr {
+        handled_access_fs: LANDLOCK_ACCESS_FS_ALL,
+    };
+    let fd = unsafe {
+        libc::syscall(
+            SYS_LANDLOCK_CREATE_RULESET,
+            &attr as *const LandlockRulesetAttr,
+            size_of::<LandlockRulesetAttr>(),
+            0,
+        )
+    };
+    if fd < 0 {
+        let err = io::Error::last_os_error();
+        return match err.raw_os_error() {
+            Some(libc::ENOSYS) | Some(libc::EOPNOTSUPP) => Ok(false),
+            _ => Err(io_error("Fail to create landlock ruleset", err)),
+        };
+    }
+    let fd = fd as libc::c_int;
+
+    let res = rules
+        .iter()
+        .try_for_each(|rule| add_landlock_rule(fd, rule))
+        .and_then(|_| set_no_new_privs())
+        .and_then(|_| {
+            let res = unsafe { libc::syscall(SYS_LANDLOCK_RESTRICT_SELF, fd, 0) };
+            if res < 0 {
+                let err = io::Error::last_os_error();
+                Err(io_error("Fail to restrict by landlock ruleset", err))
+            } else {
+                Ok(true)
+            }
+        });
+
+    unsafe { libc::close(fd) };
+
+    res
+}
+
+/// The instruction of classic BPF.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SockFilter {
+    pub code: u16,
+    pub jt: u8,
+    pub jf: u8,
+    pub k: u32,
+}
+
+#[repr(C)]
+struct SockFprog {
+    len: libc::c_ushort,
+    filter: *const SockFilter,
+}
+
+const BPF_LD_W_ABS: u16 = 0x20;
+const BPF_JMP_JEQ_K: u16 = 0x15;
+const BPF_RET_K: u16 = 0x06;
+
+// The offsets in seccomp_data structure.
+const SECCOMP_DATA_NR_OFFSET: u32 = 0;
+const SECCOMP_DATA_ARCH_OFFSET: u32 = 4;
+
+const SECCOMP_SET_MODE_FILTER: libc::c_uint = 1;
+const SECCOMP_GET_ACTION_AVAIL: libc::c_uint = 2;
+const SECCOMP_FILTER_FLAG_TSYNC: libc::c_uint = 1;
+const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+const SECCOMP_RET_LOG: u32 = 0x7ffc_0000;
+const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: Option<u32> = Some(0xc000_003e);
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: Option<u32> = Some(0xc000_00b7);
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const AUDIT_ARCH: Option<u32> = None;
+
+/// The system calls allowed by the seccomp filter, used by the runtime, GLib, and the underlying
+/// libraries; e.g. to operate character devices and sockets, to poll file descriptors, to
+/// manage memory and threads, and to read procfs.
+#[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
+const ALLOWED_SYSCALLS: &[libc::c_long] = &[
+    // File descriptors.
+    libc::SYS_read,
+    libc::SYS_write,
+    libc::SYS_readv,
+    libc::SYS_writev,
+    libc::SYS_pread64,
+    libc::SYS_pwrite64,
+    libc::SYS_lseek,
+    libc::SYS_openat,
+    libc::SYS_close,
+    libc::SYS_ioctl,
+    libc::SYS_fcntl,
+    libc::SYS_dup,
+    libc::SYS_dup3,
+    libc::SYS_pipe2,
+    libc::SYS_eventfd2,
+    libc::SYS_fstat,
+    libc::SYS_newfstatat,
+    libc::SYS_statx,
+    libc::SYS_getdents64,
+    libc::SYS_readlinkat,
+    libc::SYS_faccessat,
+    libc::SYS_unlinkat,
+    // Polling.
+    libc::SYS_ppoll,
+    libc::SYS_pselect6,
+    libc::SYS_epoll_create1,
+    libc::SYS_epoll_ctl,
+    libc::SYS_epoll_pwait,
+    libc::SYS_timerfd_create,
+    libc::SYS_timerfd_settime,
+    libc::SYS_timerfd_gettime,
+    // Sockets.
+    libc::SYS_socket,
+    libc::SYS_socketpair,
+    libc::SYS_bind,
+    libc::SYS_listen,
+    libc::SYS_accept,
+    libc::SYS_accept4,
+    libc::SYS_connect,
+    libc::SYS_shutdown,
+    libc::SYS_sendto,
+    libc::SYS_recvfrom,
+    libc::SYS_sendmsg,
+    libc::SYS_recvmsg,
+    libc::SYS_getsockopt,
+    libc::SYS_setsockopt,
+    libc::SYS_getsockname,
+    libc::SYS_getpeername,
+    // Memory.
+    libc::SYS_brk,
+    libc::SYS_mmap,
+    libc::SYS_munmap,
+    libc::SYS_mremap,
+    libc::SYS_mprotect,
+    libc::SYS_madvise,
+    libc::SYS_membarrier,
+    // Threads and process.
+    libc::SYS_clone,
+    libc::SYS_clone3,
+    libc::SYS_exit,
+    libc::SYS_exit_group,
+    libc::SYS_futex,
+    libc::SYS_set_robust_list,
+    libc::SYS_get_robust_list,
+    libc::SYS_set_tid_address,
+    libc::SYS_rseq,
+    libc::SYS_sched_yield,
+    libc::SYS_sched_getaffinity,
+    libc::SYS_getpid,
+    libc::SYS_gettid,
+    libc::SYS_getppid,
+    libc::SYS_getuid,
+    libc::SYS_geteuid,
+    libc::SYS_getgid,
+    libc::SYS_getegid,
+    libc::SYS_umask,
+    libc::SYS_getresuid,
+    libc::SYS_getresgid,
+    libc::SYS_tgkill,
+    libc::SYS_prctl,
+    libc::SYS_prlimit64,
+    libc::SYS_uname,
+    libc::SYS_getrandom,
+    libc::SYS_restart_syscall,
+    // Signals.
+    libc::SYS_rt_sigaction,
+    libc::SYS_rt_sigprocmask,
+    libc::SYS_rt_sigreturn,
+    libc::SYS_sigaltstack,
+    // Time.
+    libc::SYS_clock_gettime,
+    libc::SYS_clock_getres,
+    libc::SYS_clock_nanosleep,
+    libc::SYS_nanosleep,
+    libc::SYS_gettimeofday,
+    // The legacy system calls available in the architecture.
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_open,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_stat,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_lstat,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_access,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_readlink,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_unlink,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_dup2,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_pipe,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_poll,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_select,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_epoll_wait,
+    #[cfg(target_arch = "x86_64")]
+    libc::SYS_arch_prctl,
+];
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+const ALLOWED_SYSCALLS: &[libc::c_long] = &[];
+
+fn stmt(code: u16, k: u32) -> SockFilter {
+    SockFilter {
+        code,
+        jt: 0,
+        jf: 0,
+        k,
+    }
+}
+
+fn jump(code: u16, k: u32, jt: u8, jf: u8) -> SockFilter {
+    SockFilter { code, jt, jf, k }
+}
+
+/// Build the program of seccomp filter to allow the system calls. The other system calls are
+/// allowed after logged by kernel. The process is killed when the system call is for the other
+/// architecture.
+pub fn build_seccomp_filter(arch: u32, syscalls: &[libc::c_long]) -> Vec<SockFilter> {
+    let mut prog = vec![
+        stmt(BPF_LD_W_ABS, SECCOMP_DATA_ARCH_OFFSET),
+        jump(BPF_JMP_JEQ_K, arch, 1, 0),
+        stmt(BPF_RET_K, SECCOMP_RET_KILL_PROCESS),
+        stmt(BPF_LD_W_ABS, SECCOMP_DATA_NR_OFFSET),
+    ];
+    syscalls.iter().for_each(|&nr| {
+        prog.push(jump(BPF_JMP_JEQ_K, nr as u32, 0, 1));
+        prog.push(stmt(BPF_RET_K, SECCOMP_RET_ALLOW));
+    });
+    prog.push(stmt(BPF_RET_K, SECCOMP_RET_LOG));
+    prog
+}
+
+/// Log the system calls not used by the service program. False is returned when the architecture
+/// or the action to log is not supported.
+pub fn restrict_syscalls() -> Result<bool, Error> {
+    let arch = match AUDIT_ARCH {
+        Some(arch) => arch,
+        None => return Ok(false),
+    };
+
+    // The kernel older than v4.14 does not support the action to log, and handles it as the
+    // action to kill.
+    let action = SECCOMP_RET_LOG;
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_seccomp,
+            SECCOMP_GET_ACTION_AVAIL,
+            0,
+            &action as *const u32,
+        )
+    };
+    if res < 0 {
+        return Ok(false);
+    }
+
+    let prog = build_seccomp_filter(arch, ALLOWED_SYSCALLS);
+    let fprog = SockFprog {
+        len: prog.len() as libc::c_ushort,
+        filter: prog.as_ptr(),
+    };
+
+    set_no_new_privs()?;
+
+    // The filter is applied to the threads running already as well, if any.
+    let res = unsafe {
+        libc::syscall(
+            libc::SYS_seccomp,
+            SECCOMP_SET_MODE_FILTER,
+            SECCOMP_FILTER_FLAG_TSYNC,
+            &fprog as *const SockFprog,
+        )
+    };
+    if res < 0 {
+        let err = io::Error::last_os_error();
+        match err.raw_os_error() {
+            Some(libc::ENOSYS) | Some(libc::EINVAL) => Ok(false),
+            _ => Err(io_error("Fail to install seccomp filter", err)),
+        }
+    } else if res > 0 {
+        let msg = format!("Fail to apply seccomp filter to the thread: {}", res);
+        Err(Error::new(FileError::Io, &msg))
+    } else {
+        Ok(true)
+    }
+}
+
+/// Engage the sandbox when the environment variable is given. It should be called after opening
+/// the character devices for the unit, and before launching the threads for dispatchers, since
+/// landlock restricts the calling thread and the threads launched by it only.
+pub fn engage_sandbox() -> Result<(), Error> {
+    if std::env::var_os(SANDBOX_ENV).is_none() {
+        return Ok(());
+    }
+
+    let rules = collect_sandbox_rules(|name| std::env::var_os(name));
+    let landlock = restrict_file_system(&rules)?;
+    report_feature(
+        "sandbox-landlock",
+        if landlock { "engaged" } else { "unavailable" },
+    );
+
+    let seccomp = restrict_syscalls()?;
+    report_feature(
+        "sandbox-seccomp",
+        if seccomp { "logging" } else { "unavailable" },
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn seccomp_filter_build() {
+        let prog = build_seccomp_filter(0xc000_003e, &[59, 101]);
+        assert_eq!(prog.len(), 9);
+        assert_eq!(prog[1], jump(BPF_JMP_JEQ_K, 0xc000_003e, 1, 0));
+        assert_eq!(prog[4], jump(BPF_JMP_JEQ_K, 59, 0, 1));
+        assert_eq!(prog[5], stmt(BPF_RET_K, SECCOMP_RET_ALLOW));
+        assert_eq!(prog[6], jump(BPF_JMP_JEQ_K, 101, 0, 1));
+        assert_eq!(prog[8], stmt(BPF_RET_K, SECCOMP_RET_LOG));
+    }
+
+    #[test]
+    fn sandbox_rules_collect() {
+        let rules = collect_sandbox_rules(|name| match name {
+            ALIAS_MAP_ENV => Some(OsString::from("/etc/aliases.txt")),
+            MIRROR_ENV => Some(OsString::from("secondary:/run/mirror/card1.sock")),
+            _ => None,
+        });
+        assert_eq!(rules.len(), 4);
+        assert_eq!(rules[0].path, PathBuf::from("/proc"));
+        assert_eq!(
+            rules[2],
+            SandboxRule::new("/etc/aliases.txt", LANDLOCK_ACCESS_FS_READ_FILE)
+        );
+        assert_eq!(
+            rules[3],
+            SandboxRule::new(
+                "/run/mirror",
+                LANDLOCK_ACCESS_FS_MAKE_SOCK | LANDLOCK_ACCESS_FS_REMOVE_FILE
+            )
+        );
+    }
+}
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Periodic verification of cached state against hardware.
+//!
+//! Some devices have no notification mechanism for the change of parameters by front panel or by
+//! the other host in IEEE 1394 bus. The helper selects rotating subset of elements in each
+//! period of low-frequency timer, so that the runtime reads the parameters from hardware again
+//! and reconciles the elements if they differ. It is disabled unless the interval is given by
+//! the environment variable for the family.
+
+use {super::*, glib::FileError, std::time::Duration};
+
+/// The prefix of environment variable for interval of verification in seconds. The name of
+/// family in upper case follows, like 'SND_FIREWIRE_CTL_SCRUB_INTERVAL_BEBOB'.
+pub const SCRUB_INTERVAL_ENV_PREFIX: &str = "SND_FIREWIRE_CTL_SCRUB_INTERVAL";
+
+/// The name of feature in the report for verification.
+pub const SCRUB_FEATURE_NAME: &str = "scrub";
+
+/// Parse the interval in seconds. Zero means to disable verification.
+pub fn parse_scrub_interval(arg: &str) -> Result<Option<Duration>, Error> {
+    arg.trim()
+        .parse::<u64>()
+        .map(|secs| {
+            if secs > 0 {
+                Some(Duration::from_secs(secs))
+            } else {
+                None
+            }
+        })
+        .map_err(|_| {
+            let msg = format!("Invalid interval of verification: {}", arg);
+            Error::new(FileError::Inval, &msg)
+        })
+}
+
+/// Retrieve the interval of verification for the family from the environment variable.
+pub fn scrub_interval_from_env(family: &str) -> Result<Option<Duration>, Error> {
+    let name = format!("{}_{}", SCRUB_INTERVAL_ENV_PREFIX, family.to_uppercase());
+    match std::env::var(&name) {
+        Ok(arg) => parse_scrub_interval(&arg),
+        Err(_) => Ok(None),
+    }
+}
+
+/// The state of rotation over elements.
+#[derive(Debug)]
+pub struct Scrubber {
+    batch_size: usize,
+    pos: usize,
+}
+
+impl Default for Scrubber {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_BATCH_SIZE)
+    }
+}
+
+impl Scrubber {
+    /// The default number of elements verified in one period, small enough not to disturb the
+    /// other transactions.
+    pub const DEFAULT_BATCH_SIZE: usize = 8;
+
+    pub fn new(batch_size: usize) -> Self {
+        Self { batch_size, pos: 0 }
+    }
+
+    /// Select the next subset of the given elements.
+    pub fn next_batch<T: Clone>(&mut self, elem_id_list: &[T]) -> Vec<T> {
+        if elem_id_list.len() == 0 {
+            return Vec::new();
+        }
+
+        let count = self.batch_size.min(elem_id_list.len());
+        let pos = self.pos % elem_id_list.len();
+        self.pos = (pos + count) % elem_id_list.len();
+
+        elem_id_list
+            .iter()
+            .cycle()
+            .skip(pos)
+            .take(count)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn scrub_rotation() {
+        let list = [0, 1, 2, 3, 4];
+        let mut scrubber = Scrubber::new(2);
+        assert_eq!(scrubber.next_batch(&list), vec![0, 1]);
+        assert_eq!(scrubber.next_batch(&list), vec![2, 3]);
+        assert_eq!(scrubber.next_batch(&list), vec![4, 0]);
+        assert_eq!(scrubber.next_batch(&list[..1]), vec![0]);
+        assert_eq!(scrubber.next_batch::<i32>(&[]), vec![]);
+
+        let mut scrubber = Scrubber::new(8);
+        assert_eq!(scrubber.next_batch(&list), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn scrub_interval_parse() {
+        assert_eq!(
+            parse_scrub_interval("30").unwrap(),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(parse_scrub_interval("0").unwrap(), None);
+        assert!(parse_scrub_interval("-1").is_err());
+    }
+}
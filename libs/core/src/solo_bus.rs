@@ -0,0 +1,362 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Mode of solo in monitor bus over the solo implemented by the device.
+//!
+//! The device dims or mutes the other channels of monitor bus when a channel is soloed, in the
+//! manner of AFL (after-fader listen). In PFL (pre-fader listen) mode, the gain of soloed channel
+//! is additionally set to unity so that it is heard regardless of the position of its fader, then
+//! releasing the solo restores it. Muted channels are left as is.
+//!
+//! The mode is implemented over the elements for gain of channel, thus the change is propagated
+//! to the device by the model as the change of value in the elements. The gain changed by the
+//! others (e.g. user space applications) during solo is respected at release.
+
+use {
+    super::{card_cntr::*, feature_report::*, *},
+    alsactl::{prelude::*, *},
+    glib::FileError,
+};
+
+/// The name of element for mode of solo.
+pub const SOLO_BUS_MODE_NAME: &str = "solo-bus-mode";
+
+/// The mode of solo.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SoloBusMode {
+    /// After-fader listen.
+    Afl,
+    /// Pre-fader listen.
+    Pfl,
+}
+
+impl Default for SoloBusMode {
+    fn default() -> Self {
+        Self::Afl
+    }
+}
+
+const SOLO_BUS_MODES: [(SoloBusMode, &str); 2] =
+    [(SoloBusMode::Afl, "AFL"), (SoloBusMode::Pfl, "PFL")];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum SoloBusState {
+    Idle,
+    // The pair of original gain and the gain applied in PFL mode.
+    Applied(i32, i32),
+    // The gain was changed by the others during solo.
+    Overridden,
+}
+
+impl Default for SoloBusState {
+    fn default() -> Self {
+        Self::Idle
+    }
+}
+
+/// The state of solo for channels in monitor bus.
+#[derive(Default, Debug)]
+pub struct SoloBus {
+    states: Vec<SoloBusState>,
+}
+
+impl SoloBus {
+    /// Compute the gains of channels according to the mode and the state of solo and mute. The
+    /// original gains are kept while soloed in PFL mode, then they are returned at release.
+    pub fn update(
+        &mut self,
+        mode: SoloBusMode,
+        solos: &[bool],
+        mutes: &[bool],
+        gains: &[i32],
+        unity: i32,
+    ) -> Vec<i32> {
+        self.states.resize(gains.len(), Default::default());
+
+        gains
+            .iter()
+            .zip(solos)
+            .zip(mutes)
+            .zip(self.states.iter_mut())
+            .map(|(((&gain, &solo), &mute), state)| {
+                let pfl = solo && !mute && mode == SoloBusMode::Pfl;
+                match *state {
+                    SoloBusState::Applied(_, applied) if applied != gain => {
+                        *state = if pfl {
+                            SoloBusState::Overridden
+                        } else {
+                            SoloBusState::Idle
+                        };
+                        gain
+                    }
+                    SoloBusState::Applied(orig, applied) => {
+                        if pfl {
+                            applied
+                        } else {
+                            *state = SoloBusState::Idle;
+                            orig
+                        }
+                    }
+                    SoloBusState::Overridden => {
+                        if !pfl {
+                            *state = SoloBusState::Idle;
+                        }
+                        gain
+                    }
+                    SoloBusState::Idle => {
+                        if pfl {
+                            *state = SoloBusState::Applied(gain, unity);
+                            unity
+                        } else {
+                            gain
+                        }
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+/// The elements for monitor bus to which the mode of solo is added.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SoloBusTarget {
+    /// The name of element for gain of channels.
+    pub gain: &'static str,
+    /// The name of element for mute of channels.
+    pub mute: &'static str,
+    /// The name of element for solo of channels, implemented by the device.
+    pub solo: &'static str,
+    /// The value of gain for unity in PFL mode. The maximum value of element is used if None.
+    pub unity: Option<i32>,
+}
+
+#[derive(Debug)]
+struct SoloBusEntry {
+    gain: ElemId,
+    mute: ElemId,
+    solo: ElemId,
+    value_count: usize,
+    unity: i32,
+    bus: SoloBus,
+}
+
+/// The state of solo for monitor buses.
+#[derive(Default, Debug)]
+pub struct SoloBusCtl {
+    entries: Vec<SoloBusEntry>,
+    mode: SoloBusMode,
+    mode_elem_id: Option<ElemId>,
+}
+
+fn find_elem_id_list(elem_id_list: &[ElemId], name: &str) -> Vec<ElemId> {
+    let mut list: Vec<ElemId> = elem_id_list
+        .iter()
+        .filter(|elem_id| elem_id.name() == name)
+        .cloned()
+        .collect();
+    list.sort_by_key(|elem_id| elem_id.index());
+    list
+}
+
+fn read_int_shape(card: &Card, elem_id: &ElemId) -> Result<(usize, i32), Error> {
+    let elem_info = card.elem_info(elem_id)?;
+    match &elem_info {
+        ElemInfo::Integer(info) => Ok((info.value_count() as usize, info.value_max())),
+        _ => {
+            let msg = format!("{} is not integer element", elem_id.name());
+            Err(Error::new(FileError::Inval, &msg))
+        }
+    }
+}
+
+impl SoloBusCtl {
+    /// Add the element for mode of solo when any monitor bus in the given targets is found.
+    pub fn load(
+        &mut self,
+        card_cntr: &mut CardCntr,
+        targets: &[SoloBusTarget],
+    ) -> Result<(), Error> {
+        let elem_id_list = card_cntr.card.elem_id_list()?;
+
+        for target in targets {
+            let gains = find_elem_id_list(&elem_id_list, target.gain);
+            let mutes = find_elem_id_list(&elem_id_list, target.mute);
+            let solos = find_elem_id_list(&elem_id_list, target.solo);
+            if gains.len() == 0 || gains.len() != mutes.len() || gains.len() != solos.len() {
+                continue;
+            }
+
+            let (value_count, max) = read_int_shape(&card_cntr.card, &gains[0])?;
+
+            gains
+                .into_iter()
+                .zip(mutes)
+                .zip(solos)
+                .for_each(|((gain, mute), solo)| {
+                    self.entries.push(SoloBusEntry {
+                        gain,
+                        mute,
+                        solo,
+                        value_count,
+                        unity: target.unity.unwrap_or(max),
+                        bus: Default::default(),
+                    });
+                });
+        }
+
+        if self.entries.len() == 0 {
+            return Ok(());
+        }
+
+        let labels: Vec<&str> = SOLO_BUS_MODES.iter().map(|&(_, label)| label).collect();
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, SOLO_BUS_MODE_NAME, 0);
+        let elem_id_list = card_cntr.add_enum_elems(&elem_id, 1, 1, &labels, None, true)?;
+
+        // The element can be reused, thus the mode is restored from it.
+        let mut elem_value = ElemValue::new();
+        card_cntr
+            .card
+            .read_elem_value(&elem_id_list[0], &mut elem_value)?;
+        self.mode = SOLO_BUS_MODES
+            .iter()
+            .nth(elem_value.enumerated()[0] as usize)
+            .map(|&(mode, _)| mode)
+            .unwrap_or_default();
+        self.mode_elem_id = Some(elem_id_list[0].clone());
+
+        Ok(())
+    }
+
+    /// Handle the event of element. True is returned when the element is for mode of solo. The
+    /// elements for solo and mute are also watched, while False is returned so that the model
+    /// handles them. The failure to apply is reported in the list of features.
+    pub fn handle_elem_event(
+        &mut self,
+        card_cntr: &mut CardCntr,
+        elem_id: &ElemId,
+        events: &ElemEventMask,
+    ) -> bool {
+        let is_mode = self.mode_elem_id.as_ref() == Some(elem_id);
+        let is_watched = self
+            .entries
+            .iter()
+            .any(|entry| entry.solo.eq(elem_id) || entry.mute.eq(elem_id));
+        if !is_mode && !is_watched {
+            return false;
+        }
+
+        if events.contains(ElemEventMask::VALUE) {
+            let res = if is_mode {
+                self.apply_mode(&card_cntr.card, elem_id)
+            } else {
+                let card = &card_cntr.card;
+                let mode = self.mode;
+                self.entries
+                    .iter_mut()
+                    .filter(|entry| entry.solo.eq(elem_id) || entry.mute.eq(elem_id))
+                    .try_for_each(|entry| apply_entry(card, mode, entry))
+            };
+            if let Err(e) = res {
+                report_feature(SOLO_BUS_MODE_NAME, &format!("failed: {}", e));
+            }
+        }
+
+        is_mode
+    }
+
+    fn apply_mode(&mut self, card: &Card, elem_id: &ElemId) -> Result<(), Error> {
+        let mut elem_value = ElemValue::new();
+        card.read_elem_value(elem_id, &mut elem_value)?;
+        let pos = elem_value.enumerated()[0] as usize;
+        let &(mode, _) = SOLO_BUS_MODES.iter().nth(pos).ok_or_else(|| {
+            let msg = format!("Invalid index of solo mode: {}", pos);
+            Error::new(FileError::Inval, &msg)
+        })?;
+        self.mode = mode;
+
+        self.entries
+            .iter_mut()
+            .try_for_each(|entry| apply_entry(card, mode, entry))
+    }
+}
+
+fn apply_entry(card: &Card, mode: SoloBusMode, entry: &mut SoloBusEntry) -> Result<(), Error> {
+    let count = entry.value_count;
+
+    let mut elem_value = ElemValue::new();
+    card.read_elem_value(&entry.solo, &mut elem_value)?;
+    let solos = elem_value.boolean()[..count].to_vec();
+
+    card.read_elem_value(&entry.mute, &mut elem_value)?;
+    let mutes = elem_value.boolean()[..count].to_vec();
+
+    let mut gain_value = ElemValue::new();
+    card.read_elem_value(&entry.gain, &mut gain_value)?;
+    let gains = gain_value.int()[..count].to_vec();
+
+    let vals = entry.bus.update(mode, &solos, &mutes, &gains, entry.unity);
+    if vals != gains {
+        // The change is dispatched to the model by the event of element.
+        gain_value.set_int(&vals);
+        card.write_elem_value(&entry.gain, &gain_value)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn solo_bus_afl() {
+        let mut bus = SoloBus::default();
+        let mutes = [false, false, true];
+
+        // The gains are left as is.
+        let gains = bus.update(
+            SoloBusMode::Afl,
+            &[true, false, false],
+            &mutes,
+            &[50, 80, 30],
+            100,
+        );
+        assert_eq!(gains, vec![50, 80, 30]);
+
+        let gains = bus.update(SoloBusMode::Afl, &[false; 3], &mutes, &gains, 100);
+        assert_eq!(gains, vec![50, 80, 30]);
+    }
+
+    #[test]
+    fn solo_bus_pfl() {
+        let mut bus = SoloBus::default();
+        let mutes = [false, false, true];
+
+        let gains = bus.update(
+            SoloBusMode::Pfl,
+            &[false, true, true],
+            &mutes,
+            &[50, 80, 30],
+            100,
+        );
+        assert_eq!(gains, vec![50, 100, 30]);
+
+        // Released.
+        let gains = bus.update(SoloBusMode::Pfl, &[false; 3], &mutes, &gains, 100);
+        assert_eq!(gains, vec![50, 80, 30]);
+
+        // The gain changed by the others during solo is respected at release.
+        let gains = bus.update(SoloBusMode::Pfl, &[true, false, false], &mutes, &gains, 100);
+        assert_eq!(gains, vec![100, 80, 30]);
+        let gains = bus.update(
+            SoloBusMode::Pfl,
+            &[true, false, false],
+            &mutes,
+            &[90, 80, 30],
+            100,
+        );
+        assert_eq!(gains, vec![90, 80, 30]);
+        let gains = bus.update(SoloBusMode::Pfl, &[false; 3], &mutes, &gains, 100);
+        assert_eq!(gains, vec![90, 80, 30]);
+    }
+}
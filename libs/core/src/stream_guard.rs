@@ -0,0 +1,234 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Policy to protect controls critical to packet streaming.
+//!
+//! The change of sampling rate, mode of digital interface, and map of stream channels takes the
+//! unit to reconfigure its packet streaming, thus it interrupts the running PCM substreams such
+//! as JACK server. The module detects the running PCM substreams by procfs of ALSA, and rejects
+//! the change while they are running. The policy is enabled by default and configurable per
+//! runtime. The runtime gives the names of elements critical to packet streaming, and the change
+//! of them by applications is reverted. The runtime also checks the policy before changing them
+//! by itself.
+
+use {
+    super::{card_cntr::*, feature_report::*, *},
+    alsactl::{prelude::*, *},
+    hitaki::AlsaFirewireError,
+};
+
+/// The name of element to configure the policy.
+pub const STREAM_WRITE_PROTECT_NAME: &str = "stream-write-protect";
+
+/// Whether the PCM substream is active according to the content of its status node in procfs.
+pub fn is_pcm_substream_active(status: &str) -> bool {
+    status
+        .lines()
+        .filter_map(|line| line.strip_prefix("state:"))
+        .any(|state| match state.trim() {
+            "PREPARED" | "RUNNING" | "XRUN" | "DRAINING" | "PAUSED" => true,
+            _ => false,
+        })
+}
+
+/// Retrieve the sampling rate of PCM substream according to the content of its hw_params node in
+/// procfs.
+pub fn pcm_substream_rate(hw_params: &str) -> Option<u32> {
+    hw_params
+        .lines()
+        .filter_map(|line| line.strip_prefix("rate:"))
+        .find_map(|rate| rate.split_whitespace().next())
+        .and_then(|rate| rate.parse().ok())
+}
+
+// Read the node of each PCM substream in procfs. The name of substream and the content of node
+// are returned.
+fn read_pcm_substream_nodes(card_id: u32, node: &str) -> Vec<(String, String)> {
+    let card_path = format!("/proc/asound/card{}", card_id);
+    let mut nodes: Vec<(String, String)> = std::fs::read_dir(&card_path)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| name.starts_with("pcm"))
+                .flat_map(|pcm| {
+                    let pcm_path = format!("{}/{}", card_path, pcm);
+                    std::fs::read_dir(&pcm_path)
+                        .map(|entries| {
+                            entries
+                                .filter_map(|entry| entry.ok())
+                                .filter_map(|entry| entry.file_name().into_string().ok())
+                                .filter(|name| name.starts_with("sub"))
+                                .filter_map(|sub| {
+                                    let path = format!("{}/{}/{}", pcm_path, sub, node);
+                                    std::fs::read_to_string(path)
+                                        .ok()
+                                        .map(|content| (format!("{}/{}", pcm, sub), content))
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_else(|_| Vec::new())
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    nodes.sort();
+    nodes
+}
+
+/// Detect the active PCM substreams of the sound card. The name of each substream is returned,
+/// like 'pcm0p/sub0'.
+pub fn active_pcm_substreams(card_id: u32) -> Vec<String> {
+    read_pcm_substream_nodes(card_id, "status")
+        .into_iter()
+        .filter(|(_, status)| is_pcm_substream_active(status))
+        .map(|(name, _)| name)
+        .collect()
+}
+
+/// Detect the sampling rates of PCM substreams of the sound card, of which hardware parameters
+/// are configured.
+pub fn configured_pcm_rates(card_id: u32) -> Vec<u32> {
+    let mut rates: Vec<u32> = read_pcm_substream_nodes(card_id, "hw_params")
+        .iter()
+        .filter_map(|(_, hw_params)| pcm_substream_rate(hw_params))
+        .collect();
+    rates.sort();
+    rates.dedup();
+    rates
+}
+
+/// The policy to protect controls critical to packet streaming.
+#[derive(Debug)]
+pub struct StreamGuard {
+    card_id: u32,
+    protect: bool,
+    critical_elems: Vec<String>,
+}
+
+impl Default for StreamGuard {
+    fn default() -> Self {
+        Self {
+            card_id: 0,
+            protect: true,
+            critical_elems: Default::default(),
+        }
+    }
+}
+
+impl StreamGuard {
+    /// Add the element to configure the policy, with the names of elements critical to packet
+    /// streaming.
+    pub fn load(
+        &mut self,
+        card_cntr: &mut CardCntr,
+        card_id: u32,
+        critical_elems: &[&str],
+    ) -> Result<(), Error> {
+        self.card_id = card_id;
+        self.critical_elems = critical_elems.iter().map(|name| name.to_string()).collect();
+
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, STREAM_WRITE_PROTECT_NAME, 0);
+        let elem_id_list = card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
+
+        let elem_value = ElemValue::new();
+        elem_value.set_bool(&[self.protect]);
+        card_cntr
+            .card
+            .write_elem_value(&elem_id_list[0], &elem_value)
+    }
+
+    /// Handle the event of element. True is returned when the element is for the policy, or the
+    /// change of element critical to packet streaming is rejected, thus the element is back to
+    /// the value applied to the unit. The reason of rejection and the failure to restore the
+    /// value are recorded in the report of features, thus the runtime should update it.
+    pub fn handle_elem_event(
+        &mut self,
+        card_cntr: &mut CardCntr,
+        elem_id: &ElemId,
+        events: &ElemEventMask,
+    ) -> bool {
+        let name = elem_id.name();
+
+        if name == STREAM_WRITE_PROTECT_NAME {
+            if events.contains(ElemEventMask::VALUE) {
+                let mut elem_value = ElemValue::new();
+                if card_cntr
+                    .card
+                    .read_elem_value(elem_id, &mut elem_value)
+                    .is_ok()
+                {
+                    self.protect = elem_value.boolean()[0];
+                }
+            }
+            true
+        } else if events.contains(ElemEventMask::VALUE)
+            && self.critical_elems.iter().any(|n| n == name.as_str())
+        {
+            match self.check(&name) {
+                Ok(_) => false,
+                Err(e) => {
+                    let msg = match card_cntr.restore_elem_value(elem_id) {
+                        Ok(_) => e.to_string(),
+                        Err(err) => format!("{}, and fail to restore: {}", e, err),
+                    };
+                    report_feature(STREAM_WRITE_PROTECT_NAME, &msg);
+                    true
+                }
+            }
+        } else {
+            false
+        }
+    }
+
+    /// Check whether the control critical to packet streaming is allowed to be changed. The
+    /// error reports the running PCM substreams in detail.
+    pub fn check(&self, name: &str) -> Result<(), Error> {
+        if !self.protect {
+            return Ok(());
+        }
+
+        let substreams = active_pcm_substreams(self.card_id);
+        if substreams.len() > 0 {
+            let msg = format!(
+                "{} is not changed while PCM substreams are running: {}",
+                name,
+                substreams.join(", ")
+            );
+            Err(Error::new(AlsaFirewireError::IsLocked, &msg))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pcm_substream_status() {
+        assert!(!is_pcm_substream_active("closed\n"));
+        assert!(!is_pcm_substream_active(
+            "state: OPEN\nowner_pid   : 1234\n"
+        ));
+        assert!(!is_pcm_substream_active(
+            "state: SETUP\nowner_pid   : 1234\n"
+        ));
+        assert!(is_pcm_substream_active(
+            "state: PREPARED\nowner_pid   : 1234\n"
+        ));
+        assert!(is_pcm_substream_active(
+            "state: RUNNING\nowner_pid   : 1234\ntrigger_time: 1.0\n"
+        ));
+    }
+
+    #[test]
+    fn pcm_substream_hw_params() {
+        assert_eq!(pcm_substream_rate("closed\n"), None);
+        assert_eq!(
+            pcm_substream_rate("access: MMAP_INTERLEAVED\nformat: S32_LE\nrate: 48000 (48000/1)\n"),
+            Some(48000)
+        );
+    }
+}
@@ -0,0 +1,250 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Statistics of packet streaming for the unit.
+//!
+//! ALSA HwDep character device for units on IEEE 1394 bus reports no counter of events in
+//! packet streaming, while the drivers stop the packet streaming at discontinuity of packets,
+//! then PCM substreams fall into XRUN state. The helper counts the transitions of PCM substreams
+//! to XRUN state by snooping their status in procfs, the loss of stream lock, and the bus reset,
+//! then exposes them by read-only elements so that users can correlate audio glitches with the
+//! problems in the bus. The element for the number of glitches in the last minute is updated as
+//! well, and the change of value is notified to applications as usual.
+//!
+//! The status in procfs is read at each period of timer for metering, thus the short XRUN state
+//! recovered by applications within the period can be missed.
+
+use {
+    super::{card_cntr::*, metrics::*, *},
+    alsactl::{prelude::*, *},
+    std::{
+        collections::VecDeque,
+        path::PathBuf,
+        time::{Duration, Instant},
+    },
+};
+
+/// The name of element for the number of XRUN in PCM substreams.
+pub const STREAM_XRUN_COUNT_NAME: &str = "stream-xrun-count";
+/// The name of element for the number of loss of stream lock.
+pub const STREAM_UNLOCK_COUNT_NAME: &str = "stream-unlock-count";
+/// The name of element for the number of bus reset.
+pub const BUS_RESET_COUNT_NAME: &str = "bus-reset-count";
+/// The name of element for the number of any glitch in the last minute.
+pub const STREAM_GLITCH_RATE_NAME: &str = "stream-glitch-rate";
+
+/// Parse the content of status file for PCM substream in procfs, then retrieve whether the
+/// substream is in XRUN state.
+pub fn parse_pcm_status_xrun(text: &str) -> bool {
+    text.lines()
+        .filter_map(|line| line.split_once(':'))
+        .any(|(key, val)| key.trim() == "state" && val.trim() == "XRUN")
+}
+
+/// Count the transitions to XRUN state against the states in the last period.
+pub fn count_xrun_transitions(prev: &[bool], curr: &[bool]) -> usize {
+    curr.iter()
+        .enumerate()
+        .filter(|&(i, &xrun)| xrun && !prev.get(i).copied().unwrap_or(false))
+        .count()
+}
+
+fn list_pcm_status_paths(card_id: u32) -> Vec<PathBuf> {
+    let dir = PathBuf::from(format!("/proc/asound/card{}", card_id));
+
+    let read_dir_names = |path: &PathBuf, prefix: &str| -> Vec<PathBuf> {
+        let mut list: Vec<PathBuf> = std::fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| {
+                        entry
+                            .file_name()
+                            .to_str()
+                            .map(|name| name.starts_with(prefix))
+                            .unwrap_or_default()
+                    })
+                    .map(|entry| entry.path())
+                    .collect()
+            })
+            .unwrap_or_default();
+        list.sort();
+        list
+    };
+
+    read_dir_names(&dir, "pcm")
+        .iter()
+        .flat_map(|pcm| read_dir_names(pcm, "sub"))
+        .map(|sub| sub.join("status"))
+        .collect()
+}
+
+/// The counter of glitches in the window of time.
+#[derive(Default, Debug)]
+pub struct GlitchRate {
+    events: VecDeque<Instant>,
+}
+
+impl GlitchRate {
+    /// The window of time.
+    pub const WINDOW: Duration = Duration::from_secs(60);
+
+    /// Record the glitches at the time.
+    pub fn record(&mut self, now: Instant, count: usize) {
+        (0..count).for_each(|_| self.events.push_back(now));
+    }
+
+    /// The number of glitches in the window until the time.
+    pub fn rate(&mut self, now: Instant) -> usize {
+        while let Some(&at) = self.events.front() {
+            if now.duration_since(at) < Self::WINDOW {
+                break;
+            }
+            self.events.pop_front();
+        }
+        self.events.len()
+    }
+}
+
+/// The statistics of packet streaming.
+#[derive(Default, Debug)]
+pub struct StreamStats {
+    /// The number of transitions to XRUN state in PCM substreams.
+    pub xruns: u64,
+    /// The number of loss of stream lock.
+    pub unlocks: u64,
+    /// The number of bus reset.
+    pub bus_resets: u64,
+    paths: Vec<PathBuf>,
+    states: Vec<bool>,
+    rate: GlitchRate,
+    elem_id_list: Vec<ElemId>,
+}
+
+impl StreamStats {
+    const COUNT_MAX: i32 = i32::MAX;
+
+    /// Add the elements for statistics of the sound card.
+    pub fn load(&mut self, card_cntr: &mut CardCntr, card_id: u32) -> Result<(), Error> {
+        self.paths = list_pcm_status_paths(card_id);
+        self.states = vec![false; self.paths.len()];
+
+        // The elements are not unlocked so that the other processes can not change them.
+        [
+            STREAM_XRUN_COUNT_NAME,
+            STREAM_UNLOCK_COUNT_NAME,
+            BUS_RESET_COUNT_NAME,
+            STREAM_GLITCH_RATE_NAME,
+        ]
+        .iter()
+        .try_for_each(|name| {
+            let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, name, 0);
+            card_cntr
+                .add_int_elems(&elem_id, 1, 0, Self::COUNT_MAX, 1, 1, None, false)
+                .map(|mut elem_id_list| self.elem_id_list.append(&mut elem_id_list))
+        })
+    }
+
+    /// Whether the element is for statistics.
+    pub fn has_elem(&self, elem_id: &ElemId) -> bool {
+        self.elem_id_list.iter().any(|e| e.eq(elem_id))
+    }
+
+    /// Record the notification of stream lock.
+    pub fn record_stream_lock(&mut self, locked: bool) {
+        if !locked {
+            self.unlocks += 1;
+            self.rate.record(Instant::now(), 1);
+        }
+    }
+
+    /// Record the bus reset.
+    pub fn record_bus_reset(&mut self) {
+        self.bus_resets += 1;
+        self.rate.record(Instant::now(), 1);
+    }
+
+    /// Read the status of PCM substreams in procfs.
+    pub fn poll(&mut self) {
+        let states: Vec<bool> = self
+            .paths
+            .iter()
+            .map(|path| {
+                std::fs::read_to_string(path)
+                    .map(|text| parse_pcm_status_xrun(&text))
+                    .unwrap_or_default()
+            })
+            .collect();
+        let count = count_xrun_transitions(&self.states, &states);
+        self.states = states;
+
+        if count > 0 {
+            self.xruns += count as u64;
+            self.rate.record(Instant::now(), count);
+        }
+    }
+
+    /// Copy the counters to metrics.
+    pub fn update_metrics(&self, metrics: &mut UnitMetrics) {
+        metrics.stream_xruns = self.xruns;
+        metrics.stream_unlocks = self.unlocks;
+        metrics.bus_resets = self.bus_resets;
+    }
+
+    /// Update the elements when the value differs.
+    pub fn update(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
+        let rate = self.rate.rate(Instant::now()) as u64;
+
+        self.elem_id_list.iter().try_for_each(|elem_id| {
+            let val = match elem_id.name().as_str() {
+                STREAM_XRUN_COUNT_NAME => self.xruns,
+                STREAM_UNLOCK_COUNT_NAME => self.unlocks,
+                BUS_RESET_COUNT_NAME => self.bus_resets,
+                STREAM_GLITCH_RATE_NAME => rate,
+                _ => return Ok(()),
+            };
+            let val = std::cmp::min(val, Self::COUNT_MAX as u64) as i32;
+
+            let mut elem_value = ElemValue::new();
+            card_cntr.card.read_elem_value(elem_id, &mut elem_value)?;
+            if elem_value.int()[0] != val {
+                elem_value.set_int(&[val]);
+                card_cntr.card.write_elem_value(elem_id, &elem_value)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pcm_status_parse() {
+        let text = "state: RUNNING\nowner_pid   : 1234\ntrigger_time: 1.2\n";
+        assert!(!parse_pcm_status_xrun(text));
+        let text = "state: XRUN\nowner_pid   : 1234\n";
+        assert!(parse_pcm_status_xrun(text));
+        assert!(!parse_pcm_status_xrun("closed\n"));
+    }
+
+    #[test]
+    fn xrun_transitions() {
+        assert_eq!(count_xrun_transitions(&[false, false], &[true, false]), 1);
+        // The substream kept in XRUN state is not counted again.
+        assert_eq!(count_xrun_transitions(&[true, false], &[true, true]), 1);
+        assert_eq!(count_xrun_transitions(&[], &[true, true]), 2);
+    }
+
+    #[test]
+    fn glitch_rate() {
+        let mut rate = GlitchRate::default();
+        let now = Instant::now();
+        rate.record(now, 2);
+        rate.record(now + Duration::from_secs(30), 1);
+        assert_eq!(rate.rate(now + Duration::from_secs(59)), 3);
+        assert_eq!(rate.rate(now + Duration::from_secs(60)), 1);
+        assert_eq!(rate.rate(now + Duration::from_secs(90)), 0);
+    }
+}
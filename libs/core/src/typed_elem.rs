@@ -0,0 +1,258 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Typed wrappers of element value.
+//!
+//! The wrappers keep the shape of element; the number of values and the available range of
+//! them. They validate the values given by user space applications, convert them into the type
+//! used by protocol, and detect the values changed against the old ones. Unlike the closure-based
+//! ElemValueAccessor, the number of values is not given at each call site, thus the values
+//! beyond the actual number of channels are never processed.
+//!
+//! The wrappers are expected to be associated constants of the structure for control, like:
+//!
+//! ```ignore
+//! const MODE_ELEM: EnumElem = EnumElem::new(1, MODES.len());
+//! ```
+
+use {
+    super::*,
+    alsactl::{prelude::*, ElemValue},
+    glib::FileError,
+    std::{convert::TryFrom, fmt::Debug},
+};
+
+/// The common operations for typed wrapper of element.
+pub trait TypedElem {
+    /// The type of value used by protocol.
+    type Value: Copy + PartialEq + Debug;
+
+    /// The number of values in the element.
+    fn count(&self) -> usize;
+
+    /// Retrieve the values from the element value with validation and conversion.
+    fn decode(&self, elem_value: &ElemValue) -> Result<Vec<Self::Value>, Error>;
+
+    /// Put the values into the element value with conversion.
+    fn encode(&self, elem_value: &ElemValue, vals: &[Self::Value]);
+
+    /// Fill the values of element by the callback for each index.
+    fn set_vals<F>(&self, elem_value: &ElemValue, cb: F) -> Result<(), Error>
+    where
+        F: FnMut(usize) -> Result<Self::Value, Error>,
+    {
+        (0..self.count())
+            .map(cb)
+            .collect::<Result<Vec<Self::Value>, Error>>()
+            .map(|vals| self.encode(elem_value, &vals))
+    }
+
+    /// Put the value into the first of element.
+    fn set_val(&self, elem_value: &ElemValue, val: Self::Value) {
+        self.encode(elem_value, &[val])
+    }
+
+    /// Retrieve the first value of element after validation.
+    fn get_val(&self, elem_value: &ElemValue) -> Result<Self::Value, Error> {
+        self.decode(elem_value).map(|vals| vals[0])
+    }
+
+    /// Retrieve the values changed against the old element value after validation.
+    fn changes(
+        &self,
+        new: &ElemValue,
+        old: &ElemValue,
+    ) -> Result<Vec<(usize, Self::Value)>, Error> {
+        let new_vals = self.decode(new)?;
+        // The old values are not validated since they can be out of range at initial state.
+        let old_vals: Vec<Option<Self::Value>> = match self.decode(old) {
+            Ok(vals) => vals.into_iter().map(Some).collect(),
+            Err(_) => vec![None; self.count()],
+        };
+        Ok(detect_changes(&new_vals, &old_vals))
+    }
+}
+
+fn detect_changes<T: Copy + PartialEq>(new: &[T], old: &[Option<T>]) -> Vec<(usize, T)> {
+    new.iter()
+        .zip(old)
+        .enumerate()
+        .filter(|(_, (n, o))| Some(**n) != **o)
+        .map(|(idx, (&n, _))| (idx, n))
+        .collect()
+}
+
+fn check_count(count: usize, len: usize) -> Result<(), Error> {
+    if count > len {
+        let msg = format!(
+            "The number of values {} is over the capacity {}",
+            count, len
+        );
+        Err(Error::new(FileError::Inval, &msg))
+    } else {
+        Ok(())
+    }
+}
+
+/// The wrapper of element with boolean values.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BoolElem {
+    /// The number of values.
+    pub count: usize,
+}
+
+impl BoolElem {
+    pub const fn new(count: usize) -> Self {
+        Self { count }
+    }
+}
+
+impl TypedElem for BoolElem {
+    type Value = bool;
+
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn decode(&self, elem_value: &ElemValue) -> Result<Vec<bool>, Error> {
+        let vals = elem_value.boolean();
+        check_count(self.count, vals.len())?;
+        Ok(vals[..self.count].to_vec())
+    }
+
+    fn encode(&self, elem_value: &ElemValue, vals: &[bool]) {
+        let mut raw = elem_value.boolean().to_vec();
+        raw.iter_mut().zip(vals).for_each(|(r, &v)| *r = v);
+        elem_value.set_bool(&raw);
+    }
+}
+
+/// The wrapper of element with enumerated values, converted into the index of item.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EnumElem {
+    /// The number of values.
+    pub count: usize,
+    /// The number of items.
+    pub items: usize,
+}
+
+impl EnumElem {
+    pub const fn new(count: usize, items: usize) -> Self {
+        Self { count, items }
+    }
+}
+
+fn check_enum_val(val: u32, items: usize) -> Result<usize, Error> {
+    if (val as usize) < items {
+        Ok(val as usize)
+    } else {
+        let msg = format!("Invalid index of item: {} over {}", val, items);
+        Err(Error::new(FileError::Inval, &msg))
+    }
+}
+
+impl TypedElem for EnumElem {
+    type Value = usize;
+
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn decode(&self, elem_value: &ElemValue) -> Result<Vec<usize>, Error> {
+        let vals = elem_value.enumerated();
+        check_count(self.count, vals.len())?;
+        vals[..self.count]
+            .iter()
+            .map(|&val| check_enum_val(val, self.items))
+            .collect()
+    }
+
+    fn encode(&self, elem_value: &ElemValue, vals: &[usize]) {
+        let mut raw = elem_value.enumerated().to_vec();
+        raw.iter_mut().zip(vals).for_each(|(r, &v)| *r = v as u32);
+        elem_value.set_enum(&raw);
+    }
+}
+
+/// The wrapper of element with integer values, converted into the type for protocol.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct IntElem<N> {
+    /// The number of values.
+    pub count: usize,
+    /// The minimum value.
+    pub min: N,
+    /// The maximum value.
+    pub max: N,
+}
+
+impl<N> IntElem<N> {
+    pub const fn new(count: usize, min: N, max: N) -> Self {
+        Self { count, min, max }
+    }
+}
+
+fn check_int_val<N>(val: i32, min: N, max: N) -> Result<N, Error>
+where
+    N: Copy + PartialOrd + TryFrom<i32> + Debug,
+{
+    N::try_from(val)
+        .ok()
+        .filter(|v| *v >= min && *v <= max)
+        .ok_or_else(|| {
+            let msg = format!("Invalid value: {} out of {:?}..={:?}", val, min, max);
+            Error::new(FileError::Inval, &msg)
+        })
+}
+
+impl<N> TypedElem for IntElem<N>
+where
+    N: Copy + PartialOrd + TryFrom<i32> + Into<i32> + Debug,
+{
+    type Value = N;
+
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn decode(&self, elem_value: &ElemValue) -> Result<Vec<N>, Error> {
+        let vals = elem_value.int();
+        check_count(self.count, vals.len())?;
+        vals[..self.count]
+            .iter()
+            .map(|&val| check_int_val(val, self.min, self.max))
+            .collect()
+    }
+
+    fn encode(&self, elem_value: &ElemValue, vals: &[N]) {
+        let mut raw = elem_value.int().to_vec();
+        raw.iter_mut().zip(vals).for_each(|(r, &v)| *r = v.into());
+        elem_value.set_int(&raw);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn typed_elem_validation() {
+        assert_eq!(check_enum_val(2, 3).unwrap(), 2);
+        assert!(check_enum_val(3, 3).is_err());
+
+        assert_eq!(check_int_val::<i16>(-0x100, -0x7fff, 0).unwrap(), -0x100);
+        assert!(check_int_val::<i16>(1, -0x7fff, 0).is_err());
+        assert!(check_int_val::<i16>(0x10000, i16::MIN, i16::MAX).is_err());
+        assert!(check_int_val::<u8>(-1, 0, 0xff).is_err());
+
+        assert!(check_count(4, 128).is_ok());
+        assert!(check_count(129, 128).is_err());
+    }
+
+    #[test]
+    fn typed_elem_changes() {
+        let new = [1, 2, 3, 4];
+        let old = [Some(1), Some(5), Some(3), None];
+        assert_eq!(detect_changes(&new, &old), vec![(1, 2), (3, 4)]);
+        assert_eq!(detect_changes(&new[..2], &old), vec![(1, 2)]);
+    }
+}
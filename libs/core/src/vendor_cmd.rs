@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Console of vendor-specific command for maintainers.
+//!
+//! The module includes helpers for a pair of bytes elements to send raw frame of vendor-specific
+//! command and to receive the response, so that maintainers and advanced users can experiment
+//! with undocumented commands without building custom binaries. The module is available when
+//! `vendor-cmd-console` feature is enabled.
+//!
+//! The frame consists of quadlets in big endian order. The first quadlet is the number of
+//! following quadlets, and the rest of element is filled with zero. The layout of the following
+//! quadlets is specific to each runtime.
+
+use {
+    super::{card_cntr::*, *},
+    alsactl::{prelude::*, *},
+    glib::FileError,
+};
+
+/// The name of element to send the frame of command.
+pub const VENDOR_CMD_NAME: &str = "vendor-command";
+
+/// The name of element to receive the frame of response.
+pub const VENDOR_RESP_NAME: &str = "vendor-response";
+
+/// The size of frame in bytes.
+pub const VENDOR_FRAME_SIZE: usize = 512;
+
+/// The maximum number of quadlets in the frame, except for the first quadlet.
+pub const VENDOR_FRAME_MAX_QUADLETS: usize = VENDOR_FRAME_SIZE / 4 - 1;
+
+/// Add the elements for command and response. The element for response is not unlocked so that
+/// the other processes can not change it.
+pub fn add_vendor_cmd_elems(card_cntr: &mut CardCntr) -> Result<(), Error> {
+    let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, VENDOR_CMD_NAME, 0);
+    let _ = card_cntr.add_bytes_elems(&elem_id, 1, VENDOR_FRAME_SIZE, None, true)?;
+
+    let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, VENDOR_RESP_NAME, 0);
+    let _ = card_cntr.add_bytes_elems(&elem_id, 1, VENDOR_FRAME_SIZE, None, false)?;
+
+    Ok(())
+}
+
+/// Parse the frame into quadlets.
+pub fn parse_vendor_frame(raw: &[u8]) -> Result<Vec<u32>, Error> {
+    if raw.len() != VENDOR_FRAME_SIZE {
+        let msg = format!("Unexpected size of frame: {}", raw.len());
+        return Err(Error::new(FileError::Inval, &msg));
+    }
+
+    let mut quadlet = [0; 4];
+    let quadlets: Vec<u32> = raw
+        .chunks_exact(4)
+        .map(|chunk| {
+            quadlet.copy_from_slice(chunk);
+            u32::from_be_bytes(quadlet)
+        })
+        .collect();
+
+    let count = quadlets[0] as usize;
+    if count > VENDOR_FRAME_MAX_QUADLETS {
+        let msg = format!("Unexpected number of quadlets in frame: {}", count);
+        return Err(Error::new(FileError::Inval, &msg));
+    }
+
+    Ok(quadlets[1..(1 + count)].to_vec())
+}
+
+/// Build the frame from quadlets.
+pub fn build_vendor_frame(quadlets: &[u32]) -> Result<Vec<u8>, Error> {
+    if quadlets.len() > VENDOR_FRAME_MAX_QUADLETS {
+        let msg = format!("Unexpected number of quadlets in frame: {}", quadlets.len());
+        return Err(Error::new(FileError::Inval, &msg));
+    }
+
+    let mut raw = Vec::with_capacity(VENDOR_FRAME_SIZE);
+    raw.extend_from_slice(&(quadlets.len() as u32).to_be_bytes());
+    quadlets
+        .iter()
+        .for_each(|quadlet| raw.extend_from_slice(&quadlet.to_be_bytes()));
+    raw.resize(VENDOR_FRAME_SIZE, 0);
+    Ok(raw)
+}
+
+/// Write the response into the element.
+pub fn write_vendor_resp(card_cntr: &mut CardCntr, quadlets: &[u32]) -> Result<(), Error> {
+    let raw = build_vendor_frame(quadlets)?;
+    let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, VENDOR_RESP_NAME, 0);
+    let mut elem_value = ElemValue::new();
+    elem_value.set_bytes(&raw);
+    card_cntr.card.write_elem_value(&elem_id, &elem_value)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn vendor_frame_serdes() {
+        let quadlets = [0x00000003, 0x00000005, 0x01234567];
+        let raw = build_vendor_frame(&quadlets).unwrap();
+        assert_eq!(raw.len(), VENDOR_FRAME_SIZE);
+        assert_eq!(&raw[..8], &[0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x03]);
+        assert_eq!(parse_vendor_frame(&raw).unwrap(), quadlets);
+
+        let mut raw = vec![0; VENDOR_FRAME_SIZE];
+        raw[..4].copy_from_slice(&(VENDOR_FRAME_MAX_QUADLETS as u32 + 1).to_be_bytes());
+        assert!(parse_vendor_frame(&raw).is_err());
+        assert!(parse_vendor_frame(&raw[..8]).is_err());
+
+        let quadlets = vec![0; VENDOR_FRAME_MAX_QUADLETS + 1];
+        assert!(build_vendor_frame(&quadlets).is_err());
+    }
+}
@@ -4,12 +4,19 @@ mod model;
 
 use {
     alsactl::{prelude::*, *},
-    core::{card_cntr::*, dispatcher::*, elem_value_accessor::*, RuntimeOperation},
+    core::{
+        card_cntr::*, diagnostics::*, dispatcher::*, elem_alias::*, elem_locale::*,
+        elem_value_accessor::*, feature_report::*, meter_trigger::*, panic_ctl::*, stream_guard::*,
+        RuntimeOperation,
+    },
     firewire_digi00x_protocols as protocols,
     glib::{
         source, {Error, FileError},
     },
-    hinawa::{prelude::{FwNodeExt, FwNodeExtManual}, FwNode, FwReq},
+    hinawa::{
+        prelude::{FwNodeExt, FwNodeExtManual},
+        FwNode, FwReq,
+    },
     hitaki::{prelude::*, *},
     ieee1212_config_rom::ConfigRom,
     model::*,
@@ -42,6 +49,10 @@ pub struct Dg00xRuntime {
     notified_elems: Vec<ElemId>,
     timer: Option<Dispatcher>,
     measured_elem_id_list: Vec<ElemId>,
+    stream_guard: StreamGuard,
+    feature_report: FeatureReport,
+    panic_ctl: PanicCtl,
+    meter_trigger: MeterTrigger,
 }
 
 impl<'a> Drop for Dg00xRuntime {
@@ -62,6 +73,11 @@ impl<'a> Drop for Dg00xRuntime {
 // NOTE: Additionally, in model ID field:
 //   0x000001: the console models
 //   0x000002: the rack models
+//
+// The control surface of console models (faders, buttons, and transport) is not operated by the
+// runtime. ALSA firewire-digi00x driver receives the messages from the surface by asynchronous
+// transaction and delivers them via the dedicated ALSA rawmidi substream, thus the sequencer
+// applications can use them directly as well as messages for the surface.
 const SPECIFIER_ID_DIGI002: u32 = 0x0000a3;
 const SPECIFIER_ID_DIGI002_RACK: u32 = 0x0000a4;
 const SPECIFIER_ID_DIGI003: u32 = 0x0000aa;
@@ -70,12 +86,12 @@ const SPECIFIER_ID_DIGI003_RACK: u32 = 0x0000ab;
 impl RuntimeOperation<u32> for Dg00xRuntime {
     fn new(card_id: u32) -> Result<Self, Error> {
         let unit = SndDigi00x::new();
-        unit.open(&format!("/dev/snd/hwC{}D0", card_id), 0)?;
+        unit.open(&Self::devnode_path(&format!("hwC{}D0", card_id)), 0)?;
 
         let card_cntr = CardCntr::default();
         card_cntr.card.open(card_id, 0)?;
 
-        let cdev = format!("/dev/{}", unit.node_device().unwrap());
+        let cdev = Self::devnode_path(&unit.node_device().unwrap());
         let node = FwNode::new();
         node.open(&cdev)?;
         let rom = node.config_rom()?;
@@ -112,6 +128,10 @@ impl RuntimeOperation<u32> for Dg00xRuntime {
             notified_elems,
             measured_elem_id_list,
             timer,
+            stream_guard: Default::default(),
+            feature_report: Default::default(),
+            panic_ctl: Default::default(),
+            meter_trigger: Default::default(),
         })
     }
 
@@ -119,6 +139,8 @@ impl RuntimeOperation<u32> for Dg00xRuntime {
         self.launch_node_event_dispatcher()?;
         self.launch_system_event_dispatcher()?;
 
+        setup_label_translations(&mut self.card_cntr);
+
         match &mut self.model {
             Model::Digi002(m) => m.load(&mut self.unit, &mut self.card_cntr),
             Model::Digi003(m) => m.load(&mut self.unit, &mut self.card_cntr),
@@ -139,6 +161,21 @@ impl RuntimeOperation<u32> for Dg00xRuntime {
             let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
         }
 
+        let card_id = self.unit.0.card_id();
+        self.stream_guard
+            .load(&mut self.card_cntr, card_id, &Self::STREAM_CRITICAL_ELEMS)?;
+
+        self.panic_ctl
+            .load(&mut self.card_cntr, &Self::PANIC_TARGETS)?;
+
+        self.meter_trigger
+            .setup(&self.card_cntr, Self::METER_TRIGGER_CLIENT_NAME);
+
+        setup_aliases(&mut self.card_cntr);
+
+        self.feature_report.load(&mut self.card_cntr)?;
+        self.feature_report.update(&mut self.card_cntr)?;
+
         Ok(())
     }
 
@@ -155,6 +192,27 @@ impl RuntimeOperation<u32> for Dg00xRuntime {
                     println!("IEEE 1394 bus is updated: {}", generation);
                 }
                 Event::Elem((elem_id, events)) => {
+                    if self.feature_report.has_elem(&elem_id) {
+                        // The change of value is by the runtime itself.
+                        continue;
+                    }
+
+                    if self
+                        .panic_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .stream_guard
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
                     if elem_id.name() != Self::TIMER_NAME {
                         let _ = match &mut self.model {
                             Model::Digi002(m) => self.card_cntr.dispatch_elem_event(
@@ -170,6 +228,7 @@ impl RuntimeOperation<u32> for Dg00xRuntime {
                                 m,
                             ),
                         };
+                        let _ = self.feature_report.update(&mut self.card_cntr);
                     } else {
                         let mut elem_value = ElemValue::new();
                         if self
@@ -216,6 +275,8 @@ impl RuntimeOperation<u32> for Dg00xRuntime {
                             m,
                         ),
                     };
+                    self.meter_trigger
+                        .check_and_report(&mut self.card_cntr, &self.feature_report);
                 }
             }
         }
@@ -224,14 +285,31 @@ impl RuntimeOperation<u32> for Dg00xRuntime {
     }
 }
 
+impl DiagnosticsOperation<u32> for Dg00xRuntime {
+    fn collect_diagnostics(card_id: &u32) -> Result<Vec<DiagnosticsEntry>, Error> {
+        collect_common_diagnostics(*card_id)
+    }
+}
+
 impl<'a> Dg00xRuntime {
     const NODE_DISPATCHER_NAME: &'a str = "node event dispatcher";
     const SYSTEM_DISPATCHER_NAME: &'a str = "system event dispatcher";
     const TIMER_DISPATCHER_NAME: &'a str = "interval timer dispatcher";
 
     const TIMER_NAME: &'a str = "metering";
+    const METER_TRIGGER_CLIENT_NAME: &'static str = "Digi 00x meter trigger";
     const TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
 
+    // The controls critical to packet streaming, of which change is rejected while PCM
+    // substreams are running.
+    const STREAM_CRITICAL_ELEMS: [&'a str; 2] = ["local-clock-rate", "optical-interface"];
+
+    // The elements reset by panic switch.
+    const PANIC_TARGETS: [PanicTarget; 1] = [PanicTarget {
+        name: "monitor-source-gain",
+        state: None,
+    }];
+
     fn launch_node_event_dispatcher(&mut self) -> Result<(), Error> {
         let name = Self::NODE_DISPATCHER_NAME.to_string();
         let mut dispatcher = Dispatcher::run(name)?;
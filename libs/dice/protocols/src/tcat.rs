@@ -24,7 +24,10 @@ use {
     super::*,
     glib::{error::ErrorDomain, Quark},
     hinawa::{prelude::FwReqExtManual, FwTcode},
-    std::convert::TryFrom,
+    std::{
+        convert::TryFrom,
+        sync::atomic::{AtomicUsize, Ordering},
+    },
 };
 
 mod utils;
@@ -134,9 +137,25 @@ pub struct GeneralProtocol;
 
 const BASE_ADDR: u64 = 0xffffe0000000;
 
+// The maximum size of payload for block transactions, which can be restricted for the node at
+// flaky link.
+static MAX_FRAME_SIZE: AtomicUsize = AtomicUsize::new(GeneralProtocol::MAX_FRAME_SIZE);
+
 impl GeneralProtocol {
     const MAX_FRAME_SIZE: usize = 512;
 
+    /// Restrict the size of payload for block transactions. The size is aligned to quadlet and
+    /// limited up to 512 bytes.
+    pub fn set_max_frame_size(size: usize) {
+        let size = (size / 4 * 4).clamp(4, Self::MAX_FRAME_SIZE);
+        MAX_FRAME_SIZE.store(size, Ordering::Relaxed);
+    }
+
+    /// The size of payload for block transactions.
+    pub fn max_frame_size() -> usize {
+        MAX_FRAME_SIZE.load(Ordering::Relaxed)
+    }
+
     pub fn read(
         req: &mut FwReq,
         node: &mut FwNode,
@@ -147,7 +166,7 @@ impl GeneralProtocol {
         let mut addr = BASE_ADDR + offset as u64;
 
         while frames.len() > 0 {
-            let len = std::cmp::min(frames.len(), Self::MAX_FRAME_SIZE);
+            let len = std::cmp::min(frames.len(), Self::max_frame_size());
             let tcode = if len == 4 {
                 FwTcode::ReadQuadletRequest
             } else {
@@ -173,7 +192,7 @@ impl GeneralProtocol {
         let mut addr = BASE_ADDR + (offset as u64);
 
         while frames.len() > 0 {
-            let len = std::cmp::min(frames.len(), Self::MAX_FRAME_SIZE);
+            let len = std::cmp::min(frames.len(), Self::max_frame_size());
             let tcode = if len == 4 {
                 FwTcode::WriteQuadletRequest
             } else {
@@ -215,7 +234,7 @@ impl GeneralProtocol {
     const NOTIFY_EXT_STATUS: u32 = 0x00000040;
 
     pub fn has_rx_config_changed(msg: u32) -> bool {
-        msg & msg & Self::NOTIFY_RX_CFG_CHG > 0
+        msg & Self::NOTIFY_RX_CFG_CHG > 0
     }
 
     pub fn has_tx_config_changed(msg: u32) -> bool {
@@ -26,6 +26,10 @@ use {
     std::cmp::Ordering,
 };
 
+// NOTE: The protocol extension includes no section for GPIO, thus the state of footswitch and
+// front panel lock is not available in the sections. Some models may expose them in application
+// section, while the layout is not investigated yet.
+
 /// Sections for protocol extension.
 #[derive(Default, Clone, Copy, Debug, Eq, PartialEq)]
 pub struct ExtensionSections {
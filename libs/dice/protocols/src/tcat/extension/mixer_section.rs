@@ -15,6 +15,16 @@ impl MixerSectionProtocol {
     const SATURATION_OFFSET: usize = 0x00;
     const COEFF_OFFSET: usize = 0x04;
 
+    /// The minimum value of coefficient for mute.
+    pub const COEF_MIN: u32 = 0;
+    /// The maximum value of coefficient. The coefficient is unsigned 2:14 fixed-point.
+    pub const COEF_MAX: u32 = 0x0000ffff;
+    /// The value of coefficient for unity gain.
+    pub const COEF_UNITY: u32 = 0x00004000;
+
+    /// The value of coefficient for the maximum in 0.01 dB, approximately +12.04 dB.
+    pub const COEF_DB_MAX: i32 = 1204;
+
     pub fn read_saturation(
         req: &mut FwReq,
         node: &mut FwNode,
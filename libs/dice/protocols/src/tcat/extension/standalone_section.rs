@@ -5,6 +5,12 @@
 //!
 //! The module includes structure, enumeration, and trait and its implementation for standalone
 //! section in protocol extension defined by TCAT for ASICs of DICE.
+//!
+//! The section includes configurations of sampling clock only. No field for attenuation of output
+//! level is defined for the case that the unit operates without host, thus the level of output in
+//! standalone mode is out of the section. Some vendors put such parameters in application
+//! section with their own layout (e.g. TC Electronic Konnekt series), and the support of them is
+//! implemented per model.
 
 use super::{global_section::*, *};
 
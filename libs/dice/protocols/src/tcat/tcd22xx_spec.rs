@@ -36,6 +36,9 @@ pub struct Output {
     pub label: Option<&'static str>,
 }
 
+/// The pair of stream blocks and the index of PCM channel in ALSA PCM substream for them.
+pub type StreamBlkPcmChannels = (Vec<(SrcBlk, usize)>, Vec<(DstBlk, usize)>);
+
 pub trait Tcd22xxSpecOperation {
     // For each model.
     const INPUTS: &'static [Input];
@@ -128,6 +131,38 @@ pub trait Tcd22xxSpecOperation {
         (src_blk_list, dst_blk_list)
     }
 
+    /// Compute the index of PCM channel in ALSA PCM substream for each stream block. ALSA dice
+    /// driver arranges the PCM channels of the first stream, then the ones of the second stream,
+    /// in the PCM substream.
+    fn compute_stream_blk_pcm_channels(
+        tx_entries: &[FormatEntry],
+        rx_entries: &[FormatEntry],
+    ) -> StreamBlkPcmChannels {
+        let mut offset = 0;
+        let dst_blk_list = tx_entries
+            .iter()
+            .zip([DstBlkId::Avs0, DstBlkId::Avs1])
+            .flat_map(|(entry, id)| {
+                let base = offset;
+                offset += entry.pcm_count as usize;
+                (0..entry.pcm_count).map(move |ch| (DstBlk { id, ch }, base + ch as usize))
+            })
+            .collect();
+
+        let mut offset = 0;
+        let src_blk_list = rx_entries
+            .iter()
+            .zip([SrcBlkId::Avs0, SrcBlkId::Avs1])
+            .flat_map(|(entry, id)| {
+                let base = offset;
+                offset += entry.pcm_count as usize;
+                (0..entry.pcm_count).map(move |ch| (SrcBlk { id, ch }, base + ch as usize))
+            })
+            .collect();
+
+        (src_blk_list, dst_blk_list)
+    }
+
     fn compute_avail_mixer_blk_pair(
         caps: &ExtensionCaps,
         rate_mode: RateMode,
@@ -0,0 +1,164 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Raw access to application section in protocol extension for models without specific support.
+//!
+//! The layout of application section is specific to vendor. The window of the section is exposed
+//! by bytes element at the offset given by integer element, so that advanced users can investigate
+//! the layout and contribute mappings. The window is read again when the offset is changed. The
+//! element is read-only unless `appl-space-write` feature is enabled for maintainers, and the last
+//! write is recorded in the report of features.
+
+use {
+    super::*,
+    protocols::tcat::extension::{appl_section::*, *},
+};
+
+const APPL_SPACE_OFFSET_NAME: &str = "application-space-offset";
+const APPL_SPACE_DATA_NAME: &str = "application-space-data";
+
+const APPL_SPACE_WRITE_FEATURE: &str = "appl-space-write";
+
+#[derive(Default)]
+pub struct ApplSpaceCtl {
+    size: usize,
+    offset: usize,
+    data: Vec<u8>,
+    pub notified_elem_list: Vec<ElemId>,
+}
+
+impl ApplSpaceCtl {
+    const WINDOW_SIZE: usize = 256;
+
+    pub fn load(
+        &mut self,
+        unit: &mut (SndDice, FwNode),
+        req: &mut FwReq,
+        sections: &ExtensionSections,
+        timeout_ms: u32,
+        card_cntr: &mut CardCntr,
+    ) -> Result<(), Error> {
+        self.data = vec![0; Self::WINDOW_SIZE];
+        self.size = sections.application.size;
+        if self.size < 4 {
+            return Ok(());
+        }
+
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, APPL_SPACE_OFFSET_NAME, 0);
+        let max = ((self.size - 4) / 4 * 4) as i32;
+        let _ = card_cntr.add_int_elems(&elem_id, 1, 0, max, 4, 1, None, true)?;
+
+        // The other processes can not change the element unless the feature is enabled.
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, APPL_SPACE_DATA_NAME, 0);
+        let mut elem_id_list = card_cntr.add_bytes_elems(
+            &elem_id,
+            1,
+            Self::WINDOW_SIZE,
+            None,
+            cfg!(feature = "appl-space-write"),
+        )?;
+        self.notified_elem_list.append(&mut elem_id_list);
+
+        self.cache(unit, req, sections, timeout_ms)
+    }
+
+    // The length of window within the section.
+    fn window_len(&self) -> usize {
+        std::cmp::min(Self::WINDOW_SIZE, self.size.saturating_sub(self.offset))
+    }
+
+    fn cache(
+        &mut self,
+        unit: &mut (SndDice, FwNode),
+        req: &mut FwReq,
+        sections: &ExtensionSections,
+        timeout_ms: u32,
+    ) -> Result<(), Error> {
+        let mut data = vec![0; Self::WINDOW_SIZE];
+        let len = self.window_len();
+        ApplSectionProtocol::read_appl_data(
+            req,
+            &mut unit.1,
+            sections,
+            self.offset,
+            &mut data[..len],
+            timeout_ms,
+        )?;
+        self.data = data;
+        Ok(())
+    }
+
+    pub fn read(&self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            APPL_SPACE_OFFSET_NAME => {
+                elem_value.set_int(&[self.offset as i32]);
+                Ok(true)
+            }
+            _ => self.read_notified_elem(elem_id, elem_value),
+        }
+    }
+
+    pub fn read_notified_elem(
+        &self,
+        elem_id: &ElemId,
+        elem_value: &mut ElemValue,
+    ) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            APPL_SPACE_DATA_NAME => {
+                elem_value.set_bytes(&self.data);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    pub fn write(
+        &mut self,
+        unit: &mut (SndDice, FwNode),
+        req: &mut FwReq,
+        sections: &ExtensionSections,
+        elem_id: &ElemId,
+        new: &ElemValue,
+        timeout_ms: u32,
+    ) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            APPL_SPACE_OFFSET_NAME => {
+                let offset = new.int()[0] as usize;
+                if offset % 4 > 0 || offset + 4 > self.size {
+                    let msg = format!("Invalid offset of application section: {}", offset);
+                    Err(Error::new(FileError::Inval, &msg))?;
+                }
+                self.offset = offset;
+                self.cache(unit, req, sections, timeout_ms).map(|_| true)
+            }
+            APPL_SPACE_DATA_NAME => {
+                if !cfg!(feature = "appl-space-write") {
+                    let msg = "Write to application section is not allowed";
+                    Err(Error::new(FileError::Perm, msg))?;
+                }
+
+                // The bytes out of the section are ignored.
+                let len = self.window_len();
+                let mut data = new.bytes()[..len].to_vec();
+                if data[..] == self.data[..len] {
+                    return Ok(true);
+                }
+
+                let record = format!("offset 0x{:04x}, {} bytes", self.offset, len);
+                report_feature(APPL_SPACE_WRITE_FEATURE, &record);
+
+                ApplSectionProtocol::write_appl_data(
+                    req,
+                    &mut unit.1,
+                    sections,
+                    self.offset,
+                    &mut data,
+                    timeout_ms,
+                )?;
+                self.data[..len].copy_from_slice(&data);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
@@ -2,7 +2,7 @@
 // Copyright (c) 2020 Takashi Sakamoto
 
 use {
-    super::{tcd22xx_ctl::*, *},
+    super::{appl_space_ctl::*, tcd22xx_ctl::*, *},
     protocols::tcat::{extension::*, tcd22xx_spec::*},
 };
 
@@ -13,6 +13,7 @@ pub struct ExtensionModel {
     extension_sections: ExtensionSections,
     ctl: CommonCtl,
     tcd22xx_ctl: ExtensionTcd22xxCtl,
+    appl_space_ctl: ApplSpaceCtl,
 }
 
 const TIMEOUT_MS: u32 = 20;
@@ -57,6 +58,13 @@ impl CtlModel<(SndDice, FwNode)> for ExtensionModel {
             &self.extension_sections,
             TIMEOUT_MS,
         )?;
+        self.appl_space_ctl.load(
+            unit,
+            &mut self.req,
+            &self.extension_sections,
+            TIMEOUT_MS,
+            card_cntr,
+        )?;
 
         Ok(())
     }
@@ -85,6 +93,8 @@ impl CtlModel<(SndDice, FwNode)> for ExtensionModel {
             TIMEOUT_MS,
         )? {
             Ok(true)
+        } else if self.appl_space_ctl.read(elem_id, elem_value)? {
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -117,6 +127,15 @@ impl CtlModel<(SndDice, FwNode)> for ExtensionModel {
             TIMEOUT_MS,
         )? {
             Ok(true)
+        } else if self.appl_space_ctl.write(
+            unit,
+            &mut self.req,
+            &self.extension_sections,
+            elem_id,
+            new,
+            TIMEOUT_MS,
+        )? {
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -127,6 +146,7 @@ impl NotifyModel<(SndDice, FwNode), u32> for ExtensionModel {
     fn get_notified_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
         elem_id_list.extend_from_slice(&self.ctl.notified_elem_list);
         self.tcd22xx_ctl.get_notified_elem_list(elem_id_list);
+        elem_id_list.extend_from_slice(&self.appl_space_ctl.notified_elem_list);
     }
 
     fn parse_notification(&mut self, unit: &mut (SndDice, FwNode), msg: &u32) -> Result<(), Error> {
@@ -153,6 +173,11 @@ impl NotifyModel<(SndDice, FwNode), u32> for ExtensionModel {
             Ok(true)
         } else if self.tcd22xx_ctl.read_notified_elem(elem_id, elem_value)? {
             Ok(true)
+        } else if self
+            .appl_space_ctl
+            .read_notified_elem(elem_id, elem_value)?
+        {
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -9,6 +9,7 @@ mod minimal_model;
 mod presonus;
 mod tcelectronic;
 
+mod appl_space_ctl;
 mod blackbird_model;
 mod extension_model;
 mod focusrite;
@@ -20,7 +21,11 @@ use {
     alsa_ctl_tlv_codec::DbInterval,
     alsactl::{prelude::*, *},
     common_ctl::*,
-    core::{card_cntr::*, dispatcher::*, elem_value_accessor::*, RuntimeOperation},
+    core::{
+        card_cntr::*, card_guid::*, diagnostics::*, dispatcher::*, elem_alias::*, elem_locale::*,
+        elem_value_accessor::*, feature_report::*, link_quirk::*, meter_trigger::*, panic_ctl::*,
+        ref_level::*, stream_guard::*, RuntimeOperation,
+    },
     firewire_dice_protocols as protocols,
     glib::{source, Error, FileError},
     hinawa::{
@@ -30,7 +35,9 @@ use {
     hitaki::{prelude::*, *},
     model::*,
     nix::sys::signal,
-    protocols::tcat::{global_section::*, *},
+    protocols::tcat::{
+        global_section::*, rx_stream_format_section::*, tx_stream_format_section::*, *,
+    },
     std::sync::mpsc,
 };
 
@@ -51,18 +58,42 @@ pub struct DiceRuntime {
     tx: mpsc::SyncSender<Event>,
     dispatchers: Vec<Dispatcher>,
     timer: Option<Dispatcher>,
+    ref_level: RefLevel,
+    feature_report: FeatureReport,
+    panic_ctl: PanicCtl,
+    meter_trigger: MeterTrigger,
+    stream_guard: StreamGuard,
 }
 
 impl RuntimeOperation<u32> for DiceRuntime {
     fn new(card_id: u32) -> Result<Self, Error> {
         let unit = SndDice::new();
-        let path = format!("/dev/snd/hwC{}D0", card_id);
+        let path = Self::devnode_path(&format!("hwC{}D0", card_id));
         unit.open(&path, 0)?;
 
-        let path = format!("/dev/{}", unit.node_device().unwrap());
+        let path = Self::devnode_path(&unit.node_device().unwrap());
         let node = FwNode::new();
         node.open(&path)?;
 
+        let guid = node
+            .config_rom()
+            .ok()
+            .and_then(guid_from_config_rom)
+            .unwrap_or_default();
+        if let Some(res) = LinkQuirk::from_env(guid) {
+            if let Some(size) = res?.max_payload() {
+                GeneralProtocol::set_max_frame_size(size);
+                report_feature(
+                    "link-quirk-payload",
+                    &GeneralProtocol::max_frame_size().to_string(),
+                );
+                println!(
+                    "The payload of block transaction is restricted to {} bytes",
+                    GeneralProtocol::max_frame_size()
+                );
+            }
+        }
+
         let model = DiceModel::new(&node)?;
 
         let card_cntr = CardCntr::default();
@@ -83,6 +114,11 @@ impl RuntimeOperation<u32> for DiceRuntime {
             tx,
             dispatchers,
             timer,
+            ref_level: Default::default(),
+            feature_report: Default::default(),
+            panic_ctl: Default::default(),
+            meter_trigger: Default::default(),
+            stream_guard: Default::default(),
         })
     }
 
@@ -90,6 +126,8 @@ impl RuntimeOperation<u32> for DiceRuntime {
         self.launch_node_event_dispatcher()?;
         self.launch_system_event_dispatcher()?;
 
+        setup_label_translations(&mut self.card_cntr);
+
         self.model.load(&mut self.unit, &mut self.card_cntr)?;
 
         if self.model.measured_elem_list.len() > 0 {
@@ -97,6 +135,24 @@ impl RuntimeOperation<u32> for DiceRuntime {
             let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
         }
 
+        self.ref_level
+            .load(&mut self.card_cntr, &self.model.measured_elem_list)?;
+
+        let card_id = self.unit.0.card_id();
+        self.stream_guard
+            .load(&mut self.card_cntr, card_id, &Self::STREAM_CRITICAL_ELEMS)?;
+
+        self.panic_ctl
+            .load(&mut self.card_cntr, &Self::PANIC_TARGETS)?;
+
+        self.meter_trigger
+            .setup(&self.card_cntr, Self::METER_TRIGGER_CLIENT_NAME);
+
+        setup_aliases(&mut self.card_cntr);
+
+        self.feature_report.load(&mut self.card_cntr)?;
+        self.feature_report.update(&mut self.card_cntr)?;
+
         Ok(())
     }
 
@@ -110,6 +166,36 @@ impl RuntimeOperation<u32> for DiceRuntime {
                         println!("IEEE 1394 bus is updated: {}", generation);
                     }
                     Event::Elem(elem_id, events) => {
+                        if self
+                            .ref_level
+                            .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                        {
+                            let _ = self.feature_report.update(&mut self.card_cntr);
+                            continue;
+                        }
+
+                        if self
+                            .panic_ctl
+                            .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                        {
+                            let _ = self.feature_report.update(&mut self.card_cntr);
+                            continue;
+                        }
+
+                        if self.stream_guard.handle_elem_event(
+                            &mut self.card_cntr,
+                            &elem_id,
+                            &events,
+                        ) {
+                            let _ = self.feature_report.update(&mut self.card_cntr);
+                            continue;
+                        }
+
+                        if self.feature_report.has_elem(&elem_id) {
+                            // The change of value is by the runtime itself.
+                            continue;
+                        }
+
                         if elem_id.name() != Self::TIMER_NAME {
                             let _ = self.model.dispatch_elem_event(
                                 &mut self.unit,
@@ -117,6 +203,16 @@ impl RuntimeOperation<u32> for DiceRuntime {
                                 &elem_id,
                                 &events,
                             );
+
+                            // The elements depending on the changed one are updated without any
+                            // notification; e.g. the window of application section read again at
+                            // the change of offset.
+                            if Self::DEPENDED_ELEMS.contains(&elem_id.name().as_str()) {
+                                let _ =
+                                    self.model
+                                        .dispatch_msg(&mut self.unit, &mut self.card_cntr, 0);
+                            }
+                            let _ = self.feature_report.update(&mut self.card_cntr);
                         } else {
                             let mut elem_value = ElemValue::new();
                             let _ = self
@@ -142,6 +238,8 @@ impl RuntimeOperation<u32> for DiceRuntime {
                         let _ = self
                             .model
                             .measure_elems(&mut self.unit, &mut self.card_cntr);
+                        self.meter_trigger
+                            .check_and_report(&mut self.card_cntr, &self.feature_report);
                     }
                 }
             }
@@ -150,6 +248,110 @@ impl RuntimeOperation<u32> for DiceRuntime {
     }
 }
 
+// The range of nickname in global section, redacted in diagnostics.
+const GLOBAL_NICKNAME_RANGE: std::ops::Range<usize> = 0x0c..0x4c;
+
+// Format the names of channels in stream formats for PCM channels in ALSA PCM substream, one
+// channel per line. ALSA dice driver arranges the PCM channels of the first stream, then the ones
+// of the second stream, in the PCM substream. The name is empty if the firmware has no name.
+fn format_pcm_channel_names(direction: &str, streams: &[(u32, &[String])]) -> String {
+    streams
+        .iter()
+        .enumerate()
+        .flat_map(|(i, &(pcm_count, labels))| {
+            (0..pcm_count as usize).map(move |ch| (i, ch, labels.get(ch)))
+        })
+        .enumerate()
+        .map(|(pcm_ch, (i, ch, label))| {
+            let name = label.map(|l| l.as_str()).unwrap_or_default();
+            format!("{} {} stream-{} {} {:?}\n", direction, pcm_ch, i, ch, name)
+        })
+        .collect()
+}
+
+impl DiagnosticsOperation<u32> for DiceRuntime {
+    fn collect_diagnostics(card_id: &u32) -> Result<Vec<DiagnosticsEntry>, Error> {
+        let mut entries = collect_common_diagnostics(*card_id)?;
+
+        let unit = SndDice::new();
+        unit.open(&Self::devnode_path(&format!("hwC{}D0", card_id)), 0)?;
+
+        let mut node = FwNode::new();
+        node.open(&Self::devnode_path(&unit.node_device().unwrap()))?;
+
+        // The dispatcher is required to receive responses of transactions.
+        let mut dispatcher = Dispatcher::run(Self::NODE_DISPATCHER_NAME.to_string())?;
+        dispatcher.attach_fw_node(&node, |_| ())?;
+
+        let mut req = FwReq::new();
+        let sections = GeneralProtocol::read_general_sections(
+            &mut req,
+            &mut node,
+            Self::DIAGNOSTICS_TIMEOUT_MS,
+        )?;
+
+        [
+            ("global-section.bin", &sections.global, true),
+            (
+                "tx-stream-format-section.bin",
+                &sections.tx_stream_format,
+                false,
+            ),
+            (
+                "rx-stream-format-section.bin",
+                &sections.rx_stream_format,
+                false,
+            ),
+            ("ext-sync-section.bin", &sections.ext_sync, false),
+        ]
+        .iter()
+        .filter(|(_, section, _)| section.size > 0)
+        .try_for_each(|&(name, section, has_nickname)| {
+            let mut data = vec![0; section.size];
+            GeneralProtocol::read(
+                &mut req,
+                &mut node,
+                section.offset,
+                &mut data,
+                Self::DIAGNOSTICS_TIMEOUT_MS,
+            )?;
+            if has_nickname && data.len() >= GLOBAL_NICKNAME_RANGE.end {
+                data[GLOBAL_NICKNAME_RANGE].iter_mut().for_each(|b| *b = 0);
+            }
+            entries.push(DiagnosticsEntry::new(name, data));
+            Ok::<(), Error>(())
+        })?;
+
+        // The names of channels in current stream formats for PCM channels, so that the channels
+        // in ALSA PCM substream can be identified after the change of routing.
+        let tx_entries = TxStreamFormatSectionProtocol::read_entries(
+            &mut req,
+            &mut node,
+            &sections,
+            Self::DIAGNOSTICS_TIMEOUT_MS,
+        )?;
+        let rx_entries = RxStreamFormatSectionProtocol::read_entries(
+            &mut req,
+            &mut node,
+            &sections,
+            Self::DIAGNOSTICS_TIMEOUT_MS,
+        )?;
+        let tx_streams: Vec<(u32, &[String])> = tx_entries
+            .iter()
+            .map(|entry| (entry.pcm, &entry.labels[..]))
+            .collect();
+        let rx_streams: Vec<(u32, &[String])> = rx_entries
+            .iter()
+            .map(|entry| (entry.pcm, &entry.labels[..]))
+            .collect();
+        let mut text = format_pcm_channel_names("capture", &tx_streams);
+        text.push_str(&format_pcm_channel_names("playback", &rx_streams));
+        entries.push(DiagnosticsEntry::new("pcm-channels.txt", text.into_bytes()));
+
+        Ok(entries)
+    }
+}
+
 impl Drop for DiceRuntime {
     fn drop(&mut self) {
         // At first, stop event loop in all of dispatchers to avoid queueing new events.
@@ -171,8 +373,87 @@ impl DiceRuntime {
     const TIMER_DISPATCHER_NAME: &'static str = "interval timer dispatcher";
 
     const TIMER_NAME: &'static str = "metering";
+    const METER_TRIGGER_CLIENT_NAME: &'static str = "DICE meter trigger";
     const TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
 
+    const DIAGNOSTICS_TIMEOUT_MS: u32 = 100;
+
+    // The controls critical to packet streaming, of which change is rejected while PCM
+    // substreams are running. The mode of optical interface changes the format of stream.
+    const STREAM_CRITICAL_ELEMS: [&'static str; 3] = [
+        "clock-rate",
+        "optical-iface-b-mode",
+        "optical-output-interface-mode",
+    ];
+
+    // The elements reset by panic switch.
+    const PANIC_TARGETS: [PanicTarget; 14] = [
+        PanicTarget {
+            name: "output-mute",
+            state: Some(true),
+        },
+        PanicTarget {
+            name: "monitor-output-mute",
+            state: Some(true),
+        },
+        PanicTarget {
+            name: "output-group-mute",
+            state: Some(true),
+        },
+        PanicTarget {
+            name: "mixer-phys-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "mixer-stream-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "mixer-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "mixer-stream-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "send-phys-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "send-stream-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "reverb-return-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "mixer-output-volume",
+            state: None,
+        },
+        PanicTarget {
+            name: "output-volume",
+            state: None,
+        },
+        PanicTarget {
+            name: "monitor-output-volume",
+            state: None,
+        },
+        PanicTarget {
+            name: "output-group-volume",
+            state: None,
+        },
+    ];
+
+    // The controls on which the other controls depend, of which change is followed by the update
+    // of the other controls.
+    const DEPENDED_ELEMS: [&'static str; 3] = [
+        "application-space-offset",
+        "master-knob-target",
+        "master-knob-assign",
+    ];
+
     fn launch_node_event_dispatcher(&mut self) -> Result<(), Error> {
         let name = Self::NODE_DISPATCHER_NAME.to_string();
         let mut dispatcher = Dispatcher::run(name)?;
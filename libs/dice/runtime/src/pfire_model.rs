@@ -19,7 +19,7 @@ pub struct Pfire2626Model {
 }
 
 #[derive(Default)]
-struct Pfire2626SpecificCtl([bool; Pfire2626Protocol::KNOB_COUNT]);
+struct Pfire2626SpecificCtl([bool; Pfire2626Protocol::KNOB_COUNT], Vec<ElemId>);
 
 impl SpecificCtlOperation<Pfire2626Protocol> for Pfire2626SpecificCtl {
     fn state(&self) -> &[bool] {
@@ -29,6 +29,10 @@ impl SpecificCtlOperation<Pfire2626Protocol> for Pfire2626SpecificCtl {
     fn state_mut(&mut self) -> &mut [bool] {
         &mut self.0
     }
+
+    fn notified_elem_list_mut(&mut self) -> &mut Vec<ElemId> {
+        &mut self.1
+    }
 }
 
 impl CtlModel<(SndDice, FwNode)> for Pfire2626Model {
@@ -160,6 +164,7 @@ impl NotifyModel<(SndDice, FwNode), u32> for Pfire2626Model {
     fn get_notified_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
         elem_id_list.extend_from_slice(&self.ctl.notified_elem_list);
         self.tcd22xx_ctl.get_notified_elem_list(elem_id_list);
+        elem_id_list.extend_from_slice(&self.specific_ctl.1);
     }
 
     fn parse_notification(&mut self, unit: &mut (SndDice, FwNode), msg: &u32) -> Result<(), Error> {
@@ -186,6 +191,8 @@ impl NotifyModel<(SndDice, FwNode), u32> for Pfire2626Model {
             Ok(true)
         } else if self.tcd22xx_ctl.read_notified_elem(elem_id, elem_value)? {
             Ok(true)
+        } else if self.specific_ctl.read_notified_elem(elem_id, elem_value)? {
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -250,7 +257,7 @@ pub struct Pfire610Model {
 }
 
 #[derive(Default)]
-struct Pfire610SpecificCtl([bool; Pfire610Protocol::KNOB_COUNT]);
+struct Pfire610SpecificCtl([bool; Pfire610Protocol::KNOB_COUNT], Vec<ElemId>);
 
 impl SpecificCtlOperation<Pfire610Protocol> for Pfire610SpecificCtl {
     fn state(&self) -> &[bool] {
@@ -260,6 +267,10 @@ impl SpecificCtlOperation<Pfire610Protocol> for Pfire610SpecificCtl {
     fn state_mut(&mut self) -> &mut [bool] {
         &mut self.0
     }
+
+    fn notified_elem_list_mut(&mut self) -> &mut Vec<ElemId> {
+        &mut self.1
+    }
 }
 
 impl CtlModel<(SndDice, FwNode)> for Pfire610Model {
@@ -391,6 +402,7 @@ impl NotifyModel<(SndDice, FwNode), u32> for Pfire610Model {
     fn get_notified_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
         elem_id_list.extend_from_slice(&self.ctl.notified_elem_list);
         self.tcd22xx_ctl.get_notified_elem_list(elem_id_list);
+        elem_id_list.extend_from_slice(&self.specific_ctl.1);
     }
 
     fn parse_notification(&mut self, unit: &mut (SndDice, FwNode), msg: &u32) -> Result<(), Error> {
@@ -417,6 +429,8 @@ impl NotifyModel<(SndDice, FwNode), u32> for Pfire610Model {
             Ok(true)
         } else if self.tcd22xx_ctl.read_notified_elem(elem_id, elem_value)? {
             Ok(true)
+        } else if self.specific_ctl.read_notified_elem(elem_id, elem_value)? {
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -485,12 +499,16 @@ fn standalone_converter_mode_to_str(mode: &StandaloneConverterMode) -> &'static
 }
 
 const MASTER_KNOB_NAME: &str = "master-knob-target";
+const MASTER_KNOB_ASSIGN_NAME: &str = "master-knob-assign";
 const OPT_IFACE_B_MODE_NAME: &str = "optical-iface-b-mode";
 const STANDALONE_CONVERTER_MODE_NAME: &str = "standalone-converter-mode";
 
 trait SpecificCtlOperation<T: PfireSpecificOperation> {
     fn state(&self) -> &[bool];
     fn state_mut(&mut self) -> &mut [bool];
+    fn notified_elem_list_mut(&mut self) -> &mut Vec<ElemId>;
+
+    const MASTER_KNOB_ASSIGN_NONE_LABEL: &'static str = "none";
 
     // MEMO: Both models support 'Output{id: DstBlkId::Ins0, count: 8}'.
     const MASTER_KNOB_TARGET_LABELS: [&'static str; 4] = [
@@ -505,9 +523,20 @@ trait SpecificCtlOperation<T: PfireSpecificOperation> {
         StandaloneConverterMode::AdOnly,
     ];
 
-    fn load(&self, card_cntr: &mut CardCntr) -> Result<(), Error> {
+    fn load(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
         let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, MASTER_KNOB_NAME, 0);
-        let _ = card_cntr.add_bool_elems(&elem_id, 1, T::KNOB_COUNT, true)?;
+        card_cntr
+            .add_bool_elems(&elem_id, 1, T::KNOB_COUNT, true)
+            .map(|mut elem_id_list| self.notified_elem_list_mut().append(&mut elem_id_list))?;
+
+        // The knob can control several pairs of outputs, while the element is for the usual case
+        // to assign it to one pair.
+        let mut labels = vec![Self::MASTER_KNOB_ASSIGN_NONE_LABEL];
+        labels.extend_from_slice(&Self::MASTER_KNOB_TARGET_LABELS);
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, MASTER_KNOB_ASSIGN_NAME, 0);
+        card_cntr
+            .add_enum_elems(&elem_id, 1, 1, &labels, None, true)
+            .map(|mut elem_id_list| self.notified_elem_list_mut().append(&mut elem_id_list))?;
 
         // NOTE: ClockSource::Tdif is used for second optical interface as 'ADAT_AUX'.
         if T::HAS_OPT_IFACE_B {
@@ -553,6 +582,11 @@ trait SpecificCtlOperation<T: PfireSpecificOperation> {
                 elem_value.set_bool(&self.state());
                 Ok(true)
             }
+            // The state is read for the element of master knob target in advance.
+            MASTER_KNOB_ASSIGN_NAME => {
+                elem_value.set_enum(&[Self::master_knob_assign(self.state())]);
+                Ok(true)
+            }
             OPT_IFACE_B_MODE_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
                 let mode = T::read_opt_iface_b_mode(req, &mut unit.1, sections, timeout_ms)?;
                 let pos = Self::OPT_IFACE_B_MODES
@@ -595,6 +629,20 @@ trait SpecificCtlOperation<T: PfireSpecificOperation> {
                 T::write_knob_assign(req, &mut unit.1, sections, &self.state(), timeout_ms)?;
                 Ok(true)
             }
+            MASTER_KNOB_ASSIGN_NAME => {
+                let val = new.enumerated()[0] as usize;
+                if val > T::KNOB_COUNT {
+                    let msg = format!("Invalid value for index of master knob target: {}", val);
+                    Err(Error::new(FileError::Inval, &msg))?;
+                }
+                let mut targets = vec![false; T::KNOB_COUNT];
+                if val > 0 {
+                    targets[val - 1] = true;
+                }
+                T::write_knob_assign(req, &mut unit.1, sections, &targets, timeout_ms)?;
+                self.state_mut().copy_from_slice(&targets);
+                Ok(true)
+            }
             OPT_IFACE_B_MODE_NAME => ElemValueAccessor::<u32>::get_val(new, |val| {
                 let &mode = Self::OPT_IFACE_B_MODES
                     .iter()
@@ -624,4 +672,29 @@ trait SpecificCtlOperation<T: PfireSpecificOperation> {
             _ => Ok(false),
         }
     }
+
+    // The first pair of outputs is used when several pairs are assigned.
+    fn master_knob_assign(targets: &[bool]) -> u32 {
+        targets
+            .iter()
+            .position(|&assigned| assigned)
+            .map(|pos| 1 + pos as u32)
+            .unwrap_or(0)
+    }
+
+    // The elements for master knob share the state, thus either of them is updated after the
+    // other is changed.
+    fn read_notified_elem(&self, elem_id: &ElemId, elem_value: &ElemValue) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            MASTER_KNOB_NAME => {
+                elem_value.set_bool(self.state());
+                Ok(true)
+            }
+            MASTER_KNOB_ASSIGN_NAME => {
+                elem_value.set_enum(&[Self::master_knob_assign(self.state())]);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 }
@@ -3,6 +3,7 @@
 
 use {
     super::*,
+    alsa_ctl_tlv_codec::CTL_VALUE_MUTE,
     protocols::tcat::{
         extension::{
             peak_section::*,
@@ -193,18 +194,31 @@ where
 {
 }
 
+// The labels of stream blocks according to PCM channels.
+type StreamBlkLabels = (Vec<(SrcBlk, String)>, Vec<(DstBlk, String)>);
+
 #[derive(Default, Debug)]
 struct RouterCtl {
     // Maximum number block in low rate mode.
     real_blk_pair: (Vec<SrcBlk>, Vec<DstBlk>),
     stream_blk_pair: (Vec<SrcBlk>, Vec<DstBlk>),
     mixer_blk_pair: (Vec<SrcBlk>, Vec<DstBlk>),
+    // The labels of stream blocks according to PCM channels for models with two streams.
+    stream_labels: StreamBlkLabels,
+    // The stream format entries at each available mode of rate, and the current mode of rate.
+    stream_format_entries: Vec<(RateMode, Vec<FormatEntry>, Vec<FormatEntry>)>,
+    rate_mode: RateMode,
     notified_elem_list: Vec<ElemId>,
 }
 
 const ROUTER_OUT_SRC_NAME: &str = "output-source";
 const ROUTER_CAP_SRC_NAME: &str = "stream-source";
 const ROUTER_MIXER_SRC_NAME: &str = "mixer-source";
+const TX_STREAM_CHANNEL_NAMES_NAME: &str = "tx-stream-channel-names";
+const RX_STREAM_CHANNEL_NAMES_NAME: &str = "rx-stream-channel-names";
+
+// The size of element for names of channels in stream formats.
+const STREAM_CHANNEL_NAMES_SIZE: usize = 512;
 
 pub trait RouterCtlOperation<T: Tcd22xxRouterOperation>: Tcd22xxCtlOperation<T>
 where
@@ -212,6 +226,9 @@ where
 {
     const NONE_SRC_LABEL: &'static str = "None";
 
+    const PLAYBACK_PCM_LABEL: &'static str = "Playback";
+    const CAPTURE_PCM_LABEL: &'static str = "Capture";
+
     fn load_router(
         &mut self,
         node: &mut FwNode,
@@ -237,11 +254,26 @@ where
                     rate_modes.push(m);
                 }
             });
+        // The index of PCM channel for stream block at each mode of rate, and whether the two
+        // streams are used at any mode of rate.
+        let mut src_pcm_channels: Vec<(SrcBlk, usize)> = Vec::new();
+        let mut dst_pcm_channels: Vec<(DstBlk, usize)> = Vec::new();
+        let mut dual_streams = (false, false);
         rate_modes.iter().try_for_each(|&m| {
             CurrentConfigSectionProtocol::read_current_stream_format_entries(
                 req, node, sections, &ctls.caps, m, timeout_ms,
             )
             .map(|(tx, rx)| {
+                ctls.router_ctl
+                    .stream_format_entries
+                    .push((m, tx.clone(), rx.clone()));
+
+                dual_streams.0 |= rx.iter().filter(|entry| entry.pcm_count > 0).count() > 1;
+                dual_streams.1 |= tx.iter().filter(|entry| entry.pcm_count > 0).count() > 1;
+                let (src_chs, dst_chs) = T::compute_stream_blk_pcm_channels(&tx, &rx);
+                src_pcm_channels.extend(src_chs);
+                dst_pcm_channels.extend(dst_chs);
+
                 let (tx_blk, rx_blk) = T::compute_avail_stream_blk_pair(&tx, &rx);
                 tx_blk.iter().for_each(|src| {
                     if ctls
@@ -273,10 +305,29 @@ where
         ctls.router_ctl.stream_blk_pair.0.sort();
         ctls.router_ctl.stream_blk_pair.1.sort();
 
+        // For models with two streams, the label of stream block is according to the PCM channel
+        // in ALSA PCM substream as long as it is the same at all modes of rate. Otherwise, the
+        // label of stream block is used.
+        if dual_streams.0 {
+            ctls.router_ctl.stream_labels.0 = Self::compute_stream_pcm_labels(
+                &ctls.router_ctl.stream_blk_pair.0,
+                &src_pcm_channels,
+                Self::PLAYBACK_PCM_LABEL,
+            );
+        }
+        if dual_streams.1 {
+            ctls.router_ctl.stream_labels.1 = Self::compute_stream_pcm_labels(
+                &ctls.router_ctl.stream_blk_pair.1,
+                &dst_pcm_channels,
+                Self::CAPTURE_PCM_LABEL,
+            );
+        }
+
         ctls.router_ctl.mixer_blk_pair = T::compute_avail_mixer_blk_pair(&ctls.caps, RateMode::Low);
 
         Self::add_an_elem_for_src(
             card_cntr,
+            &ctls.router_ctl.stream_labels,
             ROUTER_OUT_SRC_NAME,
             &ctls.router_ctl.real_blk_pair.1,
             &[
@@ -289,6 +340,7 @@ where
 
         Self::add_an_elem_for_src(
             card_cntr,
+            &ctls.router_ctl.stream_labels,
             ROUTER_CAP_SRC_NAME,
             &ctls.router_ctl.stream_blk_pair.1,
             &[
@@ -300,6 +352,7 @@ where
 
         Self::add_an_elem_for_src(
             card_cntr,
+            &ctls.router_ctl.stream_labels,
             ROUTER_MIXER_SRC_NAME,
             &ctls.router_ctl.mixer_blk_pair.1,
             &[
@@ -309,11 +362,55 @@ where
         )
         .map(|mut elem_id_list| ctls.router_ctl.notified_elem_list.append(&mut elem_id_list))?;
 
+        // The names of channels given by firmware can change according to the mode of rate, thus
+        // they are not used for the labels of elements above.
+        [TX_STREAM_CHANNEL_NAMES_NAME, RX_STREAM_CHANNEL_NAMES_NAME]
+            .iter()
+            .try_for_each(|name| {
+                let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, name, 0);
+                card_cntr
+                    .add_bytes_elems(&elem_id, 1, STREAM_CHANNEL_NAMES_SIZE, None, false)
+                    .map(|mut elem_id_list| {
+                        ctls.router_ctl.notified_elem_list.append(&mut elem_id_list)
+                    })
+            })?;
+
         Ok(())
     }
 
+    /// Build the names of PCM channels in the stream format entries, separated by newline.
+    fn build_stream_channel_names(entries: &[FormatEntry]) -> Vec<u8> {
+        let names = entries
+            .iter()
+            .flat_map(|entry| entry.labels.iter().take(entry.pcm_count as usize))
+            .map(|label| label.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n");
+
+        let mut raw = names.into_bytes();
+        raw.resize(STREAM_CHANNEL_NAMES_SIZE, 0);
+        raw
+    }
+
     fn read_router(&self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
         match elem_id.name().as_str() {
+            TX_STREAM_CHANNEL_NAMES_NAME | RX_STREAM_CHANNEL_NAMES_NAME => {
+                let router_ctl = &self.tcd22xx_ctl().router_ctl;
+                let entries = router_ctl
+                    .stream_format_entries
+                    .iter()
+                    .find(|(mode, _, _)| router_ctl.rate_mode.eq(mode))
+                    .map(|(_, tx, rx)| {
+                        if elem_id.name().as_str() == TX_STREAM_CHANNEL_NAMES_NAME {
+                            &tx[..]
+                        } else {
+                            &rx[..]
+                        }
+                    })
+                    .unwrap_or(&[]);
+                elem_value.set_bytes(&Self::build_stream_channel_names(entries));
+                Ok(true)
+            }
             ROUTER_OUT_SRC_NAME => {
                 let ctls = self.tcd22xx_ctl();
                 Self::read_elem_src(
@@ -431,20 +528,57 @@ where
         }
     }
 
+    fn compute_stream_pcm_labels<B: Copy + Eq>(
+        blks: &[B],
+        pcm_channels: &[(B, usize)],
+        prefix: &str,
+    ) -> Vec<(B, String)> {
+        blks.iter()
+            .filter_map(|blk| {
+                let mut chs = pcm_channels
+                    .iter()
+                    .filter(|(b, _)| b.eq(blk))
+                    .map(|(_, ch)| *ch);
+                chs.next().and_then(|ch| {
+                    if chs.all(|c| c == ch) {
+                        Some((*blk, format!("{}-{}", prefix, ch)))
+                    } else {
+                        None
+                    }
+                })
+            })
+            .collect()
+    }
+
     fn add_an_elem_for_src(
         card_cntr: &mut CardCntr,
+        stream_labels: &StreamBlkLabels,
         label: &str,
         dsts: &[DstBlk],
         srcs: &[&[SrcBlk]],
     ) -> Result<Vec<ElemId>, Error> {
         let targets = dsts
             .iter()
-            .map(|&dst| T::dst_blk_label(dst))
+            .map(|dst| {
+                stream_labels
+                    .1
+                    .iter()
+                    .find(|(d, _)| d.eq(dst))
+                    .map(|(_, l)| l.clone())
+                    .unwrap_or_else(|| T::dst_blk_label(*dst))
+            })
             .collect::<Vec<String>>();
         let mut sources = srcs
             .iter()
             .flat_map(|srcs| *srcs)
-            .map(|src| T::src_blk_label(src))
+            .map(|src| {
+                stream_labels
+                    .0
+                    .iter()
+                    .find(|(s, _)| s.eq(src))
+                    .map(|(_, l)| l.clone())
+                    .unwrap_or_else(|| T::src_blk_label(src))
+            })
             .collect::<Vec<String>>();
         sources.insert(0, Self::NONE_SRC_LABEL.to_string());
 
@@ -546,14 +680,15 @@ pub trait MixerCtlOperation<T>: Tcd22xxCtlOperation<T>
 where
     T: Tcd22xxSpecOperation + Tcd22xxRouterOperation + Tcd22xxMixerOperation,
 {
-    const COEF_MIN: i32 = 0;
-    const COEF_MAX: i32 = 0x0000ffffi32; // 2:14 Fixed-point.
+    const COEF_MIN: i32 = MixerSectionProtocol::COEF_MIN as i32;
+    const COEF_MAX: i32 = MixerSectionProtocol::COEF_MAX as i32; // 2:14 Fixed-point.
     const COEF_STEP: i32 = 1;
+    // The coefficient is linear to amplitude, and the minimum is for mute.
     const COEF_TLV: DbInterval = DbInterval {
-        min: -6000,
-        max: 400,
-        linear: false,
-        mute_avail: false,
+        min: CTL_VALUE_MUTE,
+        max: MixerSectionProtocol::COEF_DB_MAX,
+        linear: true,
+        mute_avail: true,
     };
 
     fn load_mixer(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
@@ -1012,6 +1147,7 @@ where
         let rate_mode = RateMode::from(config.rate);
 
         let ctls = self.tcd22xx_ctl_mut();
+        ctls.router_ctl.rate_mode = rate_mode;
         T::cache(
             &mut unit.1,
             req,
@@ -1103,7 +1239,14 @@ where
         timeout_ms: u32,
         msg: u32,
     ) -> Result<(), Error> {
-        if GeneralProtocol::has_clock_accepted(msg) {
+        // NOTE: The protocol extension defines no bit in notification for the change of router
+        // and mixer by the other applications or front panel. The current configuration of router
+        // can be changed when the unit reconfigures the sampling clock or the stream formats, thus
+        // the sections are cached again at the notification for them.
+        if GeneralProtocol::has_clock_accepted(msg)
+            || GeneralProtocol::has_rx_config_changed(msg)
+            || GeneralProtocol::has_tx_config_changed(msg)
+        {
             self.cache(unit, req, sections, extension_sections, timeout_ms)?;
         }
         Ok(())
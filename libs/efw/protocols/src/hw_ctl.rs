@@ -17,6 +17,15 @@ const CMD_GET_FLAGS: u32 = 4;
 const CMD_BLINK_LED: u32 = 5;
 const CMD_RECONNECT: u32 = 6;
 
+// NOTE: The flags include no bit for copyright and generation status in channel status of
+// IEC 60958 (SCMS), thus the copy protection of S/PDIF output is not configurable. The channel
+// status is configurable just for professional use and non-audio data.
+
+// NOTE: Neither the flags nor the commands in any known category include routing of MIDI input
+// port to MIDI output port inside the board module. MIDI messages from the input port are always
+// delivered to host by isochronous packet stream, thus MIDI through is not available without
+// host.
+
 /// The type of hardware control.
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum HwCtlFlag {
@@ -202,6 +202,25 @@ const AF8: u32 = 0x00000af8;
 const AFP8: u32 = 0x00000af9;
 const AF12: u32 = 0x0000af12;
 
+/// The requirement of firmware for command. Some commands are available just for specific models
+/// or newer version of firmware, thus the runtime should check it before adding control elements
+/// for them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FirmwareRequirement {
+    /// The list of hardware type to support the command. Any model if empty.
+    pub hw_types: &'static [u32],
+    /// The minimum version of ARM firmware to support the command.
+    pub min_arm_version: u32,
+}
+
+impl FirmwareRequirement {
+    /// Whether the hardware type and the version of ARM firmware satisfy the requirement.
+    pub fn is_satisfied(&self, hw_type: u32, arm_version: u32) -> bool {
+        (self.hw_types.len() == 0 || self.hw_types.iter().any(|&t| t == hw_type))
+            && arm_version >= self.min_arm_version
+    }
+}
+
 impl HwInfo {
     fn parse(&mut self, quads: &[u32]) -> Result<(), Error> {
         self.caps = Self::parse_caps(quads[0], quads[3]);
@@ -227,6 +246,11 @@ impl HwInfo {
         Ok(())
     }
 
+    /// Whether the unit satisfies the requirement of firmware for command.
+    pub fn satisfies(&self, req: &FirmwareRequirement) -> bool {
+        req.is_satisfied(self.hw_type, self.arm_version)
+    }
+
     fn parse_caps(flags: u32, hw_type: u32) -> Vec<HwCap> {
         let mut caps: Vec<HwCap> = (0..16)
             .filter(|i| (1 << i) & flags > 0)
@@ -423,3 +447,27 @@ pub trait HwInfoProtocol: EfwProtocolExtManual {
 }
 
 impl<O: EfwProtocolExtManual> HwInfoProtocol for O {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn firmware_requirement() {
+        let req = FirmwareRequirement {
+            hw_types: &[AF2, AF4],
+            min_arm_version: 0x05080000,
+        };
+        assert!(req.is_satisfied(AF2, 0x05080000));
+        assert!(req.is_satisfied(AF4, 0x05090000));
+        assert!(!req.is_satisfied(AF4, 0x05070000));
+        assert!(!req.is_satisfied(AF8, 0x05080000));
+
+        let req = FirmwareRequirement {
+            hw_types: &[],
+            min_arm_version: 0x05000000,
+        };
+        assert!(req.is_satisfied(O1200F, 0x05000000));
+        assert!(!req.is_satisfied(O1200F, 0x04080000));
+    }
+}
@@ -13,6 +13,10 @@ const CATEGORY_ROBOT_GUITAR: u32 = 10;
 const CMD_SET_CHARGE_STATE: u32 = 7;
 const CMD_GET_CHARGE_STATE: u32 = 8;
 
+// NOTE: The other commands in the category are not documented. The selection of tuning preset and
+// tone is done by the guitar itself. The mode of hex pickup is configured by the flag of hardware
+// control.
+
 /// State of charging for Robot Guitar.
 #[derive(Debug)]
 pub struct GuitarChargeState {
@@ -52,6 +52,13 @@ mod imp {
     pub const RESPONSE_OFFSET: u64 = 0xecc080000000;
     pub const MAX_FRAME_SIZE: usize = 0x200;
 
+    const TIMEOUT_MS: u32 = 100;
+
+    // The retry blocks the thread of caller, thus the number of retries and the interval are
+    // small enough; 10, 20, and 40 milliseconds.
+    const MAX_RETRIES: u32 = 3;
+    const BACKOFF_MS: u64 = 10;
+
     #[derive(Default)]
     pub struct EfwTransactionPrivate(RefCell<u32>, RefCell<Option<FwNode>>);
 
@@ -136,21 +143,43 @@ mod imp {
         }
     }
 
+    // The request subaction is not accepted by the node when it is busy or the generation of bus
+    // is changed, thus the retry never duplicates the command. The command is not necessarily
+    // idempotent, thus the cancelled transaction is not retried since the node may receive the
+    // request subaction.
+    fn is_transient(error: &Error) -> bool {
+        match error.kind::<FwReqError>() {
+            Some(FwReqError::Busy) | Some(FwReqError::Generation) => true,
+            _ => false,
+        }
+    }
+
     impl EfwProtocolImpl for EfwTransactionPrivate {
         fn transmit_request(&self, _: &Self::Type, buffer: &[u8]) -> Result<(), Error> {
             if let Some(node) = self.1.borrow().as_ref() {
                 let req = FwReq::new();
-                let mut frame = buffer.to_owned();
-                req.transaction_sync(
-                    node,
-                    FwTcode::WriteBlockRequest,
-                    COMMAND_OFFSET,
-                    frame.len(),
-                    &mut frame,
-                    100,
-                )?;
-
-                Ok(())
+                let mut retry = 0;
+
+                loop {
+                    let mut frame = buffer.to_owned();
+                    let res = req.transaction_sync(
+                        node,
+                        FwTcode::WriteBlockRequest,
+                        COMMAND_OFFSET,
+                        frame.len(),
+                        &mut frame,
+                        TIMEOUT_MS,
+                    );
+
+                    match res {
+                        Err(e) if retry < MAX_RETRIES && is_transient(&e) => {
+                            let backoff_ms = BACKOFF_MS << retry;
+                            std::thread::sleep(std::time::Duration::from_millis(backoff_ms));
+                            retry += 1;
+                        }
+                        _ => break res,
+                    }
+                }
             } else {
                 Err(Error::new(EfwProtocolError::Bad, "Not prepared."))
             }
@@ -18,17 +18,108 @@ fn clk_src_to_str(src: &ClkSrc) -> &'static str {
     }
 }
 
+/// The watchdog to switch the source of clock to internal when the selected external source
+/// is not detected, and optionally back when it is detected again. The state of detection should
+/// continue for the count of periods of metering timer given as hysteresis, thus the watchdog
+/// works just while the timer is enabled.
+#[derive(Debug, Clone)]
+pub struct ClkFallback {
+    pub enable: bool,
+    pub restore: bool,
+    pub hysteresis: u32,
+    // The external source selected before the fallback.
+    selected: Option<ClkSrc>,
+    count: u32,
+}
+
+impl Default for ClkFallback {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            restore: false,
+            hysteresis: Self::HYSTERESIS_MIN,
+            selected: None,
+            count: 0,
+        }
+    }
+}
+
+impl ClkFallback {
+    pub const HYSTERESIS_MIN: u32 = 1;
+    pub const HYSTERESIS_MAX: u32 = 20;
+
+    /// Check the detection of sources, then return the source to which the clock should be
+    /// switched.
+    pub fn check(&mut self, curr: ClkSrc, detected_srcs: &[(ClkSrc, bool)]) -> Option<ClkSrc> {
+        if !self.enable {
+            self.selected = None;
+            self.count = 0;
+            return None;
+        }
+
+        // The source not reported by the meter is regarded as detected.
+        let is_detected = |src: ClkSrc| {
+            detected_srcs
+                .iter()
+                .find(|(s, _)| src.eq(s))
+                .map(|&(_, detected)| detected)
+                .unwrap_or(true)
+        };
+
+        match self.selected {
+            Some(_) if curr != ClkSrc::Internal => {
+                // The source is changed by the others after the fallback.
+                self.selected = None;
+                self.count = 0;
+                None
+            }
+            Some(selected) => {
+                if self.restore && is_detected(selected) {
+                    self.count += 1;
+                    if self.count >= self.hysteresis {
+                        self.selected = None;
+                        self.count = 0;
+                        return Some(selected);
+                    }
+                } else {
+                    self.count = 0;
+                }
+                None
+            }
+            None if curr != ClkSrc::Internal && !is_detected(curr) => {
+                self.count += 1;
+                if self.count >= self.hysteresis {
+                    self.selected = Some(curr);
+                    self.count = 0;
+                    Some(ClkSrc::Internal)
+                } else {
+                    None
+                }
+            }
+            None => {
+                self.count = 0;
+                None
+            }
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct ClkCtl {
     pub notified_elem_id_list: Vec<ElemId>,
+    pub measured_elem_id_list: Vec<ElemId>,
     pub curr_src: ClkSrc,
     pub curr_rate: u32,
     srcs: Vec<ClkSrc>,
     rates: Vec<u32>,
+    fallback: ClkFallback,
 }
 
 const SRC_NAME: &str = "clock-source";
 const RATE_NAME: &str = "clock-rate";
+const FALLBACK_ENABLE_NAME: &str = "clock-fallback-enable";
+const FALLBACK_RESTORE_NAME: &str = "clock-fallback-restore";
+const FALLBACK_HYSTERESIS_NAME: &str = "clock-fallback-hysteresis";
 
 impl ClkCtl {
     pub fn load(
@@ -48,7 +139,11 @@ impl ClkCtl {
         let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, SRC_NAME, 0);
         card_cntr
             .add_enum_elems(&elem_id, 1, 1, &labels, None, true)
-            .map(|mut elem_id_list| self.notified_elem_id_list.append(&mut elem_id_list))?;
+            .map(|elem_id_list| {
+                // The source can be switched by the watchdog at metering.
+                self.measured_elem_id_list.extend_from_slice(&elem_id_list);
+                self.notified_elem_id_list.extend_from_slice(&elem_id_list);
+            })?;
 
         let labels: Vec<String> = hwinfo
             .clk_rates
@@ -61,6 +156,28 @@ impl ClkCtl {
             .add_enum_elems(&elem_id, 1, 1, &labels, None, true)
             .map(|mut elem_id_list| self.notified_elem_id_list.append(&mut elem_id_list))?;
 
+        // The watchdog is available when the internal source is selectable.
+        if self.srcs.iter().find(|s| ClkSrc::Internal.eq(s)).is_some() {
+            let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, FALLBACK_ENABLE_NAME, 0);
+            let _ = card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
+
+            let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, FALLBACK_RESTORE_NAME, 0);
+            let _ = card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
+
+            let elem_id =
+                ElemId::new_by_name(ElemIfaceType::Card, 0, 0, FALLBACK_HYSTERESIS_NAME, 0);
+            let _ = card_cntr.add_int_elems(
+                &elem_id,
+                1,
+                ClkFallback::HYSTERESIS_MIN as i32,
+                ClkFallback::HYSTERESIS_MAX as i32,
+                1,
+                1,
+                None,
+                true,
+            )?;
+        }
+
         Ok(())
     }
 
@@ -99,6 +216,21 @@ impl ClkCtl {
                     .unwrap() as u32)
             })
             .map(|_| true),
+            FALLBACK_ENABLE_NAME => {
+                ElemValueAccessor::<bool>::set_val(elem_value, || Ok(self.fallback.enable))
+                    .map(|_| true)
+            }
+            FALLBACK_RESTORE_NAME => {
+                ElemValueAccessor::<bool>::set_val(elem_value, || Ok(self.fallback.restore))
+                    .map(|_| true)
+            }
+            FALLBACK_HYSTERESIS_NAME => {
+                ElemValueAccessor::<i32>::set_val(
+                    elem_value,
+                    || Ok(self.fallback.hysteresis as i32),
+                )
+                .map(|_| true)
+            }
             _ => Ok(false),
         }
     }
@@ -140,6 +272,61 @@ impl ClkCtl {
                 })?;
                 Ok(true)
             }
+            FALLBACK_ENABLE_NAME => {
+                ElemValueAccessor::<bool>::get_val(new, |val| {
+                    self.fallback.enable = val;
+                    Ok(())
+                })?;
+                Ok(true)
+            }
+            FALLBACK_RESTORE_NAME => {
+                ElemValueAccessor::<bool>::get_val(new, |val| {
+                    self.fallback.restore = val;
+                    Ok(())
+                })?;
+                Ok(true)
+            }
+            FALLBACK_HYSTERESIS_NAME => {
+                ElemValueAccessor::<i32>::get_val(new, |val| {
+                    self.fallback.hysteresis = (val as u32)
+                        .clamp(ClkFallback::HYSTERESIS_MIN, ClkFallback::HYSTERESIS_MAX);
+                    Ok(())
+                })?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    /// Check the detection of sources reported by the meter, then switch the source of clock by
+    /// the watchdog. The switch is subject to the policy to protect packet streaming, and the
+    /// state of watchdog is kept as is when the switch fails.
+    pub fn watch_detection(
+        &mut self,
+        unit: &mut SndEfw,
+        guard: &StreamGuard,
+        detected_srcs: &[(ClkSrc, bool)],
+        timeout_ms: u32,
+    ) -> Result<(), Error> {
+        let mut fallback = self.fallback.clone();
+        if let Some(src) = fallback.check(self.curr_src, detected_srcs) {
+            guard.check(SRC_NAME)?;
+            unit.lock()?;
+            let res = unit.set_clock(Some(src), None, timeout_ms);
+            let _ = unit.unlock();
+            res.map(|_| self.curr_src = src)?;
+        }
+        self.fallback = fallback;
+        Ok(())
+    }
+
+    pub fn measure_elem(
+        &mut self,
+        elem_id: &ElemId,
+        elem_value: &mut ElemValue,
+    ) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            SRC_NAME => self.read(elem_id, elem_value),
             _ => Ok(false),
         }
     }
@@ -3,7 +3,7 @@
 
 use {
     super::*,
-    protocols::{hw_info::*, robot_guitar::*},
+    protocols::{hw_ctl::*, hw_info::*, robot_guitar::*},
 };
 
 #[derive(Default)]
@@ -12,6 +12,7 @@ pub struct GuitarCtl;
 const MANUAL_CHARGE_NAME: &str = "guitar-manual-chage";
 const AUTO_CHARGE_NAME: &str = "guitar-auto-chage";
 const SUSPEND_TO_CHARGE: &str = "guitar-suspend-to-charge";
+const HEX_INPUT_NAME: &str = "guitar-hex-input";
 
 impl GuitarCtl {
     const MIN_SEC: i32 = 0;
@@ -19,6 +20,18 @@ impl GuitarCtl {
     const STEP_SEC: i32 = 1;
 
     pub fn load(&mut self, hwinfo: &HwInfo, card_cntr: &mut CardCntr) -> Result<(), Error> {
+        let is_robot_guitar = hwinfo
+            .caps
+            .iter()
+            .find(|&e| *e == HwCap::RobotGuitar)
+            .is_some();
+
+        if is_robot_guitar {
+            // The signal from each string of hex pickup is delivered to the individual channel.
+            let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, HEX_INPUT_NAME, 0);
+            let _ = card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
+        }
+
         let has_guitar_charge = hwinfo
             .caps
             .iter()
@@ -75,6 +88,16 @@ impl GuitarCtl {
                 })?;
                 Ok(true)
             }
+            HEX_INPUT_NAME => {
+                ElemValueAccessor::<bool>::set_val(elem_value, || {
+                    let flags = unit.get_flags(timeout_ms)?;
+                    Ok(flags
+                        .iter()
+                        .find(|&flag| *flag == HwCtlFlag::GuitarHexInput)
+                        .is_some())
+                })?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -112,6 +135,17 @@ impl GuitarCtl {
                 })?;
                 Ok(true)
             }
+            HEX_INPUT_NAME => {
+                ElemValueAccessor::<bool>::get_val(new, |val| {
+                    let flags = [HwCtlFlag::GuitarHexInput];
+                    if val {
+                        unit.set_flags(Some(&flags), None, timeout_ms)
+                    } else {
+                        unit.set_flags(None, Some(&flags), timeout_ms)
+                    }
+                })?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+use {super::*, protocols::hw_info::*};
+
+#[derive(Default)]
+pub struct InfoCtl {
+    dsp_version: u32,
+    arm_version: u32,
+    fpga_version: u32,
+    rx_channels: [usize; 3],
+    tx_channels: [usize; 3],
+}
+
+const DSP_VERSION_NAME: &str = "dsp-version";
+const ARM_VERSION_NAME: &str = "arm-version";
+const FPGA_VERSION_NAME: &str = "fpga-version";
+// The number of channels in stream for each of three ranges of sampling rate; up to 48.0 kHz, up
+// to 96.0 kHz, and up to 192.0 kHz.
+const RX_CHANNELS_NAME: &str = "playback-stream-channels";
+const TX_CHANNELS_NAME: &str = "capture-stream-channels";
+
+impl InfoCtl {
+    pub fn load(&mut self, hwinfo: &HwInfo, card_cntr: &mut CardCntr) -> Result<(), Error> {
+        self.dsp_version = hwinfo.dsp_version;
+        self.arm_version = hwinfo.arm_version;
+        self.fpga_version = hwinfo.fpga_version;
+        self.rx_channels = hwinfo.rx_channels;
+        self.tx_channels = hwinfo.tx_channels;
+
+        // The elements are not unlocked so that the other processes can not change them.
+        [DSP_VERSION_NAME, ARM_VERSION_NAME, FPGA_VERSION_NAME]
+            .iter()
+            .try_for_each(|name| {
+                let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, name, 0);
+                card_cntr
+                    .add_int_elems(&elem_id, 1, 0, i32::MAX, 1, 1, None, false)
+                    .map(|_| ())
+            })?;
+
+        [RX_CHANNELS_NAME, TX_CHANNELS_NAME]
+            .iter()
+            .try_for_each(|name| {
+                let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, name, 0);
+                card_cntr
+                    .add_int_elems(&elem_id, 1, 0, i32::MAX, 1, 3, None, false)
+                    .map(|_| ())
+            })?;
+
+        Ok(())
+    }
+
+    pub fn read(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            DSP_VERSION_NAME => {
+                elem_value.set_int(&[self.dsp_version as i32]);
+                Ok(true)
+            }
+            ARM_VERSION_NAME => {
+                elem_value.set_int(&[self.arm_version as i32]);
+                Ok(true)
+            }
+            FPGA_VERSION_NAME => {
+                elem_value.set_int(&[self.fpga_version as i32]);
+                Ok(true)
+            }
+            RX_CHANNELS_NAME => {
+                let vals: Vec<i32> = self.rx_channels.iter().map(|&ch| ch as i32).collect();
+                elem_value.set_int(&vals);
+                Ok(true)
+            }
+            TX_CHANNELS_NAME => {
+                let vals: Vec<i32> = self.tx_channels.iter().map(|&ch| ch as i32).collect();
+                elem_value.set_int(&vals);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
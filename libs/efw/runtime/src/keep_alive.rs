@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Detection of firmware which no longer responds.
+//!
+//! Firmware of Fireworks board module occasionally stops responding to transaction, while the
+//! node still stays on IEEE 1394 bus. A lightweight transaction is periodically sent to detect
+//! it, and the unit is regarded as unhealthy when it fails several times in a row.
+
+use {super::*, protocols::hw_ctl::*};
+
+const TIMEOUT_MS: u32 = 100;
+
+#[derive(Debug)]
+pub struct KeepAlive {
+    failures: usize,
+    healthy: bool,
+    elem_id: Option<ElemId>,
+}
+
+impl Default for KeepAlive {
+    fn default() -> Self {
+        Self {
+            failures: 0,
+            healthy: true,
+            elem_id: None,
+        }
+    }
+}
+
+impl KeepAlive {
+    pub const HEALTHY_NAME: &'static str = "unit-healthy";
+
+    // The number of consecutive failures to regard the unit as unhealthy.
+    const FAILURE_THRESHOLD: usize = 3;
+
+    pub fn load(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, Self::HEALTHY_NAME, 0);
+        let elem_id_list = card_cntr.add_bool_elems(&elem_id, 1, 1, false)?;
+        self.elem_id = elem_id_list.first().cloned();
+        self.update_elem(card_cntr)
+    }
+
+    /// Send the lightweight transaction, then update the status. The error is returned when the
+    /// unit is detected to be unhealthy.
+    pub fn check(&mut self, unit: &mut SndEfw, card_cntr: &mut CardCntr) -> Result<(), Error> {
+        match unit.get_clock(TIMEOUT_MS) {
+            Ok(_) => {
+                self.failures = 0;
+                if !self.healthy {
+                    println!("Fireworks firmware responds again.");
+                    self.healthy = true;
+                    self.update_elem(card_cntr)?;
+                }
+                Ok(())
+            }
+            Err(e) => {
+                self.failures += 1;
+                if self.healthy && self.failures >= Self::FAILURE_THRESHOLD {
+                    self.healthy = false;
+                    self.update_elem(card_cntr)?;
+                    let msg = format!(
+                        "Fireworks firmware fails to respond {} times: {}",
+                        self.failures, e
+                    );
+                    Err(Error::new(FileError::Io, &msg))
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Take the unit to disappear from IEEE 1394 bus, then to appear again, to recover the
+    /// firmware.
+    pub fn recover(&mut self, unit: &mut SndEfw) -> Result<(), Error> {
+        unit.reconnect(TIMEOUT_MS)
+    }
+
+    fn update_elem(&self, card_cntr: &mut CardCntr) -> Result<(), Error> {
+        if let Some(elem_id) = &self.elem_id {
+            let elem_value = ElemValue::new();
+            elem_value.set_bool(&[self.healthy]);
+            card_cntr.card.write_elem_value(elem_id, &elem_value)
+        } else {
+            Ok(())
+        }
+    }
+}
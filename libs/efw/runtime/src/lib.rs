@@ -5,15 +5,23 @@ mod model;
 mod clk_ctl;
 mod guitar_ctl;
 mod iec60958_ctl;
+mod info_ctl;
 mod input_ctl;
+mod keep_alive;
 mod meter_ctl;
 mod mixer_ctl;
 mod output_ctl;
 mod port_ctl;
+#[cfg(feature = "vendor-cmd-console")]
+mod vendor_cmd_ctl;
 
 use {
     alsactl::{prelude::*, *},
-    core::{card_cntr::*, dispatcher::*, elem_value_accessor::*, *},
+    core::{
+        card_cntr::*, diagnostics::*, dispatcher::*, elem_alias::*, elem_locale::*,
+        elem_value_accessor::*, feature_report::*, meter_trigger::*, midi_map::*, mono_fold::*,
+        panic_ctl::*, ref_level::*, solo_bus::*, stream_guard::*, *,
+    },
     firewire_fireworks_protocols as protocols,
     glib::{source, Error, FileError},
     hinawa::{prelude::{FwNodeExtManual, FwNodeExt}, FwNode},
@@ -27,8 +35,10 @@ enum Event {
     Disconnected,
     BusReset(u32),
     Timer,
+    KeepAlive,
     Elem((ElemId, ElemEventMask)),
     StreamLock(bool),
+    MidiMap(MidiMapEvents),
 }
 
 pub struct EfwRuntime {
@@ -40,6 +50,15 @@ pub struct EfwRuntime {
     tx: mpsc::SyncSender<Event>,
     dispatchers: Vec<Dispatcher>,
     timer: Option<Dispatcher>,
+    keep_alive: keep_alive::KeepAlive,
+    ref_level: RefLevel,
+    solo_bus_ctl: SoloBusCtl,
+    mono_fold_ctl: MonoFoldCtl,
+    midi_map: MidiMap,
+    meter_trigger: MeterTrigger,
+    stream_guard: StreamGuard,
+    feature_report: FeatureReport,
+    panic_ctl: PanicCtl,
     measured_elem_id_list: Vec<ElemId>,
     notified_elem_id_list: Vec<ElemId>,
 }
@@ -62,13 +81,10 @@ impl Drop for EfwRuntime {
 impl RuntimeOperation<u32> for EfwRuntime {
     fn new(card_id: u32) -> Result<Self, Error> {
         let unit = SndEfw::default();
-        unit.open(&format!("/dev/snd/hwC{}D0", card_id), 0)?;
+        unit.open(&Self::devnode_path(&format!("hwC{}D0", card_id)), 0)?;
 
         let node = FwNode::new();
-        node.open(&format!(
-            "/dev/{}",
-            unit.node_device().unwrap()
-        ))?;
+        node.open(&Self::devnode_path(&unit.node_device().unwrap()))?;
         let data = node.config_rom()?;
         let model = model::EfwModel::new(&data)?;
 
@@ -87,6 +103,15 @@ impl RuntimeOperation<u32> for EfwRuntime {
             tx,
             dispatchers: Default::default(),
             timer: Default::default(),
+            keep_alive: Default::default(),
+            ref_level: Default::default(),
+            solo_bus_ctl: Default::default(),
+            mono_fold_ctl: Default::default(),
+            midi_map: Default::default(),
+            meter_trigger: Default::default(),
+            stream_guard: Default::default(),
+            feature_report: Default::default(),
+            panic_ctl: Default::default(),
             measured_elem_id_list: Default::default(),
             notified_elem_id_list: Default::default(),
         })
@@ -96,17 +121,58 @@ impl RuntimeOperation<u32> for EfwRuntime {
         self.launch_node_event_dispatcher()?;
         self.launch_system_event_dispatcher()?;
 
+        setup_label_translations(&mut self.card_cntr);
+
         self.model.load(&mut self.unit, &mut self.card_cntr)?;
 
         let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, Self::TIMER_NAME, 0);
         let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
 
+        let card_id = self.unit.card_id();
+        self.stream_guard
+            .load(&mut self.card_cntr, card_id, &Self::STREAM_CRITICAL_ELEMS)?;
+
+        #[cfg(feature = "vendor-cmd-console")]
+        core::vendor_cmd::add_vendor_cmd_elems(&mut self.card_cntr)?;
+
+        self.keep_alive.load(&mut self.card_cntr)?;
+        self.launch_keep_alive_dispatcher()?;
+
         self.model
             .get_measure_elem_list(&mut self.measured_elem_id_list);
 
+        self.ref_level
+            .load(&mut self.card_cntr, &self.measured_elem_id_list)?;
+
+        self.solo_bus_ctl
+            .load(&mut self.card_cntr, &Self::SOLO_BUS_TARGETS)?;
+
+        self.mono_fold_ctl
+            .load(&mut self.card_cntr, &Self::MONO_FOLD_TARGETS)?;
+
+        // The map is optional, thus any failure is not fatal.
+        if let Err(e) = self
+            .midi_map
+            .load(&self.card_cntr, Self::MIDI_MAP_CLIENT_NAME)
+        {
+            report_feature(MIDI_MAP_FEATURE_NAME, &e.to_string());
+        }
+        self.launch_midi_map_dispatcher()?;
+
+        self.meter_trigger
+            .setup(&self.card_cntr, Self::MIDI_MAP_CLIENT_NAME);
+
         self.model
             .get_notified_elem_list(&mut self.notified_elem_id_list);
 
+        self.panic_ctl
+            .load(&mut self.card_cntr, &Self::PANIC_TARGETS)?;
+
+        setup_aliases(&mut self.card_cntr);
+
+        self.feature_report.load(&mut self.card_cntr)?;
+        self.feature_report.update(&mut self.card_cntr)?;
+
         Ok(())
     }
 
@@ -123,13 +189,86 @@ impl RuntimeOperation<u32> for EfwRuntime {
                     println!("IEEE 1394 bus is updated: {}", generation);
                 }
                 Event::Timer => {
+                    let _ = self
+                        .model
+                        .watch_clk_detection(&mut self.unit, &self.stream_guard);
                     let _ = self.card_cntr.measure_elems(
                         &mut self.unit,
                         &self.measured_elem_id_list,
                         &mut self.model,
                     );
+                    self.meter_trigger
+                        .check_and_report(&mut self.card_cntr, &self.feature_report);
+                }
+                Event::KeepAlive => {
+                    if let Err(e) = self.keep_alive.check(&mut self.unit, &mut self.card_cntr) {
+                        report_feature(keep_alive::KeepAlive::HEALTHY_NAME, &e.to_string());
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        // The node disappears from the bus and the runtime is expected to finish
+                        // by the disconnection, then to be restarted.
+                        self.keep_alive.recover(&mut self.unit).map_err(|e| {
+                            let msg = format!("Fail to recover Fireworks firmware: {}", e);
+                            Error::new(FileError::Io, &msg)
+                        })?;
+                    }
                 }
                 Event::Elem((elem_id, events)) => {
+                    if self.feature_report.has_elem(&elem_id) {
+                        // The change of value is by the runtime itself.
+                        continue;
+                    }
+
+                    #[cfg(feature = "vendor-cmd-console")]
+                    if vendor_cmd_ctl::handle_elem_event(
+                        &mut self.unit,
+                        &mut self.card_cntr,
+                        &elem_id,
+                        &events,
+                    ) {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .ref_level
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .solo_bus_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .mono_fold_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .panic_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .stream_guard
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
                     if elem_id.name() != Self::TIMER_NAME {
                         let _ = self.card_cntr.dispatch_elem_event(
                             &mut self.unit,
@@ -137,6 +276,7 @@ impl RuntimeOperation<u32> for EfwRuntime {
                             &events,
                             &mut self.model,
                         );
+                        let _ = self.feature_report.update(&mut self.card_cntr);
                     } else {
                         let mut elem_value = ElemValue::new();
                         if self
@@ -162,20 +302,112 @@ impl RuntimeOperation<u32> for EfwRuntime {
                         &mut self.model,
                     );
                 }
+                Event::MidiMap(events) => {
+                    self.midi_map
+                        .handle_seq_events(&mut self.card_cntr, &events);
+                    let _ = self.feature_report.update(&mut self.card_cntr);
+                }
             }
         }
         Ok(())
     }
 }
 
+impl DiagnosticsOperation<u32> for EfwRuntime {
+    fn collect_diagnostics(card_id: &u32) -> Result<Vec<DiagnosticsEntry>, Error> {
+        use protocols::hw_info::*;
+
+        let mut entries = collect_common_diagnostics(*card_id)?;
+
+        let mut unit = SndEfw::default();
+        unit.open(&Self::devnode_path(&format!("hwC{}D0", card_id)), 0)?;
+
+        // The dispatcher is required to receive responses of transactions.
+        let mut dispatcher = Dispatcher::run(Self::NODE_DISPATCHER_NAME.to_string())?;
+        dispatcher.attach_alsa_firewire(&unit, |_| ())?;
+
+        let mut hwinfo = HwInfo::default();
+        unit.get_hw_info(&mut hwinfo, Self::DIAGNOSTICS_TIMEOUT_MS)?;
+        // The GUID is redacted.
+        hwinfo.guid = 0;
+        entries.push(DiagnosticsEntry::new(
+            "hw-info.txt",
+            format!("{:#?}\n", hwinfo).into_bytes(),
+        ));
+
+        Ok(entries)
+    }
+}
+
 impl EfwRuntime {
     const NODE_DISPATCHER_NAME: &'static str = "node event dispatcher";
     const SYSTEM_DISPATCHER_NAME: &'static str = "system event dispatcher";
     const TIMER_DISPATCHER_NAME: &'static str = "interval timer dispatcher";
+    const KEEP_ALIVE_DISPATCHER_NAME: &'static str = "keep-alive dispatcher";
+    const MIDI_MAP_DISPATCHER_NAME: &'static str = "MIDI map dispatcher";
+
+    const MIDI_MAP_CLIENT_NAME: &'static str = "Fireworks mixer";
 
     const TIMER_NAME: &'static str = "metering";
     const TIMER_INTERVAL: time::Duration = time::Duration::from_millis(50);
 
+    const KEEP_ALIVE_INTERVAL: time::Duration = time::Duration::from_secs(5);
+
+    const DIAGNOSTICS_TIMEOUT_MS: u32 = 100;
+
+    // The controls critical to packet streaming, of which change is rejected while PCM
+    // substreams are running.
+    const STREAM_CRITICAL_ELEMS: [&'static str; 5] = [
+        "clock-source",
+        "clock-rate",
+        "digital-mode",
+        "stream-playback-routing",
+        "stream-capture-routing",
+    ];
+
+    // The elements reset by panic switch.
+    const PANIC_TARGETS: [PanicTarget; 6] = [
+        PanicTarget {
+            name: "output-mute",
+            state: Some(true),
+        },
+        PanicTarget {
+            name: "monitor-solo",
+            state: Some(false),
+        },
+        PanicTarget {
+            name: "playback-solo",
+            state: Some(false),
+        },
+        PanicTarget {
+            name: "monitor-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "playback-volume",
+            state: None,
+        },
+        PanicTarget {
+            name: "output-volume",
+            state: None,
+        },
+    ];
+
+    // The mode of solo over the solo function in DSP or FPGA. The gain is expressed in fixed
+    // point number of 8.24 format.
+    const SOLO_BUS_TARGETS: [SoloBusTarget; 1] = [SoloBusTarget {
+        gain: "monitor-gain",
+        mute: "monitor-mute",
+        solo: "monitor-solo",
+        unity: Some(0x01000000),
+    }];
+
+    // The fold-down to mono orchestrated by software, for the mixer to each playback.
+    const MONO_FOLD_TARGETS: [MonoFoldTarget; 1] = [MonoFoldTarget {
+        pans: &[("monitor-pan", None)],
+        fold: "monitor-mono-fold",
+    }];
+
     fn launch_node_event_dispatcher(&mut self) -> Result<(), Error> {
         let name = Self::NODE_DISPATCHER_NAME.to_string();
         let mut dispatcher = Dispatcher::run(name)?;
@@ -237,6 +469,40 @@ impl EfwRuntime {
         Ok(())
     }
 
+    fn launch_keep_alive_dispatcher(&mut self) -> Result<(), Error> {
+        let name = Self::KEEP_ALIVE_DISPATCHER_NAME.to_string();
+        let mut dispatcher = Dispatcher::run(name)?;
+
+        let tx = self.tx.clone();
+        dispatcher.attach_interval_handler(Self::KEEP_ALIVE_INTERVAL, move || {
+            let _ = tx.send(Event::KeepAlive);
+            source::Continue(true)
+        });
+
+        self.dispatchers.push(dispatcher);
+
+        Ok(())
+    }
+
+    fn launch_midi_map_dispatcher(&mut self) -> Result<(), Error> {
+        if !self.midi_map.is_loaded() {
+            return Ok(());
+        }
+
+        let name = Self::MIDI_MAP_DISPATCHER_NAME.to_string();
+        let mut dispatcher = Dispatcher::run(name)?;
+
+        let tx = self.tx.clone();
+        self.midi_map
+            .attach_dispatcher(&mut dispatcher, move |events| {
+                let _ = tx.send(Event::MidiMap(events));
+            })?;
+
+        self.dispatchers.push(dispatcher);
+
+        Ok(())
+    }
+
     fn start_interval_timer(&mut self) -> Result<(), Error> {
         let mut dispatcher = Dispatcher::run(Self::TIMER_DISPATCHER_NAME.to_string())?;
         let tx = self.tx.clone();
@@ -1,7 +1,10 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 // Copyright (c) 2020 Takashi Sakamoto
 
-use {super::*, protocols::hw_info::*};
+use {
+    super::*,
+    protocols::{hw_info::*, ClkSrc},
+};
 
 #[derive(Default)]
 pub struct MeterCtl {
@@ -9,6 +12,9 @@ pub struct MeterCtl {
     meters: Option<HwMeter>,
     midi_inputs: usize,
     midi_outputs: usize,
+    // The identifier of element, the position of the first meter, and the number of meters.
+    input_meter_elems: Vec<(ElemId, usize, usize)>,
+    output_meter_elems: Vec<(ElemId, usize, usize)>,
 }
 
 const CLK_DETECT_NAME: &str = "clock-detect";
@@ -20,11 +26,76 @@ const GUITAR_STEREO_CONNECT_NAME: &str = "guitar-stereo-detect";
 const GUITAR_HEX_SIGNAL_NAME: &str = "guitar-hex-signal-detect";
 const GUITAR_CHARGE_STATE_NAME: &str = "guitar-charge-state-detect";
 
+fn phys_group_type_to_name(group_type: &PhysGroupType) -> &'static str {
+    match group_type {
+        PhysGroupType::Analog => "analog",
+        PhysGroupType::Spdif => "spdif",
+        PhysGroupType::Adat => "adat",
+        PhysGroupType::SpdifOrAdat => "spdif-or-adat",
+        PhysGroupType::AnalogMirror => "analog-mirror",
+        PhysGroupType::Headphones => "headphone",
+        PhysGroupType::I2s => "i2s",
+        PhysGroupType::Guitar => "guitar",
+        PhysGroupType::PiezoGuitar => "piezo-guitar",
+        PhysGroupType::GuitarString => "guitar-string",
+        PhysGroupType::Unknown(_) => "unknown",
+    }
+}
+
 impl MeterCtl {
     const COEF_MIN: i32 = 0;
     const COEF_MAX: i32 = 0x007fffff;
     const COEF_STEP: i32 = 1;
 
+    // The element is added for each physical group. The meters out of groups are available in
+    // the element without the name of group.
+    fn add_meter_elems(
+        card_cntr: &mut CardCntr,
+        entries: &[PhysGroupEntry],
+        count: usize,
+        name: &str,
+    ) -> Result<Vec<(ElemId, usize, usize)>, Error> {
+        // The name of element, the index of group among the groups of the same type, the position
+        // of the first meter, and the number of meters. The meters out of the count are truncated.
+        let mut groups: Vec<(String, u32, usize, usize)> = Vec::new();
+        let mut grouped = 0;
+        entries.iter().for_each(|entry| {
+            let group_count = entry.group_count.min(count - grouped);
+            if group_count > 0 {
+                let elem_name = format!("{}-{}", phys_group_type_to_name(&entry.group_type), name);
+                let index = groups
+                    .iter()
+                    .filter(|(n, _, _, _)| n.eq(&elem_name))
+                    .count();
+                groups.push((elem_name, index as u32, grouped, group_count));
+                grouped += group_count;
+            }
+        });
+
+        if grouped < count {
+            groups.push((name.to_string(), 0, grouped, count - grouped));
+        }
+
+        groups
+            .into_iter()
+            .map(|(elem_name, index, offset, count)| {
+                let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, &elem_name, index);
+                card_cntr
+                    .add_int_elems(
+                        &elem_id,
+                        1,
+                        Self::COEF_MIN,
+                        Self::COEF_MAX,
+                        Self::COEF_STEP,
+                        count,
+                        None,
+                        false,
+                    )
+                    .map(|elem_id_list| (elem_id_list[0].clone(), offset, count))
+            })
+            .collect()
+    }
+
     pub fn load(&mut self, hwinfo: &HwInfo, card_cntr: &mut CardCntr) -> Result<(), Error> {
         self.meters = Some(HwMeter::new(
             &hwinfo.clk_srcs,
@@ -52,31 +123,27 @@ impl MeterCtl {
             self.measure_elems.extend_from_slice(&elem_id_list);
         }
 
-        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, INPUT_METERS_NAME, 0);
-        let elem_id_list = card_cntr.add_int_elems(
-            &elem_id,
-            1,
-            Self::COEF_MIN,
-            Self::COEF_MAX,
-            Self::COEF_STEP,
+        self.input_meter_elems = Self::add_meter_elems(
+            card_cntr,
+            &hwinfo.phys_inputs,
             hwinfo.mixer_captures,
-            None,
-            false,
+            INPUT_METERS_NAME,
         )?;
-        self.measure_elems.extend_from_slice(&elem_id_list);
+        let measure_elems = &mut self.measure_elems;
+        self.input_meter_elems
+            .iter()
+            .for_each(|(elem_id, _, _)| measure_elems.push(elem_id.clone()));
 
-        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, OUTPUT_METERS_NAME, 0);
-        let elem_id_list = card_cntr.add_int_elems(
-            &elem_id,
-            1,
-            Self::COEF_MIN,
-            Self::COEF_MAX,
-            Self::COEF_STEP,
+        self.output_meter_elems = Self::add_meter_elems(
+            card_cntr,
+            &hwinfo.phys_outputs,
             hwinfo.mixer_playbacks,
-            None,
-            false,
+            OUTPUT_METERS_NAME,
         )?;
-        self.measure_elems.extend_from_slice(&elem_id_list);
+        let measure_elems = &mut self.measure_elems;
+        self.output_meter_elems
+            .iter()
+            .for_each(|(elem_id, _, _)| measure_elems.push(elem_id.clone()));
 
         let has_robot_guitar = hwinfo
             .caps
@@ -109,6 +176,13 @@ impl MeterCtl {
         Ok(())
     }
 
+    pub fn detected_clk_srcs(&self) -> &[(ClkSrc, bool)] {
+        self.meters
+            .as_ref()
+            .map(|meters| &meters.detected_clk_srcs[..])
+            .unwrap_or(&[])
+    }
+
     pub fn measure_states(&mut self, unit: &mut SndEfw, timeout_ms: u32) -> Result<(), Error> {
         match &mut self.meters {
             Some(meters) => unit.get_hw_meter(meters, timeout_ms),
@@ -124,6 +198,23 @@ impl MeterCtl {
         elem_id: &ElemId,
         elem_value: &mut ElemValue,
     ) -> Result<bool, Error> {
+        if let Some(meters) = &self.meters {
+            let found = self
+                .input_meter_elems
+                .iter()
+                .map(|entry| (entry, &meters.phys_input_meters))
+                .chain(
+                    self.output_meter_elems
+                        .iter()
+                        .map(|entry| (entry, &meters.phys_output_meters)),
+                )
+                .find(|((id, _, _), _)| id.eq(elem_id));
+            if let Some(((_, offset, count), vals)) = found {
+                elem_value.set_int(&vals[*offset..(*offset + *count)]);
+                return Ok(true);
+            }
+        }
+
         match elem_id.name().as_str() {
             CLK_DETECT_NAME => {
                 if let Some(meters) = &self.meters {
@@ -154,22 +245,6 @@ impl MeterCtl {
                     Ok(false)
                 }
             }
-            INPUT_METERS_NAME => {
-                if let Some(meters) = &self.meters {
-                    elem_value.set_int(&meters.phys_input_meters);
-                    Ok(true)
-                } else {
-                    Ok(false)
-                }
-            }
-            OUTPUT_METERS_NAME => {
-                if let Some(meters) = &self.meters {
-                    elem_value.set_int(&meters.phys_output_meters);
-                    Ok(true)
-                } else {
-                    Ok(false)
-                }
-            }
             GUITAR_STEREO_CONNECT_NAME => {
                 if let Some(meters) = &self.meters {
                     elem_value.set_bool(&[meters.guitar_stereo_connect]);
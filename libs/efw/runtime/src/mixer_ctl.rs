@@ -37,6 +37,8 @@ impl MixerCtl {
         mute_avail: false,
     };
 
+    // NOTE: the panning is computed by DSP or FPGA in the unit, thus the pan law is fixed in
+    // firmware and not selectable from the runtime.
     const PAN_MIN: i32 = 0;
     const PAN_MAX: i32 = 255;
     const PAN_STEP: i32 = 1;
@@ -18,6 +18,7 @@ pub struct EfwModel {
     meter_ctl: meter_ctl::MeterCtl,
     guitar_ctl: guitar_ctl::GuitarCtl,
     iec60958_ctl: iec60958_ctl::Iec60958Ctl,
+    info_ctl: info_ctl::InfoCtl,
 }
 
 impl EfwModel {
@@ -66,6 +67,16 @@ impl EfwModel {
             },
         }
     }
+
+    /// Switch the source of clock by the watchdog according to the latest detection of sources.
+    pub fn watch_clk_detection(
+        &mut self,
+        unit: &mut SndEfw,
+        guard: &StreamGuard,
+    ) -> Result<(), Error> {
+        self.clk_ctl
+            .watch_detection(unit, guard, self.meter_ctl.detected_clk_srcs(), TIMEOUT_MS)
+    }
 }
 
 impl CtlModel<SndEfw> for EfwModel {
@@ -81,6 +92,7 @@ impl CtlModel<SndEfw> for EfwModel {
         self.meter_ctl.load(&hwinfo, card_cntr)?;
         self.guitar_ctl.load(&hwinfo, card_cntr)?;
         self.iec60958_ctl.load(&hwinfo, card_cntr)?;
+        self.info_ctl.load(&hwinfo, card_cntr)?;
         Ok(())
     }
 
@@ -113,6 +125,8 @@ impl CtlModel<SndEfw> for EfwModel {
             .read(unit, elem_id, elem_value, TIMEOUT_MS)?
         {
             Ok(true)
+        } else if self.info_ctl.read(elem_id, elem_value)? {
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -150,6 +164,7 @@ impl CtlModel<SndEfw> for EfwModel {
 
 impl MeasureModel<SndEfw> for EfwModel {
     fn get_measure_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
+        elem_id_list.extend_from_slice(&self.clk_ctl.measured_elem_id_list);
         elem_id_list.extend_from_slice(&self.meter_ctl.measure_elems);
     }
 
@@ -163,7 +178,13 @@ impl MeasureModel<SndEfw> for EfwModel {
         elem_id: &ElemId,
         elem_value: &mut ElemValue,
     ) -> Result<bool, Error> {
-        self.meter_ctl.measure_elem(elem_id, elem_value)
+        if self.clk_ctl.measure_elem(elem_id, elem_value)? {
+            Ok(true)
+        } else if self.meter_ctl.measure_elem(elem_id, elem_value)? {
+            Ok(true)
+        } else {
+            Ok(false)
+        }
     }
 }
 
@@ -50,6 +50,32 @@ const DIG_MODE_NAME: &str = "digital-mode";
 const PHANTOM_NAME: &str = "phantom-powering";
 const RX_MAP_NAME: &str = "stream-playback-routing";
 const TX_MAP_NAME: &str = "stream-capture-routing";
+// The number of entries available in the stream map at the current rate of sampling.
+const RX_MAP_ACTIVE_PAIRS_NAME: &str = "stream-playback-routing-active-pairs";
+const TX_MAP_ACTIVE_PAIRS_NAME: &str = "stream-capture-routing-active-pairs";
+
+// The index of the range of sampling rate; up to 48.0 kHz, up to 96.0 kHz, and up to 192.0 kHz.
+fn rate_bracket(rate: u32) -> usize {
+    if rate <= 48000 {
+        0
+    } else if rate <= 96000 {
+        1
+    } else {
+        2
+    }
+}
+
+fn check_stream_map_entries(entries: &[Option<usize>], active_pairs: usize) -> Result<(), Error> {
+    if entries[active_pairs..].iter().any(|entry| entry.is_some()) {
+        let msg = format!(
+            "Just {} pairs of stream are available at the current rate of sampling",
+            active_pairs
+        );
+        Err(Error::new(FileError::Inval, &msg))
+    } else {
+        Ok(())
+    }
+}
 
 fn create_stream_map_labels(phys_entries: &[PhysGroupEntry]) -> Vec<String> {
     let mut labels = vec!["Disable".to_string()];
@@ -197,6 +223,24 @@ impl PortCtl {
             self.cache(unit, curr_rate, timeout_ms)?;
 
             if has_tx_mapping {
+                // The element is not unlocked so that the other processes can not change it.
+                let elem_id =
+                    ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, TX_MAP_ACTIVE_PAIRS_NAME, 0);
+                card_cntr
+                    .add_int_elems(
+                        &elem_id,
+                        1,
+                        0,
+                        self.tx_stream_map.len() as i32,
+                        1,
+                        1,
+                        None,
+                        false,
+                    )
+                    .map(|mut elem_id_list| {
+                        self.notified_elem_id_list.append(&mut elem_id_list);
+                    })?;
+
                 let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, TX_MAP_NAME, 0);
                 card_cntr
                     .add_enum_elems(
@@ -213,6 +257,24 @@ impl PortCtl {
             }
 
             if has_rx_mapping {
+                // The element is not unlocked so that the other processes can not change it.
+                let elem_id =
+                    ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, RX_MAP_ACTIVE_PAIRS_NAME, 0);
+                card_cntr
+                    .add_int_elems(
+                        &elem_id,
+                        1,
+                        0,
+                        self.rx_stream_map.len() as i32,
+                        1,
+                        1,
+                        None,
+                        false,
+                    )
+                    .map(|mut elem_id_list| {
+                        self.notified_elem_id_list.append(&mut elem_id_list);
+                    })?;
+
                 let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, RX_MAP_NAME, 0);
                 card_cntr
                     .add_enum_elems(
@@ -232,21 +294,48 @@ impl PortCtl {
         Ok(())
     }
 
+    fn rx_active_pairs(&self, rate: u32) -> usize {
+        std::cmp::min(
+            self.rx_stream_pair_counts[rate_bracket(rate)],
+            self.rx_stream_map.len(),
+        )
+    }
+
+    fn tx_active_pairs(&self, rate: u32) -> usize {
+        std::cmp::min(
+            self.tx_stream_pair_counts[rate_bracket(rate)],
+            self.tx_stream_map.len(),
+        )
+    }
+
+    // The number of available entries in the stream map depends on the range of sampling rate,
+    // thus the entries are read again for the current rate. The unavailable entries are
+    // disabled.
     pub fn cache(
         &mut self,
         unit: &mut SndEfw,
         curr_rate: u32,
         timeout_ms: u32,
     ) -> Result<(), Error> {
+        let rx_active_pairs = self.rx_active_pairs(curr_rate);
+        let tx_active_pairs = self.tx_active_pairs(curr_rate);
+
+        let mut rx_stream_map = vec![None; self.rx_stream_map.len()];
+        let mut tx_stream_map = vec![None; self.tx_stream_map.len()];
+
         unit.get_stream_map(
             curr_rate,
             self.phys_out_pairs,
             self.phys_in_pairs,
-            &mut self.rx_stream_map,
-            &mut self.tx_stream_map,
+            &mut rx_stream_map[..rx_active_pairs],
+            &mut tx_stream_map[..tx_active_pairs],
             timeout_ms,
         )
-        .map(|_| self.curr_rate = curr_rate)
+        .map(|_| {
+            self.rx_stream_map = rx_stream_map;
+            self.tx_stream_map = tx_stream_map;
+            self.curr_rate = curr_rate;
+        })
     }
 
     pub fn read(
@@ -299,6 +388,14 @@ impl PortCtl {
                 enum_values_from_entries(elem_value, &self.tx_stream_map);
                 Ok(true)
             }
+            RX_MAP_ACTIVE_PAIRS_NAME => {
+                elem_value.set_int(&[self.rx_active_pairs(self.curr_rate) as i32]);
+                Ok(true)
+            }
+            TX_MAP_ACTIVE_PAIRS_NAME => {
+                elem_value.set_int(&[self.tx_active_pairs(self.curr_rate) as i32]);
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -338,12 +435,15 @@ impl PortCtl {
             RX_MAP_NAME => {
                 let mut rx_stream_map = vec![Default::default(); self.rx_stream_map.len()];
                 enum_values_to_entries(new, &mut rx_stream_map);
+                let rx_active_pairs = self.rx_active_pairs(self.curr_rate);
+                let tx_active_pairs = self.tx_active_pairs(self.curr_rate);
+                check_stream_map_entries(&rx_stream_map, rx_active_pairs)?;
                 unit.set_stream_map(
                     self.curr_rate,
                     self.phys_out_pairs,
                     self.phys_in_pairs,
-                    &rx_stream_map,
-                    &self.tx_stream_map,
+                    &rx_stream_map[..rx_active_pairs],
+                    &self.tx_stream_map[..tx_active_pairs],
                     timeout_ms,
                 )
                 .map(|_| {
@@ -354,12 +454,15 @@ impl PortCtl {
             TX_MAP_NAME => {
                 let mut tx_stream_map = vec![Default::default(); self.tx_stream_map.len()];
                 enum_values_to_entries(new, &mut tx_stream_map);
+                let rx_active_pairs = self.rx_active_pairs(self.curr_rate);
+                let tx_active_pairs = self.tx_active_pairs(self.curr_rate);
+                check_stream_map_entries(&tx_stream_map, tx_active_pairs)?;
                 unit.set_stream_map(
                     self.curr_rate,
                     self.phys_out_pairs,
                     self.phys_in_pairs,
-                    &self.rx_stream_map,
-                    &tx_stream_map,
+                    &self.rx_stream_map[..rx_active_pairs],
+                    &tx_stream_map[..tx_active_pairs],
                     timeout_ms,
                 )
                 .map(|_| {
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Console of Fireworks transaction for maintainers.
+//!
+//! The frame of command consists of category, command, the number of parameters in response, and
+//! arguments. The frame of response consists of parameters.
+
+use {super::*, core::vendor_cmd::*};
+
+const TIMEOUT_MS: u32 = 100;
+
+fn execute_cmd(unit: &mut SndEfw, quadlets: &[u32]) -> Result<Vec<u32>, Error> {
+    if quadlets.len() < 3 {
+        let msg = format!(
+            "Unexpected number of quadlets in command: {}",
+            quadlets.len()
+        );
+        return Err(Error::new(FileError::Inval, &msg));
+    }
+
+    let category = quadlets[0];
+    let command = quadlets[1];
+    let param_count = quadlets[2] as usize;
+    let args = &quadlets[3..];
+
+    if param_count > VENDOR_FRAME_MAX_QUADLETS {
+        let msg = format!("Unexpected number of parameters: {}", param_count);
+        return Err(Error::new(FileError::Inval, &msg));
+    }
+
+    let mut params = vec![0; param_count];
+    unit.transaction(category, command, args, &mut params, TIMEOUT_MS)
+        .map(|_| params)
+}
+
+/// Handle the event of element for console. Return true if the element is for console.
+pub fn handle_elem_event(
+    unit: &mut SndEfw,
+    card_cntr: &mut CardCntr,
+    elem_id: &ElemId,
+    events: &ElemEventMask,
+) -> bool {
+    match elem_id.name().as_str() {
+        VENDOR_CMD_NAME => {
+            if !events.contains(ElemEventMask::VALUE) {
+                return true;
+            }
+
+            let mut elem_value = ElemValue::new();
+            let res = card_cntr
+                .card
+                .read_elem_value(elem_id, &mut elem_value)
+                .and_then(|_| parse_vendor_frame(&elem_value.bytes()))
+                .and_then(|quadlets| execute_cmd(unit, &quadlets))
+                .and_then(|params| write_vendor_resp(card_cntr, &params));
+            if let Err(e) = res {
+                report_feature(VENDOR_CMD_NAME, &format!("failed: {}", e));
+            }
+            true
+        }
+        // The change of value is by the runtime itself.
+        VENDOR_RESP_NAME => true,
+        _ => false,
+    }
+}
@@ -4,6 +4,7 @@
 use {
     super::{former_ctls::*, *},
     alsa_ctl_tlv_codec::DbInterval,
+    core::gain_staging::*,
     protocols::{
         former::{ff400::*, *},
         *,
@@ -19,10 +20,31 @@ pub struct Ff400Model {
     mixer_ctl: MixerCtl,
     status_ctl: StatusCtl,
     cfg_ctl: CfgCtl,
+    gain_staging: GainStaging,
+    gain_staging_elem_list: Vec<ElemId>,
 }
 
 const TIMEOUT_MS: u32 = 100;
 
+const INPUT_GAIN_STAGING_NAME: &str = "input-gain-staging";
+
+impl Ff400Model {
+    // The meters are watched for 3 seconds with the interval of metering timer.
+    const GAIN_STAGING_MEASURE_COUNT: usize = 60;
+
+    const MIC_GAIN_RANGE: InputGainRange = InputGainRange {
+        min: InputGainCtl::MIC_GAIN_MIN,
+        max: InputGainCtl::MIC_GAIN_MAX,
+        db_per_step: 1.0,
+    };
+
+    const LINE_GAIN_RANGE: InputGainRange = InputGainRange {
+        min: InputGainCtl::LINE_GAIN_MIN,
+        max: InputGainCtl::LINE_GAIN_MAX,
+        db_per_step: 0.5,
+    };
+}
+
 impl CtlModel<(SndUnit, FwNode)> for Ff400Model {
     fn load(
         &mut self,
@@ -47,6 +69,13 @@ impl CtlModel<(SndUnit, FwNode)> for Ff400Model {
             card_cntr,
             TIMEOUT_MS,
         )?;
+
+        // NOTE: the meters are watched as long as metering timer is enabled.
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, INPUT_GAIN_STAGING_NAME, 0);
+        card_cntr
+            .add_bool_elems(&elem_id, 1, 1, true)
+            .map(|mut elem_id_list| self.gain_staging_elem_list.append(&mut elem_id_list))?;
+
         Ok(())
     }
 
@@ -64,6 +93,8 @@ impl CtlModel<(SndUnit, FwNode)> for Ff400Model {
             Ok(true)
         } else if self.cfg_ctl.read(elem_id, elem_value)? {
             Ok(true)
+        } else if self.read_gain_staging(elem_id, elem_value) {
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -96,16 +127,97 @@ impl CtlModel<(SndUnit, FwNode)> for Ff400Model {
             .write(unit, &mut self.req, elem_id, old, new, TIMEOUT_MS)?
         {
             Ok(true)
+        } else if elem_id.name().as_str() == INPUT_GAIN_STAGING_NAME {
+            if new.boolean()[0] {
+                self.gain_staging.start(
+                    Self::INPUT_GAIN_RANGES.len(),
+                    Self::GAIN_STAGING_MEASURE_COUNT,
+                );
+            } else {
+                self.gain_staging.stop();
+            }
+            Ok(true)
         } else {
             Ok(false)
         }
     }
 }
 
+impl Ff400Model {
+    fn read_gain_staging(&self, elem_id: &ElemId, elem_value: &mut ElemValue) -> bool {
+        if elem_id.name().as_str() == INPUT_GAIN_STAGING_NAME {
+            elem_value.set_bool(&[self.gain_staging.is_running()]);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl GainStagingOperation<(SndUnit, FwNode)> for Ff400Model {
+    // The gain for microphone is applied to input 1 and 2, and the gain for line to input 3
+    // and 4.
+    const INPUT_GAIN_RANGES: &'static [InputGainRange] = &[
+        Self::MIC_GAIN_RANGE,
+        Self::MIC_GAIN_RANGE,
+        Self::LINE_GAIN_RANGE,
+        Self::LINE_GAIN_RANGE,
+    ];
+
+    fn gain_staging(&mut self) -> &mut GainStaging {
+        &mut self.gain_staging
+    }
+
+    fn input_peaks(&self) -> Vec<f64> {
+        self.meter_ctl
+            .0
+            .analog_inputs
+            .iter()
+            .take(Self::INPUT_GAIN_RANGES.len())
+            .map(|&level| level as f64 / Ff400Protocol::LEVEL_MAX as f64)
+            .collect()
+    }
+
+    fn input_gains(&self) -> Vec<i32> {
+        let status = &self.input_gain_ctl.status;
+        status
+            .mic
+            .iter()
+            .chain(&status.line)
+            .map(|&gain| gain as i32)
+            .collect()
+    }
+
+    fn write_input_gains(
+        &mut self,
+        unit: &mut (SndUnit, FwNode),
+        gains: &[i32],
+    ) -> Result<(), Error> {
+        let gains: Vec<i8> = gains.iter().map(|&gain| gain as i8).collect();
+        let status = &mut self.input_gain_ctl.status;
+        Ff400Protocol::write_input_mic_gains(
+            &mut self.req,
+            &mut unit.1,
+            status,
+            &gains[..2],
+            TIMEOUT_MS,
+        )?;
+        Ff400Protocol::write_input_line_gains(
+            &mut self.req,
+            &mut unit.1,
+            status,
+            &gains[2..],
+            TIMEOUT_MS,
+        )
+    }
+}
+
 impl MeasureModel<(SndUnit, FwNode)> for Ff400Model {
     fn get_measure_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
         elem_id_list.extend_from_slice(&self.meter_ctl.1);
         elem_id_list.extend_from_slice(&self.status_ctl.measured_elem_list);
+        elem_id_list.extend_from_slice(&self.input_gain_ctl.elem_id_list);
+        elem_id_list.extend_from_slice(&self.gain_staging_elem_list);
     }
 
     fn measure_states(&mut self, unit: &mut (SndUnit, FwNode)) -> Result<(), Error> {
@@ -113,6 +225,11 @@ impl MeasureModel<(SndUnit, FwNode)> for Ff400Model {
             .measure_states(unit, &mut self.req, TIMEOUT_MS)?;
         self.status_ctl
             .measure_states(unit, &mut self.req, TIMEOUT_MS)?;
+
+        if self.gain_staging.is_running() {
+            self.update_gain_staging(unit)?;
+        }
+
         Ok(())
     }
 
@@ -126,6 +243,10 @@ impl MeasureModel<(SndUnit, FwNode)> for Ff400Model {
             Ok(true)
         } else if self.status_ctl.measure_elem(elem_id, elem_value)? {
             Ok(true)
+        } else if self.input_gain_ctl.read(elem_id, elem_value)? {
+            Ok(true)
+        } else if self.read_gain_staging(elem_id, elem_value) {
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -174,6 +295,8 @@ impl FormerMixerCtlOperation<Ff400Protocol> for MixerCtl {
 #[derive(Default, Debug)]
 struct InputGainCtl {
     status: Ff400InputGainStatus,
+    // The gains are changed by gain staging as well.
+    elem_id_list: Vec<ElemId>,
 }
 
 const MIC_GAIN_NAME: &str = "mic-input-gain";
@@ -210,28 +333,32 @@ impl InputGainCtl {
         Ff400Protocol::init_input_gains(req, &mut unit.1, &mut self.status, timeout_ms)?;
 
         let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, MIC_GAIN_NAME, 0);
-        card_cntr.add_int_elems(
-            &elem_id,
-            1,
-            Self::MIC_GAIN_MIN,
-            Self::MIC_GAIN_MAX,
-            Self::MIC_GAIN_STEP,
-            2,
-            Some(&Vec::<u32>::from(&Self::MIC_GAIN_TLV)),
-            true,
-        )?;
+        card_cntr
+            .add_int_elems(
+                &elem_id,
+                1,
+                Self::MIC_GAIN_MIN,
+                Self::MIC_GAIN_MAX,
+                Self::MIC_GAIN_STEP,
+                2,
+                Some(&Vec::<u32>::from(&Self::MIC_GAIN_TLV)),
+                true,
+            )
+            .map(|mut elem_id_list| self.elem_id_list.append(&mut elem_id_list))?;
 
         let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, LINE_GAIN_NAME, 0);
-        card_cntr.add_int_elems(
-            &elem_id,
-            1,
-            Self::LINE_GAIN_MIN,
-            Self::LINE_GAIN_MAX,
-            Self::LINE_GAIN_STEP,
-            2,
-            Some(&Vec::<u32>::from(&Self::LINE_GAIN_TLV)),
-            true,
-        )?;
+        card_cntr
+            .add_int_elems(
+                &elem_id,
+                1,
+                Self::LINE_GAIN_MIN,
+                Self::LINE_GAIN_MAX,
+                Self::LINE_GAIN_STEP,
+                2,
+                Some(&Vec::<u32>::from(&Self::LINE_GAIN_TLV)),
+                true,
+            )
+            .map(|mut elem_id_list| self.elem_id_list.append(&mut elem_id_list))?;
 
         Ok(())
     }
@@ -13,7 +13,11 @@ mod latter_ctls;
 
 use {
     alsactl::{prelude::*, *},
-    core::{card_cntr::*, dispatcher::*, elem_value_accessor::*, RuntimeOperation},
+    core::{
+        card_cntr::*, diagnostics::*, dispatcher::*, elem_alias::*, elem_locale::*,
+        elem_value_accessor::*, feature_report::*, meter_trigger::*, panic_ctl::*,
+        RuntimeOperation,
+    },
     firewire_fireface_protocols as protocols,
     glib::{source, Error, FileError},
     hinawa::{
@@ -42,15 +46,18 @@ pub struct FfRuntime {
     tx: mpsc::SyncSender<Event>,
     dispatchers: Vec<Dispatcher>,
     timer: Option<Dispatcher>,
+    feature_report: FeatureReport,
+    panic_ctl: PanicCtl,
+    meter_trigger: MeterTrigger,
 }
 
 impl RuntimeOperation<u32> for FfRuntime {
     fn new(card_id: u32) -> Result<Self, Error> {
         let unit = SndUnit::new();
-        let path = format!("/dev/snd/hwC{}D0", card_id);
+        let path = Self::devnode_path(&format!("hwC{}D0", card_id));
         unit.open(&path, 0)?;
 
-        let cdev = format!("/dev/{}", unit.node_device().unwrap());
+        let cdev = Self::devnode_path(&unit.node_device().unwrap());
         let node = FwNode::new();
         node.open(&cdev)?;
 
@@ -75,6 +82,9 @@ impl RuntimeOperation<u32> for FfRuntime {
             tx,
             dispatchers,
             timer,
+            feature_report: Default::default(),
+            panic_ctl: Default::default(),
+            meter_trigger: Default::default(),
         })
     }
 
@@ -82,6 +92,8 @@ impl RuntimeOperation<u32> for FfRuntime {
         self.launch_node_event_dispatcher()?;
         self.launch_system_event_dispatcher()?;
 
+        setup_label_translations(&mut self.card_cntr);
+
         self.model.load(&mut self.unit, &mut self.card_cntr)?;
 
         if self.model.measured_elem_list.len() > 0 {
@@ -89,6 +101,17 @@ impl RuntimeOperation<u32> for FfRuntime {
             let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
         }
 
+        self.panic_ctl
+            .load(&mut self.card_cntr, &Self::PANIC_TARGETS)?;
+
+        self.meter_trigger
+            .setup(&self.card_cntr, Self::METER_TRIGGER_CLIENT_NAME);
+
+        setup_aliases(&mut self.card_cntr);
+
+        self.feature_report.load(&mut self.card_cntr)?;
+        self.feature_report.update(&mut self.card_cntr)?;
+
         Ok(())
     }
 
@@ -102,6 +125,19 @@ impl RuntimeOperation<u32> for FfRuntime {
                         println!("IEEE 1394 bus is updated: {}", generation);
                     }
                     Event::Elem(elem_id, events) => {
+                        if self.feature_report.has_elem(&elem_id) {
+                            // The change of value is by the runtime itself.
+                            continue;
+                        }
+
+                        if self
+                            .panic_ctl
+                            .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                        {
+                            let _ = self.feature_report.update(&mut self.card_cntr);
+                            continue;
+                        }
+
                         if elem_id.name() != Self::TIMER_NAME {
                             let _ = self.model.dispatch_elem_event(
                                 &mut self.unit,
@@ -109,6 +145,7 @@ impl RuntimeOperation<u32> for FfRuntime {
                                 &elem_id,
                                 &events,
                             );
+                            let _ = self.feature_report.update(&mut self.card_cntr);
                         } else {
                             let mut elem_value = alsactl::ElemValue::new();
                             let _ = self
@@ -129,6 +166,8 @@ impl RuntimeOperation<u32> for FfRuntime {
                         let _ = self
                             .model
                             .measure_elems(&mut self.unit, &mut self.card_cntr);
+                        self.meter_trigger
+                            .check_and_report(&mut self.card_cntr, &self.feature_report);
                     }
                 }
             }
@@ -137,6 +176,12 @@ impl RuntimeOperation<u32> for FfRuntime {
     }
 }
 
+impl DiagnosticsOperation<u32> for FfRuntime {
+    fn collect_diagnostics(card_id: &u32) -> Result<Vec<DiagnosticsEntry>, Error> {
+        collect_common_diagnostics(*card_id)
+    }
+}
+
 impl Drop for FfRuntime {
     fn drop(&mut self) {
         // At first, stop event loop in all of dispatchers to avoid queueing new events.
@@ -158,8 +203,45 @@ impl<'a> FfRuntime {
     const TIMER_DISPATCHER_NAME: &'a str = "interval timer dispatcher";
 
     const TIMER_NAME: &'a str = "metering";
+    const METER_TRIGGER_CLIENT_NAME: &'static str = "Fireface meter trigger";
     const TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
 
+    // The elements reset by panic switch.
+    const PANIC_TARGETS: [PanicTarget; 8] = [
+        PanicTarget {
+            name: "mixer:analog-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "mixer:line-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "mixer:mic-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "mixer:spdif-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "mixer:adat-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "mixer:stream-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "output:volume",
+            state: None,
+        },
+        PanicTarget {
+            name: "output-volume",
+            state: None,
+        },
+    ];
+
     fn launch_node_event_dispatcher(&mut self) -> Result<(), Error> {
         let name = Self::NODE_DISPATCHER_NAME.to_string();
         let mut dispatcher = Dispatcher::run(name)?;
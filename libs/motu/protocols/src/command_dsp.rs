@@ -2138,19 +2138,28 @@ pub struct CommandDspMonitorState {
     pub listenback_volume: f32,
     pub focus: FocusTarget,
     pub assign_target: TargetPort,
+    /// Whether the main volume is dimmed. The main volume is kept without dim.
+    pub dim: bool,
 }
 
 fn create_monitor_commands(
     state: &CommandDspMonitorState,
     target_ports: &[TargetPort],
+    dim_ratio: f32,
 ) -> Vec<DspCmd> {
     let pos = target_ports
         .iter()
         .position(|p| state.assign_target.eq(p))
         .unwrap_or_default();
 
+    let volume = if state.dim {
+        state.main_volume * dim_ratio
+    } else {
+        state.main_volume
+    };
+
     vec![
-        DspCmd::Monitor(MonitorCmd::Volume(state.main_volume)),
+        DspCmd::Monitor(MonitorCmd::Volume(volume)),
         DspCmd::Monitor(MonitorCmd::TalkbackEnable(state.talkback_enable)),
         DspCmd::Monitor(MonitorCmd::ListenbackEnable(state.listenback_enable)),
         DspCmd::Monitor(MonitorCmd::TalkbackVolume(state.talkback_volume)),
@@ -2164,9 +2173,17 @@ fn parse_monitor_command(
     state: &mut CommandDspMonitorState,
     cmd: &MonitorCmd,
     target_ports: &[TargetPort],
+    dim_ratio: f32,
 ) {
     match cmd {
-        MonitorCmd::Volume(val) => state.main_volume = *val,
+        // The main volume can be changed by the knob of device during dim.
+        MonitorCmd::Volume(val) => {
+            state.main_volume = if state.dim {
+                (*val / dim_ratio).min(1.0)
+            } else {
+                *val
+            };
+        }
         MonitorCmd::TalkbackEnable(val) => state.talkback_enable = *val,
         MonitorCmd::ListenbackEnable(val) => state.listenback_enable = *val,
         MonitorCmd::TalkbackVolume(val) => state.talkback_volume = *val,
@@ -2190,10 +2207,13 @@ pub trait CommandDspMonitorOperation: CommandDspOperation {
     const VOLUME_MIN: f32 = 0.0;
     const VOLUME_MAX: f32 = 1.0;
 
+    /// The ratio of main volume during dim, approximately -20 dB.
+    const DIM_RATIO: f32 = 0.1;
+
     fn parse_monitor_commands(state: &mut CommandDspMonitorState, cmds: &[DspCmd]) {
         cmds.iter().for_each(|cmd| {
             if let DspCmd::Monitor(c) = cmd {
-                parse_monitor_command(state, c, Self::RETURN_ASSIGN_TARGETS);
+                parse_monitor_command(state, c, Self::RETURN_ASSIGN_TARGETS, Self::DIM_RATIO);
             }
         });
     }
@@ -2206,8 +2226,9 @@ pub trait CommandDspMonitorOperation: CommandDspOperation {
         old: &mut CommandDspMonitorState,
         timeout_ms: u32,
     ) -> Result<(), Error> {
-        let mut new_cmds = create_monitor_commands(&state, Self::RETURN_ASSIGN_TARGETS);
-        let old_cmds = create_monitor_commands(old, Self::RETURN_ASSIGN_TARGETS);
+        let mut new_cmds =
+            create_monitor_commands(&state, Self::RETURN_ASSIGN_TARGETS, Self::DIM_RATIO);
+        let old_cmds = create_monitor_commands(old, Self::RETURN_ASSIGN_TARGETS, Self::DIM_RATIO);
         new_cmds.retain(|cmd| old_cmds.iter().find(|c| c.eq(&cmd)).is_none());
         Self::send_commands(req, node, sequence_number, &new_cmds, timeout_ms).map(|_| *old = state)
     }
@@ -2238,6 +2259,71 @@ pub struct CommandDspMixerState {
     pub source: [CommandDspMixerSourceState; MIXER_COUNT],
 }
 
+fn morph_f32_values(dst: &mut [f32], src: &[f32], target: &[f32], position: f32) {
+    dst.iter_mut()
+        .zip(src.iter().zip(target))
+        .for_each(|(d, (&s, &t))| *d = s + (t - s) * position);
+}
+
+/// Interpolate the state of mixer between two scenes according to the position, between 0.0 for
+/// the source scene and 1.0 for the target scene. The continuous parameters such as gain, volume,
+/// and panning are interpolated linearly. The discrete parameters such as mute, solo, and
+/// assignment follow the scene nearer to the position.
+pub fn morph_mixer_state(
+    src: &CommandDspMixerState,
+    target: &CommandDspMixerState,
+    position: f32,
+) -> CommandDspMixerState {
+    let position = position.clamp(0.0, 1.0);
+    let mut state = if position < 0.5 {
+        src.clone()
+    } else {
+        target.clone()
+    };
+
+    morph_f32_values(
+        &mut state.output_volume,
+        &src.output_volume,
+        &target.output_volume,
+        position,
+    );
+    morph_f32_values(
+        &mut state.reverb_send,
+        &src.reverb_send,
+        &target.reverb_send,
+        position,
+    );
+    morph_f32_values(
+        &mut state.reverb_return,
+        &src.reverb_return,
+        &target.reverb_return,
+        position,
+    );
+
+    state
+        .source
+        .iter_mut()
+        .zip(src.source.iter().zip(&target.source))
+        .for_each(|(d, (s, t))| {
+            morph_f32_values(&mut d.gain, &s.gain, &t.gain, position);
+            morph_f32_values(&mut d.pan, &s.pan, &t.pan, position);
+            morph_f32_values(
+                &mut d.stereo_balance,
+                &s.stereo_balance,
+                &t.stereo_balance,
+                position,
+            );
+            morph_f32_values(
+                &mut d.stereo_width,
+                &s.stereo_width,
+                &t.stereo_width,
+                position,
+            );
+        });
+
+    state
+}
+
 fn create_mixer_commands(
     state: &CommandDspMixerState,
     source_count: usize,
@@ -3465,6 +3551,33 @@ mod test {
         assert_eq!(c[0], cmd);
     }
 
+    #[test]
+    fn reverb_state_round_trip() {
+        let state = CommandDspReverbState {
+            enable: true,
+            split_point: SplitPoint::Mixer,
+            pre_delay: 50,
+            shelf_filter_freq: 8000,
+            shelf_filter_attenuation: -20,
+            decay_time: 3000,
+            freq_time: [40, 60, 80],
+            freq_crossover: [400, 5000],
+            width: 0.5,
+            reflection_mode: RoomShape::D,
+            reflection_size: 75,
+            reflection_level: -0.25,
+        };
+
+        let cmds = create_reverb_command(&state);
+        let mut s = CommandDspReverbState::default();
+        cmds.iter().for_each(|cmd| {
+            if let DspCmd::Reverb(c) = cmd {
+                parse_reverb_command(&mut s, c);
+            }
+        });
+        assert_eq!(state, s);
+    }
+
     #[test]
     fn message_decode_test() {
         let raw = [
@@ -3530,4 +3643,56 @@ mod test {
         assert_eq!(cmds[13], DspCmd::Input(InputCmd::Width(1, 0.0)));
         assert_eq!(cmds.len(), 14);
     }
+
+    #[test]
+    fn mixer_state_morph() {
+        let mut src = CommandDspMixerState::default();
+        src.output_volume[0] = 0.25;
+        src.output_mute[0] = true;
+        src.source[0].gain = vec![0.0, 1.0];
+        src.source[0].mute = vec![false, true];
+
+        let mut target = CommandDspMixerState::default();
+        target.output_volume[0] = 0.75;
+        target.output_mute[0] = false;
+        target.source[0].gain = vec![1.0, 0.0];
+        target.source[0].mute = vec![true, false];
+
+        let state = morph_mixer_state(&src, &target, 0.0);
+        assert_eq!(state, src);
+
+        let state = morph_mixer_state(&src, &target, 1.0);
+        assert_eq!(state, target);
+
+        let state = morph_mixer_state(&src, &target, 0.25);
+        assert_eq!(state.output_volume[0], 0.375);
+        assert_eq!(state.output_mute[0], true);
+        assert_eq!(state.source[0].gain, vec![0.25, 0.75]);
+        assert_eq!(state.source[0].mute, vec![false, true]);
+
+        let state = morph_mixer_state(&src, &target, 0.75);
+        assert_eq!(state.output_mute[0], false);
+        assert_eq!(state.source[0].gain, vec![0.75, 0.25]);
+        assert_eq!(state.source[0].mute, vec![true, false]);
+    }
+
+    #[test]
+    fn monitor_dim_commands() {
+        let mut state = CommandDspMonitorState {
+            main_volume: 0.5,
+            dim: true,
+            ..Default::default()
+        };
+
+        let cmds = create_monitor_commands(&state, &[], 0.1);
+        assert_eq!(cmds[0], DspCmd::Monitor(MonitorCmd::Volume(0.5 * 0.1)));
+
+        // The volume changed by the knob during dim is kept without dim.
+        parse_monitor_command(&mut state, &MonitorCmd::Volume(0.08), &[], 0.1);
+        assert_eq!(state.main_volume, 0.08 / 0.1);
+
+        state.dim = false;
+        parse_monitor_command(&mut state, &MonitorCmd::Volume(0.08), &[], 0.1);
+        assert_eq!(state.main_volume, 0.08);
+    }
 }
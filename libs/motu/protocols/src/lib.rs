@@ -131,6 +131,21 @@ pub enum ClkRate {
     R192000,
 }
 
+impl ClkRate {
+    /// The number of audio channels in ADAT interface at the rate. The channels are multiplexed
+    /// by S/MUX at higher rates.
+    pub fn adat_channel_count(&self) -> usize {
+        match self {
+            Self::R44100 | Self::R48000 => 8,
+            Self::R88200 | Self::R96000 => 4,
+            Self::R176400 | Self::R192000 => 2,
+        }
+    }
+}
+
+/// The number of audio channels in S/PDIF interface on optical (TOSLINK) connector.
+pub const OPT_SPDIF_CHANNEL_COUNT: usize = 2;
+
 const BUSY_DURATION: u64 = 150;
 const DISPLAY_CHARS: usize = 4 * 4;
 
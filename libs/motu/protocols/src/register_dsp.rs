@@ -12,7 +12,51 @@
 //! when changing the cache, the driver generates notification to the application.
 //! `RegisterDspEvent` is available to parse the notification.
 
-use {super::*, hitaki::SndMotuRegisterDspParameter};
+use {
+    super::*,
+    glib::{error::ErrorDomain, Quark},
+    hitaki::SndMotuRegisterDspParameter,
+};
+
+/// Any error of register DSP.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RegisterDspError {
+    /// The value read back from the register is different from the written value.
+    WriteIgnored,
+    Invalid(i32),
+}
+
+impl std::fmt::Display for RegisterDspError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let msg = match self {
+            RegisterDspError::WriteIgnored => "write-ignored",
+            RegisterDspError::Invalid(_) => "invalid",
+        };
+
+        write!(f, "RegisterDspError::{}", msg)
+    }
+}
+
+impl ErrorDomain for RegisterDspError {
+    fn domain() -> Quark {
+        Quark::from_str("motu-register-dsp-error-quark")
+    }
+
+    fn code(self) -> i32 {
+        match self {
+            RegisterDspError::WriteIgnored => 0,
+            RegisterDspError::Invalid(v) => v,
+        }
+    }
+
+    fn from(code: i32) -> Option<Self> {
+        let enumeration = match code {
+            0 => RegisterDspError::WriteIgnored,
+            _ => RegisterDspError::Invalid(code),
+        };
+        Some(enumeration)
+    }
+}
 
 const EV_TYPE_MIXER_SRC_GAIN: u8 = 0x02;
 const EV_TYPE_MIXER_SRC_PAN: u8 = 0x03;
@@ -56,7 +100,7 @@ const MIXER_OUTPUT_DESTINATION_MASK: u32 = 0x00000f00;
 const MIXER_OUTPUT_VOLUME_MASK: u32 = 0x000000ff;
 
 /// State of mixer output.
-#[derive(Default)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct RegisterDspMixerOutputState {
     pub volume: [u8; MIXER_COUNT],
     pub mute: [bool; MIXER_COUNT],
@@ -238,6 +282,10 @@ pub trait RegisterDspMixerOutputOperation {
 const MIXER_RETURN_ENABLE_OFFSET: usize = 0x0c18;
 
 /// The trait for operation of mixer return.
+///
+/// The register includes a flag to enable the return of outputs to the stream only. Unlike the
+/// models with command DSP (`MonitorCmd::ReturnAssign`), no register is known to select the pair
+/// of outputs for the return, thus the selection is not available.
 pub trait RegisterDspMixerReturnOperation {
     fn read_mixer_return_enable(
         req: &mut FwReq,
@@ -266,7 +314,7 @@ pub trait RegisterDspMixerReturnOperation {
 }
 
 /// State of sources in mixer entiry.
-#[derive(Default, Clone)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct RegisterDspMixerMonauralSourceEntry {
     pub gain: Vec<u8>,
     pub pan: Vec<u8>,
@@ -275,7 +323,7 @@ pub struct RegisterDspMixerMonauralSourceEntry {
 }
 
 /// State of mixer sources.
-#[derive(Default)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct RegisterDspMixerMonauralSourceState(
     pub [RegisterDspMixerMonauralSourceEntry; MIXER_COUNT],
 );
@@ -533,7 +581,7 @@ const MIXER_STEREO_SOURCE_COUNT: usize = 6;
 const MIXER_STEREO_SOURCE_PAIR_COUNT: usize = MIXER_STEREO_SOURCE_COUNT / 2;
 
 /// State of sources in mixer entiry.
-#[derive(Default, Clone)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct RegisterDspMixerStereoSourceEntry {
     pub gain: [u8; MIXER_STEREO_SOURCE_COUNT],
     pub pan: [u8; MIXER_STEREO_SOURCE_COUNT],
@@ -544,7 +592,7 @@ pub struct RegisterDspMixerStereoSourceEntry {
 }
 
 /// State of mixer sources.
-#[derive(Default)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct RegisterDspMixerStereoSourceState(pub [RegisterDspMixerStereoSourceEntry; MIXER_COUNT]);
 
 const MIXER_SOURCE_PAIRED_WIDTH_FLAG: u32 = 0x00400000;
@@ -895,7 +943,7 @@ const MASTER_VOLUME_OFFSET: usize = 0x0c0c;
 const PHONE_VOLUME_OFFSET: usize = 0x0c10;
 
 /// State of output.
-#[derive(Default)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct RegisterDspOutputState {
     pub master_volume: u8,
     pub phone_volume: u8,
@@ -984,7 +1032,7 @@ pub trait RegisterDspOutputOperation {
 }
 
 /// State of inputs in 828mkII.
-#[derive(Default)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct RegisterDspLineInputState {
     pub level: Vec<NominalSignalLevel>,
     /// + 6dB.
@@ -1129,7 +1177,7 @@ pub trait Traveler828mk2LineInputOperation {
 const MONAURAL_INPUT_COUNT: usize = 10;
 
 /// State of input in Ultralite.
-#[derive(Default)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct RegisterDspMonauralInputState {
     pub gain: [u8; MONAURAL_INPUT_COUNT],
     pub invert: [bool; MONAURAL_INPUT_COUNT],
@@ -1138,7 +1186,7 @@ pub struct RegisterDspMonauralInputState {
 const STEREO_INPUT_COUNT: usize = 6;
 
 /// State of input in Audio Express, and 4 pre.
-#[derive(Default)]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
 pub struct RegisterDspStereoInputState {
     pub gain: [u8; STEREO_INPUT_COUNT],
     pub invert: [bool; STEREO_INPUT_COUNT],
@@ -5,6 +5,9 @@
 //!
 //! The modules includes structure, enumeration, and trait and its implementation for protocol
 //! used in version 2 devices of Mark of the Unicorn FireWire series.
+//!
+//! 2408mk3 and 24I/O are not supported. They are connected to PCI-424 card via AudioWire
+//! interface, and they are not available on IEEE 1394 bus.
 
 use super::{register_dsp::*, *};
 
@@ -126,6 +129,17 @@ pub enum V2OptIfaceMode {
     Spdif,
 }
 
+impl V2OptIfaceMode {
+    /// The number of audio channels in the mode at the rate.
+    pub fn channel_count(&self, rate: &ClkRate) -> usize {
+        match self {
+            Self::None => 0,
+            Self::Adat => rate.adat_channel_count(),
+            Self::Spdif => OPT_SPDIF_CHANNEL_COUNT,
+        }
+    }
+}
+
 const OPT_IN_IFACE_LABEL: &str = "optical-input-iface-v2";
 const OPT_IN_IFACE_MASK: u32 = 0x00000300;
 const OPT_IN_IFACE_SHIFT: usize = 8;
@@ -231,6 +231,17 @@ impl Default for V3OptIfaceMode {
     }
 }
 
+impl V3OptIfaceMode {
+    /// The number of audio channels in the mode at the rate.
+    pub fn channel_count(&self, rate: &ClkRate) -> usize {
+        match self {
+            Self::Disabled => 0,
+            Self::Adat => rate.adat_channel_count(),
+            Self::Spdif => OPT_SPDIF_CHANNEL_COUNT,
+        }
+    }
+}
+
 fn get_opt_iface_masks(target: V3OptIfaceTarget, is_out: bool) -> (u32, u32) {
     let mut enabled_mask = 0x00000001;
     if is_out {
@@ -299,6 +310,11 @@ fn get_opt_iface_mode(
 }
 
 /// The trait for optical interface protocol in version 3.
+///
+/// The mode of optical interface is the only known way to reduce the number of data channels in
+/// packet streaming. No register is known to disable the pair of computer return individually,
+/// and the format of packet is configured by ALSA firewire-motu driver at the start of packet
+/// streaming. Thus the mode should be changed while the packet streaming is inactive.
 pub trait V3OptIfaceOperation {
     const TARGETS: &'static [V3OptIfaceTarget];
 
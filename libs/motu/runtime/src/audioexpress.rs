@@ -41,7 +41,7 @@ struct ClkCtl;
 impl V3ClkCtlOperation<AudioExpressProtocol> for ClkCtl {}
 
 #[derive(Default)]
-struct MixerOutputCtl(RegisterDspMixerOutputState, Vec<ElemId>);
+struct MixerOutputCtl(RegisterDspMixerOutputState, Vec<ElemId>, MuteRamp);
 
 impl RegisterDspMixerOutputCtlOperation<AudioExpressProtocol> for MixerOutputCtl {
     fn state(&self) -> &RegisterDspMixerOutputState {
@@ -51,6 +51,14 @@ impl RegisterDspMixerOutputCtlOperation<AudioExpressProtocol> for MixerOutputCtl
     fn state_mut(&mut self) -> &mut RegisterDspMixerOutputState {
         &mut self.0
     }
+
+    fn mute_ramp(&self) -> &MuteRamp {
+        &self.2
+    }
+
+    fn mute_ramp_mut(&mut self) -> &mut MuteRamp {
+        &mut self.2
+    }
 }
 
 #[derive(Default)]
@@ -80,7 +88,7 @@ impl RegisterDspMixerStereoSourceCtlOperation<AudioExpressProtocol> for MixerSou
 }
 
 #[derive(Default)]
-struct OutputCtl(RegisterDspOutputState, Vec<ElemId>);
+struct OutputCtl(RegisterDspOutputState, Vec<ElemId>, RegisterDspOutputState);
 
 impl RegisterDspOutputCtlOperation<AudioExpressProtocol> for OutputCtl {
     fn state(&self) -> &RegisterDspOutputState {
@@ -90,6 +98,14 @@ impl RegisterDspOutputCtlOperation<AudioExpressProtocol> for OutputCtl {
     fn state_mut(&mut self) -> &mut RegisterDspOutputState {
         &mut self.0
     }
+
+    fn knob_state(&self) -> &RegisterDspOutputState {
+        &self.2
+    }
+
+    fn knob_state_mut(&mut self) -> &mut RegisterDspOutputState {
+        &mut self.2
+    }
 }
 
 #[derive(Default)]
@@ -124,7 +140,8 @@ impl CtlModel<(SndMotu, FwNode)> for AudioExpress {
         unit: &mut (SndMotu, FwNode),
         card_cntr: &mut CardCntr,
     ) -> Result<(), Error> {
-        unit.0.read_parameter(&mut self.params)?;
+        // The initial values are used instead when the image of parameters is not available.
+        read_register_dsp_parameter(&unit.0, &mut self.params)?;
 
         self.clk_ctls.load(card_cntr)?;
         self.phone_assign_ctl
@@ -265,16 +282,15 @@ impl NotifyModel<(SndMotu, FwNode), bool> for AudioExpress {
         is_locked: &bool,
     ) -> Result<(), Error> {
         if *is_locked {
-            unit.0.read_parameter(&mut self.params).map(|_| {
+            if read_register_dsp_parameter(&unit.0, &mut self.params)? {
                 self.phone_assign_ctl.parse_dsp_parameter(&self.params);
                 self.mixer_output_ctl.parse_dsp_parameter(&self.params);
                 self.mixer_source_ctl.parse_dsp_parameter(&self.params);
                 self.input_ctl.parse_dsp_parameter(&self.params);
                 self.output_ctl.parse_dsp_parameter(&self.params);
-            })
-        } else {
-            Ok(())
+            }
         }
+        Ok(())
     }
 
     fn read_notified_elem(
@@ -362,3 +378,55 @@ impl MeasureModel<(SndMotu, FwNode)> for AudioExpress {
         }
     }
 }
+
+impl WriteVerificationModel<(SndMotu, FwNode)> for AudioExpress {
+    fn verify_elem(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        elem_id: &ElemId,
+    ) -> Result<bool, Error> {
+        if self
+            .mixer_output_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else if self
+            .mixer_source_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else if self
+            .output_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else if self
+            .input_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl MuteRampModel<(SndMotu, FwNode)> for AudioExpress {
+    fn set_mute_ramp_duration(&mut self, duration: Duration) {
+        self.mixer_output_ctl.set_mute_ramp_duration(duration);
+    }
+
+    fn is_mute_ramp_active(&self) -> bool {
+        self.mixer_output_ctl.is_mute_ramp_active()
+    }
+
+    fn advance_mute_ramp(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<(), Error> {
+        self.mixer_output_ctl
+            .advance_mute_ramp(unit, &mut self.req, TIMEOUT_MS)
+    }
+
+    fn finish_mute_ramp(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<(), Error> {
+        self.mixer_output_ctl
+            .finish_mute_ramp(unit, &mut self.req, TIMEOUT_MS)
+    }
+}
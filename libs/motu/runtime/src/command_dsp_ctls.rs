@@ -424,6 +424,7 @@ const TALKBACK_ENABLE_NAME: &str = "talkback-enable";
 const LISTENBACK_ENABLE_NAME: &str = "listenback-enable";
 const TALKBACK_VOLUME_NAME: &str = "talkback-volume";
 const LISTENBACK_VOLUME_NAME: &str = "listenback-volume";
+const MAIN_DIM_NAME: &str = "main-dim";
 
 pub trait CommandDspMonitorCtlOperation<T: CommandDspMonitorOperation> {
     fn state(&self) -> &CommandDspMonitorState;
@@ -456,7 +457,7 @@ pub trait CommandDspMonitorCtlOperation<T: CommandDspMonitorOperation> {
                 .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))
         })?;
 
-        [TALKBACK_ENABLE_NAME, LISTENBACK_ENABLE_NAME]
+        [TALKBACK_ENABLE_NAME, LISTENBACK_ENABLE_NAME, MAIN_DIM_NAME]
             .iter()
             .try_for_each(|name| {
                 let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, name, 0);
@@ -483,6 +484,10 @@ pub trait CommandDspMonitorCtlOperation<T: CommandDspMonitorOperation> {
                 elem_value.set_bool(&[self.state().listenback_enable]);
                 Ok(true)
             }
+            MAIN_DIM_NAME => {
+                elem_value.set_bool(&[self.state().dim]);
+                Ok(true)
+            }
             TALKBACK_VOLUME_NAME => {
                 let val = (self.state().talkback_volume * Self::F32_CONVERT_SCALE) as i32;
                 elem_value.set_int(&[val]);
@@ -547,6 +552,19 @@ pub trait CommandDspMonitorCtlOperation<T: CommandDspMonitorOperation> {
                 )
                 .map(|_| true)
             }
+            MAIN_DIM_NAME => {
+                let mut state = self.state().clone();
+                state.dim = elem_value.boolean()[0];
+                T::write_monitor_state(
+                    req,
+                    &mut unit.1,
+                    sequence_number,
+                    state,
+                    self.state_mut(),
+                    timeout_ms,
+                )
+                .map(|_| true)
+            }
             TALKBACK_VOLUME_NAME => {
                 let mut state = self.state().clone();
                 state.talkback_volume = (elem_value.int()[0] as f32) / Self::F32_CONVERT_SCALE;
@@ -605,15 +623,43 @@ const MIXER_SOURCE_STEREO_PAIR_MODE_NAME: &str = "mixer-source-stereo-mode";
 const MIXER_SOURCE_STEREO_BALANCE_NAME: &str = "mixer-source-stereo-balance";
 const MIXER_SOURCE_STEREO_WIDTH_NAME: &str = "mixer-source-stereo-width";
 
+const MIXER_SCENE_STORE_NAME: &str = "mixer-scene-store";
+pub const MIXER_SCENE_MORPH_NAME: &str = "mixer-scene-morph";
+
+const MIXER_SCENE_COUNT: usize = 2;
+
+/// The interval of timer to advance morph between the scenes of mixer.
+pub const MIXER_SCENE_MORPH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(20);
+
+/// The scenes of mixer and the position of morph between them.
+#[derive(Default, Debug)]
+pub struct CommandDspMixerScenes {
+    pub scenes: [Option<CommandDspMixerState>; MIXER_SCENE_COUNT],
+    pub position: i32,
+    /// The position at which the morph in progress stops.
+    pub target: Option<i32>,
+}
+
 pub trait CommandDspMixerCtlOperation<T: CommandDspMixerOperation> {
     fn state(&self) -> &CommandDspMixerState;
     fn state_mut(&mut self) -> &mut CommandDspMixerState;
 
+    fn scenes(&self) -> &CommandDspMixerScenes;
+    fn scenes_mut(&mut self) -> &mut CommandDspMixerScenes;
+
     const SOURCE_STEREO_PAIR_MODES: [SourceStereoPairMode; 2] =
         [SourceStereoPairMode::Width, SourceStereoPairMode::LrBalance];
 
     const F32_CONVERT_SCALE: f32 = 1000000.0;
 
+    const SCENE_MORPH_MIN: i32 = 0;
+    const SCENE_MORPH_MAX: i32 = 100;
+    const SCENE_MORPH_STEP: i32 = 1;
+
+    // The morph is performed in steps at each tick of timer so that the commands are sent at
+    // bounded rate.
+    const SCENE_MORPH_INCREMENT: i32 = 5;
+
     fn load(&mut self, card_cntr: &mut CardCntr) -> Result<Vec<ElemId>, Error> {
         let state = T::create_mixer_state();
         *self.state_mut() = state;
@@ -777,6 +823,21 @@ pub trait CommandDspMixerCtlOperation<T: CommandDspMixerOperation> {
             )
             .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))?;
 
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, MIXER_SCENE_STORE_NAME, 0);
+        card_cntr.add_bool_elems(&elem_id, 1, MIXER_SCENE_COUNT, true)?;
+
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, MIXER_SCENE_MORPH_NAME, 0);
+        card_cntr.add_int_elems(
+            &elem_id,
+            1,
+            Self::SCENE_MORPH_MIN,
+            Self::SCENE_MORPH_MAX,
+            Self::SCENE_MORPH_STEP,
+            1,
+            None,
+            true,
+        )?;
+
         Ok(notified_elem_id_list)
     }
 
@@ -793,12 +854,13 @@ pub trait CommandDspMixerCtlOperation<T: CommandDspMixerOperation> {
     fn read(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
         match elem_id.name().as_str() {
             MIXER_OUTPUT_DESTINATION_NAME => {
-                ElemValueAccessor::<u32>::set_vals(elem_value, T::MIXER_COUNT, |idx| {
+                let elem = EnumElem::new(T::MIXER_COUNT, T::OUTPUT_PORTS.len());
+                elem.set_vals(elem_value, |idx| {
                     let pos = T::OUTPUT_PORTS
                         .iter()
                         .position(|p| self.state().output_assign[idx].eq(p))
                         .unwrap();
-                    Ok(pos as u32)
+                    Ok(pos)
                 })
                 .map(|_| true)
             }
@@ -833,12 +895,14 @@ pub trait CommandDspMixerCtlOperation<T: CommandDspMixerOperation> {
             }
             MIXER_SOURCE_STEREO_PAIR_MODE_NAME => {
                 let mixer = elem_id.index() as usize;
-                ElemValueAccessor::<u32>::set_vals(elem_value, T::SOURCE_PORTS.len(), |idx| {
+                let elem =
+                    EnumElem::new(T::SOURCE_PORTS.len(), Self::SOURCE_STEREO_PAIR_MODES.len());
+                elem.set_vals(elem_value, |idx| {
                     let pos = Self::SOURCE_STEREO_PAIR_MODES
                         .iter()
                         .position(|m| self.state().source[mixer].stereo_mode[idx].eq(m))
                         .unwrap();
-                    Ok(pos as u32)
+                    Ok(pos)
                 })
                 .map(|_| true)
             }
@@ -850,6 +914,20 @@ pub trait CommandDspMixerCtlOperation<T: CommandDspMixerOperation> {
                 let mixer = elem_id.index() as usize;
                 Self::read_f32_values(elem_value, &self.state().source[mixer].stereo_width)
             }
+            MIXER_SCENE_STORE_NAME => {
+                let vals: Vec<bool> = self
+                    .scenes()
+                    .scenes
+                    .iter()
+                    .map(|scene| scene.is_some())
+                    .collect();
+                elem_value.set_bool(&vals);
+                Ok(true)
+            }
+            MIXER_SCENE_MORPH_NAME => {
+                elem_value.set_int(&[self.scenes().position]);
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -986,10 +1064,93 @@ pub trait CommandDspMixerCtlOperation<T: CommandDspMixerOperation> {
                     Ok(())
                 })
             }
+            MIXER_SCENE_STORE_NAME => {
+                let vals = &elem_value.boolean()[..MIXER_SCENE_COUNT];
+                let state = self.state().clone();
+                self.scenes_mut()
+                    .scenes
+                    .iter_mut()
+                    .zip(vals)
+                    .for_each(|(scene, &stored)| {
+                        if !stored {
+                            *scene = None;
+                        } else if scene.is_none() {
+                            *scene = Some(state.clone());
+                        }
+                    });
+                Ok(true)
+            }
+            MIXER_SCENE_MORPH_NAME => {
+                if self.scenes().scenes.iter().any(|scene| scene.is_none()) {
+                    let msg = "Both scenes should be stored to morph mixer";
+                    Err(Error::new(FileError::Inval, msg))
+                } else {
+                    // The morph is advanced by the runtime.
+                    let position = elem_value.int()[0];
+                    let scenes = self.scenes_mut();
+                    scenes.target = if scenes.position != position {
+                        Some(position)
+                    } else {
+                        None
+                    };
+                    Ok(true)
+                }
+            }
             _ => Ok(false),
         }
     }
 
+    /// Advance the morph in progress by one step between the two scenes. True is returned while
+    /// the morph is still in progress.
+    fn advance_scene_morph(
+        &mut self,
+        sequence_number: &mut u8,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        timeout_ms: u32,
+    ) -> Result<bool, Error> {
+        let scenes = self.scenes();
+        let (src, target, position) = match (&scenes.scenes, scenes.target) {
+            ([Some(src), Some(target)], Some(position)) => (src.clone(), target.clone(), position),
+            _ => {
+                self.scenes_mut().target = None;
+                return Ok(false);
+            }
+        };
+
+        let curr = self.scenes().position;
+        let curr = if curr < position {
+            std::cmp::min(curr + Self::SCENE_MORPH_INCREMENT, position)
+        } else {
+            std::cmp::max(curr - Self::SCENE_MORPH_INCREMENT, position)
+        };
+
+        let state = morph_mixer_state(&src, &target, curr as f32 / Self::SCENE_MORPH_MAX as f32);
+        let res = T::write_mixer_state(
+            req,
+            &mut unit.1,
+            sequence_number,
+            state,
+            self.state_mut(),
+            timeout_ms,
+        );
+
+        let scenes = self.scenes_mut();
+        match res {
+            Ok(_) => {
+                scenes.position = curr;
+                if curr == position {
+                    scenes.target = None;
+                }
+                Ok(scenes.target.is_some())
+            }
+            Err(e) => {
+                scenes.target = None;
+                Err(e)
+            }
+        }
+    }
+
     fn parse_commands(&mut self, cmds: &[DspCmd]) {
         T::parse_mixer_commands(self.state_mut(), cmds);
     }
@@ -1311,12 +1472,13 @@ pub trait CommandDspEqualizerCtlOperation<T: CommandDspOperation, U: Default> {
     ) -> Result<bool, Error> {
         assert_eq!(levels.len(), Self::CH_COUNT);
 
-        ElemValueAccessor::<u32>::set_vals(elem_value, Self::CH_COUNT, |idx| {
+        let elem = EnumElem::new(Self::CH_COUNT, Self::ROLL_OFF_LEVELS.len());
+        elem.set_vals(elem_value, |idx| {
             let pos = Self::ROLL_OFF_LEVELS
                 .iter()
                 .position(|l| levels[idx].eq(l))
                 .unwrap();
-            Ok(pos as u32)
+            Ok(pos)
         })
         .map(|_| true)
     }
@@ -1327,12 +1489,13 @@ pub trait CommandDspEqualizerCtlOperation<T: CommandDspOperation, U: Default> {
     ) -> Result<bool, Error> {
         assert_eq!(filter_types.len(), Self::CH_COUNT);
 
-        ElemValueAccessor::<u32>::set_vals(elem_value, Self::CH_COUNT, |idx| {
+        let elem = EnumElem::new(Self::CH_COUNT, Self::FILTER_TYPE_5.len());
+        elem.set_vals(elem_value, |idx| {
             let pos = Self::FILTER_TYPE_5
                 .iter()
                 .position(|f| filter_types[idx].eq(f))
                 .unwrap();
-            Ok(pos as u32)
+            Ok(pos)
         })
         .map(|_| true)
     }
@@ -1343,12 +1506,13 @@ pub trait CommandDspEqualizerCtlOperation<T: CommandDspOperation, U: Default> {
     ) -> Result<bool, Error> {
         assert_eq!(filter_types.len(), Self::CH_COUNT);
 
-        ElemValueAccessor::<u32>::set_vals(elem_value, Self::CH_COUNT, |idx| {
+        let elem = EnumElem::new(Self::CH_COUNT, Self::FILTER_TYPE_4.len());
+        elem.set_vals(elem_value, |idx| {
             let pos = Self::FILTER_TYPE_4
                 .iter()
                 .position(|f| filter_types[idx].eq(f))
                 .unwrap();
-            Ok(pos as u32)
+            Ok(pos)
         })
         .map(|_| true)
     }
@@ -2185,12 +2349,13 @@ pub trait CommandDspDynamicsCtlOperation<T: CommandDspOperation, U: Default> {
     ) -> Result<bool, Error> {
         assert_eq!(modes.len(), Self::CH_COUNT);
 
-        ElemValueAccessor::<u32>::set_vals(elem_value, Self::CH_COUNT, |idx| {
+        let elem = EnumElem::new(Self::CH_COUNT, Self::LEVEL_DETECT_MODES.len());
+        elem.set_vals(elem_value, |idx| {
             let pos = Self::LEVEL_DETECT_MODES
                 .iter()
                 .position(|m| modes[idx].eq(m))
                 .unwrap();
-            Ok(pos as u32)
+            Ok(pos)
         })
         .map(|_| true)
     }
@@ -2198,12 +2363,13 @@ pub trait CommandDspDynamicsCtlOperation<T: CommandDspOperation, U: Default> {
     fn read_leveler_mode(elem_value: &mut ElemValue, modes: &[LevelerMode]) -> Result<bool, Error> {
         assert_eq!(modes.len(), Self::CH_COUNT);
 
-        ElemValueAccessor::<u32>::set_vals(elem_value, Self::CH_COUNT, |idx| {
+        let elem = EnumElem::new(Self::CH_COUNT, Self::LEVELER_MODES.len());
+        elem.set_vals(elem_value, |idx| {
             let pos = Self::LEVELER_MODES
                 .iter()
                 .position(|m| modes[idx].eq(m))
                 .unwrap();
-            Ok(pos as u32)
+            Ok(pos)
         })
         .map(|_| true)
     }
@@ -2529,7 +2695,88 @@ fn input_stereo_pair_mode_to_string(mode: &InputStereoPairMode) -> &'static str
     }
 }
 
-const INPUT_PHASE_NAME: &str = "input-phase";
+// The pair of channels; left and right, is linked when the pair flag is enabled. The value of
+// the other channel follows the changed value, like CueMix FX.
+fn link_paired_vals<V: Copy + PartialEq>(links: &[(bool, bool)], old: &[V], new: &mut [V]) {
+    links.iter().enumerate().for_each(|(i, &(linked, newly))| {
+        let (l, r) = (i * 2, i * 2 + 1);
+        if newly {
+            // The value of left channel is used for the newly paired channels.
+            new[r] = new[l];
+        } else if linked {
+            if new[l] != old[l] {
+                new[r] = new[l];
+            } else if new[r] != old[r] {
+                new[l] = new[r];
+            }
+        }
+    });
+}
+
+fn link_paired_input_state(old: &CommandDspInputState, new: &mut CommandDspInputState) {
+    // The pair flags of both channels are changed at once.
+    let all: Vec<(bool, bool)> = vec![(true, false); new.pair.len() / 2];
+    link_paired_vals(&all, &old.pair, &mut new.pair);
+
+    let links: Vec<(bool, bool)> = (0..new.pair.len() / 2)
+        .map(|i| {
+            let linked = new.pair[i * 2];
+            (linked, linked && !old.pair[i * 2])
+        })
+        .collect();
+
+    link_paired_vals(&links, &old.gain, &mut new.gain);
+
+    let (o, n) = (&old.equalizer, &mut new.equalizer);
+    link_paired_vals(&links, &o.enable, &mut n.enable);
+    link_paired_vals(&links, &o.hpf_enable, &mut n.hpf_enable);
+    link_paired_vals(&links, &o.hpf_slope, &mut n.hpf_slope);
+    link_paired_vals(&links, &o.hpf_freq, &mut n.hpf_freq);
+    link_paired_vals(&links, &o.lpf_enable, &mut n.lpf_enable);
+    link_paired_vals(&links, &o.lpf_slope, &mut n.lpf_slope);
+    link_paired_vals(&links, &o.lpf_freq, &mut n.lpf_freq);
+    link_paired_vals(&links, &o.lf_enable, &mut n.lf_enable);
+    link_paired_vals(&links, &o.lf_type, &mut n.lf_type);
+    link_paired_vals(&links, &o.lf_freq, &mut n.lf_freq);
+    link_paired_vals(&links, &o.lf_gain, &mut n.lf_gain);
+    link_paired_vals(&links, &o.lf_width, &mut n.lf_width);
+    link_paired_vals(&links, &o.lmf_enable, &mut n.lmf_enable);
+    link_paired_vals(&links, &o.lmf_type, &mut n.lmf_type);
+    link_paired_vals(&links, &o.lmf_freq, &mut n.lmf_freq);
+    link_paired_vals(&links, &o.lmf_gain, &mut n.lmf_gain);
+    link_paired_vals(&links, &o.lmf_width, &mut n.lmf_width);
+    link_paired_vals(&links, &o.mf_enable, &mut n.mf_enable);
+    link_paired_vals(&links, &o.mf_type, &mut n.mf_type);
+    link_paired_vals(&links, &o.mf_freq, &mut n.mf_freq);
+    link_paired_vals(&links, &o.mf_gain, &mut n.mf_gain);
+    link_paired_vals(&links, &o.mf_width, &mut n.mf_width);
+    link_paired_vals(&links, &o.hmf_enable, &mut n.hmf_enable);
+    link_paired_vals(&links, &o.hmf_type, &mut n.hmf_type);
+    link_paired_vals(&links, &o.hmf_freq, &mut n.hmf_freq);
+    link_paired_vals(&links, &o.hmf_gain, &mut n.hmf_gain);
+    link_paired_vals(&links, &o.hmf_width, &mut n.hmf_width);
+    link_paired_vals(&links, &o.hf_enable, &mut n.hf_enable);
+    link_paired_vals(&links, &o.hf_type, &mut n.hf_type);
+    link_paired_vals(&links, &o.hf_freq, &mut n.hf_freq);
+    link_paired_vals(&links, &o.hf_gain, &mut n.hf_gain);
+    link_paired_vals(&links, &o.hf_width, &mut n.hf_width);
+
+    let (o, n) = (&old.dynamics, &mut new.dynamics);
+    link_paired_vals(&links, &o.enable, &mut n.enable);
+    link_paired_vals(&links, &o.comp_enable, &mut n.comp_enable);
+    link_paired_vals(&links, &o.comp_detect_mode, &mut n.comp_detect_mode);
+    link_paired_vals(&links, &o.comp_threshold, &mut n.comp_threshold);
+    link_paired_vals(&links, &o.comp_ratio, &mut n.comp_ratio);
+    link_paired_vals(&links, &o.comp_attack, &mut n.comp_attack);
+    link_paired_vals(&links, &o.comp_release, &mut n.comp_release);
+    link_paired_vals(&links, &o.comp_gain, &mut n.comp_gain);
+    link_paired_vals(&links, &o.leveler_enable, &mut n.leveler_enable);
+    link_paired_vals(&links, &o.leveler_mode, &mut n.leveler_mode);
+    link_paired_vals(&links, &o.leveler_makeup, &mut n.leveler_makeup);
+    link_paired_vals(&links, &o.leveler_reduce, &mut n.leveler_reduce);
+}
+
+const INPUT_POLARITY_NAME: &str = "input-polarity";
 const INPUT_PAIR_NAME: &str = "input-pair";
 const INPUT_GAIN_NAME: &str = "input-gain";
 const INPUT_SWAP_NAME: &str = "input-swap";
@@ -2562,7 +2809,7 @@ pub trait CommandDspInputCtlOperation<T: CommandDspInputOperation> {
 
         let mut notified_elem_id_list = Vec::new();
 
-        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, INPUT_PHASE_NAME, 0);
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, INPUT_POLARITY_NAME, 0);
         card_cntr
             .add_bool_elems(&elem_id, 1, T::INPUT_PORTS.len(), true)
             .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))?;
@@ -2684,7 +2931,7 @@ pub trait CommandDspInputCtlOperation<T: CommandDspInputOperation> {
 
     fn read(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            INPUT_PHASE_NAME => {
+            INPUT_POLARITY_NAME => {
                 elem_value.set_bool(&self.state().phase);
                 Ok(true)
             }
@@ -2701,12 +2948,13 @@ pub trait CommandDspInputCtlOperation<T: CommandDspInputOperation> {
                 Ok(true)
             }
             INPUT_STEREO_MODE_NAME => {
-                ElemValueAccessor::<u32>::set_vals(elem_value, T::INPUT_PORTS.len(), |idx| {
+                let elem = EnumElem::new(T::INPUT_PORTS.len(), Self::STEREO_PAIR_MODES.len());
+                elem.set_vals(elem_value, |idx| {
                     let pos = Self::STEREO_PAIR_MODES
                         .iter()
                         .position(|p| self.state().stereo_mode[idx].eq(p))
                         .unwrap();
-                    Ok(pos as u32)
+                    Ok(pos)
                 })
                 .map(|_| true)
             }
@@ -2756,7 +3004,7 @@ pub trait CommandDspInputCtlOperation<T: CommandDspInputOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            INPUT_PHASE_NAME => {
+            INPUT_POLARITY_NAME => {
                 let vals = &elem_value.boolean()[..T::INPUT_PORTS.len()];
                 self.write_state(sequence_number, unit, req, timeout_ms, |state| {
                     state.phase.copy_from_slice(&vals);
@@ -2879,6 +3127,7 @@ pub trait CommandDspInputCtlOperation<T: CommandDspInputOperation> {
     {
         let mut state = self.state().clone();
         func(&mut state)?;
+        link_paired_input_state(self.state(), &mut state);
         T::write_input_state(
             req,
             &mut unit.1,
@@ -2955,6 +3204,7 @@ where
     {
         let mut state = self.state().clone();
         func(&mut state.equalizer)?;
+        link_paired_input_state(self.state(), &mut state);
         T::write_input_state(
             req,
             &mut unit.1,
@@ -3006,6 +3256,7 @@ where
     {
         let mut state = self.state().clone();
         func(&mut state.dynamics)?;
+        link_paired_input_state(self.state(), &mut state);
         T::write_input_state(
             req,
             &mut unit.1,
@@ -3394,6 +3645,10 @@ where
 }
 
 const RESOURCE_USAGE_NAME: &str = "resource-usage";
+const RESOURCE_OVERRUN_NAME: &str = "resource-overrun";
+
+// NOTE: The message from the peer includes the total usage of DSP resource and one byte flag
+// whose meaning is unknown. The usage for each effect is not available.
 
 pub trait CommandDspResourcebCtlOperation {
     fn state(&self) -> &u32;
@@ -3418,6 +3673,12 @@ pub trait CommandDspResourcebCtlOperation {
             )
             .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))?;
 
+        // The element is not unlocked so that the other processes can not change it.
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, RESOURCE_OVERRUN_NAME, 0);
+        card_cntr
+            .add_bool_elems(&elem_id, 1, 1, false)
+            .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))?;
+
         Ok(notified_elem_id_list)
     }
 
@@ -3428,6 +3689,12 @@ pub trait CommandDspResourcebCtlOperation {
                 elem_value.set_int(&[val]);
                 Ok(true)
             }
+            RESOURCE_OVERRUN_NAME => {
+                // The resource is exhausted, thus any additional effect is not available.
+                let max = (ResourceCmd::USAGE_MAX * Self::F32_CONVERT_SCALE) as u32;
+                elem_value.set_bool(&[*self.state() >= max]);
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -3,11 +3,16 @@
 
 pub use {
     super::{
-        command_dsp_ctls::*, f828mk3::*, f828mk3_hybrid::*, track16::*, traveler_mk3::*,
-        ultralite_mk3::*, ultralite_mk3_hybrid::*, *,
+        command_dsp_ctls::*, direct_monitor_ctls::*, f828mk3::*, f828mk3_hybrid::*,
+        mute_group_ctls::*, track16::*, traveler_mk3::*, ultralite_mk3::*, ultralite_mk3_hybrid::*,
+        *,
     },
     alsactl::{prelude::*, *},
-    core::{card_cntr::*, dispatcher::*},
+    core::{
+        card_cntr::*, dispatcher::*, driver_caps::*, elem_alias::*, elem_locale::*,
+        feature_report::*, meter_trigger::*, midi_map::*, mono_fold::*, panic_ctl::*, solo_bus::*,
+        stream_guard::*,
+    },
     glib::source,
     hinawa::{prelude::FwRespExtManual, FwRcode, FwResp, FwTcode},
     nix::sys::signal::Signal,
@@ -47,7 +52,17 @@ where
     msg_handler: Arc<Mutex<CommandDspMessageHandler>>,
     cmd_notified_elem_id_list: Vec<ElemId>,
     timer: Option<Dispatcher>,
+    morph_timer: Option<Dispatcher>,
     measured_elem_id_list: Vec<ElemId>,
+    mute_group_ctl: MuteGroupCtl,
+    direct_monitor_ctl: DirectMonitorCtl,
+    panic_ctl: PanicCtl,
+    meter_trigger: MeterTrigger,
+    solo_bus_ctl: SoloBusCtl,
+    mono_fold_ctl: MonoFoldCtl,
+    midi_map: MidiMap,
+    feature_report: FeatureReport,
+    stream_guard: StreamGuard,
 }
 
 impl<T> Drop for Version3Runtime<T>
@@ -83,15 +98,80 @@ enum Event {
     Notify(u32),
     DspMsg,
     Timer,
+    Morph,
+    MidiMap(MidiMapEvents),
 }
 
 const NODE_DISPATCHER_NAME: &str = "node event dispatcher";
 const SYSTEM_DISPATCHER_NAME: &str = "system event dispatcher";
 const TIMER_DISPATCHER_NAME: &str = "interval timer dispatcher";
+const MORPH_DISPATCHER_NAME: &str = "scene morph timer dispatcher";
+const MIDI_MAP_DISPATCHER_NAME: &str = "MIDI map dispatcher";
+
+const MIDI_MAP_CLIENT_NAME: &str = "MOTU mixer";
 
 const TIMER_NAME: &str = "metering";
+const METER_TRIGGER_CLIENT_NAME: &str = "MOTU meter trigger";
 const TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
 
+// The elements reset by panic switch.
+const PANIC_TARGETS: [PanicTarget; 7] = [
+    PanicTarget {
+        name: "mixer-output-mute",
+        state: Some(true),
+    },
+    PanicTarget {
+        name: "mixer-source-solo",
+        state: Some(false),
+    },
+    PanicTarget {
+        name: "mixer-source-gain",
+        state: None,
+    },
+    PanicTarget {
+        name: "mixer-output-volume",
+        state: None,
+    },
+    PanicTarget {
+        name: "main-volume",
+        state: None,
+    },
+    PanicTarget {
+        name: "headphone-volume",
+        state: None,
+    },
+    PanicTarget {
+        name: "master-output-volume",
+        state: None,
+    },
+];
+
+// The mode of solo over the solo function in DSP.
+const SOLO_BUS_TARGETS: [SoloBusTarget; 1] = [SoloBusTarget {
+    gain: "mixer-source-gain",
+    mute: "mixer-soruce-mute",
+    solo: "mixer-source-solo",
+    unity: None,
+}];
+
+// The fold-down to mono orchestrated by software. The width of stereo sources is also reduced.
+const MONO_FOLD_TARGETS: [MonoFoldTarget; 1] = [MonoFoldTarget {
+    pans: &[
+        ("mixer-source-pan", None),
+        ("mixer-source-stereo-balance", None),
+        ("mixer-source-stereo-width", None),
+    ],
+    fold: "mixer-output-mono-fold",
+}];
+
+// The controls critical to packet streaming, of which change is rejected while PCM substreams are
+// running. The mode of optical interface changes the format of stream.
+const STREAM_CRITICAL_ELEMS: [&str; 3] = [
+    "sampling-rate",
+    "optical-iface-in-mode",
+    "optical-iface-out-mode",
+];
+
 impl<T> Version3Runtime<T>
 where
     for<'a> T: Default
@@ -121,7 +201,17 @@ where
             msg_handler: Default::default(),
             cmd_notified_elem_id_list: Default::default(),
             timer: Default::default(),
+            morph_timer: Default::default(),
             measured_elem_id_list: Default::default(),
+            mute_group_ctl: Default::default(),
+            direct_monitor_ctl: Default::default(),
+            panic_ctl: Default::default(),
+            meter_trigger: Default::default(),
+            solo_bus_ctl: Default::default(),
+            mono_fold_ctl: Default::default(),
+            midi_map: Default::default(),
+            feature_report: Default::default(),
+            stream_guard: Default::default(),
         })
     }
 
@@ -180,6 +270,8 @@ where
             Err(Error::new(FileError::Io, "No message for state arrived."))?;
         }
 
+        setup_label_translations(&mut self.card_cntr);
+
         self.model.load(&mut self.unit, &mut self.card_cntr)?;
         NotifyModel::<(SndMotu, FwNode), u32>::get_notified_elem_list(
             &mut self.model,
@@ -193,13 +285,42 @@ where
         self.model
             .get_measure_elem_list(&mut self.measured_elem_id_list);
 
-        // This is supported by ALSA firewire-motu driver in Linux kernel v5.16 or later.
-        let mut image = [0f32; 400];
-        let result = self.unit.0.read_float_meter(&mut image);
-        if result.is_ok() && self.measured_elem_id_list.len() > 0 {
-            let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, TIMER_NAME, 0);
-            let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
+        if self.measured_elem_id_list.len() > 0 {
+            // The metering is not available in old kernels, thus it is not fatal.
+            let mut image = [0f32; 400];
+            if probe_driver_feature(DriverFeature::MotuCommandDspMeter, || {
+                self.unit.0.read_float_meter(&mut image)
+            })? {
+                let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, TIMER_NAME, 0);
+                let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
+            }
+        }
+
+        self.mute_group_ctl.load(&mut self.card_cntr)?;
+        self.direct_monitor_ctl.load(&mut self.card_cntr)?;
+        self.panic_ctl.load(&mut self.card_cntr, &PANIC_TARGETS)?;
+        self.solo_bus_ctl
+            .load(&mut self.card_cntr, &SOLO_BUS_TARGETS)?;
+        self.mono_fold_ctl
+            .load(&mut self.card_cntr, &MONO_FOLD_TARGETS)?;
+
+        // The map is optional, thus any failure is not fatal.
+        if let Err(e) = self.midi_map.load(&self.card_cntr, MIDI_MAP_CLIENT_NAME) {
+            report_feature(MIDI_MAP_FEATURE_NAME, &e.to_string());
         }
+        self.launch_midi_map_dispatcher()?;
+
+        let card_id = self.unit.0.card_id();
+        self.stream_guard
+            .load(&mut self.card_cntr, card_id, &STREAM_CRITICAL_ELEMS)?;
+
+        self.meter_trigger
+            .setup(&self.card_cntr, METER_TRIGGER_CLIENT_NAME);
+
+        setup_aliases(&mut self.card_cntr);
+
+        self.feature_report.load(&mut self.card_cntr)?;
+        self.feature_report.update(&mut self.card_cntr)?;
 
         Ok(())
     }
@@ -217,6 +338,60 @@ where
                     println!("IEEE 1394 bus is updated: {}", generation);
                 }
                 Event::Elem((elem_id, events)) => {
+                    if self.feature_report.has_elem(&elem_id) {
+                        // The change of value is by the runtime itself.
+                        continue;
+                    }
+
+                    if self
+                        .stream_guard
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .mute_group_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self.direct_monitor_ctl.handle_elem_event(
+                        &mut self.card_cntr,
+                        &elem_id,
+                        &events,
+                    ) {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .panic_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .solo_bus_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .mono_fold_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
                     if elem_id.name() != TIMER_NAME {
                         let _ = self.card_cntr.dispatch_elem_event(
                             &mut self.unit,
@@ -224,6 +399,22 @@ where
                             &events,
                             &mut self.model,
                         );
+
+                        // The morph between scenes of mixer is advanced at each tick of timer.
+                        if elem_id.name() == MIXER_SCENE_MORPH_NAME && self.morph_timer.is_none() {
+                            let _ = self.start_morph_timer();
+                        }
+
+                        // The parameters of paired input channel can be changed as well.
+                        if elem_id.name().starts_with("input-") {
+                            let cmds: Vec<DspCmd> = Vec::new();
+                            let _ = self.card_cntr.dispatch_notification(
+                                &mut self.unit,
+                                &cmds,
+                                &self.cmd_notified_elem_id_list,
+                                &mut self.model,
+                            );
+                        }
                     } else {
                         let mut elem_value = ElemValue::new();
                         let _ = self
@@ -271,6 +462,33 @@ where
                         &self.measured_elem_id_list,
                         &mut self.model,
                     );
+                    self.meter_trigger
+                        .check_and_report(&mut self.card_cntr, &self.feature_report);
+                }
+                Event::Morph => {
+                    match self.model.advance_scene_morph(&mut self.unit) {
+                        Ok(true) => (),
+                        Ok(false) => self.morph_timer = None,
+                        Err(e) => {
+                            self.morph_timer = None;
+                            report_feature(MIXER_SCENE_MORPH_NAME, &format!("failed: {}", e));
+                            let _ = self.feature_report.update(&mut self.card_cntr);
+                        }
+                    }
+
+                    // The elements for mixer are updated by the intermediate state.
+                    let cmds: Vec<DspCmd> = Vec::new();
+                    let _ = self.card_cntr.dispatch_notification(
+                        &mut self.unit,
+                        &cmds,
+                        &self.cmd_notified_elem_id_list,
+                        &mut self.model,
+                    );
+                }
+                Event::MidiMap(events) => {
+                    self.midi_map
+                        .handle_seq_events(&mut self.card_cntr, &events);
+                    let _ = self.feature_report.update(&mut self.card_cntr);
                 }
             }
         }
@@ -306,6 +524,25 @@ where
         Ok(())
     }
 
+    fn launch_midi_map_dispatcher(&mut self) -> Result<(), Error> {
+        if !self.midi_map.is_loaded() {
+            return Ok(());
+        }
+
+        let name = MIDI_MAP_DISPATCHER_NAME.to_string();
+        let mut dispatcher = Dispatcher::run(name)?;
+
+        let tx = self.tx.clone();
+        self.midi_map
+            .attach_dispatcher(&mut dispatcher, move |events| {
+                let _ = tx.send(Event::MidiMap(events));
+            })?;
+
+        self.dispatchers.push(dispatcher);
+
+        Ok(())
+    }
+
     fn start_interval_timer(&mut self) -> Result<(), Error> {
         let mut dispatcher = Dispatcher::run(TIMER_DISPATCHER_NAME.to_string())?;
         let tx = self.tx.clone();
@@ -319,6 +556,19 @@ where
         Ok(())
     }
 
+    fn start_morph_timer(&mut self) -> Result<(), Error> {
+        let mut dispatcher = Dispatcher::run(MORPH_DISPATCHER_NAME.to_string())?;
+        let tx = self.tx.clone();
+        dispatcher.attach_interval_handler(MIXER_SCENE_MORPH_INTERVAL, move || {
+            let _ = tx.send(Event::Morph);
+            source::Continue(true)
+        });
+
+        self.morph_timer = Some(dispatcher);
+
+        Ok(())
+    }
+
     fn stop_interval_timer(&mut self) {
         if let Some(dispatcher) = &self.timer {
             drop(dispatcher);
@@ -360,4 +610,7 @@ pub trait CommandDspModel: NotifyModel<(SndMotu, FwNode), Vec<DspCmd>> {
         F: Fn(&FwResp, FwTcode, u64, u32, u32, u32, u32, &[u8]) -> FwRcode + 'static;
     fn begin_messaging(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<(), Error>;
     fn release_message_handler(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<(), Error>;
+    /// Advance the morph of mixer between scenes by one step. True is returned while the morph
+    /// is still in progress.
+    fn advance_scene_morph(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<bool, Error>;
 }
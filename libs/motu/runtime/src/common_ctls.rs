@@ -4,7 +4,7 @@
 use {
     super::*,
     alsactl::*,
-    core::{card_cntr::CardCntr, elem_value_accessor::ElemValueAccessor},
+    core::{card_cntr::CardCntr, typed_elem::*},
     hinawa::FwReq,
 };
 
@@ -43,8 +43,8 @@ pub trait PhoneAssignCtlOperation<T: AssignOperation> {
     fn read(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
         match elem_id.name().as_str() {
             PHONE_ASSIGN_NAME => {
-                ElemValueAccessor::<u32>::set_val(elem_value, || Ok(*self.state() as u32))
-                    .map(|_| true)
+                EnumElem::new(1, T::ASSIGN_PORTS.len()).set_val(elem_value, *self.state());
+                Ok(true)
             }
             _ => Ok(false),
         }
@@ -59,11 +59,12 @@ pub trait PhoneAssignCtlOperation<T: AssignOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            PHONE_ASSIGN_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
-                T::set_phone_assign(req, &mut unit.1, val as usize, timeout_ms)
-                    .map(|_| *self.state_mut() = val as usize)
-            })
-            .map(|_| true),
+            PHONE_ASSIGN_NAME => {
+                let val = EnumElem::new(1, T::ASSIGN_PORTS.len()).get_val(elem_value)?;
+                T::set_phone_assign(req, &mut unit.1, val, timeout_ms)
+                    .map(|_| *self.state_mut() = val)?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -83,6 +84,8 @@ const WORD_OUT_MODES: [WordClkSpeedMode; 2] = [
     WordClkSpeedMode::FollowSystemClk,
 ];
 
+const WORD_OUT_MODE_ELEM: EnumElem = EnumElem::new(1, WORD_OUT_MODES.len());
+
 pub trait WordClkCtlOperation<T: WordClkOperation> {
     fn state(&self) -> &WordClkSpeedMode;
     fn state_mut(&mut self) -> &mut WordClkSpeedMode;
@@ -115,14 +118,14 @@ pub trait WordClkCtlOperation<T: WordClkOperation> {
 
     fn read(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            WORD_OUT_MODE_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
+            WORD_OUT_MODE_NAME => {
                 let pos = WORD_OUT_MODES
                     .iter()
                     .position(|m| self.state().eq(m))
                     .unwrap();
-                Ok(pos as u32)
-            })
-            .map(|_| true),
+                WORD_OUT_MODE_ELEM.set_val(elem_value, pos);
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -136,15 +139,13 @@ pub trait WordClkCtlOperation<T: WordClkOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            WORD_OUT_MODE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
-                let &mode = WORD_OUT_MODES.iter().nth(val as usize).ok_or_else(|| {
-                    let msg = format!("Invalid argument for index of word clock speed: {}", val);
-                    Error::new(FileError::Inval, &msg)
-                })?;
+            WORD_OUT_MODE_NAME => {
+                let pos = WORD_OUT_MODE_ELEM.get_val(elem_value)?;
+                let mode = WORD_OUT_MODES[pos];
                 T::set_word_out(req, &mut unit.1, mode, timeout_ms)
-                    .map(|_| *self.state_mut() = mode)
-            })
-            .map(|_| true),
+                    .map(|_| *self.state_mut() = mode)?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -183,10 +184,12 @@ pub trait AesebuRateConvertCtlOperation<T: AesebuRateConvertOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            AESEBU_RATE_CONVERT_MODE_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
-                T::get_aesebu_rate_convert_mode(req, &mut unit.1, timeout_ms).map(|val| val as u32)
-            })
-            .map(|_| true),
+            AESEBU_RATE_CONVERT_MODE_NAME => {
+                let elem = EnumElem::new(1, T::AESEBU_RATE_CONVERT_MODES.len());
+                T::get_aesebu_rate_convert_mode(req, &mut unit.1, timeout_ms)
+                    .map(|val| elem.set_val(elem_value, val))?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -200,10 +203,12 @@ pub trait AesebuRateConvertCtlOperation<T: AesebuRateConvertOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            AESEBU_RATE_CONVERT_MODE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
-                T::set_aesebu_rate_convert_mode(req, &mut unit.1, val as usize, timeout_ms)
-            })
-            .map(|_| true),
+            AESEBU_RATE_CONVERT_MODE_NAME => {
+                let elem = EnumElem::new(1, T::AESEBU_RATE_CONVERT_MODES.len());
+                let val = elem.get_val(elem_value)?;
+                T::set_aesebu_rate_convert_mode(req, &mut unit.1, val, timeout_ms)?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -317,16 +322,18 @@ pub trait LevelMetersCtlOperation<T: LevelMetersOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            PEAK_HOLD_TIME_MODE_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
+            PEAK_HOLD_TIME_MODE_NAME => {
+                let elem = EnumElem::new(1, T::LEVEL_METERS_HOLD_TIME_MODES.len());
                 T::get_level_meters_peak_hold_time_mode(req, &mut unit.1, timeout_ms)
-                    .map(|val| val as u32)
-            })
-            .map(|_| true),
-            CLIP_HOLD_TIME_MODE_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
+                    .map(|val| elem.set_val(elem_value, val))?;
+                Ok(true)
+            }
+            CLIP_HOLD_TIME_MODE_NAME => {
+                let elem = EnumElem::new(1, T::LEVEL_METERS_HOLD_TIME_MODES.len());
                 T::get_level_meters_clip_hold_time_mode(req, &mut unit.1, timeout_ms)
-                    .map(|val| val as u32)
-            })
-            .map(|_| true),
+                    .map(|val| elem.set_val(elem_value, val))?;
+                Ok(true)
+            }
             _ => self.refer(elem_id, elem_value),
         }
     }
@@ -334,12 +341,14 @@ pub trait LevelMetersCtlOperation<T: LevelMetersOperation> {
     fn refer(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
         match elem_id.name().as_str() {
             AESEBU_MODE_NAME => {
-                ElemValueAccessor::<u32>::set_val(elem_value, || Ok(self.state().0 as u32))
-                    .map(|_| true)
+                let elem = EnumElem::new(1, T::LEVEL_METERS_AESEBU_MODES.len());
+                elem.set_val(elem_value, self.state().0);
+                Ok(true)
             }
             PROGRAMMABLE_MODE_NAME => {
-                ElemValueAccessor::<u32>::set_val(elem_value, || Ok(self.state().1 as u32))
-                    .map(|_| true)
+                let elem = EnumElem::new(1, T::LEVEL_METERS_PROGRAMMABLE_MODES.len());
+                elem.set_val(elem_value, self.state().1);
+                Ok(true)
             }
             _ => Ok(false),
         }
@@ -354,24 +363,32 @@ pub trait LevelMetersCtlOperation<T: LevelMetersOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            PEAK_HOLD_TIME_MODE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
-                T::set_level_meters_peak_hold_time_mode(req, &mut unit.1, val as usize, timeout_ms)
-            })
-            .map(|_| true),
-            CLIP_HOLD_TIME_MODE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
-                T::set_level_meters_clip_hold_time_mode(req, &mut unit.1, val as usize, timeout_ms)
-            })
-            .map(|_| true),
-            AESEBU_MODE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
-                T::set_level_meters_aesebu_mode(req, &mut unit.1, val as usize, timeout_ms)
-                    .map(|_| self.state_mut().0 = val as usize)
-            })
-            .map(|_| true),
-            PROGRAMMABLE_MODE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
-                T::set_level_meters_programmable_mode(req, &mut unit.1, val as usize, timeout_ms)
-                    .map(|_| self.state_mut().1 = val as usize)
-            })
-            .map(|_| true),
+            PEAK_HOLD_TIME_MODE_NAME => {
+                let elem = EnumElem::new(1, T::LEVEL_METERS_HOLD_TIME_MODES.len());
+                let val = elem.get_val(elem_value)?;
+                T::set_level_meters_peak_hold_time_mode(req, &mut unit.1, val, timeout_ms)?;
+                Ok(true)
+            }
+            CLIP_HOLD_TIME_MODE_NAME => {
+                let elem = EnumElem::new(1, T::LEVEL_METERS_HOLD_TIME_MODES.len());
+                let val = elem.get_val(elem_value)?;
+                T::set_level_meters_clip_hold_time_mode(req, &mut unit.1, val, timeout_ms)?;
+                Ok(true)
+            }
+            AESEBU_MODE_NAME => {
+                let elem = EnumElem::new(1, T::LEVEL_METERS_AESEBU_MODES.len());
+                let val = elem.get_val(elem_value)?;
+                T::set_level_meters_aesebu_mode(req, &mut unit.1, val, timeout_ms)
+                    .map(|_| self.state_mut().0 = val)?;
+                Ok(true)
+            }
+            PROGRAMMABLE_MODE_NAME => {
+                let elem = EnumElem::new(1, T::LEVEL_METERS_PROGRAMMABLE_MODES.len());
+                let val = elem.get_val(elem_value)?;
+                T::set_level_meters_programmable_mode(req, &mut unit.1, val, timeout_ms)
+                    .map(|_| self.state_mut().1 = val)?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
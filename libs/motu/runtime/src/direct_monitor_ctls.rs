@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Quick toggle of direct monitoring for mixer sources.
+//!
+//! When enabled for the source, the gain and pan of the source in the mixer assigned to main
+//! output are set to unity and center, so that the input is monitored without latency. The
+//! previous values are restored when disabled. The toggle is implemented over the elements for
+//! gain and pan of mixer source, thus the change is propagated to the device by the model as
+//! the change of value in the elements.
+
+use {
+    super::*,
+    alsactl::{prelude::*, *},
+    core::{card_cntr::CardCntr, feature_report::*},
+};
+
+const DIRECT_MONITOR_NAME: &str = "direct-monitor";
+
+const MIXER_SOURCE_GAIN_NAME: &str = "mixer-source-gain";
+const MIXER_SOURCE_PAN_NAME: &str = "mixer-source-pan";
+const MIXER_OUTPUT_DST_NAME: &str = "mixer-output-destination";
+
+// The prefix of label for main output in the element of mixer output destination.
+const MAIN_OUTPUT_LABEL_PREFIX: &str = "Main-";
+
+#[derive(Default, Debug)]
+pub struct DirectMonitorCtl {
+    // The elements for gain and pan of source, indexed by mixer.
+    gains: Vec<ElemId>,
+    pans: Vec<ElemId>,
+    dst: Option<ElemId>,
+    gain_unity: i32,
+    pan_center: i32,
+    switch: Option<ElemId>,
+    // The mixer, gain, and pan of source saved when direct monitoring is enabled.
+    saved: Vec<Option<(usize, i32, i32)>>,
+}
+
+fn find_elems(elem_id_list: &[ElemId], name: &str) -> Vec<ElemId> {
+    let mut elem_id_list: Vec<ElemId> = elem_id_list
+        .iter()
+        .filter(|elem_id| elem_id.name() == name)
+        .cloned()
+        .collect();
+    elem_id_list.sort_by_key(|elem_id| elem_id.index());
+    elem_id_list
+}
+
+fn read_int_info(card: &Card, elem_id: &ElemId) -> Result<(i32, i32, usize), Error> {
+    match card.elem_info(elem_id)? {
+        ElemInfo::Integer(info) => Ok((
+            info.value_min(),
+            info.value_max(),
+            info.value_count() as usize,
+        )),
+        _ => {
+            let msg = format!("{} is not integer element", elem_id.name());
+            Err(Error::new(FileError::Inval, &msg))
+        }
+    }
+}
+
+impl DirectMonitorCtl {
+    pub fn load(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
+        let elem_id_list = card_cntr.card.elem_id_list()?;
+
+        self.gains = find_elems(&elem_id_list, MIXER_SOURCE_GAIN_NAME);
+        self.pans = find_elems(&elem_id_list, MIXER_SOURCE_PAN_NAME);
+        self.dst = find_elems(&elem_id_list, MIXER_OUTPUT_DST_NAME)
+            .into_iter()
+            .next();
+
+        if self.gains.len() == 0 {
+            return Ok(());
+        }
+
+        // The maximum of gain is unity in both register DSP and command DSP models.
+        let (_, gain_max, source_count) = read_int_info(&card_cntr.card, &self.gains[0])?;
+        self.gain_unity = gain_max;
+
+        // The models with stereo sources have balance and width instead of pan.
+        if self.pans.len() == self.gains.len() {
+            let (pan_min, pan_max, _) = read_int_info(&card_cntr.card, &self.pans[0])?;
+            self.pan_center = (pan_min + pan_max) / 2;
+        } else {
+            self.pans.clear();
+        }
+        self.saved = vec![None; source_count];
+
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, DIRECT_MONITOR_NAME, 0);
+        let elem_id_list = card_cntr.add_bool_elems(&elem_id, 1, source_count, true)?;
+
+        // The previous values are not available after restart, thus the switch is cleared.
+        let elem_value = ElemValue::new();
+        elem_value.set_bool(&vec![false; source_count]);
+        card_cntr
+            .card
+            .write_elem_value(&elem_id_list[0], &elem_value)?;
+        self.switch = Some(elem_id_list[0].clone());
+
+        Ok(())
+    }
+
+    /// Handle the event of element. True is returned when the element is for direct monitoring.
+    pub fn handle_elem_event(
+        &mut self,
+        card_cntr: &mut CardCntr,
+        elem_id: &ElemId,
+        events: &ElemEventMask,
+    ) -> bool {
+        if self.switch.as_ref() != Some(elem_id) {
+            return false;
+        }
+
+        if events.contains(ElemEventMask::VALUE) {
+            if let Err(e) = self.apply_switch(&card_cntr.card, elem_id) {
+                report_feature(DIRECT_MONITOR_NAME, &format!("failed: {}", e));
+            }
+        }
+
+        true
+    }
+
+    // The mixer of which output is assigned to main output. The first mixer is used when not
+    // found.
+    fn main_mixer(&self, card: &Card) -> Result<usize, Error> {
+        let elem_id = match &self.dst {
+            Some(elem_id) => elem_id,
+            None => return Ok(0),
+        };
+
+        let labels = match card.elem_info(elem_id)? {
+            ElemInfo::Enumerated(info) => info.labels(),
+            _ => Vec::new(),
+        };
+
+        let mut elem_value = ElemValue::new();
+        card.read_elem_value(elem_id, &mut elem_value)?;
+
+        let mixer = elem_value
+            .enumerated()
+            .iter()
+            .take(self.gains.len())
+            .position(|&val| {
+                labels
+                    .get(val as usize)
+                    .map(|label| label.starts_with(MAIN_OUTPUT_LABEL_PREFIX))
+                    .unwrap_or_default()
+            })
+            .unwrap_or_default();
+
+        Ok(mixer)
+    }
+
+    fn apply_switch(&mut self, card: &Card, elem_id: &ElemId) -> Result<(), Error> {
+        let mut elem_value = ElemValue::new();
+        card.read_elem_value(elem_id, &mut elem_value)?;
+        let vals: Vec<bool> = elem_value.boolean()[..self.saved.len()].to_vec();
+
+        let main_mixer = self.main_mixer(card)?;
+        let gain_unity = self.gain_unity;
+        let pan_center = self.pan_center;
+
+        // The list of mixer, source, gain, and pan to be written.
+        let mut changes = Vec::new();
+        vals.iter()
+            .zip(self.saved.iter_mut())
+            .enumerate()
+            .for_each(|(src, (&enabled, saved))| {
+                if enabled && saved.is_none() {
+                    changes.push((main_mixer, src, None));
+                } else if !enabled {
+                    if let Some((mixer, gain, pan)) = saved.take() {
+                        changes.push((mixer, src, Some((gain, pan))));
+                    }
+                }
+            });
+
+        changes.iter().try_for_each(|&(mixer, src, restored)| {
+            let mut gain_value = ElemValue::new();
+            card.read_elem_value(&self.gains[mixer], &mut gain_value)?;
+            let mut gains = gain_value.int().to_vec();
+
+            let mut pan_value = ElemValue::new();
+            let mut pans = match self.pans.get(mixer) {
+                Some(elem_id) => {
+                    card.read_elem_value(elem_id, &mut pan_value)?;
+                    pan_value.int().to_vec()
+                }
+                None => vec![pan_center; gains.len()],
+            };
+
+            match restored {
+                Some((gain, pan)) => {
+                    gains[src] = gain;
+                    pans[src] = pan;
+                }
+                None => {
+                    self.saved[src] = Some((mixer, gains[src], pans[src]));
+                    gains[src] = gain_unity;
+                    pans[src] = pan_center;
+                }
+            }
+
+            // The change is dispatched to the model by the event of element.
+            gain_value.set_int(&gains);
+            card.write_elem_value(&self.gains[mixer], &gain_value)?;
+            match self.pans.get(mixer) {
+                Some(elem_id) => {
+                    pan_value.set_int(&pans);
+                    card.write_elem_value(elem_id, &pan_value)
+                }
+                None => Ok(()),
+            }
+        })
+    }
+}
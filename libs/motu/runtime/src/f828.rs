@@ -119,6 +119,8 @@ const OPT_IN_IFACE_MODE_NAME: &str = "optical-iface-in-mode";
 const OPT_OUT_IFACE_MODE_NAME: &str = "optical-iface-out-mode";
 
 impl SpecificCtl {
+    const OPT_IFACE_MODE_ELEM: EnumElem = EnumElem::new(1, F828Protocol::OPT_IFACE_MODES.len());
+
     fn load(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
         let labels: Vec<&str> = F828Protocol::OPT_IFACE_MODES
             .iter()
@@ -143,16 +145,16 @@ impl SpecificCtl {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            OPT_IN_IFACE_MODE_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
+            OPT_IN_IFACE_MODE_NAME => {
                 F828Protocol::get_optical_input_iface_mode(req, &mut unit.1, timeout_ms)
-                    .map(|val| val as u32)
-            })
-            .map(|_| true),
-            OPT_OUT_IFACE_MODE_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
+                    .map(|val| Self::OPT_IFACE_MODE_ELEM.set_val(elem_value, val))?;
+                Ok(true)
+            }
+            OPT_OUT_IFACE_MODE_NAME => {
                 F828Protocol::get_optical_output_iface_mode(req, &mut unit.1, timeout_ms)
-                    .map(|val| val as u32)
-            })
-            .map(|_| true),
+                    .map(|val| Self::OPT_IFACE_MODE_ELEM.set_val(elem_value, val))?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -166,30 +168,22 @@ impl SpecificCtl {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            OPT_IN_IFACE_MODE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
+            OPT_IN_IFACE_MODE_NAME => {
+                let val = Self::OPT_IFACE_MODE_ELEM.get_val(elem_value)?;
                 unit.0.lock()?;
-                let res = F828Protocol::set_optical_input_iface_mode(
-                    req,
-                    &mut unit.1,
-                    val as usize,
-                    timeout_ms,
-                );
+                let res =
+                    F828Protocol::set_optical_input_iface_mode(req, &mut unit.1, val, timeout_ms);
                 unit.0.unlock()?;
-                res
-            })
-            .map(|_| true),
-            OPT_OUT_IFACE_MODE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
+                res.map(|_| true)
+            }
+            OPT_OUT_IFACE_MODE_NAME => {
+                let val = Self::OPT_IFACE_MODE_ELEM.get_val(elem_value)?;
                 unit.0.lock()?;
-                let res = F828Protocol::set_optical_output_iface_mode(
-                    req,
-                    &mut unit.1,
-                    val as usize,
-                    timeout_ms,
-                );
+                let res =
+                    F828Protocol::set_optical_output_iface_mode(req, &mut unit.1, val, timeout_ms);
                 unit.0.unlock()?;
-                res
-            })
-            .map(|_| true),
+                res.map(|_| true)
+            }
             _ => Ok(false),
         }
     }
@@ -10,6 +10,7 @@ pub struct F828mk2 {
     req: FwReq,
     clk_ctls: ClkCtl,
     opt_iface_ctl: OptIfaceCtl,
+    opt_iface_ch_ctl: OptIfaceChCtl,
     phone_assign_ctl: PhoneAssignCtl,
     word_clk_ctl: WordClkCtl,
     mixer_output_ctl: MixerOutputCtl,
@@ -69,7 +70,20 @@ impl V2OptIfaceCtlOperation<F828mk2Protocol> for OptIfaceCtl {
 }
 
 #[derive(Default)]
-struct MixerOutputCtl(RegisterDspMixerOutputState, Vec<ElemId>);
+struct OptIfaceChCtl((usize, usize), Vec<ElemId>);
+
+impl V2OptIfaceChannelCtlOperation<F828mk2Protocol> for OptIfaceChCtl {
+    fn state(&self) -> &(usize, usize) {
+        &self.0
+    }
+
+    fn state_mut(&mut self) -> &mut (usize, usize) {
+        &mut self.0
+    }
+}
+
+#[derive(Default)]
+struct MixerOutputCtl(RegisterDspMixerOutputState, Vec<ElemId>, MuteRamp);
 
 impl RegisterDspMixerOutputCtlOperation<F828mk2Protocol> for MixerOutputCtl {
     fn state(&self) -> &RegisterDspMixerOutputState {
@@ -79,6 +93,14 @@ impl RegisterDspMixerOutputCtlOperation<F828mk2Protocol> for MixerOutputCtl {
     fn state_mut(&mut self) -> &mut RegisterDspMixerOutputState {
         &mut self.0
     }
+
+    fn mute_ramp(&self) -> &MuteRamp {
+        &self.2
+    }
+
+    fn mute_ramp_mut(&mut self) -> &mut MuteRamp {
+        &mut self.2
+    }
 }
 
 #[derive(Default)]
@@ -108,7 +130,7 @@ impl RegisterDspMixerMonauralSourceCtlOperation<F828mk2Protocol> for MixerSource
 }
 
 #[derive(Default)]
-struct OutputCtl(RegisterDspOutputState, Vec<ElemId>);
+struct OutputCtl(RegisterDspOutputState, Vec<ElemId>, RegisterDspOutputState);
 
 impl RegisterDspOutputCtlOperation<F828mk2Protocol> for OutputCtl {
     fn state(&self) -> &RegisterDspOutputState {
@@ -118,6 +140,14 @@ impl RegisterDspOutputCtlOperation<F828mk2Protocol> for OutputCtl {
     fn state_mut(&mut self) -> &mut RegisterDspOutputState {
         &mut self.0
     }
+
+    fn knob_state(&self) -> &RegisterDspOutputState {
+        &self.2
+    }
+
+    fn knob_state_mut(&mut self) -> &mut RegisterDspOutputState {
+        &mut self.2
+    }
 }
 
 #[derive(Default)]
@@ -156,6 +186,9 @@ impl CtlModel<(SndMotu, FwNode)> for F828mk2 {
         self.opt_iface_ctl
             .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
             .map(|mut elem_id_list| self.opt_iface_ctl.1.append(&mut elem_id_list))?;
+        self.opt_iface_ch_ctl
+            .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
+            .map(|mut elem_id_list| self.opt_iface_ch_ctl.1.append(&mut elem_id_list))?;
         self.phone_assign_ctl
             .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
             .map(|mut elem_id_list| self.phone_assign_ctl.1.append(&mut elem_id_list))?;
@@ -195,6 +228,8 @@ impl CtlModel<(SndMotu, FwNode)> for F828mk2 {
             Ok(true)
         } else if self.opt_iface_ctl.read(elem_id, elem_value)? {
             Ok(true)
+        } else if self.opt_iface_ch_ctl.read(elem_id, elem_value)? {
+            Ok(true)
         } else if self.phone_assign_ctl.read(elem_id, elem_value)? {
             Ok(true)
         } else if self.word_clk_ctl.read(elem_id, elem_value)? {
@@ -308,6 +343,7 @@ impl NotifyModel<(SndMotu, FwNode), u32> for F828mk2 {
 
 impl NotifyModel<(SndMotu, FwNode), bool> for F828mk2 {
     fn get_notified_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
+        elem_id_list.extend_from_slice(&self.opt_iface_ch_ctl.1);
         elem_id_list.extend_from_slice(&self.phone_assign_ctl.1);
         elem_id_list.extend_from_slice(&self.mixer_output_ctl.1);
         elem_id_list.extend_from_slice(&self.mixer_source_ctl.1);
@@ -321,16 +357,18 @@ impl NotifyModel<(SndMotu, FwNode), bool> for F828mk2 {
         is_locked: &bool,
     ) -> Result<(), Error> {
         if *is_locked {
-            unit.0.read_parameter(&mut self.params).map(|_| {
+            // The rate and the mode of optical interface are settled when streaming starts.
+            self.opt_iface_ch_ctl
+                .cache(unit, &mut self.req, TIMEOUT_MS)?;
+            if read_register_dsp_parameter(&unit.0, &mut self.params)? {
                 self.phone_assign_ctl.parse_dsp_parameter(&self.params);
                 self.mixer_output_ctl.parse_dsp_parameter(&self.params);
                 self.mixer_source_ctl.parse_dsp_parameter(&self.params);
                 self.output_ctl.parse_dsp_parameter(&self.params);
                 self.line_input_ctl.parse_dsp_parameter(&self.params);
-            })
-        } else {
-            Ok(())
+            }
         }
+        Ok(())
     }
 
     fn read_notified_elem(
@@ -339,7 +377,9 @@ impl NotifyModel<(SndMotu, FwNode), bool> for F828mk2 {
         elem_id: &ElemId,
         elem_value: &mut ElemValue,
     ) -> Result<bool, Error> {
-        if self.phone_assign_ctl.read(elem_id, elem_value)? {
+        if self.opt_iface_ch_ctl.read(elem_id, elem_value)? {
+            Ok(true)
+        } else if self.phone_assign_ctl.read(elem_id, elem_value)? {
             Ok(true)
         } else if self.mixer_output_ctl.read(elem_id, elem_value)? {
             Ok(true)
@@ -418,3 +458,55 @@ impl MeasureModel<(SndMotu, FwNode)> for F828mk2 {
         }
     }
 }
+
+impl WriteVerificationModel<(SndMotu, FwNode)> for F828mk2 {
+    fn verify_elem(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        elem_id: &ElemId,
+    ) -> Result<bool, Error> {
+        if self
+            .mixer_output_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else if self
+            .mixer_source_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else if self
+            .output_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else if self
+            .line_input_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl MuteRampModel<(SndMotu, FwNode)> for F828mk2 {
+    fn set_mute_ramp_duration(&mut self, duration: Duration) {
+        self.mixer_output_ctl.set_mute_ramp_duration(duration);
+    }
+
+    fn is_mute_ramp_active(&self) -> bool {
+        self.mixer_output_ctl.is_mute_ramp_active()
+    }
+
+    fn advance_mute_ramp(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<(), Error> {
+        self.mixer_output_ctl
+            .advance_mute_ramp(unit, &mut self.req, TIMEOUT_MS)
+    }
+
+    fn finish_mute_ramp(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<(), Error> {
+        self.mixer_output_ctl
+            .finish_mute_ramp(unit, &mut self.req, TIMEOUT_MS)
+    }
+}
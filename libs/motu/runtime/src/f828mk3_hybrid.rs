@@ -12,6 +12,7 @@ pub struct F828mk3Hybrid {
     clk_ctls: ClkCtl,
     port_assign_ctl: PortAssignCtl,
     opt_iface_ctl: OptIfaceCtl,
+    opt_iface_ch_ctl: OptIfaceChCtl,
     phone_assign_ctl: PhoneAssignCtl,
     word_clk_ctl: WordClkCtl,
     sequence_number: u8,
@@ -74,6 +75,19 @@ struct OptIfaceCtl;
 
 impl V3OptIfaceCtlOperation<F828mk3HybridProtocol> for OptIfaceCtl {}
 
+#[derive(Default)]
+struct OptIfaceChCtl((Vec<usize>, Vec<usize>), Vec<ElemId>);
+
+impl V3OptIfaceChannelCtlOperation<F828mk3HybridProtocol> for OptIfaceChCtl {
+    fn state(&self) -> &(Vec<usize>, Vec<usize>) {
+        &self.0
+    }
+
+    fn state_mut(&mut self) -> &mut (Vec<usize>, Vec<usize>) {
+        &mut self.0
+    }
+}
+
 #[derive(Default)]
 struct ReverbCtl(CommandDspReverbState, Vec<ElemId>);
 
@@ -101,7 +115,7 @@ impl CommandDspMonitorCtlOperation<F828mk3HybridProtocol> for MonitorCtl {
 }
 
 #[derive(Default)]
-struct MixerCtl(CommandDspMixerState, Vec<ElemId>);
+struct MixerCtl(CommandDspMixerState, Vec<ElemId>, CommandDspMixerScenes);
 
 impl CommandDspMixerCtlOperation<F828mk3HybridProtocol> for MixerCtl {
     fn state(&self) -> &CommandDspMixerState {
@@ -111,6 +125,14 @@ impl CommandDspMixerCtlOperation<F828mk3HybridProtocol> for MixerCtl {
     fn state_mut(&mut self) -> &mut CommandDspMixerState {
         &mut self.0
     }
+
+    fn scenes(&self) -> &CommandDspMixerScenes {
+        &self.2
+    }
+
+    fn scenes_mut(&mut self) -> &mut CommandDspMixerScenes {
+        &mut self.2
+    }
 }
 
 #[derive(Default)]
@@ -176,6 +198,9 @@ impl CtlModel<(SndMotu, FwNode)> for F828mk3Hybrid {
             .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
             .map(|mut elem_id_list| self.port_assign_ctl.1.append(&mut elem_id_list))?;
         self.opt_iface_ctl.load(card_cntr)?;
+        self.opt_iface_ch_ctl
+            .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
+            .map(|mut elem_id_list| self.opt_iface_ch_ctl.1.append(&mut elem_id_list))?;
         self.phone_assign_ctl
             .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
             .map(|mut elem_id_list| self.phone_assign_ctl.1.append(&mut elem_id_list))?;
@@ -236,6 +261,8 @@ impl CtlModel<(SndMotu, FwNode)> for F828mk3Hybrid {
             .read(unit, &mut self.req, elem_id, elem_value, TIMEOUT_MS)?
         {
             Ok(true)
+        } else if self.opt_iface_ch_ctl.read(elem_id, elem_value)? {
+            Ok(true)
         } else if self.phone_assign_ctl.read(elem_id, elem_value)? {
             Ok(true)
         } else if self.word_clk_ctl.read(elem_id, elem_value)? {
@@ -388,12 +415,16 @@ impl CtlModel<(SndMotu, FwNode)> for F828mk3Hybrid {
 
 impl NotifyModel<(SndMotu, FwNode), u32> for F828mk3Hybrid {
     fn get_notified_elem_list(&mut self, elem_id_list: &mut Vec<alsactl::ElemId>) {
+        elem_id_list.extend_from_slice(&self.opt_iface_ch_ctl.1);
         elem_id_list.extend_from_slice(&self.port_assign_ctl.1);
         elem_id_list.extend_from_slice(&self.phone_assign_ctl.1);
         elem_id_list.extend_from_slice(&self.word_clk_ctl.1);
     }
 
     fn parse_notification(&mut self, unit: &mut (SndMotu, FwNode), msg: &u32) -> Result<(), Error> {
+        // The unit transmits message at any change of its configuration.
+        self.opt_iface_ch_ctl
+            .cache(unit, &mut self.req, TIMEOUT_MS)?;
         if *msg & F828mk3HybridProtocol::NOTIFY_PORT_CHANGE > 0 {
             self.port_assign_ctl
                 .cache(unit, &mut self.req, TIMEOUT_MS)?;
@@ -411,7 +442,9 @@ impl NotifyModel<(SndMotu, FwNode), u32> for F828mk3Hybrid {
         elem_id: &ElemId,
         elem_value: &mut ElemValue,
     ) -> Result<bool, Error> {
-        if self.port_assign_ctl.read(elem_id, elem_value)? {
+        if self.opt_iface_ch_ctl.read(elem_id, elem_value)? {
+            Ok(true)
+        } else if self.port_assign_ctl.read(elem_id, elem_value)? {
             Ok(true)
         } else if self.phone_assign_ctl.read(elem_id, elem_value)? {
             Ok(true)
@@ -545,4 +578,9 @@ impl CommandDspModel for F828mk3Hybrid {
         )?;
         Ok(())
     }
+
+    fn advance_scene_morph(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<bool, Error> {
+        self.mixer_ctl
+            .advance_scene_morph(&mut self.sequence_number, unit, &mut self.req, TIMEOUT_MS)
+    }
 }
@@ -10,6 +10,7 @@ pub struct F896hd {
     req: FwReq,
     clk_ctls: ClkCtl,
     opt_iface_ctl: OptIfaceCtl,
+    opt_iface_ch_ctl: OptIfaceChCtl,
     word_clk_ctl: WordClkCtl,
     aesebu_rate_convert_ctl: AesebuRateConvertCtl,
     level_meters_ctl: LevelMetersCtl,
@@ -72,7 +73,20 @@ impl V2OptIfaceCtlOperation<F896hdProtocol> for OptIfaceCtl {
 }
 
 #[derive(Default)]
-struct MixerOutputCtl(RegisterDspMixerOutputState, Vec<ElemId>);
+struct OptIfaceChCtl((usize, usize), Vec<ElemId>);
+
+impl V2OptIfaceChannelCtlOperation<F896hdProtocol> for OptIfaceChCtl {
+    fn state(&self) -> &(usize, usize) {
+        &self.0
+    }
+
+    fn state_mut(&mut self) -> &mut (usize, usize) {
+        &mut self.0
+    }
+}
+
+#[derive(Default)]
+struct MixerOutputCtl(RegisterDspMixerOutputState, Vec<ElemId>, MuteRamp);
 
 impl RegisterDspMixerOutputCtlOperation<F896hdProtocol> for MixerOutputCtl {
     fn state(&self) -> &RegisterDspMixerOutputState {
@@ -82,6 +96,14 @@ impl RegisterDspMixerOutputCtlOperation<F896hdProtocol> for MixerOutputCtl {
     fn state_mut(&mut self) -> &mut RegisterDspMixerOutputState {
         &mut self.0
     }
+
+    fn mute_ramp(&self) -> &MuteRamp {
+        &self.2
+    }
+
+    fn mute_ramp_mut(&mut self) -> &mut MuteRamp {
+        &mut self.2
+    }
 }
 
 #[derive(Default)]
@@ -111,7 +133,7 @@ impl RegisterDspMixerMonauralSourceCtlOperation<F896hdProtocol> for MixerSourceC
 }
 
 #[derive(Default)]
-struct OutputCtl(RegisterDspOutputState, Vec<ElemId>);
+struct OutputCtl(RegisterDspOutputState, Vec<ElemId>, RegisterDspOutputState);
 
 impl RegisterDspOutputCtlOperation<F896hdProtocol> for OutputCtl {
     fn state(&self) -> &RegisterDspOutputState {
@@ -121,6 +143,14 @@ impl RegisterDspOutputCtlOperation<F896hdProtocol> for OutputCtl {
     fn state_mut(&mut self) -> &mut RegisterDspOutputState {
         &mut self.0
     }
+
+    fn knob_state(&self) -> &RegisterDspOutputState {
+        &self.2
+    }
+
+    fn knob_state_mut(&mut self) -> &mut RegisterDspOutputState {
+        &mut self.2
+    }
 }
 
 #[derive(Default)]
@@ -146,6 +176,9 @@ impl CtlModel<(SndMotu, FwNode)> for F896hd {
         self.opt_iface_ctl
             .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
             .map(|mut elem_id_list| self.opt_iface_ctl.1.append(&mut elem_id_list))?;
+        self.opt_iface_ch_ctl
+            .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
+            .map(|mut elem_id_list| self.opt_iface_ch_ctl.1.append(&mut elem_id_list))?;
         self.word_clk_ctl
             .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
             .map(|mut elem_id_list| self.word_clk_ctl.1.append(&mut elem_id_list))?;
@@ -183,6 +216,8 @@ impl CtlModel<(SndMotu, FwNode)> for F896hd {
             Ok(true)
         } else if self.opt_iface_ctl.read(elem_id, elem_value)? {
             Ok(true)
+        } else if self.opt_iface_ch_ctl.read(elem_id, elem_value)? {
+            Ok(true)
         } else if self.word_clk_ctl.read(elem_id, elem_value)? {
             Ok(true)
         } else if self.aesebu_rate_convert_ctl.read(
@@ -312,6 +347,7 @@ impl NotifyModel<(SndMotu, FwNode), u32> for F896hd {
 
 impl NotifyModel<(SndMotu, FwNode), bool> for F896hd {
     fn get_notified_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
+        elem_id_list.extend_from_slice(&self.opt_iface_ch_ctl.1);
         elem_id_list.extend_from_slice(&self.mixer_output_ctl.1);
         elem_id_list.extend_from_slice(&self.mixer_source_ctl.1);
         elem_id_list.extend_from_slice(&self.output_ctl.1);
@@ -323,14 +359,16 @@ impl NotifyModel<(SndMotu, FwNode), bool> for F896hd {
         is_locked: &bool,
     ) -> Result<(), Error> {
         if *is_locked {
-            unit.0.read_parameter(&mut self.params).map(|_| {
+            // The rate and the mode of optical interface are settled when streaming starts.
+            self.opt_iface_ch_ctl
+                .cache(unit, &mut self.req, TIMEOUT_MS)?;
+            if read_register_dsp_parameter(&unit.0, &mut self.params)? {
                 self.mixer_output_ctl.parse_dsp_parameter(&self.params);
                 self.mixer_source_ctl.parse_dsp_parameter(&self.params);
                 self.output_ctl.parse_dsp_parameter(&self.params);
-            })
-        } else {
-            Ok(())
+            }
         }
+        Ok(())
     }
 
     fn read_notified_elem(
@@ -339,7 +377,9 @@ impl NotifyModel<(SndMotu, FwNode), bool> for F896hd {
         elem_id: &ElemId,
         elem_value: &mut ElemValue,
     ) -> Result<bool, Error> {
-        if self.mixer_output_ctl.read(elem_id, elem_value)? {
+        if self.opt_iface_ch_ctl.read(elem_id, elem_value)? {
+            Ok(true)
+        } else if self.mixer_output_ctl.read(elem_id, elem_value)? {
             Ok(true)
         } else if self.mixer_source_ctl.read(elem_id, elem_value)? {
             Ok(true)
@@ -409,3 +449,50 @@ impl MeasureModel<(SndMotu, FwNode)> for F896hd {
         }
     }
 }
+
+impl WriteVerificationModel<(SndMotu, FwNode)> for F896hd {
+    fn verify_elem(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        elem_id: &ElemId,
+    ) -> Result<bool, Error> {
+        if self
+            .mixer_output_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else if self
+            .mixer_source_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else if self
+            .output_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl MuteRampModel<(SndMotu, FwNode)> for F896hd {
+    fn set_mute_ramp_duration(&mut self, duration: Duration) {
+        self.mixer_output_ctl.set_mute_ramp_duration(duration);
+    }
+
+    fn is_mute_ramp_active(&self) -> bool {
+        self.mixer_output_ctl.is_mute_ramp_active()
+    }
+
+    fn advance_mute_ramp(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<(), Error> {
+        self.mixer_output_ctl
+            .advance_mute_ramp(unit, &mut self.req, TIMEOUT_MS)
+    }
+
+    fn finish_mute_ramp(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<(), Error> {
+        self.mixer_output_ctl
+            .finish_mute_ramp(unit, &mut self.req, TIMEOUT_MS)
+    }
+}
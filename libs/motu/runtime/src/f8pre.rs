@@ -10,6 +10,7 @@ pub struct F8pre {
     req: FwReq,
     clk_ctls: ClkCtl,
     opt_iface_ctl: OptIfaceCtl,
+    opt_iface_ch_ctl: OptIfaceChCtl,
     phone_assign_ctl: PhoneAssignCtl,
     mixer_output_ctl: MixerOutputCtl,
     mixer_return_ctl: MixerReturnCtl,
@@ -54,7 +55,20 @@ impl V2OptIfaceCtlOperation<F8preProtocol> for OptIfaceCtl {
 }
 
 #[derive(Default)]
-struct MixerOutputCtl(RegisterDspMixerOutputState, Vec<ElemId>);
+struct OptIfaceChCtl((usize, usize), Vec<ElemId>);
+
+impl V2OptIfaceChannelCtlOperation<F8preProtocol> for OptIfaceChCtl {
+    fn state(&self) -> &(usize, usize) {
+        &self.0
+    }
+
+    fn state_mut(&mut self) -> &mut (usize, usize) {
+        &mut self.0
+    }
+}
+
+#[derive(Default)]
+struct MixerOutputCtl(RegisterDspMixerOutputState, Vec<ElemId>, MuteRamp);
 
 impl RegisterDspMixerOutputCtlOperation<F8preProtocol> for MixerOutputCtl {
     fn state(&self) -> &RegisterDspMixerOutputState {
@@ -64,6 +78,14 @@ impl RegisterDspMixerOutputCtlOperation<F8preProtocol> for MixerOutputCtl {
     fn state_mut(&mut self) -> &mut RegisterDspMixerOutputState {
         &mut self.0
     }
+
+    fn mute_ramp(&self) -> &MuteRamp {
+        &self.2
+    }
+
+    fn mute_ramp_mut(&mut self) -> &mut MuteRamp {
+        &mut self.2
+    }
 }
 
 #[derive(Default)]
@@ -93,7 +115,7 @@ impl RegisterDspMixerMonauralSourceCtlOperation<F8preProtocol> for MixerSourceCt
 }
 
 #[derive(Default)]
-struct OutputCtl(RegisterDspOutputState, Vec<ElemId>);
+struct OutputCtl(RegisterDspOutputState, Vec<ElemId>, RegisterDspOutputState);
 
 impl RegisterDspOutputCtlOperation<F8preProtocol> for OutputCtl {
     fn state(&self) -> &RegisterDspOutputState {
@@ -103,6 +125,14 @@ impl RegisterDspOutputCtlOperation<F8preProtocol> for OutputCtl {
     fn state_mut(&mut self) -> &mut RegisterDspOutputState {
         &mut self.0
     }
+
+    fn knob_state(&self) -> &RegisterDspOutputState {
+        &self.2
+    }
+
+    fn knob_state_mut(&mut self) -> &mut RegisterDspOutputState {
+        &mut self.2
+    }
 }
 
 #[derive(Default)]
@@ -128,6 +158,9 @@ impl CtlModel<(SndMotu, FwNode)> for F8pre {
         self.opt_iface_ctl
             .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
             .map(|mut elem_id_list| self.opt_iface_ctl.1.append(&mut elem_id_list))?;
+        self.opt_iface_ch_ctl
+            .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
+            .map(|mut elem_id_list| self.opt_iface_ch_ctl.1.append(&mut elem_id_list))?;
         self.phone_assign_ctl
             .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
             .map(|mut elem_id_list| self.phone_assign_ctl.1.append(&mut elem_id_list))?;
@@ -158,6 +191,8 @@ impl CtlModel<(SndMotu, FwNode)> for F8pre {
             Ok(true)
         } else if self.opt_iface_ctl.read(elem_id, elem_value)? {
             Ok(true)
+        } else if self.opt_iface_ch_ctl.read(elem_id, elem_value)? {
+            Ok(true)
         } else if self.phone_assign_ctl.read(elem_id, elem_value)? {
             Ok(true)
         } else if self.mixer_output_ctl.read(elem_id, elem_value)? {
@@ -240,6 +275,7 @@ impl NotifyModel<(SndMotu, FwNode), u32> for F8pre {
 
 impl NotifyModel<(SndMotu, FwNode), bool> for F8pre {
     fn get_notified_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
+        elem_id_list.extend_from_slice(&self.opt_iface_ch_ctl.1);
         elem_id_list.extend_from_slice(&self.mixer_output_ctl.1);
         elem_id_list.extend_from_slice(&self.mixer_source_ctl.1);
         elem_id_list.extend_from_slice(&self.output_ctl.1);
@@ -251,14 +287,16 @@ impl NotifyModel<(SndMotu, FwNode), bool> for F8pre {
         is_locked: &bool,
     ) -> Result<(), Error> {
         if *is_locked {
-            unit.0.read_parameter(&mut self.params).map(|_| {
+            // The rate and the mode of optical interface are settled when streaming starts.
+            self.opt_iface_ch_ctl
+                .cache(unit, &mut self.req, TIMEOUT_MS)?;
+            if read_register_dsp_parameter(&unit.0, &mut self.params)? {
                 self.mixer_output_ctl.parse_dsp_parameter(&self.params);
                 self.mixer_source_ctl.parse_dsp_parameter(&self.params);
                 self.output_ctl.parse_dsp_parameter(&self.params);
-            })
-        } else {
-            Ok(())
+            }
         }
+        Ok(())
     }
 
     fn read_notified_elem(
@@ -267,7 +305,9 @@ impl NotifyModel<(SndMotu, FwNode), bool> for F8pre {
         elem_id: &ElemId,
         elem_value: &mut ElemValue,
     ) -> Result<bool, Error> {
-        if self.phone_assign_ctl.read(elem_id, elem_value)? {
+        if self.opt_iface_ch_ctl.read(elem_id, elem_value)? {
+            Ok(true)
+        } else if self.phone_assign_ctl.read(elem_id, elem_value)? {
             Ok(true)
         } else if self.mixer_output_ctl.read(elem_id, elem_value)? {
             Ok(true)
@@ -341,3 +381,50 @@ impl MeasureModel<(SndMotu, FwNode)> for F8pre {
         }
     }
 }
+
+impl WriteVerificationModel<(SndMotu, FwNode)> for F8pre {
+    fn verify_elem(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        elem_id: &ElemId,
+    ) -> Result<bool, Error> {
+        if self
+            .mixer_output_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else if self
+            .mixer_source_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else if self
+            .output_ctl
+            .verify(unit, &mut self.req, elem_id, TIMEOUT_MS)?
+        {
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+}
+
+impl MuteRampModel<(SndMotu, FwNode)> for F8pre {
+    fn set_mute_ramp_duration(&mut self, duration: Duration) {
+        self.mixer_output_ctl.set_mute_ramp_duration(duration);
+    }
+
+    fn is_mute_ramp_active(&self) -> bool {
+        self.mixer_output_ctl.is_mute_ramp_active()
+    }
+
+    fn advance_mute_ramp(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<(), Error> {
+        self.mixer_output_ctl
+            .advance_mute_ramp(unit, &mut self.req, TIMEOUT_MS)
+    }
+
+    fn finish_mute_ramp(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<(), Error> {
+        self.mixer_output_ctl
+            .finish_mute_ramp(unit, &mut self.req, TIMEOUT_MS)
+    }
+}
@@ -24,6 +24,8 @@ mod ultralite_mk3_hybrid;
 
 mod command_dsp_ctls;
 mod common_ctls;
+mod direct_monitor_ctls;
+mod mute_group_ctls;
 mod register_dsp_ctls;
 mod v1_ctls;
 mod v2_ctls;
@@ -31,7 +33,7 @@ mod v3_ctls;
 
 use {
     self::{command_dsp_runtime::*, register_dsp_runtime::*, v1_runtime::*},
-    core::RuntimeOperation,
+    core::{diagnostics::*, RuntimeOperation},
     glib::{Error, FileError},
     hinawa::{prelude::{FwNodeExt, FwNodeExtManual}, FwNode},
     hitaki::{prelude::*, *},
@@ -61,11 +63,11 @@ pub enum MotuRuntime {
 
 impl RuntimeOperation<u32> for MotuRuntime {
     fn new(card_id: u32) -> Result<Self, Error> {
-        let cdev = format!("/dev/snd/hwC{}D0", card_id);
+        let cdev = Self::devnode_path(&format!("hwC{}D0", card_id));
         let unit = SndMotu::new();
         unit.open(&cdev, 0)?;
 
-        let cdev = format!("/dev/{}", unit.node_device().unwrap());
+        let cdev = Self::devnode_path(&unit.node_device().unwrap());
         let node = FwNode::new();
         node.open(&cdev)?;
 
@@ -173,6 +175,81 @@ impl RuntimeOperation<u32> for MotuRuntime {
     }
 }
 
+// The number of mixers in the image of parameters for register DSP.
+const REGISTER_DSP_MIXER_COUNT: usize = 4;
+
+fn format_register_dsp_parameter(params: &SndMotuRegisterDspParameter) -> String {
+    let mut dump = String::new();
+
+    (0..REGISTER_DSP_MIXER_COUNT).for_each(|i| {
+        dump.push_str(&format!(
+            "mixer-source-gain[{}]: {:?}\n",
+            i,
+            params.mixer_source_gain(i)
+        ));
+        dump.push_str(&format!(
+            "mixer-source-pan[{}]: {:?}\n",
+            i,
+            params.mixer_source_pan(i)
+        ));
+    });
+    dump.push_str(&format!(
+        "mixer-output-paired-volume: {:?}\n",
+        params.mixer_output_paired_volume()
+    ));
+    dump.push_str(&format!(
+        "mixer-output-paired-flag: {:?}\n",
+        params.mixer_output_paired_flag()
+    ));
+    dump.push_str(&format!(
+        "main-output-paired-volume: {}\n",
+        params.main_output_paired_volume()
+    ));
+    dump.push_str(&format!(
+        "headphone-output-paired-volume: {}\n",
+        params.headphone_output_paired_volume()
+    ));
+    dump.push_str(&format!(
+        "headphone-output-paired-assignment: {}\n",
+        params.headphone_output_paired_assignment()
+    ));
+    dump.push_str(&format!(
+        "line-input-boost-flag: 0x{:02x}\n",
+        params.line_input_boost_flag()
+    ));
+    dump.push_str(&format!(
+        "line-input-nominal-level-flag: 0x{:02x}\n",
+        params.line_input_nominal_level_flag()
+    ));
+    dump.push_str(&format!(
+        "input-gain-and-invert: {:?}\n",
+        params.input_gain_and_invert()
+    ));
+    dump.push_str(&format!("input-flag: {:?}\n", params.input_flag()));
+
+    dump
+}
+
+impl DiagnosticsOperation<u32> for MotuRuntime {
+    fn collect_diagnostics(card_id: &u32) -> Result<Vec<DiagnosticsEntry>, Error> {
+        let mut entries = collect_common_diagnostics(*card_id)?;
+
+        let unit = SndMotu::new();
+        unit.open(&Self::devnode_path(&format!("hwC{}D0", card_id)), 0)?;
+
+        // The image of parameters is available just for the models with register DSP.
+        let mut params = SndMotuRegisterDspParameter::default();
+        if unit.read_parameter(&mut params).is_ok() {
+            entries.push(DiagnosticsEntry::new(
+                "register-dsp-parameter.txt",
+                format_register_dsp_parameter(&params).into_bytes(),
+            ));
+        }
+
+        Ok(entries)
+    }
+}
+
 pub fn clk_rate_to_str(rate: &ClkRate) -> &'static str {
     match rate {
         ClkRate::R44100 => "44100",
@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+//! Groups of mute for mixer sources and mixer outputs.
+//!
+//! Each channel of mixer source and mixer output can be assigned to one of groups, then the
+//! switch of group mutes or unmutes the channels in the group at once. The groups are implemented
+//! over the elements for mute of channel, thus the change is propagated to the device by the
+//! model as the change of value in the elements. The assignment and the switch are kept in
+//! elements, thus they are preserved across restart of runtime, and by 'alsactl store/restore'.
+
+use {
+    super::*,
+    alsactl::{prelude::*, *},
+    core::{card_cntr::CardCntr, feature_report::*},
+};
+
+const MUTE_GROUP_COUNT: usize = 4;
+
+const MUTE_GROUP_SWITCH_NAME: &str = "mute-group-switch";
+
+const MUTE_GROUP_LABELS: [&str; MUTE_GROUP_COUNT + 1] =
+    ["none", "group-1", "group-2", "group-3", "group-4"];
+
+// The names of elements for mute of channel. The name for mixer source in models with command
+// DSP includes typo.
+const MUTE_TARGET_NAMES: &[&str] = &[
+    "mixer-source-mute",
+    "mixer-soruce-mute",
+    "mixer-output-mute",
+];
+
+#[derive(Default, Debug)]
+pub struct MuteGroupCtl {
+    // The pair of element for mute of channel and element for assignment to group.
+    targets: Vec<(ElemId, ElemId)>,
+    switch: Option<ElemId>,
+    states: [bool; MUTE_GROUP_COUNT],
+}
+
+fn read_value_count(card: &Card, elem_id: &ElemId) -> Result<usize, Error> {
+    match card.elem_info(elem_id)? {
+        ElemInfo::Boolean(info) => Ok(info.value_count() as usize),
+        _ => {
+            let msg = format!("{} is not boolean element", elem_id.name());
+            Err(Error::new(FileError::Inval, &msg))
+        }
+    }
+}
+
+impl MuteGroupCtl {
+    pub fn load(&mut self, card_cntr: &mut CardCntr) -> Result<(), Error> {
+        let elem_id_list = card_cntr.card.elem_id_list()?;
+
+        MUTE_TARGET_NAMES
+            .iter()
+            .try_for_each(|&name| -> Result<(), Error> {
+                let mut targets: Vec<ElemId> = elem_id_list
+                    .iter()
+                    .filter(|elem_id| elem_id.name() == name)
+                    .cloned()
+                    .collect();
+                if targets.len() == 0 {
+                    return Ok(());
+                }
+                targets.sort_by_key(|elem_id| elem_id.index());
+
+                let value_count = read_value_count(&card_cntr.card, &targets[0])?;
+                let name = format!("{}-group", name);
+                let elem_id = ElemId::new_by_name(targets[0].iface(), 0, 0, &name, 0);
+                let assigns = card_cntr.add_enum_elems(
+                    &elem_id,
+                    targets.len(),
+                    value_count,
+                    &MUTE_GROUP_LABELS,
+                    None,
+                    true,
+                )?;
+                self.targets.extend(targets.into_iter().zip(assigns));
+
+                Ok(())
+            })?;
+
+        if self.targets.len() > 0 {
+            let elem_id =
+                ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, MUTE_GROUP_SWITCH_NAME, 0);
+            let elem_id_list = card_cntr.add_bool_elems(&elem_id, 1, MUTE_GROUP_COUNT, true)?;
+
+            // The element can be reused, thus the states of groups are restored from it.
+            let mut elem_value = ElemValue::new();
+            card_cntr
+                .card
+                .read_elem_value(&elem_id_list[0], &mut elem_value)?;
+            self.states
+                .copy_from_slice(&elem_value.boolean()[..MUTE_GROUP_COUNT]);
+            self.switch = Some(elem_id_list[0].clone());
+        }
+
+        Ok(())
+    }
+
+    /// Handle the event of element. True is returned when the element is for mute group.
+    pub fn handle_elem_event(
+        &mut self,
+        card_cntr: &mut CardCntr,
+        elem_id: &ElemId,
+        events: &ElemEventMask,
+    ) -> bool {
+        if self.switch.as_ref() == Some(elem_id) {
+            if events.contains(ElemEventMask::VALUE) {
+                if let Err(e) = self.apply_switch(&card_cntr.card, elem_id) {
+                    report_feature(MUTE_GROUP_SWITCH_NAME, &format!("failed: {}", e));
+                }
+            }
+            true
+        } else {
+            self.targets.iter().any(|(_, assign)| assign == elem_id)
+        }
+    }
+
+    fn apply_switch(&mut self, card: &Card, elem_id: &ElemId) -> Result<(), Error> {
+        let mut elem_value = ElemValue::new();
+        card.read_elem_value(elem_id, &mut elem_value)?;
+        let vals = &elem_value.boolean()[..MUTE_GROUP_COUNT];
+
+        // The pair of label index for the group and its new state.
+        let changes: Vec<(u32, bool)> = vals
+            .iter()
+            .zip(&self.states)
+            .enumerate()
+            .filter(|(_, (new, old))| new != old)
+            .map(|(i, (&new, _))| (1 + i as u32, new))
+            .collect();
+        self.states.copy_from_slice(vals);
+
+        self.targets.iter().try_for_each(|(target, assign)| {
+            let mut assign_value = ElemValue::new();
+            card.read_elem_value(assign, &mut assign_value)?;
+
+            let mut target_value = ElemValue::new();
+            card.read_elem_value(target, &mut target_value)?;
+            let mut mutes = target_value.boolean().to_vec();
+
+            let mut changed = false;
+            assign_value
+                .enumerated()
+                .iter()
+                .zip(mutes.iter_mut())
+                .for_each(|(group, mute)| {
+                    if let Some(&(_, state)) = changes.iter().find(|(g, _)| g == group) {
+                        if *mute != state {
+                            *mute = state;
+                            changed = true;
+                        }
+                    }
+                });
+
+            if changed {
+                // The change is dispatched to the model by the event of element.
+                target_value.set_bool(&mutes);
+                card.write_elem_value(target, &target_value)
+            } else {
+                Ok(())
+            }
+        })
+    }
+}
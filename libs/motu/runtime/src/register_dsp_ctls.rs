@@ -1,7 +1,56 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 // Copyright (c) 2021 Takashi Sakamoto
 
-use super::register_dsp_runtime::*;
+use {
+    super::register_dsp_runtime::*,
+    std::time::{Duration, Instant},
+};
+
+/// The maximum duration to ramp volume of mixer output, in milliseconds.
+pub const MUTE_RAMP_DURATION_MAX: u32 = 100;
+
+/// The state of ramp for volume of mixer output around mute operation.
+#[derive(Default, Debug)]
+pub struct MuteRamp {
+    ramp: LevelRamp,
+    // The flags of mute and the volume applied at the end of ramp.
+    pending: Option<(Vec<bool>, Vec<u8>)>,
+}
+
+/// Read the image of parameters in register DSP. Ok(false) is returned when the driver does not
+/// support the request, then the parameters are kept as is.
+pub fn read_register_dsp_parameter(
+    unit: &SndMotu,
+    params: &mut SndMotuRegisterDspParameter,
+) -> Result<bool, Error> {
+    probe_driver_feature(DriverFeature::MotuRegisterDspParameter, || {
+        unit.read_parameter(params)
+    })
+}
+
+/// Read back the state from registers after write operation, since some models ignore the write
+/// operation silently at some sampling rates. The cache is updated by the read value so that
+/// elements reflect the actual state of hardware. Some parameters are not available in registers,
+/// thus they are kept as is.
+pub fn verify_state<S, F>(cache: &mut S, read: F) -> Result<(), Error>
+where
+    S: Clone + PartialEq + std::fmt::Debug,
+    F: FnOnce(&mut S) -> Result<(), Error>,
+{
+    let mut state = cache.clone();
+    read(&mut state)?;
+
+    if *cache != state {
+        let msg = format!(
+            "The written parameters are not applied: expected {:?}, actual {:?}",
+            cache, state
+        );
+        *cache = state;
+        Err(Error::new(RegisterDspError::WriteIgnored, &msg))
+    } else {
+        Ok(())
+    }
+}
 
 pub trait RegisterDspPhoneAssignCtlOperation<T: AssignOperation>:
     PhoneAssignCtlOperation<T>
@@ -26,6 +75,8 @@ fn copy_int_to_elem_value<T: Copy + Into<i32>>(elem_value: &mut ElemValue, data:
 pub trait RegisterDspMixerOutputCtlOperation<T: RegisterDspMixerOutputOperation> {
     fn state(&self) -> &RegisterDspMixerOutputState;
     fn state_mut(&mut self) -> &mut RegisterDspMixerOutputState;
+    fn mute_ramp(&self) -> &MuteRamp;
+    fn mute_ramp_mut(&mut self) -> &mut MuteRamp;
 
     const VOL_TLV: DbInterval = DbInterval {
         min: 0,
@@ -89,12 +140,13 @@ pub trait RegisterDspMixerOutputCtlOperation<T: RegisterDspMixerOutputOperation>
                 Ok(true)
             }
             MIXER_OUTPUT_DST_NAME => {
-                ElemValueAccessor::<u32>::set_vals(elem_value, T::MIXER_COUNT, |idx| {
+                let elem = EnumElem::new(T::MIXER_COUNT, T::OUTPUT_DESTINATIONS.len());
+                elem.set_vals(elem_value, |idx| {
                     let val = T::OUTPUT_DESTINATIONS
                         .iter()
                         .position(|p| self.state().destination[idx].eq(p))
                         .unwrap();
-                    Ok(val as u32)
+                    Ok(val)
                 })
                 .map(|_| true)
             }
@@ -112,6 +164,8 @@ pub trait RegisterDspMixerOutputCtlOperation<T: RegisterDspMixerOutputOperation>
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
             MIXER_OUTPUT_VOLUME_NAME => {
+                // The volume given by user has priority to the ramp.
+                self.finish_mute_ramp(unit, req, timeout_ms)?;
                 let vals = &elem_value.int()[..T::MIXER_COUNT];
                 let vols: Vec<u8> = vals.iter().map(|&vol| vol as u8).collect();
                 T::write_mixer_output_volume(req, &mut unit.1, &vols, self.state_mut(), timeout_ms)
@@ -119,8 +173,7 @@ pub trait RegisterDspMixerOutputCtlOperation<T: RegisterDspMixerOutputOperation>
             }
             MIXER_OUTPUT_MUTE_NAME => {
                 let mute = &elem_value.boolean()[..T::MIXER_COUNT];
-                T::write_mixer_output_mute(req, &mut unit.1, &mute, self.state_mut(), timeout_ms)
-                    .map(|_| true)
+                self.write_mute(unit, req, mute, timeout_ms).map(|_| true)
             }
             MIXER_OUTPUT_DST_NAME => {
                 let vals = &elem_value.enumerated()[..T::MIXER_COUNT];
@@ -148,6 +201,143 @@ pub trait RegisterDspMixerOutputCtlOperation<T: RegisterDspMixerOutputOperation>
         }
     }
 
+    // The flag of mute takes effect instantly. To avoid pops, the volume of output is ramped down
+    // before muting, and ramped up after unmuting, at the tick of timer in runtime. The volume is
+    // restored at the end of ramp.
+    fn write_mute(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        mute: &[bool],
+        timeout_ms: u32,
+    ) -> Result<(), Error> {
+        self.finish_mute_ramp(unit, req, timeout_ms)?;
+
+        let curr = self.state().mute;
+        let volume = self.state().volume;
+        let now = Instant::now();
+
+        let ramp = &mut self.mute_ramp_mut().ramp;
+        let mut ramped = false;
+        mute.iter()
+            .zip(&curr)
+            .zip(&volume)
+            .enumerate()
+            .filter(|(_, ((&new, &old), _))| new != old)
+            .for_each(|(ch, ((&new, _), &vol))| {
+                let (from, to) = if new { (vol, 0) } else { (0, vol) };
+                ramped |= ramp.start(ch, from as i32, to as i32, now);
+            });
+
+        if !ramped {
+            return T::write_mixer_output_mute(
+                req,
+                &mut unit.1,
+                mute,
+                self.state_mut(),
+                timeout_ms,
+            );
+        }
+
+        self.mute_ramp_mut().pending = Some((mute.to_vec(), volume.to_vec()));
+
+        // The outputs to be unmuted are still muted, thus their volume is changed silently. The
+        // outputs to be muted are kept unmuted till the end of ramp.
+        let vols: Vec<u8> = volume
+            .iter()
+            .zip(mute.iter().zip(&curr))
+            .map(|(&vol, (&new, &old))| if old && !new { 0 } else { vol })
+            .collect();
+        let flags: Vec<bool> = mute
+            .iter()
+            .zip(&curr)
+            .map(|(&new, &old)| new && old)
+            .collect();
+
+        T::write_mixer_output_volume(req, &mut unit.1, &vols, self.state_mut(), timeout_ms)
+            .and_then(|_| {
+                T::write_mixer_output_mute(req, &mut unit.1, &flags, self.state_mut(), timeout_ms)
+            })
+            .map_err(|err| {
+                let _ = self.finish_mute_ramp(unit, req, timeout_ms);
+                err
+            })
+    }
+
+    fn set_mute_ramp_duration(&mut self, duration: Duration) {
+        let max = Duration::from_millis(MUTE_RAMP_DURATION_MAX as u64);
+        self.mute_ramp_mut()
+            .ramp
+            .set_duration(std::cmp::min(duration, max));
+    }
+
+    fn is_mute_ramp_active(&self) -> bool {
+        self.mute_ramp().ramp.is_active()
+    }
+
+    // Write the intermediate volume of ramp, then apply the flags of mute at the end.
+    fn advance_mute_ramp(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        timeout_ms: u32,
+    ) -> Result<(), Error> {
+        let step = T::MIXER_OUTPUT_VOLUME_STEP as i32;
+        let mut vols = self.state().volume;
+        self.mute_ramp_mut()
+            .ramp
+            .advance(Instant::now(), step)
+            .iter()
+            .for_each(|&(ch, vol)| vols[ch] = vol as u8);
+
+        let res =
+            T::write_mixer_output_volume(req, &mut unit.1, &vols, self.state_mut(), timeout_ms);
+        if res.is_err() || !self.is_mute_ramp_active() {
+            self.finish_mute_ramp(unit, req, timeout_ms)?;
+        }
+        res
+    }
+
+    fn finish_mute_ramp(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        timeout_ms: u32,
+    ) -> Result<(), Error> {
+        let _ = self.mute_ramp_mut().ramp.finish();
+        match self.mute_ramp_mut().pending.take() {
+            Some((mute, volume)) => {
+                T::write_mixer_output_mute(req, &mut unit.1, &mute, self.state_mut(), timeout_ms)?;
+                T::write_mixer_output_volume(
+                    req,
+                    &mut unit.1,
+                    &volume,
+                    self.state_mut(),
+                    timeout_ms,
+                )
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn verify(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        elem_id: &ElemId,
+        timeout_ms: u32,
+    ) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            MIXER_OUTPUT_VOLUME_NAME | MIXER_OUTPUT_MUTE_NAME | MIXER_OUTPUT_DST_NAME => {
+                verify_state(self.state_mut(), |state| {
+                    T::read_mixer_output_state(req, &mut unit.1, state, timeout_ms)
+                })
+                .map(|_| true)
+            }
+            _ => Ok(false),
+        }
+    }
+
     fn parse_dsp_parameter(&mut self, params: &SndMotuRegisterDspParameter) {
         T::parse_dsp_parameter(self.state_mut(), params)
     }
@@ -367,6 +557,25 @@ pub trait RegisterDspMixerMonauralSourceCtlOperation<T: RegisterDspMixerMonaural
         }
     }
 
+    fn verify(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        elem_id: &ElemId,
+        timeout_ms: u32,
+    ) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            MIXER_SOURCE_GAIN_NAME
+            | MIXER_SOURCE_PAN_NAME
+            | MIXER_SOURCE_MUTE_NAME
+            | MIXER_SOURCE_SOLO_NAME => verify_state(self.state_mut(), |state| {
+                T::read_mixer_monaural_source_state(req, &mut unit.1, state, timeout_ms)
+            })
+            .map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
     fn parse_dsp_parameter(&mut self, params: &SndMotuRegisterDspParameter) {
         T::parse_dsp_parameter(self.state_mut(), params)
     }
@@ -617,6 +826,27 @@ pub trait RegisterDspMixerStereoSourceCtlOperation<T: RegisterDspMixerStereoSour
         }
     }
 
+    fn verify(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        elem_id: &ElemId,
+        timeout_ms: u32,
+    ) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            MIXER_SOURCE_GAIN_NAME
+            | MIXER_SOURCE_PAN_NAME
+            | MIXER_SOURCE_MUTE_NAME
+            | MIXER_SOURCE_SOLO_NAME
+            | MIXER_SOURCE_STEREO_BALANCE_NAME
+            | MIXER_SOURCE_STEREO_WIDTH_NAME => verify_state(self.state_mut(), |state| {
+                T::read_mixer_stereo_source_state(req, &mut unit.1, state, timeout_ms)
+            })
+            .map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
     fn parse_dsp_parameter(&mut self, params: &SndMotuRegisterDspParameter) {
         T::parse_dsp_parameter(self.state_mut(), params)
     }
@@ -628,10 +858,16 @@ pub trait RegisterDspMixerStereoSourceCtlOperation<T: RegisterDspMixerStereoSour
 
 const MASTER_OUTPUT_VOLUME_NAME: &str = "master-output-volume";
 const PHONE_VOLUME_NAME: &str = "headphone-volume";
+// The volumes adjusted by the knobs on front panel. They are updated by the events emitted by
+// the operation of knob, but not by the write operation from software.
+const MASTER_OUTPUT_KNOB_NAME: &str = "master-output-knob";
+const PHONE_KNOB_NAME: &str = "headphone-knob";
 
 pub trait RegisterDspOutputCtlOperation<T: RegisterDspOutputOperation> {
     fn state(&self) -> &RegisterDspOutputState;
     fn state_mut(&mut self) -> &mut RegisterDspOutputState;
+    fn knob_state(&self) -> &RegisterDspOutputState;
+    fn knob_state_mut(&mut self) -> &mut RegisterDspOutputState;
 
     const VOL_TLV: DbInterval = DbInterval {
         min: -6400,
@@ -648,6 +884,7 @@ pub trait RegisterDspOutputCtlOperation<T: RegisterDspOutputOperation> {
         timeout_ms: u32,
     ) -> Result<Vec<ElemId>, Error> {
         T::read_output_state(req, &mut unit.1, self.state_mut(), timeout_ms)?;
+        *self.knob_state_mut() = self.state().clone();
 
         let mut notified_elem_id_list = Vec::new();
 
@@ -679,6 +916,25 @@ pub trait RegisterDspOutputCtlOperation<T: RegisterDspOutputOperation> {
             )
             .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))?;
 
+        // The elements are not unlocked so that the other processes can not change them.
+        [MASTER_OUTPUT_KNOB_NAME, PHONE_KNOB_NAME]
+            .iter()
+            .try_for_each(|name| {
+                let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, name, 0);
+                card_cntr
+                    .add_int_elems(
+                        &elem_id,
+                        1,
+                        T::VOLUME_MIN as i32,
+                        T::VOLUME_MAX as i32,
+                        T::VOLUME_STEP as i32,
+                        1,
+                        Some(&Vec::<u32>::from(&Self::VOL_TLV)),
+                        false,
+                    )
+                    .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))
+            })?;
+
         Ok(notified_elem_id_list)
     }
 
@@ -692,6 +948,14 @@ pub trait RegisterDspOutputCtlOperation<T: RegisterDspOutputOperation> {
                 elem_value.set_int(&[self.state().phone_volume as i32]);
                 Ok(true)
             }
+            MASTER_OUTPUT_KNOB_NAME => {
+                elem_value.set_int(&[self.knob_state().master_volume as i32]);
+                Ok(true)
+            }
+            PHONE_KNOB_NAME => {
+                elem_value.set_int(&[self.knob_state().phone_volume as i32]);
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -731,11 +995,31 @@ pub trait RegisterDspOutputCtlOperation<T: RegisterDspOutputOperation> {
         }
     }
 
+    fn verify(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        elem_id: &ElemId,
+        timeout_ms: u32,
+    ) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            MASTER_OUTPUT_VOLUME_NAME | PHONE_VOLUME_NAME => {
+                verify_state(self.state_mut(), |state| {
+                    T::read_output_state(req, &mut unit.1, state, timeout_ms)
+                })
+                .map(|_| true)
+            }
+            _ => Ok(false),
+        }
+    }
+
     fn parse_dsp_parameter(&mut self, params: &SndMotuRegisterDspParameter) {
-        T::parse_dsp_parameter(self.state_mut(), params)
+        T::parse_dsp_parameter(self.state_mut(), params);
+        T::parse_dsp_parameter(self.knob_state_mut(), params);
     }
 
     fn parse_dsp_event(&mut self, event: &RegisterDspEvent) -> bool {
+        T::parse_dsp_event(self.knob_state_mut(), event);
         T::parse_dsp_event(self.state_mut(), event)
     }
 }
@@ -785,12 +1069,13 @@ pub trait RegisterDspLineInputCtlOperation<T: Traveler828mk2LineInputOperation>
     fn read(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
         match elem_id.name().as_str() {
             INPUT_NOMINAL_LEVEL_NAME => {
-                ElemValueAccessor::<u32>::set_vals(elem_value, T::LINE_INPUT_COUNT, |idx| {
+                let elem = EnumElem::new(T::LINE_INPUT_COUNT, Self::NOMINAL_LEVELS.len());
+                elem.set_vals(elem_value, |idx| {
                     let pos = Self::NOMINAL_LEVELS
                         .iter()
                         .position(|l| self.state().level[idx].eq(l))
                         .unwrap();
-                    Ok(pos as u32)
+                    Ok(pos)
                 })
                 .map(|_| true)
             }
@@ -836,6 +1121,24 @@ pub trait RegisterDspLineInputCtlOperation<T: Traveler828mk2LineInputOperation>
         }
     }
 
+    fn verify(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        elem_id: &ElemId,
+        timeout_ms: u32,
+    ) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            INPUT_NOMINAL_LEVEL_NAME | INPUT_BOOST_NAME => {
+                verify_state(self.state_mut(), |state| {
+                    T::read_line_input_state(req, &mut unit.1, state, timeout_ms)
+                })
+                .map(|_| true)
+            }
+            _ => Ok(false),
+        }
+    }
+
     fn parse_dsp_parameter(&mut self, params: &SndMotuRegisterDspParameter) {
         T::parse_dsp_parameter(self.state_mut(), params)
     }
@@ -846,7 +1149,7 @@ pub trait RegisterDspLineInputCtlOperation<T: Traveler828mk2LineInputOperation>
 }
 
 const INPUT_GAIN_NAME: &str = "input-gain";
-const INPUT_INVERT_NAME: &str = "input-invert";
+const INPUT_POLARITY_NAME: &str = "input-polarity";
 const MIC_PHANTOM_NAME: &str = "mic-phantom";
 const MIC_PAD_NAME: &str = "mic-pad";
 const INPUT_JACK_NAME: &str = "input-jack";
@@ -890,7 +1193,7 @@ pub trait RegisterDspMonauralInputCtlOperation<T: RegisterDspMonauralInputOperat
             )
             .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))?;
 
-        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, INPUT_INVERT_NAME, 0);
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, INPUT_POLARITY_NAME, 0);
         card_cntr
             .add_bool_elems(&elem_id, 1, T::INPUT_COUNT, true)
             .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))?;
@@ -904,7 +1207,7 @@ pub trait RegisterDspMonauralInputCtlOperation<T: RegisterDspMonauralInputOperat
                 copy_int_to_elem_value(elem_value, &self.state().gain);
                 Ok(true)
             }
-            INPUT_INVERT_NAME => {
+            INPUT_POLARITY_NAME => {
                 elem_value.set_bool(&self.state().invert);
                 Ok(true)
             }
@@ -927,7 +1230,7 @@ pub trait RegisterDspMonauralInputCtlOperation<T: RegisterDspMonauralInputOperat
                 T::write_monaural_input_gain(req, &mut unit.1, &gain, self.state_mut(), timeout_ms)
                     .map(|_| true)
             }
-            INPUT_INVERT_NAME => {
+            INPUT_POLARITY_NAME => {
                 let invert = &elem_value.boolean()[..T::INPUT_COUNT];
                 T::write_monaural_input_invert(
                     req,
@@ -942,6 +1245,22 @@ pub trait RegisterDspMonauralInputCtlOperation<T: RegisterDspMonauralInputOperat
         }
     }
 
+    fn verify(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        elem_id: &ElemId,
+        timeout_ms: u32,
+    ) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            INPUT_GAIN_NAME | INPUT_POLARITY_NAME => verify_state(self.state_mut(), |state| {
+                T::read_monaural_input_state(req, &mut unit.1, state, timeout_ms)
+            })
+            .map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
     fn parse_dsp_parameter(&mut self, params: &SndMotuRegisterDspParameter) {
         T::parse_dsp_parameter(self.state_mut(), params)
     }
@@ -989,7 +1308,7 @@ pub trait RegisterDspStereoInputCtlOperation<T: RegisterDspStereoInputOperation>
             )
             .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))?;
 
-        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, INPUT_INVERT_NAME, 0);
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, INPUT_POLARITY_NAME, 0);
         card_cntr
             .add_bool_elems(&elem_id, 1, T::INPUT_COUNT, true)
             .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))?;
@@ -1023,7 +1342,7 @@ pub trait RegisterDspStereoInputCtlOperation<T: RegisterDspStereoInputOperation>
                 copy_int_to_elem_value(elem_value, &self.state().gain);
                 Ok(true)
             }
-            INPUT_INVERT_NAME => {
+            INPUT_POLARITY_NAME => {
                 elem_value.set_bool(&self.state().invert);
                 Ok(true)
             }
@@ -1062,7 +1381,7 @@ pub trait RegisterDspStereoInputCtlOperation<T: RegisterDspStereoInputOperation>
                 T::write_stereo_input_gain(req, &mut unit.1, &gain, self.state_mut(), timeout_ms)
                     .map(|_| true)
             }
-            INPUT_INVERT_NAME => {
+            INPUT_POLARITY_NAME => {
                 let invert = &elem_value.boolean()[..T::INPUT_COUNT];
                 T::write_stereo_input_invert(
                     req,
@@ -1097,6 +1416,23 @@ pub trait RegisterDspStereoInputCtlOperation<T: RegisterDspStereoInputOperation>
         }
     }
 
+    fn verify(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        elem_id: &ElemId,
+        timeout_ms: u32,
+    ) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            INPUT_GAIN_NAME | INPUT_POLARITY_NAME | MIC_PHANTOM_NAME | MIC_PAD_NAME
+            | INPUT_PAIRED_NAME => verify_state(self.state_mut(), |state| {
+                T::read_stereo_input_state(req, &mut unit.1, state, timeout_ms)
+            })
+            .map(|_| true),
+            _ => Ok(false),
+        }
+    }
+
     fn parse_dsp_parameter(&mut self, params: &SndMotuRegisterDspParameter) {
         T::parse_dsp_parameter(self.state_mut(), params)
     }
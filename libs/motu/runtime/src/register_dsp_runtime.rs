@@ -3,17 +3,22 @@
 
 pub use {
     super::{
-        audioexpress::*, common_ctls::*, f828mk2::*, f896hd::*, f8pre::*, h4pre::*,
-        register_dsp_ctls::*, traveler::*, ultralite::*, v2_ctls::*, v3_ctls::*, *,
+        audioexpress::*, common_ctls::*, direct_monitor_ctls::*, f828mk2::*, f896hd::*, f8pre::*,
+        h4pre::*, mute_group_ctls::*, register_dsp_ctls::*, traveler::*, ultralite::*, v2_ctls::*,
+        v3_ctls::*, *,
     },
     alsa_ctl_tlv_codec::DbInterval,
     alsactl::{prelude::*, *},
-    core::{card_cntr::*, dispatcher::*, elem_value_accessor::*},
+    core::{
+        card_cntr::*, dispatcher::*, driver_caps::*, elem_alias::*, elem_locale::*,
+        feature_report::*, level_ramp::*, meter_trigger::*, midi_map::*, mono_fold::*,
+        panic_ctl::*, solo_bus::*, stream_guard::*, typed_elem::*,
+    },
     glib::source,
     hinawa::FwReq,
     nix::sys::signal::Signal,
     protocols::{register_dsp::*, version_2::*, version_3::*},
-    std::sync::mpsc,
+    std::{sync::mpsc, time::Duration},
 };
 
 pub type F828mk2Runtime = RegisterDspRuntime<F828mk2>;
@@ -25,6 +30,21 @@ pub type UltraliteRuntime = RegisterDspRuntime<UltraLite>;
 pub type AudioExpressRuntime = RegisterDspRuntime<AudioExpress>;
 pub type H4preRuntime = RegisterDspRuntime<H4pre>;
 
+/// The model to ramp volume of mixer output around mute operation by the timer of runtime.
+pub trait MuteRampModel<O> {
+    fn set_mute_ramp_duration(&mut self, duration: Duration);
+    fn is_mute_ramp_active(&self) -> bool;
+    fn advance_mute_ramp(&mut self, unit: &mut O) -> Result<(), Error>;
+    fn finish_mute_ramp(&mut self, unit: &mut O) -> Result<(), Error>;
+}
+
+/// The model to read back registers after write operation.
+pub trait WriteVerificationModel<O> {
+    /// Verify the state of control for the element. Return false when the element is not for
+    /// verification.
+    fn verify_elem(&mut self, unit: &mut O, elem_id: &ElemId) -> Result<bool, Error>;
+}
+
 pub struct RegisterDspRuntime<T>
 where
     T: Default
@@ -32,7 +52,9 @@ where
         + NotifyModel<(SndMotu, FwNode), u32>
         + NotifyModel<(SndMotu, FwNode), bool>
         + NotifyModel<(SndMotu, FwNode), Vec<RegisterDspEvent>>
-        + MeasureModel<(SndMotu, FwNode)>,
+        + MeasureModel<(SndMotu, FwNode)>
+        + MuteRampModel<(SndMotu, FwNode)>
+        + WriteVerificationModel<(SndMotu, FwNode)>,
 {
     unit: (SndMotu, FwNode),
     model: T,
@@ -44,7 +66,18 @@ where
     version: u32,
     notified_elem_id_list: Vec<ElemId>,
     timer: Option<Dispatcher>,
+    ramp_timer: Option<Dispatcher>,
+    write_verification: bool,
     measured_elem_id_list: Vec<ElemId>,
+    mute_group_ctl: MuteGroupCtl,
+    direct_monitor_ctl: DirectMonitorCtl,
+    panic_ctl: PanicCtl,
+    meter_trigger: MeterTrigger,
+    solo_bus_ctl: SoloBusCtl,
+    mono_fold_ctl: MonoFoldCtl,
+    midi_map: MidiMap,
+    feature_report: FeatureReport,
+    stream_guard: StreamGuard,
 }
 
 impl<T> Drop for RegisterDspRuntime<T>
@@ -54,7 +87,9 @@ where
         + NotifyModel<(SndMotu, FwNode), u32>
         + NotifyModel<(SndMotu, FwNode), bool>
         + NotifyModel<(SndMotu, FwNode), Vec<RegisterDspEvent>>
-        + MeasureModel<(SndMotu, FwNode)>,
+        + MeasureModel<(SndMotu, FwNode)>
+        + MuteRampModel<(SndMotu, FwNode)>
+        + WriteVerificationModel<(SndMotu, FwNode)>,
 {
     fn drop(&mut self) {
         // At first, stop event loop in all of dispatchers to avoid queueing new events.
@@ -79,15 +114,84 @@ enum Event {
     LockNotify(bool),
     ChangedNotify(Vec<RegisterDspEvent>),
     Timer,
+    MidiMap(MidiMapEvents),
+    Ramp,
 }
 
 const NODE_DISPATCHER_NAME: &str = "node event dispatcher";
 const SYSTEM_DISPATCHER_NAME: &str = "system event dispatcher";
 const TIMER_DISPATCHER_NAME: &str = "interval timer dispatcher";
+const RAMP_DISPATCHER_NAME: &str = "mute ramp dispatcher";
+const MIDI_MAP_DISPATCHER_NAME: &str = "MIDI map dispatcher";
+
+const MIDI_MAP_CLIENT_NAME: &str = "MOTU mixer";
 
 const TIMER_NAME: &str = "metering";
+const METER_TRIGGER_CLIENT_NAME: &str = "MOTU meter trigger";
 const TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
 
+// The elements reset by panic switch.
+const PANIC_TARGETS: [PanicTarget; 7] = [
+    PanicTarget {
+        name: "mixer-output-mute",
+        state: Some(true),
+    },
+    PanicTarget {
+        name: "mixer-source-solo",
+        state: Some(false),
+    },
+    PanicTarget {
+        name: "mixer-source-gain",
+        state: None,
+    },
+    PanicTarget {
+        name: "mixer-output-volume",
+        state: None,
+    },
+    PanicTarget {
+        name: "main-volume",
+        state: None,
+    },
+    PanicTarget {
+        name: "headphone-volume",
+        state: None,
+    },
+    PanicTarget {
+        name: "master-output-volume",
+        state: None,
+    },
+];
+
+// The mode of solo over the solo function in DSP.
+const SOLO_BUS_TARGETS: [SoloBusTarget; 1] = [SoloBusTarget {
+    gain: "mixer-source-gain",
+    mute: "mixer-source-mute",
+    solo: "mixer-source-solo",
+    unity: None,
+}];
+
+// The fold-down to mono orchestrated by software. The width of stereo sources is also reduced.
+const MONO_FOLD_TARGETS: [MonoFoldTarget; 1] = [MonoFoldTarget {
+    pans: &[
+        ("mixer-source-pan", None),
+        ("mixer-source-stereo-balance", None),
+        ("mixer-source-stereo-width", Some(0)),
+    ],
+    fold: "mixer-output-mono-fold",
+}];
+
+// The controls critical to packet streaming, of which change is rejected while PCM substreams are
+// running. The mode of optical interface changes the format of stream.
+const STREAM_CRITICAL_ELEMS: [&str; 4] = [
+    "sampling- rate",
+    "sampling-rate",
+    "optical-iface-in-mode",
+    "optical-iface-out-mode",
+];
+
+const WRITE_VERIFICATION_NAME: &str = "write-verification";
+const MUTE_RAMP_DURATION_NAME: &str = "mute-ramp-duration";
+
 impl<T> RegisterDspRuntime<T>
 where
     T: Default
@@ -95,7 +199,9 @@ where
         + NotifyModel<(SndMotu, FwNode), u32>
         + NotifyModel<(SndMotu, FwNode), bool>
         + NotifyModel<(SndMotu, FwNode), Vec<RegisterDspEvent>>
-        + MeasureModel<(SndMotu, FwNode)>,
+        + MeasureModel<(SndMotu, FwNode)>
+        + MuteRampModel<(SndMotu, FwNode)>
+        + WriteVerificationModel<(SndMotu, FwNode)>,
 {
     pub fn new(unit: SndMotu, node: FwNode, card_id: u32, version: u32) -> Result<Self, Error> {
         let card_cntr = CardCntr::default();
@@ -114,7 +220,18 @@ where
             version,
             notified_elem_id_list: Default::default(),
             timer: Default::default(),
+            ramp_timer: Default::default(),
+            write_verification: Default::default(),
             measured_elem_id_list: Default::default(),
+            mute_group_ctl: Default::default(),
+            direct_monitor_ctl: Default::default(),
+            panic_ctl: Default::default(),
+            meter_trigger: Default::default(),
+            solo_bus_ctl: Default::default(),
+            mono_fold_ctl: Default::default(),
+            midi_map: Default::default(),
+            feature_report: Default::default(),
+            stream_guard: Default::default(),
         })
     }
 
@@ -122,6 +239,8 @@ where
         self.launch_node_event_dispatcher()?;
         self.launch_system_event_dispatcher()?;
 
+        setup_label_translations(&mut self.card_cntr);
+
         self.model.load(&mut self.unit, &mut self.card_cntr)?;
 
         NotifyModel::<(SndMotu, FwNode), u32>::get_notified_elem_list(
@@ -137,9 +256,56 @@ where
         self.model
             .get_measure_elem_list(&mut self.measured_elem_id_list);
         if self.measured_elem_id_list.len() > 0 {
-            let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, TIMER_NAME, 0);
-            let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
+            // The metering is not available in old kernels, thus it is not fatal.
+            let mut image = [0; 48];
+            if probe_driver_feature(DriverFeature::MotuRegisterDspMeter, || {
+                self.unit.0.read_byte_meter(&mut image)
+            })? {
+                let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, TIMER_NAME, 0);
+                let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
+            }
+        }
+
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, WRITE_VERIFICATION_NAME, 0);
+        let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
+
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, MUTE_RAMP_DURATION_NAME, 0);
+        let _ = self.card_cntr.add_int_elems(
+            &elem_id,
+            1,
+            0,
+            MUTE_RAMP_DURATION_MAX as i32,
+            1,
+            1,
+            None,
+            true,
+        )?;
+
+        self.mute_group_ctl.load(&mut self.card_cntr)?;
+        self.direct_monitor_ctl.load(&mut self.card_cntr)?;
+        self.panic_ctl.load(&mut self.card_cntr, &PANIC_TARGETS)?;
+        self.solo_bus_ctl
+            .load(&mut self.card_cntr, &SOLO_BUS_TARGETS)?;
+        self.mono_fold_ctl
+            .load(&mut self.card_cntr, &MONO_FOLD_TARGETS)?;
+
+        // The map is optional, thus any failure is not fatal.
+        if let Err(e) = self.midi_map.load(&self.card_cntr, MIDI_MAP_CLIENT_NAME) {
+            report_feature(MIDI_MAP_FEATURE_NAME, &e.to_string());
         }
+        self.launch_midi_map_dispatcher()?;
+
+        let card_id = self.unit.0.card_id();
+        self.stream_guard
+            .load(&mut self.card_cntr, card_id, &STREAM_CRITICAL_ELEMS)?;
+
+        self.meter_trigger
+            .setup(&self.card_cntr, METER_TRIGGER_CLIENT_NAME);
+
+        setup_aliases(&mut self.card_cntr);
+
+        self.feature_report.load(&mut self.card_cntr)?;
+        self.feature_report.update(&mut self.card_cntr)?;
 
         Ok(())
     }
@@ -157,13 +323,105 @@ where
                     println!("IEEE 1394 bus is updated: {}", generation);
                 }
                 Event::Elem((elem_id, events)) => {
-                    if elem_id.name() != TIMER_NAME {
-                        let _ = self.card_cntr.dispatch_elem_event(
+                    if self.feature_report.has_elem(&elem_id) {
+                        // The change of value is by the runtime itself.
+                        continue;
+                    }
+
+                    if self
+                        .stream_guard
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .mute_group_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self.direct_monitor_ctl.handle_elem_event(
+                        &mut self.card_cntr,
+                        &elem_id,
+                        &events,
+                    ) {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .panic_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .solo_bus_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .mono_fold_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if elem_id.name() == WRITE_VERIFICATION_NAME {
+                        let mut elem_value = ElemValue::new();
+                        let _ = self
+                            .card_cntr
+                            .card
+                            .read_elem_value(&elem_id, &mut elem_value)
+                            .map(|_| self.write_verification = elem_value.boolean()[0]);
+                    } else if elem_id.name() == MUTE_RAMP_DURATION_NAME {
+                        let mut elem_value = ElemValue::new();
+                        let _ = self
+                            .card_cntr
+                            .card
+                            .read_elem_value(&elem_id, &mut elem_value)
+                            .map(|_| {
+                                let duration = Duration::from_millis(elem_value.int()[0] as u64);
+                                self.model.set_mute_ramp_duration(duration)
+                            });
+                    } else if elem_id.name() != TIMER_NAME {
+                        let res = self.card_cntr.dispatch_elem_event(
                             &mut self.unit,
                             &elem_id,
                             &events,
                             &mut self.model,
                         );
+                        if res.is_ok()
+                            && self.write_verification
+                            && events.contains(ElemEventMask::VALUE)
+                        {
+                            let res = self.model.verify_elem(&mut self.unit, &elem_id);
+                            if res.is_err() {
+                                // The element is updated by the actual state of hardware.
+                                let _ = self.card_cntr.dispatch_elem_event(
+                                    &mut self.unit,
+                                    &elem_id,
+                                    &ElemEventMask::ADD,
+                                    &mut self.model,
+                                );
+                            }
+                        }
+                        if self.model.is_mute_ramp_active() && self.ramp_timer.is_none() {
+                            // Apply the flags of mute at once instead.
+                            if self.start_ramp_timer().is_err() {
+                                let _ = self.model.finish_mute_ramp(&mut self.unit);
+                            }
+                        }
                     } else {
                         let mut elem_value = ElemValue::new();
                         let _ = self
@@ -210,12 +468,44 @@ where
                         &self.measured_elem_id_list,
                         &mut self.model,
                     );
+                    self.meter_trigger
+                        .check_and_report(&mut self.card_cntr, &self.feature_report);
+                }
+                Event::MidiMap(events) => {
+                    self.midi_map
+                        .handle_seq_events(&mut self.card_cntr, &events);
+                    let _ = self.feature_report.update(&mut self.card_cntr);
+                }
+                Event::Ramp => {
+                    let _ = self.model.advance_mute_ramp(&mut self.unit);
+                    if !self.model.is_mute_ramp_active() {
+                        self.ramp_timer = None;
+                    }
                 }
             }
         }
         Ok(())
     }
 
+    fn launch_midi_map_dispatcher(&mut self) -> Result<(), Error> {
+        if !self.midi_map.is_loaded() {
+            return Ok(());
+        }
+
+        let name = MIDI_MAP_DISPATCHER_NAME.to_string();
+        let mut dispatcher = Dispatcher::run(name)?;
+
+        let tx = self.tx.clone();
+        self.midi_map
+            .attach_dispatcher(&mut dispatcher, move |events| {
+                let _ = tx.send(Event::MidiMap(events));
+            })?;
+
+        self.dispatchers.push(dispatcher);
+
+        Ok(())
+    }
+
     fn start_interval_timer(&mut self) -> Result<(), Error> {
         let mut dispatcher = Dispatcher::run(TIMER_DISPATCHER_NAME.to_string())?;
         let tx = self.tx.clone();
@@ -229,6 +519,19 @@ where
         Ok(())
     }
 
+    fn start_ramp_timer(&mut self) -> Result<(), Error> {
+        let mut dispatcher = Dispatcher::run(RAMP_DISPATCHER_NAME.to_string())?;
+        let tx = self.tx.clone();
+        dispatcher.attach_interval_handler(LevelRamp::INTERVAL, move || {
+            let _ = tx.send(Event::Ramp);
+            source::Continue(true)
+        });
+
+        self.ramp_timer = Some(dispatcher);
+
+        Ok(())
+    }
+
     fn stop_interval_timer(&mut self) {
         if let Some(dispatcher) = &self.timer {
             drop(dispatcher);
@@ -12,6 +12,7 @@ pub struct Track16 {
     clk_ctls: ClkCtl,
     port_assign_ctl: PortAssignCtl,
     opt_iface_ctl: OptIfaceCtl,
+    opt_iface_ch_ctl: OptIfaceChCtl,
     phone_assign_ctl: PhoneAssignCtl,
     sequence_number: u8,
     reverb_ctl: ReverbCtl,
@@ -60,6 +61,19 @@ struct OptIfaceCtl;
 
 impl V3OptIfaceCtlOperation<Track16Protocol> for OptIfaceCtl {}
 
+#[derive(Default)]
+struct OptIfaceChCtl((Vec<usize>, Vec<usize>), Vec<ElemId>);
+
+impl V3OptIfaceChannelCtlOperation<Track16Protocol> for OptIfaceChCtl {
+    fn state(&self) -> &(Vec<usize>, Vec<usize>) {
+        &self.0
+    }
+
+    fn state_mut(&mut self) -> &mut (Vec<usize>, Vec<usize>) {
+        &mut self.0
+    }
+}
+
 #[derive(Default)]
 struct ReverbCtl(CommandDspReverbState, Vec<ElemId>);
 
@@ -87,7 +101,7 @@ impl CommandDspMonitorCtlOperation<Track16Protocol> for MonitorCtl {
 }
 
 #[derive(Default)]
-struct MixerCtl(CommandDspMixerState, Vec<ElemId>);
+struct MixerCtl(CommandDspMixerState, Vec<ElemId>, CommandDspMixerScenes);
 
 impl CommandDspMixerCtlOperation<Track16Protocol> for MixerCtl {
     fn state(&self) -> &CommandDspMixerState {
@@ -97,6 +111,14 @@ impl CommandDspMixerCtlOperation<Track16Protocol> for MixerCtl {
     fn state_mut(&mut self) -> &mut CommandDspMixerState {
         &mut self.0
     }
+
+    fn scenes(&self) -> &CommandDspMixerScenes {
+        &self.2
+    }
+
+    fn scenes_mut(&mut self) -> &mut CommandDspMixerScenes {
+        &mut self.2
+    }
 }
 
 #[derive(Default)]
@@ -162,6 +184,9 @@ impl CtlModel<(SndMotu, FwNode)> for Track16 {
             .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
             .map(|mut elem_id_list| self.port_assign_ctl.1.append(&mut elem_id_list))?;
         self.opt_iface_ctl.load(card_cntr)?;
+        self.opt_iface_ch_ctl
+            .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
+            .map(|mut elem_id_list| self.opt_iface_ch_ctl.1.append(&mut elem_id_list))?;
         self.phone_assign_ctl
             .load(card_cntr, unit, &mut self.req, TIMEOUT_MS)
             .map(|mut elem_id_list| self.phone_assign_ctl.1.append(&mut elem_id_list))?;
@@ -219,6 +244,8 @@ impl CtlModel<(SndMotu, FwNode)> for Track16 {
             .read(unit, &mut self.req, elem_id, elem_value, TIMEOUT_MS)?
         {
             Ok(true)
+        } else if self.opt_iface_ch_ctl.read(elem_id, elem_value)? {
+            Ok(true)
         } else if self.phone_assign_ctl.read(elem_id, elem_value)? {
             Ok(true)
         } else if self.reverb_ctl.read(elem_id, elem_value)? {
@@ -364,11 +391,15 @@ impl CtlModel<(SndMotu, FwNode)> for Track16 {
 
 impl NotifyModel<(SndMotu, FwNode), u32> for Track16 {
     fn get_notified_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
+        elem_id_list.extend_from_slice(&self.opt_iface_ch_ctl.1);
         elem_id_list.extend_from_slice(&self.port_assign_ctl.1);
         elem_id_list.extend_from_slice(&self.phone_assign_ctl.1);
     }
 
     fn parse_notification(&mut self, unit: &mut (SndMotu, FwNode), msg: &u32) -> Result<(), Error> {
+        // The unit transmits message at any change of its configuration.
+        self.opt_iface_ch_ctl
+            .cache(unit, &mut self.req, TIMEOUT_MS)?;
         if *msg & Track16Protocol::NOTIFY_PORT_CHANGE > 0 {
             self.port_assign_ctl
                 .cache(unit, &mut self.req, TIMEOUT_MS)?;
@@ -384,7 +415,9 @@ impl NotifyModel<(SndMotu, FwNode), u32> for Track16 {
         elem_id: &ElemId,
         elem_value: &mut ElemValue,
     ) -> Result<bool, Error> {
-        if self.port_assign_ctl.read(elem_id, elem_value)? {
+        if self.opt_iface_ch_ctl.read(elem_id, elem_value)? {
+            Ok(true)
+        } else if self.port_assign_ctl.read(elem_id, elem_value)? {
             Ok(true)
         } else if self.phone_assign_ctl.read(elem_id, elem_value)? {
             Ok(true)
@@ -516,4 +549,9 @@ impl CommandDspModel for Track16 {
         )?;
         Ok(())
     }
+
+    fn advance_scene_morph(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<bool, Error> {
+        self.mixer_ctl
+            .advance_scene_morph(&mut self.sequence_number, unit, &mut self.req, TIMEOUT_MS)
+    }
 }
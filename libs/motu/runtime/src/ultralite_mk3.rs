@@ -81,7 +81,7 @@ impl CommandDspMonitorCtlOperation<UltraliteMk3Protocol> for MonitorCtl {
 }
 
 #[derive(Default)]
-struct MixerCtl(CommandDspMixerState, Vec<ElemId>);
+struct MixerCtl(CommandDspMixerState, Vec<ElemId>, CommandDspMixerScenes);
 
 impl CommandDspMixerCtlOperation<UltraliteMk3Protocol> for MixerCtl {
     fn state(&self) -> &CommandDspMixerState {
@@ -91,6 +91,14 @@ impl CommandDspMixerCtlOperation<UltraliteMk3Protocol> for MixerCtl {
     fn state_mut(&mut self) -> &mut CommandDspMixerState {
         &mut self.0
     }
+
+    fn scenes(&self) -> &CommandDspMixerScenes {
+        &self.2
+    }
+
+    fn scenes_mut(&mut self) -> &mut CommandDspMixerScenes {
+        &mut self.2
+    }
 }
 
 #[derive(Default)]
@@ -499,4 +507,9 @@ impl CommandDspModel for UltraLiteMk3 {
         )?;
         Ok(())
     }
+
+    fn advance_scene_morph(&mut self, unit: &mut (SndMotu, FwNode)) -> Result<bool, Error> {
+        self.mixer_ctl
+            .advance_scene_morph(&mut self.sequence_number, unit, &mut self.req, TIMEOUT_MS)
+    }
 }
@@ -45,14 +45,18 @@ pub trait V1ClkCtlOperation<T: V1ClkOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            RATE_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
-                T::get_clk_rate(req, &mut unit.1, timeout_ms).map(|idx| idx as u32)
-            })
-            .map(|_| true),
-            SRC_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
-                T::get_clk_src(req, &mut unit.1, timeout_ms).map(|idx| idx as u32)
-            })
-            .map(|_| true),
+            RATE_NAME => {
+                let elem = EnumElem::new(1, T::CLK_RATE_LABELS.len());
+                T::get_clk_rate(req, &mut unit.1, timeout_ms)
+                    .map(|idx| elem.set_val(elem_value, idx))?;
+                Ok(true)
+            }
+            SRC_NAME => {
+                let elem = EnumElem::new(1, T::CLK_SRC_LABELS.len());
+                T::get_clk_src(req, &mut unit.1, timeout_ms)
+                    .map(|idx| elem.set_val(elem_value, idx))?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -66,20 +70,20 @@ pub trait V1ClkCtlOperation<T: V1ClkOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            RATE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
+            RATE_NAME => {
+                let val = EnumElem::new(1, T::CLK_RATE_LABELS.len()).get_val(elem_value)?;
                 unit.0.lock()?;
-                let res = T::set_clk_rate(req, &mut unit.1, val as usize, timeout_ms);
+                let res = T::set_clk_rate(req, &mut unit.1, val, timeout_ms);
                 let _ = unit.0.unlock();
-                res
-            })
-            .map(|_| true),
-            SRC_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
+                res.map(|_| true)
+            }
+            SRC_NAME => {
+                let val = EnumElem::new(1, T::CLK_SRC_LABELS.len()).get_val(elem_value)?;
                 unit.0.lock()?;
-                let res = T::set_clk_src(req, &mut unit.1, val as usize, timeout_ms);
+                let res = T::set_clk_src(req, &mut unit.1, val, timeout_ms);
                 let _ = unit.0.unlock();
-                res
-            })
-            .map(|_| true),
+                res.map(|_| true)
+            }
             _ => Ok(false),
         }
     }
@@ -107,10 +111,12 @@ pub trait V1MonitorInputCtlOperation<T: V1MonitorInputOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            MONITOR_INPUT_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
-                T::get_monitor_input(req, &mut unit.1, timeout_ms).map(|idx| idx as u32)
-            })
-            .map(|_| true),
+            MONITOR_INPUT_NAME => {
+                let elem = EnumElem::new(1, T::MONITOR_INPUT_MODES.len());
+                T::get_monitor_input(req, &mut unit.1, timeout_ms)
+                    .map(|idx| elem.set_val(elem_value, idx))?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -124,10 +130,10 @@ pub trait V1MonitorInputCtlOperation<T: V1MonitorInputOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            MONITOR_INPUT_NAME => ElemValueAccessor::<u32>::get_val(new, |val| {
-                T::set_monitor_input(req, &mut unit.1, val as usize, timeout_ms)
-            })
-            .map(|_| true),
+            MONITOR_INPUT_NAME => {
+                let val = EnumElem::new(1, T::MONITOR_INPUT_MODES.len()).get_val(new)?;
+                T::set_monitor_input(req, &mut unit.1, val, timeout_ms).map(|_| true)
+            }
             _ => Ok(false),
         }
     }
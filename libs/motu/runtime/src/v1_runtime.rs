@@ -4,7 +4,10 @@
 pub use {
     super::{f828::*, f896::*, v1_ctls::*, *},
     alsactl::{prelude::*, *},
-    core::{card_cntr::*, dispatcher::*},
+    core::{
+        card_cntr::*, dispatcher::*, elem_alias::*, elem_locale::*, feature_report::*,
+        stream_guard::*,
+    },
     glib::source,
     nix::sys::signal::Signal,
     protocols::version_1::*,
@@ -27,6 +30,9 @@ where
     #[allow(dead_code)]
     version: u32,
     notified_elem_id_list: Vec<ElemId>,
+    stream_guard: StreamGuard,
+    feature_report: FeatureReport,
+    panic_ctl: PanicCtl,
 }
 
 impl<T> Drop for Version1Runtime<T>
@@ -58,6 +64,42 @@ enum Event {
 const NODE_DISPATCHER_NAME: &str = "node event dispatcher";
 const SYSTEM_DISPATCHER_NAME: &str = "system event dispatcher";
 
+// The controls critical to packet streaming, of which change is rejected while PCM substreams are
+// running. The mode of optical interface changes the format of stream.
+const STREAM_CRITICAL_ELEMS: [&str; 3] = [
+    "sampling- rate",
+    "optical-iface-in-mode",
+    "optical-iface-out-mode",
+];
+
+// The elements reset by panic switch.
+const PANIC_TARGETS: [PanicTarget; 6] = [
+    PanicTarget {
+        name: "mixer-output-mute",
+        state: Some(true),
+    },
+    PanicTarget {
+        name: "mixer-source-solo",
+        state: Some(false),
+    },
+    PanicTarget {
+        name: "mixer-source-gain",
+        state: None,
+    },
+    PanicTarget {
+        name: "mixer-output-volume",
+        state: None,
+    },
+    PanicTarget {
+        name: "main-volume",
+        state: None,
+    },
+    PanicTarget {
+        name: "headphone-volume",
+        state: None,
+    },
+];
+
 impl<T> Version1Runtime<T>
 where
     T: CtlModel<(SndMotu, FwNode)> + NotifyModel<(SndMotu, FwNode), u32> + Default,
@@ -78,6 +120,9 @@ where
             dispatchers: Default::default(),
             version,
             notified_elem_id_list: Default::default(),
+            stream_guard: Default::default(),
+            feature_report: Default::default(),
+            panic_ctl: Default::default(),
         })
     }
 
@@ -85,10 +130,23 @@ where
         self.launch_node_event_dispatcher()?;
         self.launch_system_event_dispatcher()?;
 
+        setup_label_translations(&mut self.card_cntr);
+
         self.model.load(&mut self.unit, &mut self.card_cntr)?;
         self.model
             .get_notified_elem_list(&mut self.notified_elem_id_list);
 
+        let card_id = self.unit.0.card_id();
+        self.stream_guard
+            .load(&mut self.card_cntr, card_id, &STREAM_CRITICAL_ELEMS)?;
+
+        self.panic_ctl.load(&mut self.card_cntr, &PANIC_TARGETS)?;
+
+        setup_aliases(&mut self.card_cntr);
+
+        self.feature_report.load(&mut self.card_cntr)?;
+        self.feature_report.update(&mut self.card_cntr)?;
+
         Ok(())
     }
 
@@ -105,12 +163,34 @@ where
                     println!("IEEE 1394 bus is updated: {}", generation);
                 }
                 Event::Elem((elem_id, events)) => {
+                    if self.feature_report.has_elem(&elem_id) {
+                        // The change of value is by the runtime itself.
+                        continue;
+                    }
+
+                    if self
+                        .panic_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .stream_guard
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
                     let _ = self.card_cntr.dispatch_elem_event(
                         &mut self.unit,
                         &elem_id,
                         &events,
                         &mut self.model,
                     );
+                    let _ = self.feature_report.update(&mut self.card_cntr);
                 }
                 Event::Notify(msg) => {
                     let _ = self.card_cntr.dispatch_notification(
@@ -40,20 +40,22 @@ pub trait V2ClkCtlOperation<T: V2ClkOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            RATE_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
-                T::get_clk_rate(req, &mut unit.1, timeout_ms).map(|idx| idx as u32)
-            })
-            .map(|_| true),
-            SRC_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
-                T::get_clk_src(req, &mut unit.1, timeout_ms).and_then(|idx| {
-                    if T::HAS_LCD {
-                        let label = clk_src_to_str(&T::CLK_SRCS[idx].0);
-                        T::update_clk_display(req, &mut unit.1, &label, timeout_ms)?;
-                    }
-                    Ok(idx as u32)
-                })
-            })
-            .map(|_| true),
+            RATE_NAME => {
+                let elem = EnumElem::new(1, T::CLK_RATES.len());
+                T::get_clk_rate(req, &mut unit.1, timeout_ms)
+                    .map(|idx| elem.set_val(elem_value, idx))?;
+                Ok(true)
+            }
+            SRC_NAME => {
+                let elem = EnumElem::new(1, T::CLK_SRCS.len());
+                let idx = T::get_clk_src(req, &mut unit.1, timeout_ms)?;
+                if T::HAS_LCD {
+                    let label = clk_src_to_str(&T::CLK_SRCS[idx].0);
+                    T::update_clk_display(req, &mut unit.1, &label, timeout_ms)?;
+                }
+                elem.set_val(elem_value, idx);
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -67,28 +69,28 @@ pub trait V2ClkCtlOperation<T: V2ClkOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            RATE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
+            RATE_NAME => {
+                let val = EnumElem::new(1, T::CLK_RATES.len()).get_val(elem_value)?;
                 unit.0.lock()?;
-                let res = T::set_clk_rate(req, &mut unit.1, val as usize, timeout_ms);
+                let res = T::set_clk_rate(req, &mut unit.1, val, timeout_ms);
                 let _ = unit.0.unlock();
-                res
-            })
-            .map(|_| true),
-            SRC_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
+                res.map(|_| true)
+            }
+            SRC_NAME => {
+                let val = EnumElem::new(1, T::CLK_SRCS.len()).get_val(elem_value)?;
                 let prev_src = T::get_clk_src(req, &mut unit.1, timeout_ms)?;
                 unit.0.lock()?;
-                let mut res = T::set_clk_src(req, &mut unit.1, val as usize, timeout_ms);
+                let mut res = T::set_clk_src(req, &mut unit.1, val, timeout_ms);
                 if res.is_ok() && T::HAS_LCD {
-                    let label = clk_src_to_str(&T::CLK_SRCS[val as usize].0);
+                    let label = clk_src_to_str(&T::CLK_SRCS[val].0);
                     res = T::update_clk_display(req, &mut unit.1, &label, timeout_ms);
                     if res.is_err() {
                         let _ = T::set_clk_src(req, &mut unit.1, prev_src, timeout_ms);
                     }
                 }
                 let _ = unit.0.unlock();
-                res
-            })
-            .map(|_| true),
+                res.map(|_| true)
+            }
             _ => Ok(false),
         }
     }
@@ -152,12 +154,14 @@ pub trait V2OptIfaceCtlOperation<T: V2OptIfaceOperation> {
     fn read(&mut self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
         match elem_id.name().as_str() {
             OPT_IN_IFACE_MODE_NAME => {
-                ElemValueAccessor::<u32>::set_val(elem_value, || Ok(self.state().0 as u32))
-                    .map(|_| true)
+                let elem = EnumElem::new(1, T::OPT_IFACE_MODES.len());
+                elem.set_val(elem_value, self.state().0);
+                Ok(true)
             }
             OPT_OUT_IFACE_MODE_NAME => {
-                ElemValueAccessor::<u32>::set_val(elem_value, || Ok(self.state().1 as u32))
-                    .map(|_| true)
+                let elem = EnumElem::new(1, T::OPT_IFACE_MODES.len());
+                elem.set_val(elem_value, self.state().1);
+                Ok(true)
             }
             _ => Ok(false),
         }
@@ -172,26 +176,96 @@ pub trait V2OptIfaceCtlOperation<T: V2OptIfaceOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            OPT_IN_IFACE_MODE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
+            OPT_IN_IFACE_MODE_NAME => {
+                let val = EnumElem::new(1, T::OPT_IFACE_MODES.len()).get_val(elem_value)?;
                 unit.0.lock()?;
-                let res = T::set_opt_in_iface_mode(req, &mut unit.1, val as usize, timeout_ms);
+                let res = T::set_opt_in_iface_mode(req, &mut unit.1, val, timeout_ms);
                 if res.is_ok() {
-                    self.state_mut().0 = val as usize;
+                    self.state_mut().0 = val;
                 }
                 unit.0.unlock()?;
-                res
-            })
-            .map(|_| true),
-            OPT_OUT_IFACE_MODE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
+                res.map(|_| true)
+            }
+            OPT_OUT_IFACE_MODE_NAME => {
+                let val = EnumElem::new(1, T::OPT_IFACE_MODES.len()).get_val(elem_value)?;
                 unit.0.lock()?;
-                let res = T::set_opt_out_iface_mode(req, &mut unit.1, val as usize, timeout_ms);
+                let res = T::set_opt_out_iface_mode(req, &mut unit.1, val, timeout_ms);
                 if res.is_ok() {
-                    self.state_mut().1 = val as usize;
+                    self.state_mut().1 = val;
                 }
                 unit.0.unlock()?;
-                res
-            })
-            .map(|_| true),
+                res.map(|_| true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+const OPT_IN_IFACE_CH_COUNT_NAME: &str = "optical-iface-in-channels";
+const OPT_OUT_IFACE_CH_COUNT_NAME: &str = "optical-iface-out-channels";
+
+// The range is not used to fill values.
+const OPT_IFACE_CH_COUNT_ELEM: IntElem<i32> = IntElem::new(1, 0, i32::MAX);
+
+/// The trait for read-only elements to report the number of channels in optical interface at
+/// current rate, so that the channels seen by host can be verified before reconfiguration.
+pub trait V2OptIfaceChannelCtlOperation<T: V2OptIfaceOperation + V2ClkOperation> {
+    fn state(&self) -> &(usize, usize);
+    fn state_mut(&mut self) -> &mut (usize, usize);
+
+    fn load(
+        &mut self,
+        card_cntr: &mut CardCntr,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        timeout_ms: u32,
+    ) -> Result<Vec<ElemId>, Error> {
+        self.cache(unit, req, timeout_ms)?;
+
+        let mut notified_elem_id_list = Vec::new();
+
+        // The elements are not unlocked so that the other processes can not change them.
+        let max = ClkRate::R48000.adat_channel_count() as i32;
+        [OPT_IN_IFACE_CH_COUNT_NAME, OPT_OUT_IFACE_CH_COUNT_NAME]
+            .iter()
+            .try_for_each(|name| {
+                let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, name, 0);
+                card_cntr
+                    .add_int_elems(&elem_id, 1, 0, max, 1, 1, None, false)
+                    .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))
+            })?;
+
+        Ok(notified_elem_id_list)
+    }
+
+    fn cache(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        timeout_ms: u32,
+    ) -> Result<(), Error> {
+        let idx = T::get_clk_rate(req, &mut unit.1, timeout_ms)?;
+        let rate = &T::CLK_RATES[idx].0;
+
+        let idx = T::get_opt_in_iface_mode(req, &mut unit.1, timeout_ms)?;
+        self.state_mut().0 = T::OPT_IFACE_MODES[idx].0.channel_count(rate);
+
+        let idx = T::get_opt_out_iface_mode(req, &mut unit.1, timeout_ms)?;
+        self.state_mut().1 = T::OPT_IFACE_MODES[idx].0.channel_count(rate);
+
+        Ok(())
+    }
+
+    fn read(&self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            OPT_IN_IFACE_CH_COUNT_NAME => {
+                OPT_IFACE_CH_COUNT_ELEM.set_val(elem_value, self.state().0 as i32);
+                Ok(true)
+            }
+            OPT_OUT_IFACE_CH_COUNT_NAME => {
+                OPT_IFACE_CH_COUNT_ELEM.set_val(elem_value, self.state().1 as i32);
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -39,19 +39,22 @@ pub trait V3ClkCtlOperation<T: V3ClkOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            RATE_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
-                T::get_clk_rate(req, &mut unit.1, timeout_ms).map(|val| val as u32)
-            })
-            .map(|_| true),
-            SRC_NAME => ElemValueAccessor::<u32>::set_val(elem_value, || {
+            RATE_NAME => {
+                let elem = EnumElem::new(1, T::CLK_RATES.len());
+                T::get_clk_rate(req, &mut unit.1, timeout_ms)
+                    .map(|val| elem.set_val(elem_value, val))?;
+                Ok(true)
+            }
+            SRC_NAME => {
+                let elem = EnumElem::new(1, T::CLK_SRCS.len());
                 let val = T::get_clk_src(req, &mut unit.1, timeout_ms)?;
                 if T::HAS_LCD {
                     let label = clk_src_to_str(&T::CLK_SRCS[val].0);
                     let _ = T::update_clk_display(req, &mut unit.1, &label, timeout_ms);
                 }
-                Ok(val as u32)
-            })
-            .map(|_| true),
+                elem.set_val(elem_value, val);
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -65,28 +68,28 @@ pub trait V3ClkCtlOperation<T: V3ClkOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            RATE_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
+            RATE_NAME => {
+                let val = EnumElem::new(1, T::CLK_RATES.len()).get_val(elem_value)?;
                 unit.0.lock()?;
-                let res = T::set_clk_rate(req, &mut unit.1, val as usize, timeout_ms);
+                let res = T::set_clk_rate(req, &mut unit.1, val, timeout_ms);
                 let _ = unit.0.unlock();
-                res
-            })
-            .map(|_| true),
-            SRC_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
+                res.map(|_| true)
+            }
+            SRC_NAME => {
+                let val = EnumElem::new(1, T::CLK_SRCS.len()).get_val(elem_value)?;
                 let prev_src = T::get_clk_src(req, &mut unit.1, timeout_ms)?;
                 unit.0.lock()?;
-                let mut res = T::set_clk_src(req, &mut unit.1, val as usize, timeout_ms);
+                let mut res = T::set_clk_src(req, &mut unit.1, val, timeout_ms);
                 if res.is_ok() && T::HAS_LCD {
-                    let label = clk_src_to_str(&T::CLK_SRCS[val as usize].0);
+                    let label = clk_src_to_str(&T::CLK_SRCS[val].0);
                     res = T::update_clk_display(req, &mut unit.1, &label, timeout_ms);
                     if res.is_err() {
                         let _ = T::set_clk_src(req, &mut unit.1, prev_src, timeout_ms);
                     }
                 }
                 let _ = unit.0.unlock();
-                res
-            })
-            .map(|_| true),
+                res.map(|_| true)
+            }
             _ => Ok(false),
         }
     }
@@ -165,16 +168,18 @@ pub trait V3PortAssignCtlOperation<T: V3PortAssignOperation> {
         timeout_ms: u32,
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
-            MAIN_ASSIGN_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
-                T::set_main_assign(req, &mut unit.1, val as usize, timeout_ms)
-                    .map(|_| self.state_mut().0 = val as usize)
-            })
-            .map(|_| true),
-            RETURN_ASSIGN_NAME => ElemValueAccessor::<u32>::get_val(elem_value, |val| {
-                T::set_return_assign(req, &mut unit.1, val as usize, timeout_ms)
-                    .map(|_| self.state_mut().1 = val as usize)
-            })
-            .map(|_| true),
+            MAIN_ASSIGN_NAME => {
+                let val = EnumElem::new(1, T::ASSIGN_PORTS.len()).get_val(elem_value)?;
+                T::set_main_assign(req, &mut unit.1, val, timeout_ms)
+                    .map(|_| self.state_mut().0 = val)?;
+                Ok(true)
+            }
+            RETURN_ASSIGN_NAME => {
+                let val = EnumElem::new(1, T::ASSIGN_PORTS.len()).get_val(elem_value)?;
+                T::set_return_assign(req, &mut unit.1, val, timeout_ms)
+                    .map(|_| self.state_mut().1 = val)?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
@@ -214,16 +219,18 @@ pub trait V3OptIfaceCtlOperation<T: V3OptIfaceOperation> {
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
             OPT_IFACE_IN_MODE_NAME => {
-                ElemValueAccessor::<u32>::set_vals(elem_value, T::TARGETS.len(), |idx| {
+                let elem = EnumElem::new(T::TARGETS.len(), T::MODES.len());
+                elem.set_vals(elem_value, |idx| {
                     T::get_opt_input_iface_mode(req, &mut unit.1, T::TARGETS[idx], timeout_ms)
-                        .map(|mode| T::MODES.iter().position(|m| m.eq(&mode)).unwrap() as u32)
+                        .map(|mode| T::MODES.iter().position(|m| m.eq(&mode)).unwrap())
                 })
                 .map(|_| true)
             }
             OPT_IFACE_OUT_MODE_NAME => {
-                ElemValueAccessor::<u32>::set_vals(elem_value, T::TARGETS.len(), |idx| {
+                let elem = EnumElem::new(T::TARGETS.len(), T::MODES.len());
+                elem.set_vals(elem_value, |idx| {
                     T::get_opt_output_iface_mode(req, &mut unit.1, T::TARGETS[idx], timeout_ms)
-                        .map(|mode| T::MODES.iter().position(|m| m.eq(&mode)).unwrap() as u32)
+                        .map(|mode| T::MODES.iter().position(|m| m.eq(&mode)).unwrap())
                 })
                 .map(|_| true)
             }
@@ -242,40 +249,30 @@ pub trait V3OptIfaceCtlOperation<T: V3OptIfaceOperation> {
     ) -> Result<bool, Error> {
         match elem_id.name().as_str() {
             OPT_IFACE_IN_MODE_NAME => {
+                let elem = EnumElem::new(T::TARGETS.len(), T::MODES.len());
+                let changes = elem.changes(new, old)?;
                 unit.0.lock()?;
-                let res =
-                    ElemValueAccessor::<u32>::get_vals(new, old, T::TARGETS.len(), |idx, val| {
-                        let &mode = T::MODES.iter().nth(val as usize).ok_or_else(|| {
-                            let msg = format!("Invalid index for mode of opt interface: {}", val);
-                            Error::new(FileError::Inval, &msg)
-                        })?;
-                        T::set_opt_input_iface_mode(
-                            req,
-                            &mut unit.1,
-                            T::TARGETS[idx],
-                            mode,
-                            timeout_ms,
-                        )
-                    });
+                let res = changes.iter().try_for_each(|&(idx, val)| {
+                    let mode = T::MODES[val];
+                    T::set_opt_input_iface_mode(req, &mut unit.1, T::TARGETS[idx], mode, timeout_ms)
+                });
                 let _ = unit.0.unlock();
                 res.and(Ok(true))
             }
             OPT_IFACE_OUT_MODE_NAME => {
+                let elem = EnumElem::new(T::TARGETS.len(), T::MODES.len());
+                let changes = elem.changes(new, old)?;
                 unit.0.lock()?;
-                let res =
-                    ElemValueAccessor::<u32>::get_vals(new, old, T::TARGETS.len(), |idx, val| {
-                        let &mode = T::MODES.iter().nth(val as usize).ok_or_else(|| {
-                            let msg = format!("Invalid index for mode of opt interface: {}", val);
-                            Error::new(FileError::Inval, &msg)
-                        })?;
-                        T::set_opt_output_iface_mode(
-                            req,
-                            &mut unit.1,
-                            T::TARGETS[idx],
-                            mode,
-                            timeout_ms,
-                        )
-                    });
+                let res = changes.iter().try_for_each(|&(idx, val)| {
+                    let mode = T::MODES[val];
+                    T::set_opt_output_iface_mode(
+                        req,
+                        &mut unit.1,
+                        T::TARGETS[idx],
+                        mode,
+                        timeout_ms,
+                    )
+                });
                 let _ = unit.0.unlock();
                 res.and(Ok(true))
             }
@@ -283,3 +280,77 @@ pub trait V3OptIfaceCtlOperation<T: V3OptIfaceOperation> {
         }
     }
 }
+
+const OPT_IFACE_IN_CH_COUNT_NAME: &str = "optical-iface-in-channels";
+const OPT_IFACE_OUT_CH_COUNT_NAME: &str = "optical-iface-out-channels";
+
+/// The trait for read-only elements to report the number of channels in each optical interface
+/// at current rate, so that the channels seen by host can be verified before reconfiguration.
+pub trait V3OptIfaceChannelCtlOperation<T: V3OptIfaceOperation + V3ClkOperation> {
+    fn state(&self) -> &(Vec<usize>, Vec<usize>);
+    fn state_mut(&mut self) -> &mut (Vec<usize>, Vec<usize>);
+
+    fn load(
+        &mut self,
+        card_cntr: &mut CardCntr,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        timeout_ms: u32,
+    ) -> Result<Vec<ElemId>, Error> {
+        self.cache(unit, req, timeout_ms)?;
+
+        let mut notified_elem_id_list = Vec::new();
+
+        // The elements are not unlocked so that the other processes can not change them.
+        let max = ClkRate::R48000.adat_channel_count() as i32;
+        [OPT_IFACE_IN_CH_COUNT_NAME, OPT_IFACE_OUT_CH_COUNT_NAME]
+            .iter()
+            .try_for_each(|name| {
+                let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, name, 0);
+                card_cntr
+                    .add_int_elems(&elem_id, 1, 0, max, 1, T::TARGETS.len(), None, false)
+                    .map(|mut elem_id_list| notified_elem_id_list.append(&mut elem_id_list))
+            })?;
+
+        Ok(notified_elem_id_list)
+    }
+
+    fn cache(
+        &mut self,
+        unit: &mut (SndMotu, FwNode),
+        req: &mut FwReq,
+        timeout_ms: u32,
+    ) -> Result<(), Error> {
+        let idx = T::get_clk_rate(req, &mut unit.1, timeout_ms)?;
+        let rate = &T::CLK_RATES[idx].0;
+
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        T::TARGETS.iter().try_for_each(|&target| {
+            T::get_opt_input_iface_mode(req, &mut unit.1, target, timeout_ms)
+                .map(|mode| inputs.push(mode.channel_count(rate)))?;
+            T::get_opt_output_iface_mode(req, &mut unit.1, target, timeout_ms)
+                .map(|mode| outputs.push(mode.channel_count(rate)))
+        })?;
+
+        *self.state_mut() = (inputs, outputs);
+
+        Ok(())
+    }
+
+    fn read(&self, elem_id: &ElemId, elem_value: &mut ElemValue) -> Result<bool, Error> {
+        match elem_id.name().as_str() {
+            OPT_IFACE_IN_CH_COUNT_NAME => {
+                let elem = IntElem::new(T::TARGETS.len(), 0, i32::MAX);
+                elem.set_vals(elem_value, |idx| Ok(self.state().0[idx] as i32))
+                    .map(|_| true)
+            }
+            OPT_IFACE_OUT_CH_COUNT_NAME => {
+                let elem = IntElem::new(T::TARGETS.len(), 0, i32::MAX);
+                elem.set_vals(elem_value, |idx| Ok(self.state().1[idx] as i32))
+                    .map(|_| true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
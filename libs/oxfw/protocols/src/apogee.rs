@@ -1123,60 +1123,29 @@ impl VendorCmd {
     }
 }
 
-/// AV/C vendor-dependent command specific to Apogee Duet FireWire.
-struct ApogeeCmd {
-    cmd: VendorCmd,
-    op: VendorDependent,
-}
-
-impl ApogeeCmd {
-    fn new(cmd: VendorCmd) -> Self {
-        ApogeeCmd {
-            cmd,
-            op: VendorDependent::new(&APOGEE_OUI),
-        }
-    }
-}
-
-impl AvcOp for ApogeeCmd {
-    const OPCODE: u8 = VendorDependent::OPCODE;
-}
+impl VendorDependentSchema for VendorCmd {
+    const COMPANY_ID: [u8; 3] = APOGEE_OUI;
 
-impl AvcControl for ApogeeCmd {
-    fn build_operands(
-        &mut self,
-        addr: &AvcAddr,
-        operands: &mut Vec<u8>,
-    ) -> Result<(), AvcCmdBuildError> {
-        let mut data = self.cmd.build_args();
-        self.cmd.append_variable(&mut data);
-        self.op.data = data;
-        AvcControl::build_operands(&mut self.op, addr, operands)
-    }
-
-    fn parse_operands(&mut self, addr: &AvcAddr, operands: &[u8]) -> Result<(), AvcRespParseError> {
-        AvcControl::parse_operands(&mut self.op, addr, operands)
+    fn build_control_data(&self, data: &mut Vec<u8>) -> Result<(), AvcCmdBuildError> {
+        data.append(&mut self.build_args());
+        self.append_variable(data);
+        Ok(())
     }
-}
 
-impl AvcStatus for ApogeeCmd {
-    fn build_operands(
-        &mut self,
-        addr: &AvcAddr,
-        operands: &mut Vec<u8>,
-    ) -> Result<(), AvcCmdBuildError> {
-        self.op.data = self.cmd.build_args();
-        AvcStatus::build_operands(&mut self.op, addr, operands)
+    fn build_status_data(&self, data: &mut Vec<u8>) -> Result<(), AvcCmdBuildError> {
+        data.append(&mut self.build_args());
+        Ok(())
     }
 
-    fn parse_operands(&mut self, addr: &AvcAddr, operands: &[u8]) -> Result<(), AvcRespParseError> {
-        AvcStatus::parse_operands(&mut self.op, addr, operands)?;
-        self.cmd
-            .parse_variable(&self.op.data)
+    fn parse_status_data(&mut self, data: &[u8]) -> Result<(), AvcRespParseError> {
+        self.parse_variable(data)
             .map_err(|_| AvcRespParseError::UnexpectedOperands(4))
     }
 }
 
+/// AV/C vendor-dependent command specific to Apogee Duet FireWire.
+type ApogeeCmd = VendorDependentOperation<VendorCmd>;
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -265,57 +265,27 @@ impl From<&VendorCmd> for u8 {
     }
 }
 
-/// AV/C vendor-dependent command specialized by TASCAM.
-struct TascamProto {
-    cmd: VendorCmd,
-    op: VendorDependent,
-}
+impl VendorDependentSchema for VendorCmd {
+    const COMPANY_ID: [u8; 3] = TEAC_OUI;
 
-impl TascamProto {
-    fn new(cmd: VendorCmd) -> Self {
-        TascamProto {
-            cmd,
-            op: VendorDependent::new(&TEAC_OUI),
-        }
+    fn build_control_data(&self, data: &mut Vec<u8>) -> Result<(), AvcCmdBuildError> {
+        data.append(&mut self.build_data());
+        self.append_variable(data);
+        Ok(())
     }
-}
 
-impl AvcOp for TascamProto {
-    const OPCODE: u8 = VendorDependent::OPCODE;
-}
-
-impl AvcControl for TascamProto {
-    fn build_operands(
-        &mut self,
-        addr: &AvcAddr,
-        operands: &mut Vec<u8>,
-    ) -> Result<(), AvcCmdBuildError> {
-        let mut data = self.cmd.build_data();
-        self.cmd.append_variable(&mut data);
-        self.op.data = data;
-        AvcControl::build_operands(&mut self.op, addr, operands)
+    fn build_status_data(&self, data: &mut Vec<u8>) -> Result<(), AvcCmdBuildError> {
+        data.append(&mut self.build_data());
+        Ok(())
     }
 
-    fn parse_operands(&mut self, addr: &AvcAddr, operands: &[u8]) -> Result<(), AvcRespParseError> {
-        AvcControl::parse_operands(&mut self.op, addr, operands)
+    fn parse_status_data(&mut self, data: &[u8]) -> Result<(), AvcRespParseError> {
+        self.parse_variable(data)
     }
 }
 
-impl AvcStatus for TascamProto {
-    fn build_operands(
-        &mut self,
-        addr: &AvcAddr,
-        operands: &mut Vec<u8>,
-    ) -> Result<(), AvcCmdBuildError> {
-        self.op.data = self.cmd.build_data();
-        AvcStatus::build_operands(&mut self.op, addr, operands)
-    }
-
-    fn parse_operands(&mut self, addr: &AvcAddr, operands: &[u8]) -> Result<(), AvcRespParseError> {
-        AvcStatus::parse_operands(&mut self.op, addr, operands)?;
-        self.cmd.parse_variable(&self.op.data)
-    }
-}
+/// AV/C vendor-dependent command specialized by TASCAM.
+type TascamProto = VendorDependentOperation<VendorCmd>;
 
 /// The implementation of AV/C transaction with quirk specific to Tascam FireOne.
 ///
@@ -1,7 +1,11 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 // Copyright (c) 2020 Takashi Sakamoto
 
-use {super::*, protocols::apogee::*};
+use {
+    super::*,
+    core::{gain_staging::*, knob_forward::*},
+    protocols::apogee::*,
+};
 
 #[derive(Default, Debug)]
 pub struct ApogeeModel {
@@ -14,12 +18,60 @@ pub struct ApogeeModel {
     input_ctl: InputCtl,
     mixer_ctl: MixerCtl,
     display_ctl: DisplayCtl,
+    gain_staging: GainStaging,
+    gain_staging_elem_list: Vec<ElemId>,
+    knob_forwarder: KnobForwarder,
 }
 
 const TIMEOUT_MS: u32 = 50;
 
+const INPUT_GAIN_STAGING_NAME: &str = "input-gain-staging";
+
+const KNOB_MIDI_FORWARD_NAME: &str = "knob-midi-forward";
+
+const KNOB_MIDI_CLIENT_NAME: &str = "Apogee Duet FireWire";
+
+// The state of knob and button is forwarded by MIDI control change: output mute, knob target,
+// output volume, and input gains.
+const KNOB_FORWARD_PARAMS: [KnobForwardParam; 5] = [
+    KnobForwardParam {
+        ctl_num: 0x50,
+        min: 0,
+        max: 1,
+    },
+    KnobForwardParam {
+        ctl_num: 0x51,
+        min: 0,
+        max: 2,
+    },
+    KnobForwardParam {
+        ctl_num: 0x07,
+        min: DuetFwKnobProtocol::VOLUME_MIN as i32,
+        max: DuetFwKnobProtocol::VOLUME_MAX as i32,
+    },
+    KnobForwardParam {
+        ctl_num: 0x10,
+        min: DuetFwKnobProtocol::GAIN_MIN as i32,
+        max: DuetFwKnobProtocol::GAIN_MAX as i32,
+    },
+    KnobForwardParam {
+        ctl_num: 0x11,
+        min: DuetFwKnobProtocol::GAIN_MIN as i32,
+        max: DuetFwKnobProtocol::GAIN_MAX as i32,
+    },
+];
+
 impl ApogeeModel {
     const FCP_TIMEOUT_MS: u32 = 100;
+
+    // The meters are watched for 3 seconds with the interval of metering timer.
+    const GAIN_STAGING_MEASURE_COUNT: usize = 60;
+
+    const INPUT_GAIN_RANGE: InputGainRange = InputGainRange {
+        min: DuetFwInputProtocol::GAIN_MIN as i32,
+        max: DuetFwInputProtocol::GAIN_MAX as i32,
+        db_per_step: 1.0,
+    };
 }
 
 impl CtlModel<(SndUnit, FwNode)> for ApogeeModel {
@@ -43,6 +95,15 @@ impl CtlModel<(SndUnit, FwNode)> for ApogeeModel {
         self.mixer_ctl.load_params(card_cntr)?;
         self.display_ctl.load_params(card_cntr)?;
 
+        // NOTE: the meters are watched as long as metering timer is enabled.
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, INPUT_GAIN_STAGING_NAME, 0);
+        card_cntr
+            .add_bool_elems(&elem_id, 1, 1, true)
+            .map(|mut elem_id_list| self.gain_staging_elem_list.append(&mut elem_id_list))?;
+
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, KNOB_MIDI_FORWARD_NAME, 0);
+        card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
+
         Ok(())
     }
 
@@ -84,6 +145,11 @@ impl CtlModel<(SndUnit, FwNode)> for ApogeeModel {
             Self::FCP_TIMEOUT_MS,
         )? {
             Ok(true)
+        } else if self.read_gain_staging(elem_id, elem_value) {
+            Ok(true)
+        } else if elem_id.name().as_str() == KNOB_MIDI_FORWARD_NAME {
+            elem_value.set_bool(&[self.knob_forwarder.is_enabled()]);
+            Ok(true)
         } else {
             Ok(false)
         }
@@ -129,18 +195,112 @@ impl CtlModel<(SndUnit, FwNode)> for ApogeeModel {
             Self::FCP_TIMEOUT_MS,
         )? {
             Ok(true)
+        } else if elem_id.name().as_str() == INPUT_GAIN_STAGING_NAME {
+            if new.boolean()[0] {
+                self.gain_staging
+                    .start(InputCtl::LABELS.len(), Self::GAIN_STAGING_MEASURE_COUNT);
+            } else {
+                self.gain_staging.stop();
+            }
+            Ok(true)
+        } else if elem_id.name().as_str() == KNOB_MIDI_FORWARD_NAME {
+            if new.boolean()[0] {
+                self.knob_forwarder.enable(KNOB_MIDI_CLIENT_NAME)?;
+            } else {
+                self.knob_forwarder.disable();
+            }
+            Ok(true)
         } else {
             Ok(false)
         }
     }
 }
 
+impl ApogeeModel {
+    fn read_gain_staging(&self, elem_id: &ElemId, elem_value: &mut ElemValue) -> bool {
+        if elem_id.name().as_str() == INPUT_GAIN_STAGING_NAME {
+            elem_value.set_bool(&[self.gain_staging.is_running()]);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl ApogeeModel {
+    fn forward_knob_state(&mut self) -> Result<(), Error> {
+        let state = &self.knob_ctl.0;
+        let target = KnobCtl::KNOB_TARGETS
+            .iter()
+            .position(|t| t.eq(&state.target))
+            .ok_or_else(|| {
+                let msg = format!("Unexpected knob target: {:?}", state.target);
+                Error::new(FileError::Io, &msg)
+            })?;
+        let vals = [
+            state.output_mute as i32,
+            target as i32,
+            state.output_volume as i32,
+            state.input_gains[0] as i32,
+            state.input_gains[1] as i32,
+        ];
+        self.knob_forwarder.forward(&KNOB_FORWARD_PARAMS, &vals)
+    }
+}
+
+impl GainStagingOperation<(SndUnit, FwNode)> for ApogeeModel {
+    const INPUT_GAIN_RANGES: &'static [InputGainRange] = &[Self::INPUT_GAIN_RANGE; 2];
+
+    fn gain_staging(&mut self) -> &mut GainStaging {
+        &mut self.gain_staging
+    }
+
+    fn input_peaks(&self) -> Vec<f64> {
+        self.meter_ctl
+            .0
+             .0
+            .iter()
+            .map(|&level| level as f64 / DuetFwInputMeterProtocol::LEVEL_MAX as f64)
+            .collect()
+    }
+
+    fn input_gains(&self) -> Vec<i32> {
+        self.input_ctl
+            .0
+            .gains
+            .iter()
+            .map(|&gain| gain as i32)
+            .collect()
+    }
+
+    fn write_input_gains(&mut self, _: &mut (SndUnit, FwNode), gains: &[i32]) -> Result<(), Error> {
+        gains.iter().enumerate().try_for_each(|(idx, &gain)| {
+            let params = &mut self.input_ctl.0;
+            // The gain is not adjustable for line level of XLR.
+            let adjustable = params.srcs[idx] != DuetFwInputSource::Xlr
+                || params.xlr_nominal_levels[idx] == DuetFwInputXlrNominalLevel::Microphone;
+            if adjustable && params.gains[idx] as i32 != gain {
+                DuetFwInputProtocol::write_gain(
+                    &mut self.avc,
+                    idx,
+                    gain as u8,
+                    params,
+                    Self::FCP_TIMEOUT_MS,
+                )
+            } else {
+                Ok(())
+            }
+        })
+    }
+}
+
 impl MeasureModel<(SndUnit, FwNode)> for ApogeeModel {
     fn get_measure_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
         elem_id_list.extend_from_slice(&self.meter_ctl.2);
         elem_id_list.extend_from_slice(&self.knob_ctl.1);
         elem_id_list.extend_from_slice(&self.output_ctl.2);
         elem_id_list.extend_from_slice(&self.input_ctl.1);
+        elem_id_list.extend_from_slice(&self.gain_staging_elem_list);
     }
 
     fn measure_states(&mut self, unit: &mut (SndUnit, FwNode)) -> Result<(), Error> {
@@ -153,6 +313,14 @@ impl MeasureModel<(SndUnit, FwNode)> for ApogeeModel {
         self.input_ctl.0.gains[0] = self.knob_ctl.0.input_gains[0];
         self.input_ctl.0.gains[1] = self.knob_ctl.0.input_gains[1];
 
+        if self.knob_forwarder.is_enabled() {
+            self.forward_knob_state()?;
+        }
+
+        if self.gain_staging.is_running() {
+            self.update_gain_staging(unit)?;
+        }
+
         Ok(())
     }
 
@@ -170,12 +338,20 @@ impl MeasureModel<(SndUnit, FwNode)> for ApogeeModel {
             Ok(true)
         } else if self.input_ctl.measure_params(elem_id, elem_value)? {
             Ok(true)
+        } else if self.read_gain_staging(elem_id, elem_value) {
+            Ok(true)
         } else {
             Ok(false)
         }
     }
 }
 
+impl ApogeeModel {
+    pub fn update_rate_labels(&mut self, card_cntr: &mut CardCntr) -> Result<bool, Error> {
+        self.common_ctl.update_rate_labels(card_cntr)
+    }
+}
+
 impl NotifyModel<(SndUnit, FwNode), bool> for ApogeeModel {
     fn get_notified_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
         elem_id_list.extend_from_slice(&self.common_ctl.notified_elem_list);
@@ -187,12 +363,17 @@ impl NotifyModel<(SndUnit, FwNode), bool> for ApogeeModel {
 
     fn read_notified_elem(
         &mut self,
-        _: &(SndUnit, FwNode),
+        unit: &(SndUnit, FwNode),
         elem_id: &ElemId,
         elem_value: &mut ElemValue,
     ) -> Result<bool, Error> {
-        self.common_ctl
-            .read(&self.avc, elem_id, elem_value, Self::FCP_TIMEOUT_MS)
+        self.common_ctl.read_notified_elem(
+            unit,
+            &self.avc,
+            elem_id,
+            elem_value,
+            Self::FCP_TIMEOUT_MS,
+        )
     }
 }
 
@@ -15,12 +15,17 @@ pub struct CommonCtl<O: Ta1394Avc<Error>> {
     input_fmt_entries: Vec<CompoundAm824Stream>,
     supported_rates: Vec<u32>,
     assumed: bool,
+    rate_mismatch: bool,
+    rates_changed: bool,
     pub notified_elem_list: Vec<ElemId>,
     _phantom: PhantomData<O>,
 }
 
 impl<O: Ta1394Avc<Error>> CommonCtl<O> {
     const CLK_RATE_NAME: &'static str = "sampling-rate";
+    const RATE_MISMATCH_NAME: &'static str = "sampling-rate-mismatch";
+
+    const REPROBE_FEATURE_NAME: &'static str = "stream-format-reprobe";
 
     const SUPPORTED_RATES: &'static [u32] = &[32000, 44100, 48000, 88200, 96000, 176400, 192000];
 
@@ -52,6 +57,22 @@ impl<O: Ta1394Avc<Error>> CommonCtl<O> {
                 self.detect_stream_formats(avc, PlugDirection::Input, timeout_ms)?;
         }
 
+        self.supported_rates = self.collect_supported_rates();
+        let labels = self.rate_labels();
+
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, Self::CLK_RATE_NAME, 0);
+        let mut elem_id_list = card_cntr.add_enum_elems(&elem_id, 1, 1, &labels, None, true)?;
+        self.notified_elem_list.append(&mut elem_id_list);
+
+        // The element is not unlocked so that the other processes can not change it.
+        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, Self::RATE_MISMATCH_NAME, 0);
+        let mut elem_id_list = card_cntr.add_bool_elems(&elem_id, 1, 1, false)?;
+        self.notified_elem_list.append(&mut elem_id_list);
+
+        Ok(())
+    }
+
+    fn collect_supported_rates(&self) -> Vec<u32> {
         let mut rates = Vec::new();
         self.output_fmt_entries.iter().for_each(|entry| {
             if rates.iter().find(|&rate| *rate == entry.freq).is_none() {
@@ -64,40 +85,129 @@ impl<O: Ta1394Avc<Error>> CommonCtl<O> {
             }
         });
         rates.sort();
-        self.supported_rates = rates;
+        rates
+    }
 
-        let labels = self
-            .supported_rates
+    fn rate_labels(&self) -> Vec<String> {
+        self.supported_rates
             .iter()
             .map(|rate| rate.to_string())
-            .collect::<Vec<String>>();
+            .collect()
+    }
 
-        let elem_id = ElemId::new_by_name(ElemIfaceType::Card, 0, 0, Self::CLK_RATE_NAME, 0);
-        let mut elem_id_list = card_cntr.add_enum_elems(&elem_id, 1, 1, &labels, None, true)?;
-        self.notified_elem_list.append(&mut elem_id_list);
+    // The available stream formats can change after changing sampling rate. Detect them again
+    // so that the list of supported rates is not stale.
+    fn reprobe_stream_formats(&mut self, avc: &O, timeout_ms: u32) -> Result<(), Error> {
+        if self.output_fmt_entries.len() > 0 {
+            self.output_fmt_entries =
+                self.detect_stream_formats(avc, PlugDirection::Output, timeout_ms)?;
+        }
+        if self.input_fmt_entries.len() > 0 {
+            self.input_fmt_entries =
+                self.detect_stream_formats(avc, PlugDirection::Input, timeout_ms)?;
+        }
+
+        let rates = self.collect_supported_rates();
+        if rates != self.supported_rates {
+            self.supported_rates = rates;
+            self.rates_changed = true;
+        }
 
         Ok(())
     }
 
-    fn read_freq(&self, avc: &O, timeout_ms: u32) -> Result<usize, Error> {
+    /// Update the labels of element for sampling rate when the list of supported rates is
+    /// changed by the former operation to change sampling rate.
+    pub fn update_rate_labels(&mut self, card_cntr: &mut CardCntr) -> Result<bool, Error> {
+        if !self.rates_changed {
+            return Ok(false);
+        }
+        self.rates_changed = false;
+
+        let pos = self
+            .notified_elem_list
+            .iter()
+            .position(|elem_id| elem_id.name().as_str() == Self::CLK_RATE_NAME)
+            .ok_or_else(|| Error::new(FileError::Noent, "Sampling rate element is not added"))?;
+        let elem_id = self.notified_elem_list.remove(pos);
+
+        let labels = self.rate_labels();
+        let mut elem_id_list = card_cntr.update_enum_labels(&elem_id, &labels)?;
+        self.notified_elem_list.append(&mut elem_id_list);
+
+        Ok(true)
+    }
+
+    fn read_fdf_freq(&self, avc: &O, timeout_ms: u32) -> Result<u32, Error> {
         // For playback direction.
         let mut op = InputPlugSignalFormat::new(0);
         avc.status(&AvcAddr::Unit, &mut op, timeout_ms)
             .map_err(|err| from_avc_err(err))?;
         let fdf = AmdtpFdf::from(op.0.fdf.as_ref());
+        Ok(fdf.freq)
+    }
 
-        if let Some(pos) = self
-            .supported_rates
-            .iter()
-            .position(|rate| *rate == fdf.freq)
-        {
+    fn read_freq(&self, avc: &O, timeout_ms: u32) -> Result<usize, Error> {
+        let freq = self.read_fdf_freq(avc, timeout_ms)?;
+
+        if let Some(pos) = self.supported_rates.iter().position(|rate| *rate == freq) {
             Ok(pos)
         } else {
-            let label = format!("Unsupported sampling rate: {}", fdf.freq);
+            let label = format!("Unsupported sampling rate: {}", freq);
             Err(Error::new(FileError::Io, &label))
         }
     }
 
+    // Read the rate of stream transmitted by the device. It follows the media clock of device,
+    // while the rate in FDF field of signal format for playback is just configured by host.
+    fn read_transmitted_freq(&self, avc: &O, timeout_ms: u32) -> Result<Option<u32>, Error> {
+        if self.output_fmt_entries.is_empty() {
+            return Ok(None);
+        }
+
+        let plug_addr = PlugAddr {
+            direction: PlugDirection::Output,
+            mode: PlugAddrMode::Unit(UnitPlugData {
+                unit_type: UnitPlugType::Pcr,
+                plug_id: 0,
+            }),
+        };
+        let mut op = ExtendedStreamFormatSingle::new(&plug_addr);
+        avc.status(&AvcAddr::Unit, &mut op, timeout_ms)
+            .map_err(|err| from_avc_err(err))?;
+
+        op.stream_format
+            .as_compound_am824_stream()
+            .map(|stream_format| Some(stream_format.freq))
+            .ok_or(Error::new(
+                FileError::Nxio,
+                "Compound AM824 stream formats are not available",
+            ))
+    }
+
+    // The device is clocked externally at the rate different from the rate of PCM substreams, then
+    // it transfers white noise without any error. Detect it by comparing the rate of stream
+    // transmitted by the device with the rate of PCM substreams.
+    fn detect_rate_mismatch(
+        &self,
+        unit: &(SndUnit, FwNode),
+        avc: &O,
+        timeout_ms: u32,
+    ) -> Result<bool, Error> {
+        if !unit.0.is_locked() {
+            return Ok(false);
+        }
+
+        let freq = match self.read_transmitted_freq(avc, timeout_ms)? {
+            Some(freq) => freq,
+            None => return Ok(false),
+        };
+        let mismatch = configured_pcm_rates(unit.0.card_id())
+            .into_iter()
+            .any(|rate| rate != freq);
+        Ok(mismatch)
+    }
+
     pub fn read(
         &mut self,
         avc: &O,
@@ -113,10 +223,29 @@ impl<O: Ta1394Avc<Error>> CommonCtl<O> {
                 })?;
                 Ok(true)
             }
+            Self::RATE_MISMATCH_NAME => {
+                elem_value.set_bool(&[self.rate_mismatch]);
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
 
+    pub fn read_notified_elem(
+        &mut self,
+        unit: &(SndUnit, FwNode),
+        avc: &O,
+        elem_id: &ElemId,
+        elem_value: &mut ElemValue,
+        timeout_ms: u32,
+    ) -> Result<bool, Error> {
+        if elem_id.name().as_str() == Self::RATE_MISMATCH_NAME {
+            self.rate_mismatch = self.detect_rate_mismatch(unit, avc, timeout_ms)?;
+        }
+
+        self.read(avc, elem_id, elem_value, timeout_ms)
+    }
+
     fn write_freq_for_fallback_mode(
         &self,
         avc: &O,
@@ -224,7 +353,14 @@ impl<O: Ta1394Avc<Error>> CommonCtl<O> {
             Self::CLK_RATE_NAME => {
                 ElemValueAccessor::<u32>::get_val(elem_value, |val| {
                     unit.0.lock()?;
-                    let res = self.write_freq(avc, val as usize, timeout_ms);
+                    let res = self.write_freq(avc, val as usize, timeout_ms).map(|_| {
+                        // The sampling rate is already changed, thus the failure is just
+                        // recorded.
+                        if let Err(e) = self.reprobe_stream_formats(avc, timeout_ms) {
+                            let msg = format!("failed: {}", e);
+                            report_feature(Self::REPROBE_FEATURE_NAME, &msg);
+                        }
+                    });
                     let _ = unit.0.unlock();
                     res
                 })?;
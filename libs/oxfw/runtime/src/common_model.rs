@@ -61,6 +61,12 @@ impl CtlModel<(SndUnit, FwNode)> for CommonModel {
     }
 }
 
+impl CommonModel {
+    pub fn update_rate_labels(&mut self, card_cntr: &mut CardCntr) -> Result<bool, Error> {
+        self.common_ctl.update_rate_labels(card_cntr)
+    }
+}
+
 impl NotifyModel<(SndUnit, FwNode), bool> for CommonModel {
     fn get_notified_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
         elem_id_list.extend_from_slice(&self.common_ctl.notified_elem_list);
@@ -72,11 +78,16 @@ impl NotifyModel<(SndUnit, FwNode), bool> for CommonModel {
 
     fn read_notified_elem(
         &mut self,
-        _: &(SndUnit, FwNode),
+        unit: &(SndUnit, FwNode),
         elem_id: &ElemId,
         elem_value: &mut ElemValue,
     ) -> Result<bool, Error> {
-        self.common_ctl
-            .read(&self.avc, elem_id, elem_value, Self::FCP_TIMEOUT_MS)
+        self.common_ctl.read_notified_elem(
+            unit,
+            &self.avc,
+            elem_id,
+            elem_value,
+            Self::FCP_TIMEOUT_MS,
+        )
     }
 }
@@ -13,7 +13,11 @@ mod common_ctl;
 use {
     alsactl::{prelude::*, *},
     common_ctl::*,
-    core::{card_cntr::*, dispatcher::*, elem_value_accessor::*, RuntimeOperation},
+    core::{
+        card_cntr::*, diagnostics::*, dispatcher::*, elem_alias::*, elem_locale::*,
+        elem_value_accessor::*, feature_report::*, meter_trigger::*, midi_activity::*,
+        panic_ctl::*, ref_level::*, scrub::*, stream_guard::*, RuntimeOperation,
+    },
     firewire_oxfw_protocols as protocols,
     glib::{source, Error, FileError},
     hinawa::{
@@ -36,6 +40,7 @@ enum Event {
     Elem((ElemId, ElemEventMask)),
     Timer,
     StreamLock(bool),
+    Scrub,
 }
 
 pub struct OxfwRuntime {
@@ -46,6 +51,14 @@ pub struct OxfwRuntime {
     tx: mpsc::SyncSender<Event>,
     dispatchers: Vec<Dispatcher>,
     timer: Option<Dispatcher>,
+    ref_level: RefLevel,
+    scrubber: Scrubber,
+    card_id: u32,
+    midi_activity: MidiActivity,
+    stream_guard: StreamGuard,
+    feature_report: FeatureReport,
+    panic_ctl: PanicCtl,
+    meter_trigger: MeterTrigger,
 }
 
 impl Drop for OxfwRuntime {
@@ -65,7 +78,7 @@ impl Drop for OxfwRuntime {
 
 impl<'a> RuntimeOperation<u32> for OxfwRuntime {
     fn new(card_id: u32) -> Result<Self, Error> {
-        let cdev = format!("/dev/snd/hwC{}D0", card_id);
+        let cdev = Self::devnode_path(&format!("hwC{}D0", card_id));
         let unit = SndUnit::new();
         unit.open(&cdev, 0)?;
 
@@ -74,7 +87,7 @@ impl<'a> RuntimeOperation<u32> for OxfwRuntime {
             return Err(Error::new(FileError::Inval, label));
         }
 
-        let cdev = format!("/dev/{}", unit.node_device().unwrap());
+        let cdev = Self::devnode_path(&unit.node_device().unwrap());
         let node = FwNode::new();
         node.open(&cdev)?;
 
@@ -108,6 +121,14 @@ impl<'a> RuntimeOperation<u32> for OxfwRuntime {
             tx,
             dispatchers: Vec::new(),
             timer: None,
+            ref_level: Default::default(),
+            scrubber: Default::default(),
+            card_id,
+            midi_activity: Default::default(),
+            stream_guard: Default::default(),
+            feature_report: Default::default(),
+            panic_ctl: Default::default(),
+            meter_trigger: Default::default(),
         })
     }
 
@@ -115,13 +136,44 @@ impl<'a> RuntimeOperation<u32> for OxfwRuntime {
         self.launch_node_event_dispatcher()?;
         self.launch_system_event_dispatcher()?;
 
+        setup_label_translations(&mut self.card_cntr);
+
         self.model.load(&mut self.unit, &mut self.card_cntr)?;
 
-        if self.model.measure_elem_list.len() > 0 {
+        self.midi_activity.load(&mut self.card_cntr, self.card_id)?;
+
+        if self.model.measure_elem_list.len() > 0 || self.midi_activity.has_ports() {
             let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, Self::TIMER_NAME, 0);
             let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
         }
 
+        self.ref_level
+            .load(&mut self.card_cntr, &self.model.measure_elem_list)?;
+
+        self.stream_guard.load(
+            &mut self.card_cntr,
+            self.card_id,
+            &Self::STREAM_CRITICAL_ELEMS,
+        )?;
+
+        // The verification is optional, thus any failure is not fatal.
+        match scrub_interval_from_env(Self::FAMILY_NAME) {
+            Ok(Some(interval)) => self.launch_scrub_dispatcher(interval)?,
+            Ok(None) => (),
+            Err(e) => report_feature(SCRUB_FEATURE_NAME, &e.to_string()),
+        }
+
+        self.panic_ctl
+            .load(&mut self.card_cntr, &Self::PANIC_TARGETS)?;
+
+        self.meter_trigger
+            .setup(&self.card_cntr, Self::METER_TRIGGER_CLIENT_NAME);
+
+        setup_aliases(&mut self.card_cntr);
+
+        self.feature_report.load(&mut self.card_cntr)?;
+        self.feature_report.update(&mut self.card_cntr)?;
+
         Ok(())
     }
 
@@ -139,6 +191,35 @@ impl<'a> RuntimeOperation<u32> for OxfwRuntime {
                     println!("IEEE 1394 bus is updated: {}", generation);
                 }
                 Event::Elem((elem_id, events)) => {
+                    if self
+                        .ref_level
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .panic_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .stream_guard
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self.feature_report.has_elem(&elem_id) {
+                        // The change of value is by the runtime itself.
+                        continue;
+                    }
+
                     if elem_id.name() != Self::TIMER_NAME {
                         let _ = self.model.dispatch_elem_event(
                             &mut self.unit,
@@ -146,6 +227,7 @@ impl<'a> RuntimeOperation<u32> for OxfwRuntime {
                             &elem_id,
                             &events,
                         );
+                        let _ = self.feature_report.update(&mut self.card_cntr);
                     } else {
                         let mut elem_value = ElemValue::new();
                         if self
@@ -167,6 +249,9 @@ impl<'a> RuntimeOperation<u32> for OxfwRuntime {
                     let _ = self
                         .model
                         .measure_elems(&mut self.unit, &mut self.card_cntr);
+                    let _ = self.midi_activity.measure(&mut self.card_cntr);
+                    self.meter_trigger
+                        .check_and_report(&mut self.card_cntr, &self.feature_report);
                 }
                 Event::StreamLock(locked) => {
                     let _ = self.model.dispatch_notification(
@@ -174,21 +259,61 @@ impl<'a> RuntimeOperation<u32> for OxfwRuntime {
                         &mut self.card_cntr,
                         locked,
                     );
+                    let _ = self.feature_report.update(&mut self.card_cntr);
                 }
+                Event::Scrub => self.scrub_elems(),
             }
         }
         Ok(())
     }
 }
 
+impl DiagnosticsOperation<u32> for OxfwRuntime {
+    fn collect_diagnostics(card_id: &u32) -> Result<Vec<DiagnosticsEntry>, Error> {
+        collect_common_diagnostics(*card_id)
+    }
+}
+
 impl<'a> OxfwRuntime {
     const NODE_DISPATCHER_NAME: &'a str = "node event dispatcher";
     const SYSTEM_DISPATCHER_NAME: &'a str = "system event dispatcher";
     const TIMER_DISPATCHER_NAME: &'a str = "interval timer dispatcher";
 
     const TIMER_NAME: &'a str = "metering";
+    const METER_TRIGGER_CLIENT_NAME: &'static str = "OXFW meter trigger";
     const TIMER_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
 
+    const FAMILY_NAME: &'a str = "oxfw";
+
+    // The controls critical to packet streaming, of which change is rejected while PCM
+    // substreams are running.
+    const STREAM_CRITICAL_ELEMS: [&'a str; 1] = ["sampling-rate"];
+
+    // The elements reset by panic switch.
+    const PANIC_TARGETS: [PanicTarget; 5] = [
+        PanicTarget {
+            name: "output-mute",
+            state: Some(true),
+        },
+        PanicTarget {
+            name: "PCM Playback Switch",
+            state: Some(false),
+        },
+        PanicTarget {
+            name: "mixer-source-gain",
+            state: None,
+        },
+        PanicTarget {
+            name: "output-volume",
+            state: None,
+        },
+        PanicTarget {
+            name: "PCM Playback Volume",
+            state: None,
+        },
+    ];
+    const SCRUB_DISPATCHER_NAME: &'a str = "scrub dispatcher";
+
     fn launch_node_event_dispatcher(&mut self) -> Result<(), Error> {
         let name = Self::NODE_DISPATCHER_NAME.to_string();
         let mut dispatcher = Dispatcher::run(name)?;
@@ -249,6 +374,43 @@ impl<'a> OxfwRuntime {
         Ok(())
     }
 
+    fn launch_scrub_dispatcher(&mut self, interval: std::time::Duration) -> Result<(), Error> {
+        let name = Self::SCRUB_DISPATCHER_NAME.to_string();
+        let mut dispatcher = Dispatcher::run(name)?;
+
+        let tx = self.tx.clone();
+        dispatcher.attach_interval_handler(interval, move || {
+            let _ = tx.send(Event::Scrub);
+            source::Continue(true)
+        });
+
+        self.dispatchers.push(dispatcher);
+
+        Ok(())
+    }
+
+    // Read the parameters from hardware again for the subset of elements except for meters.
+    fn scrub_elems(&mut self) {
+        let elem_id_list: Vec<ElemId> = self
+            .card_cntr
+            .registered_elem_id_list()
+            .into_iter()
+            .filter(|elem_id| !self.model.measure_elem_list.contains(elem_id))
+            .collect();
+
+        self.scrubber
+            .next_batch(&elem_id_list)
+            .iter()
+            .for_each(|elem_id| {
+                let _ = self.model.dispatch_elem_event(
+                    &mut self.unit,
+                    &mut self.card_cntr,
+                    elem_id,
+                    &ElemEventMask::ADD,
+                );
+            });
+    }
+
     fn start_interval_timer(&mut self) -> Result<(), Error> {
         let mut dispatcher = Dispatcher::run(Self::TIMER_DISPATCHER_NAME.to_string())?;
         let tx = self.tx.clone();
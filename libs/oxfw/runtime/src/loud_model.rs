@@ -70,6 +70,12 @@ impl CtlModel<(SndUnit, FwNode)> for LinkFwModel {
     }
 }
 
+impl LinkFwModel {
+    pub fn update_rate_labels(&mut self, card_cntr: &mut CardCntr) -> Result<bool, Error> {
+        self.common_ctl.update_rate_labels(card_cntr)
+    }
+}
+
 impl NotifyModel<(SndUnit, FwNode), bool> for LinkFwModel {
     fn get_notified_elem_list(&mut self, elem_id_list: &mut Vec<ElemId>) {
         elem_id_list.extend_from_slice(&self.common_ctl.notified_elem_list);
@@ -81,12 +87,12 @@ impl NotifyModel<(SndUnit, FwNode), bool> for LinkFwModel {
 
     fn read_notified_elem(
         &mut self,
-        _: &(SndUnit, FwNode),
+        unit: &(SndUnit, FwNode),
         elem_id: &ElemId,
         elem_value: &mut ElemValue,
     ) -> Result<bool, Error> {
         self.common_ctl
-            .read(&self.avc, elem_id, elem_value, FCP_TIMEOUT_MS)
+            .read_notified_elem(unit, &self.avc, elem_id, elem_value, FCP_TIMEOUT_MS)
     }
 }
 
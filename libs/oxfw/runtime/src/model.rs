@@ -32,6 +32,9 @@ impl OxfwModel {
             // Stanton Controllers & Systems 1 Deck (SCS.1d) has no audio functionality.
             (0x001260, 0x002000) => return Err(Error::new(FileError::Noent, "Not supported")),
             (0x000ff2, 0x000460) => OxfwCtlModel::TapcoLinkFw(Default::default()),
+            // NOTE: Behringer F-Control Audio 202 is operated by the common model. The dedicated
+            // support is declined until the layout of function blocks for playback volume and
+            // mute is investigated.
             _ => OxfwCtlModel::Common(Default::default()),
         };
         let model = OxfwModel {
@@ -61,6 +64,14 @@ impl OxfwModel {
             _ => (),
         }
 
+        self.collect_notified_elem_list();
+
+        Ok(())
+    }
+
+    fn collect_notified_elem_list(&mut self) {
+        self.notified_elem_list.clear();
+
         match &mut self.ctl_model {
             OxfwCtlModel::Fireone(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
             OxfwCtlModel::Duet(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
@@ -69,8 +80,6 @@ impl OxfwModel {
             OxfwCtlModel::TapcoLinkFw(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
             OxfwCtlModel::Common(m) => m.get_notified_elem_list(&mut self.notified_elem_list),
         }
-
-        Ok(())
     }
 
     pub fn dispatch_elem_event(
@@ -80,14 +89,29 @@ impl OxfwModel {
         elem_id: &alsactl::ElemId,
         events: &alsactl::ElemEventMask,
     ) -> Result<(), Error> {
-        match &mut self.ctl_model {
+        let res = match &mut self.ctl_model {
             OxfwCtlModel::Fireone(m) => card_cntr.dispatch_elem_event(unit, elem_id, events, m),
             OxfwCtlModel::Duet(m) => card_cntr.dispatch_elem_event(unit, elem_id, events, m),
             OxfwCtlModel::Firewave(m) => card_cntr.dispatch_elem_event(unit, elem_id, events, m),
             OxfwCtlModel::Speaker(m) => card_cntr.dispatch_elem_event(unit, elem_id, events, m),
             OxfwCtlModel::TapcoLinkFw(m) => card_cntr.dispatch_elem_event(unit, elem_id, events, m),
             OxfwCtlModel::Common(m) => card_cntr.dispatch_elem_event(unit, elem_id, events, m),
+        };
+
+        // The change of sampling rate can change the list of supported rates.
+        let updated = match &mut self.ctl_model {
+            OxfwCtlModel::Fireone(m) => m.update_rate_labels(card_cntr),
+            OxfwCtlModel::Duet(m) => m.update_rate_labels(card_cntr),
+            OxfwCtlModel::Firewave(m) => m.update_rate_labels(card_cntr),
+            OxfwCtlModel::Speaker(m) => m.update_rate_labels(card_cntr),
+            OxfwCtlModel::TapcoLinkFw(m) => m.update_rate_labels(card_cntr),
+            OxfwCtlModel::Common(m) => m.update_rate_labels(card_cntr),
+        }?;
+        if updated {
+            self.collect_notified_elem_list();
         }
+
+        res
     }
 
     pub fn measure_elems(
@@ -259,6 +259,94 @@ impl AvcStatus for VendorDependent {
     }
 }
 
+/// The schema of data specific to vendor in AV/C VENDOR-DEPENDENT command.
+///
+/// The data follows company ID in operands. The implementation is operated as AV/C control and
+/// status command by `VendorDependentOperation`, thus the protocol implementation for vendor
+/// declares the layout of data only.
+pub trait VendorDependentSchema {
+    /// The company ID of vendor.
+    const COMPANY_ID: [u8; 3];
+
+    /// Build data for control command.
+    fn build_control_data(&self, data: &mut Vec<u8>) -> Result<(), AvcCmdBuildError>;
+
+    /// Parse data in response of control command.
+    fn parse_control_data(&mut self, _: &[u8]) -> Result<(), AvcRespParseError> {
+        Ok(())
+    }
+
+    /// Build data for status command. Some vendors define no status command.
+    fn build_status_data(&self, _: &mut Vec<u8>) -> Result<(), AvcCmdBuildError> {
+        Err(AvcCmdBuildError::InvalidOperands)
+    }
+
+    /// Parse data in response of status command.
+    fn parse_status_data(&mut self, _: &[u8]) -> Result<(), AvcRespParseError> {
+        Ok(())
+    }
+}
+
+/// AV/C VENDOR-DEPENDENT command with data in the schema specific to vendor.
+#[derive(Debug)]
+pub struct VendorDependentOperation<T: VendorDependentSchema> {
+    pub cmd: T,
+    op: VendorDependent,
+}
+
+impl<T: VendorDependentSchema> VendorDependentOperation<T> {
+    pub fn new(cmd: T) -> Self {
+        Self {
+            cmd,
+            op: VendorDependent::new(&T::COMPANY_ID),
+        }
+    }
+}
+
+impl<T: VendorDependentSchema + Default> Default for VendorDependentOperation<T> {
+    fn default() -> Self {
+        Self::new(Default::default())
+    }
+}
+
+impl<T: VendorDependentSchema> AvcOp for VendorDependentOperation<T> {
+    const OPCODE: u8 = VendorDependent::OPCODE;
+}
+
+impl<T: VendorDependentSchema> AvcControl for VendorDependentOperation<T> {
+    fn build_operands(
+        &mut self,
+        addr: &AvcAddr,
+        operands: &mut Vec<u8>,
+    ) -> Result<(), AvcCmdBuildError> {
+        self.op.data.clear();
+        self.cmd.build_control_data(&mut self.op.data)?;
+        AvcControl::build_operands(&mut self.op, addr, operands)
+    }
+
+    fn parse_operands(&mut self, addr: &AvcAddr, operands: &[u8]) -> Result<(), AvcRespParseError> {
+        AvcControl::parse_operands(&mut self.op, addr, operands)?;
+        self.cmd.parse_control_data(&self.op.data)
+    }
+}
+
+impl<T: VendorDependentSchema> AvcStatus for VendorDependentOperation<T> {
+    fn build_operands(
+        &mut self,
+        addr: &AvcAddr,
+        operands: &mut Vec<u8>,
+    ) -> Result<(), AvcCmdBuildError> {
+        self.op.data.clear();
+        self.cmd.build_status_data(&mut self.op.data)?;
+        AvcStatus::build_operands(&mut self.op, addr, operands)
+    }
+
+    fn parse_operands(&mut self, addr: &AvcAddr, operands: &[u8]) -> Result<(), AvcRespParseError> {
+        AvcStatus::parse_operands(&mut self.op, addr, operands)?;
+        self.cmd.parse_status_data(&self.op.data)
+    }
+}
+
 /// The data of unit plugs for isochronous and external inputs/outputs.
 #[derive(Debug)]
 pub struct PlugInfoUnitIsocExtData {
@@ -655,6 +743,55 @@ mod test {
         );
     }
 
+    #[derive(Default, Debug)]
+    struct TestSchema(u8);
+
+    impl VendorDependentSchema for TestSchema {
+        const COMPANY_ID: [u8; 3] = [0x00, 0x01, 0x02];
+
+        fn build_control_data(&self, data: &mut Vec<u8>) -> Result<(), AvcCmdBuildError> {
+            data.extend_from_slice(&[0xde, self.0]);
+            Ok(())
+        }
+
+        fn build_status_data(&self, data: &mut Vec<u8>) -> Result<(), AvcCmdBuildError> {
+            data.extend_from_slice(&[0xde, 0xff]);
+            Ok(())
+        }
+
+        fn parse_status_data(&mut self, data: &[u8]) -> Result<(), AvcRespParseError> {
+            if data.len() < 2 {
+                Err(AvcRespParseError::TooShortResp(5))
+            } else if data[0] != 0xde {
+                Err(AvcRespParseError::UnexpectedOperands(3))
+            } else {
+                self.0 = data[1];
+                Ok(())
+            }
+        }
+    }
+
+    #[test]
+    fn vendor_dependent_schema_operands() {
+        let mut op = VendorDependentOperation::new(TestSchema(0x01));
+        let mut operands = Vec::new();
+        AvcControl::build_operands(&mut op, &AvcAddr::Unit, &mut operands).unwrap();
+        assert_eq!(&operands, &[0x00, 0x01, 0x02, 0xde, 0x01]);
+        AvcControl::parse_operands(&mut op, &AvcAddr::Unit, &operands).unwrap();
+
+        let mut op = VendorDependentOperation::new(TestSchema::default());
+        let mut operands = Vec::new();
+        AvcStatus::build_operands(&mut op, &AvcAddr::Unit, &mut operands).unwrap();
+        assert_eq!(&operands, &[0x00, 0x01, 0x02, 0xde, 0xff]);
+
+        let operands = [0x00, 0x01, 0x02, 0xde, 0x10];
+        AvcStatus::parse_operands(&mut op, &AvcAddr::Unit, &operands).unwrap();
+        assert_eq!(op.cmd.0, 0x10);
+
+        let operands = [0x00, 0x01, 0x02, 0xad, 0x10];
+        assert!(AvcStatus::parse_operands(&mut op, &AvcAddr::Unit, &operands).is_err());
+    }
+
     #[test]
     fn vendor_dependent_operands() {
         let company_id = [0x00, 0x01, 0x02];
@@ -558,6 +558,10 @@ const MASTER_FADER_ASSIGNS: [(bool, u32, u32); 2] = [
     (true, 0x00000000, 0x00004000),
 ];
 
+// NOTE: The console models have no internal mixer adjustable by encoders and buttons in
+// equalizer section. The mode of monitor just selects the source of monitor output, thus the
+// events of surface are always delivered to host.
+
 /// The trait for operation of console model.
 pub trait IsochConsoleOperation {
     fn parse_console_state(state: &mut IsochConsoleState, image: &[u32]) -> Result<(), Error> {
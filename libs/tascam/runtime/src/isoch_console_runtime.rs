@@ -5,10 +5,17 @@ use {
     super::{fw1082_model::*, fw1884_model::*, seq_cntr::*, *},
     alsactl::{prelude::*, *},
     alsaseq::{prelude::*, *},
-    core::dispatcher::*,
+    core::{
+        dispatcher::*, elem_alias::*, elem_locale::*, feature_report::*, meter_trigger::*,
+        stream_guard::*,
+    },
     nix::sys::signal,
     protocols::isoch::{fw1082::*, fw1884::*},
-    std::{marker::PhantomData, sync::mpsc, time::Duration},
+    std::{
+        marker::PhantomData,
+        sync::mpsc,
+        time::{Duration, Instant},
+    },
 };
 
 pub type Fw1884Runtime = IsochConsoleRuntime<Fw1884Model, Fw1884Protocol, Fw1884SurfaceState>;
@@ -31,6 +38,10 @@ where
     dispatchers: Vec<Dispatcher>,
     timer: Option<Dispatcher>,
     measure_elems: Vec<ElemId>,
+    stream_guard: StreamGuard,
+    feature_report: FeatureReport,
+    meter_trigger: MeterTrigger,
+    debouncer: EventDebouncer<ElemId>,
     _phantom0: PhantomData<T>,
     _phantom1: PhantomData<U>,
 }
@@ -64,8 +75,16 @@ const SYSTEM_DISPATCHER_NAME: &str = "system event dispatcher";
 const TIMER_DISPATCHER_NAME: &str = "interval timer dispatcher";
 
 const TIMER_NAME: &str = "metering";
+const METER_TRIGGER_CLIENT_NAME: &str = "TASCAM meter trigger";
 const TIMER_INTERVAL: Duration = Duration::from_millis(50);
 
+// The controls critical to packet streaming, of which change is rejected while PCM substreams are
+// running.
+const STREAM_CRITICAL_ELEMS: [&str; 1] = ["clock-rate"];
+
+// The window to coalesce bursts of events for the same element from encoders in surface.
+const ELEM_EVENT_DEBOUNCE_WINDOW: Duration = Duration::from_millis(20);
+
 impl<S, T, U> IsochConsoleRuntime<S, T, U>
 where
     S: CtlModel<(SndTascam, FwNode)>
@@ -93,6 +112,10 @@ where
             dispatchers: Default::default(),
             timer: Default::default(),
             measure_elems: Default::default(),
+            stream_guard: Default::default(),
+            feature_report: Default::default(),
+            meter_trigger: Default::default(),
+            debouncer: EventDebouncer::new(ELEM_EVENT_DEBOUNCE_WINDOW),
             _phantom0: Default::default(),
             _phantom1: Default::default(),
         })
@@ -104,83 +127,138 @@ where
 
         self.seq_cntr.open_port()?;
         self.model.initialize_sequencer(&mut self.unit.1)?;
+        setup_label_translations(&mut self.card_cntr);
+
         self.model.load(&mut self.unit, &mut self.card_cntr)?;
 
         let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, TIMER_NAME, 0);
         let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
         self.model.get_measure_elem_list(&mut self.measure_elems);
 
+        let card_id = self.unit.0.card_id();
+        self.stream_guard
+            .load(&mut self.card_cntr, card_id, &STREAM_CRITICAL_ELEMS)?;
+
+        self.meter_trigger
+            .setup(&self.card_cntr, METER_TRIGGER_CLIENT_NAME);
+
+        setup_aliases(&mut self.card_cntr);
+
+        self.feature_report.load(&mut self.card_cntr)?;
+        self.feature_report.update(&mut self.card_cntr)?;
+
         Ok(())
     }
 
     pub fn run(&mut self) -> Result<(), Error> {
         loop {
-            let ev = match self.rx.recv() {
-                Ok(ev) => ev,
-                Err(_) => continue,
+            let ev = match self.debouncer.timeout(Instant::now()) {
+                Some(timeout) => self.rx.recv_timeout(timeout).ok(),
+                None => self.rx.recv().ok(),
             };
 
-            match ev {
-                ConsoleUnitEvent::Shutdown => break,
-                ConsoleUnitEvent::Disconnected => break,
-                ConsoleUnitEvent::BusReset(generation) => {
-                    println!("IEEE 1394 bus is updated: {}", generation);
-                }
-                ConsoleUnitEvent::Elem((elem_id, events)) => {
-                    if elem_id.name() != TIMER_NAME {
-                        let _ = self.card_cntr.dispatch_elem_event(
-                            &mut self.unit,
+            if let Some(ev) = ev {
+                match ev {
+                    ConsoleUnitEvent::Shutdown => break,
+                    ConsoleUnitEvent::Disconnected => break,
+                    ConsoleUnitEvent::BusReset(generation) => {
+                        println!("IEEE 1394 bus is updated: {}", generation);
+                    }
+                    ConsoleUnitEvent::Elem((elem_id, events)) => {
+                        if self.feature_report.has_elem(&elem_id) {
+                            // The change of value is by the runtime itself.
+                            continue;
+                        }
+
+                        if self.stream_guard.handle_elem_event(
+                            &mut self.card_cntr,
                             &elem_id,
                             &events,
-                            &mut self.model,
-                        );
-                    } else {
-                        let mut elem_value = ElemValue::new();
-                        if self
-                            .card_cntr
-                            .card
-                            .read_elem_value(&elem_id, &mut elem_value)
-                            .is_ok()
-                        {
-                            let val = elem_value.boolean()[0];
-                            if val {
-                                let _ = self.start_interval_timer();
+                        ) {
+                            let _ = self.feature_report.update(&mut self.card_cntr);
+                            continue;
+                        }
+
+                        if elem_id.name() != TIMER_NAME {
+                            if events == ElemEventMask::VALUE {
+                                self.debouncer.push(elem_id, Instant::now());
                             } else {
-                                self.stop_interval_timer();
+                                let _ = self.debouncer.remove(&elem_id);
+                                let _ = self.card_cntr.dispatch_elem_event(
+                                    &mut self.unit,
+                                    &elem_id,
+                                    &events,
+                                    &mut self.model,
+                                );
+                                let _ = self.feature_report.update(&mut self.card_cntr);
+                            }
+                        } else {
+                            let mut elem_value = ElemValue::new();
+                            if self
+                                .card_cntr
+                                .card
+                                .read_elem_value(&elem_id, &mut elem_value)
+                                .is_ok()
+                            {
+                                let val = elem_value.boolean()[0];
+                                if val {
+                                    let _ = self.start_interval_timer();
+                                } else {
+                                    self.stop_interval_timer();
+                                }
                             }
                         }
                     }
-                }
-                ConsoleUnitEvent::Interval => {
-                    let _ = self.card_cntr.measure_elems(
-                        &mut self.unit,
-                        &self.measure_elems,
-                        &mut self.model,
-                    );
-                }
-                ConsoleUnitEvent::SeqAppl(events) => {
-                    let _ = self.model.dispatch_appl_events(
-                        &mut self.unit.1,
-                        &mut self.seq_cntr,
-                        &events,
-                    );
-                }
-                ConsoleUnitEvent::Surface((index, before, after)) => {
-                    let _ = self.model.dispatch_surface_event(
-                        &mut self.unit.0,
-                        &mut self.unit.1,
-                        &mut self.seq_cntr,
-                        index,
-                        before,
-                        after,
-                    );
+                    ConsoleUnitEvent::Interval => {
+                        let _ = self.card_cntr.measure_elems(
+                            &mut self.unit,
+                            &self.measure_elems,
+                            &mut self.model,
+                        );
+                        self.meter_trigger
+                            .check_and_report(&mut self.card_cntr, &self.feature_report);
+                    }
+                    ConsoleUnitEvent::SeqAppl(events) => {
+                        let _ = self.model.dispatch_appl_events(
+                            &mut self.unit.1,
+                            &mut self.seq_cntr,
+                            &events,
+                        );
+                    }
+                    ConsoleUnitEvent::Surface((index, before, after)) => {
+                        let _ = self.model.dispatch_surface_event(
+                            &mut self.unit.0,
+                            &mut self.unit.1,
+                            &mut self.seq_cntr,
+                            index,
+                            before,
+                            after,
+                        );
+                    }
                 }
             }
+
+            self.dispatch_debounced_elem_events();
         }
 
         Ok(())
     }
 
+    fn dispatch_debounced_elem_events(&mut self) {
+        let elem_id_list = self.debouncer.pop_expired(Instant::now());
+        elem_id_list.iter().for_each(|elem_id| {
+            let _ = self.card_cntr.dispatch_elem_event(
+                &mut self.unit,
+                elem_id,
+                &ElemEventMask::VALUE,
+                &mut self.model,
+            );
+        });
+        if elem_id_list.len() > 0 {
+            let _ = self.feature_report.update(&mut self.card_cntr);
+        }
+    }
+
     fn launch_node_event_dispatcher(&mut self) -> Result<(), Error> {
         let name = NODE_DISPATCHER_NAME.to_string();
         let mut dispatcher = Dispatcher::run(name)?;
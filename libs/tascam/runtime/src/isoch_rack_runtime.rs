@@ -3,7 +3,10 @@
 use {
     super::{fw1804_model::*, *},
     alsactl::{prelude::*, *},
-    core::dispatcher::*,
+    core::{
+        dispatcher::*, elem_alias::*, elem_locale::*, feature_report::*, meter_trigger::*,
+        panic_ctl::*, stream_guard::*,
+    },
     nix::sys::signal,
     std::{sync::mpsc, time::Duration},
 };
@@ -21,6 +24,10 @@ pub struct IsochRackRuntime<
     dispatchers: Vec<Dispatcher>,
     timer: Option<Dispatcher>,
     measure_elems: Vec<ElemId>,
+    stream_guard: StreamGuard,
+    feature_report: FeatureReport,
+    panic_ctl: PanicCtl,
+    meter_trigger: MeterTrigger,
 }
 
 impl<T: CtlModel<(SndTascam, FwNode)> + MeasureModel<(SndTascam, FwNode)> + Default> Drop
@@ -53,8 +60,19 @@ const SYSTEM_DISPATCHER_NAME: &str = "system event dispatcher";
 const TIMER_DISPATCHER_NAME: &str = "interval timer dispatcher";
 
 const TIMER_NAME: &str = "meter";
+const METER_TRIGGER_CLIENT_NAME: &str = "TASCAM meter trigger";
 const TIMER_INTERVAL: Duration = Duration::from_millis(50);
 
+// The controls critical to packet streaming, of which change is rejected while PCM substreams are
+// running.
+const STREAM_CRITICAL_ELEMS: [&str; 1] = ["clock-rate"];
+
+// The elements reset by panic switch.
+const PANIC_TARGETS: [PanicTarget; 1] = [PanicTarget {
+    name: "input-gain",
+    state: None,
+}];
+
 impl<T: CtlModel<(SndTascam, FwNode)> + MeasureModel<(SndTascam, FwNode)> + Default>
     IsochRackRuntime<T>
 {
@@ -74,6 +92,10 @@ impl<T: CtlModel<(SndTascam, FwNode)> + MeasureModel<(SndTascam, FwNode)> + Defa
             dispatchers: Default::default(),
             timer: Default::default(),
             measure_elems: Default::default(),
+            stream_guard: Default::default(),
+            feature_report: Default::default(),
+            panic_ctl: Default::default(),
+            meter_trigger: Default::default(),
         })
     }
 
@@ -81,12 +103,28 @@ impl<T: CtlModel<(SndTascam, FwNode)> + MeasureModel<(SndTascam, FwNode)> + Defa
         self.launch_node_event_dispatcher()?;
         self.launch_system_event_dispatcher()?;
 
+        setup_label_translations(&mut self.card_cntr);
+
         self.model.load(&mut self.unit, &mut self.card_cntr)?;
 
         let elem_id = ElemId::new_by_name(ElemIfaceType::Mixer, 0, 0, TIMER_NAME, 0);
         let _ = self.card_cntr.add_bool_elems(&elem_id, 1, 1, true)?;
         self.model.get_measure_elem_list(&mut self.measure_elems);
 
+        let card_id = self.unit.0.card_id();
+        self.stream_guard
+            .load(&mut self.card_cntr, card_id, &STREAM_CRITICAL_ELEMS)?;
+
+        self.panic_ctl.load(&mut self.card_cntr, &PANIC_TARGETS)?;
+
+        self.meter_trigger
+            .setup(&self.card_cntr, METER_TRIGGER_CLIENT_NAME);
+
+        setup_aliases(&mut self.card_cntr);
+
+        self.feature_report.load(&mut self.card_cntr)?;
+        self.feature_report.update(&mut self.card_cntr)?;
+
         Ok(())
     }
 
@@ -104,6 +142,27 @@ impl<T: CtlModel<(SndTascam, FwNode)> + MeasureModel<(SndTascam, FwNode)> + Defa
                     println!("IEEE 1394 bus is updated: {}", generation);
                 }
                 RackUnitEvent::Elem((elem_id, events)) => {
+                    if self.feature_report.has_elem(&elem_id) {
+                        // The change of value is by the runtime itself.
+                        continue;
+                    }
+
+                    if self
+                        .panic_ctl
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
+                    if self
+                        .stream_guard
+                        .handle_elem_event(&mut self.card_cntr, &elem_id, &events)
+                    {
+                        let _ = self.feature_report.update(&mut self.card_cntr);
+                        continue;
+                    }
+
                     if elem_id.name() != TIMER_NAME {
                         let _ = self.card_cntr.dispatch_elem_event(
                             &mut self.unit,
@@ -111,6 +170,7 @@ impl<T: CtlModel<(SndTascam, FwNode)> + MeasureModel<(SndTascam, FwNode)> + Defa
                             &events,
                             &mut self.model,
                         );
+                        let _ = self.feature_report.update(&mut self.card_cntr);
                     } else {
                         let mut elem_value = ElemValue::new();
                         if self
@@ -134,6 +194,8 @@ impl<T: CtlModel<(SndTascam, FwNode)> + MeasureModel<(SndTascam, FwNode)> + Defa
                         &self.measure_elems,
                         &mut self.model,
                     );
+                    self.meter_trigger
+                        .check_and_report(&mut self.card_cntr, &self.feature_report);
                 }
             }
         }
@@ -16,7 +16,7 @@ mod seq_cntr;
 use {
     alsaseq::{prelude::*, *},
     asynch_runtime::*,
-    core::{card_cntr::*, RuntimeOperation},
+    core::{card_cntr::*, diagnostics::*, node_devices::*, RuntimeOperation},
     firewire_tascam_protocols as protocols,
     glib::{source, Error, FileError, IsA},
     hinawa::{
@@ -49,11 +49,20 @@ impl RuntimeOperation<(String, u32)> for TascamRuntime {
     fn new((subsystem, sysnum): (String, u32)) -> Result<Self, Error> {
         match subsystem.as_str() {
             "snd" => {
+                // The runtime for the first sound card of node serves it.
+                let devices = NodeDevices::discover(sysnum)?;
+                if let Some(card_id) = devices.primary_card().filter(|&id| id != sysnum) {
+                    let msg = format!(
+                        "The node {} is served by the runtime for sound card {}",
+                        devices.node_device, card_id
+                    );
+                    return Err(Error::new(FileError::Exist, &msg));
+                }
+
                 let unit = SndTascam::new();
-                let devnode = format!("/dev/snd/hwC{}D0", sysnum);
-                unit.open(&devnode, 0)?;
+                open_card_hwdep(sysnum, |devnode| unit.open(devnode, 0))?;
 
-                let devnode = format!("/dev/{}", unit.node_device().unwrap());
+                let devnode = Self::devnode_path(&unit.node_device().unwrap());
                 let node = FwNode::new();
                 node.open(&devnode)?;
 
@@ -82,7 +91,7 @@ impl RuntimeOperation<(String, u32)> for TascamRuntime {
             }
             "fw" => {
                 let node = FwNode::new();
-                let devnode = format!("/dev/fw{}", sysnum);
+                let devnode = Self::devnode_path(&format!("fw{}", sysnum));
                 node.open(&devnode)?;
 
                 let data = node.config_rom()?;
@@ -126,6 +135,29 @@ impl RuntimeOperation<(String, u32)> for TascamRuntime {
     }
 }
 
+impl DiagnosticsOperation<(String, u32)> for TascamRuntime {
+    fn collect_diagnostics(
+        (subsystem, sysnum): &(String, u32),
+    ) -> Result<Vec<DiagnosticsEntry>, Error> {
+        match subsystem.as_str() {
+            "snd" => collect_common_diagnostics(*sysnum),
+            "fw" => {
+                // The node is not bound to sound card, thus the content of configuration ROM is
+                // just collected.
+                let node = FwNode::new();
+                node.open(&Self::devnode_path(&format!("fw{}", sysnum)))?;
+                let mut raw = node.config_rom()?.to_vec();
+                redact_config_rom(&mut raw);
+                Ok(vec![DiagnosticsEntry::new("config-rom.bin", raw)])
+            }
+            _ => {
+                let label = "Invalid name of subsystem";
+                Err(Error::new(FileError::Nodev, &label))
+            }
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct SequencerState<U> {
     map: Vec<MachineItem>,
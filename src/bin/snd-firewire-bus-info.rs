@@ -0,0 +1,379 @@
+// SPDX-License-Identifier: GPL-3.0-or-later
+// Copyright (c) 2022 Takashi Sakamoto
+
+use {
+    glib::{FileError, MainContext, MainLoop},
+    hinawa::{
+        prelude::{FwNodeExt, FwNodeExtManual, FwReqExtManual},
+        FwNode, FwNodeError, FwReq, FwTcode,
+    },
+    std::{path::PathBuf, sync::Arc, thread},
+};
+
+const TIMEOUT_MS: u32 = 100;
+
+// CSR registers defined in IEEE 1394.
+const CSR_TOPOLOGY_MAP_OFFSET: u64 = 0xfffff0001000;
+const MAX_SELF_ID_COUNT: usize = 255;
+
+const SPEED_LABELS: [&str; 4] = ["S100", "S200", "S400", "S800"];
+
+/// The information in bus information block of configuration ROM.
+#[derive(Default, Debug)]
+struct BusInfo {
+    guid: u64,
+    max_rec: u32,
+    link_speed: u32,
+}
+
+impl BusInfo {
+    fn parse(raw: &[u8]) -> Option<Self> {
+        if raw.len() < 20 {
+            return None;
+        }
+
+        let mut quadlet = [0; 4];
+        quadlet.copy_from_slice(&raw[8..12]);
+        let bus_options = u32::from_be_bytes(quadlet);
+
+        quadlet.copy_from_slice(&raw[12..16]);
+        let guid_hi = u32::from_be_bytes(quadlet);
+        quadlet.copy_from_slice(&raw[16..20]);
+        let guid_lo = u32::from_be_bytes(quadlet);
+
+        Some(BusInfo {
+            guid: ((guid_hi as u64) << 32) | (guid_lo as u64),
+            max_rec: (bus_options >> 12) & 0x0f,
+            link_speed: bus_options & 0x07,
+        })
+    }
+
+    fn max_payload(&self) -> usize {
+        if self.max_rec > 0 {
+            1 << (self.max_rec + 1)
+        } else {
+            0
+        }
+    }
+}
+
+/// The content of self ID packet zero.
+#[derive(Default, Debug)]
+struct SelfId {
+    phy_id: u32,
+    link_active: bool,
+    gap_count: u32,
+    speed: u32,
+    contender: bool,
+}
+
+impl SelfId {
+    fn parse(quadlet: u32) -> Option<Self> {
+        // Extended self ID packets are not interesting here.
+        if quadlet >> 30 != 0x02 || quadlet & 0x00800000 > 0 {
+            None
+        } else {
+            Some(SelfId {
+                phy_id: (quadlet >> 24) & 0x3f,
+                link_active: quadlet & 0x00400000 > 0,
+                gap_count: (quadlet >> 16) & 0x3f,
+                speed: (quadlet >> 14) & 0x03,
+                contender: quadlet & 0x00000800 > 0,
+            })
+        }
+    }
+}
+
+struct NodeEntry {
+    path: String,
+    node: FwNode,
+    card: Option<PathBuf>,
+}
+
+fn speed_label(speed: u32) -> &'static str {
+    SPEED_LABELS
+        .iter()
+        .nth(speed as usize)
+        .map(|&label| label)
+        .unwrap_or("unknown")
+}
+
+// The parent device of the node in sysfs is the host controller of bus.
+fn detect_card(path: &str) -> Option<PathBuf> {
+    PathBuf::from(path).file_name().and_then(|name| {
+        let sysfs = PathBuf::from("/sys/bus/firewire/devices").join(name);
+        std::fs::canonicalize(sysfs)
+            .ok()
+            .and_then(|p| p.parent().map(|p| p.to_path_buf()))
+    })
+}
+
+fn open_node(path: &str) -> Result<FwNode, String> {
+    let node = FwNode::new();
+    node.open(path).map_err(|e| {
+        let cause = if let Some(error) = e.kind::<FileError>() {
+            match error {
+                FileError::Isdir => "is directory",
+                FileError::Acces => "access permission",
+                FileError::Noent => "not exists",
+                _ => "unknown",
+            }
+            .to_string()
+        } else if let Some(error) = e.kind::<FwNodeError>() {
+            match error {
+                FwNodeError::Disconnected => "disconnected",
+                FwNodeError::Failed => "ioctl error",
+                _ => "unknown",
+            }
+            .to_string()
+        } else {
+            e.to_string()
+        };
+        format!(
+            "Fail to open firewire character device {}: {} {}",
+            path, cause, e
+        )
+    })?;
+    Ok(node)
+}
+
+fn enumerate_nodes(ctx: &MainContext) -> Vec<NodeEntry> {
+    let mut paths: Vec<String> = std::fs::read_dir("/dev")
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| name.starts_with("fw") && name[2..].parse::<u32>().is_ok())
+                .map(|name| format!("/dev/{}", name))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort_by_key(|path| path[7..].parse::<u32>().unwrap_or(u32::MAX));
+
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            // Some of nodes are not accessible due to permission. Just skip them.
+            let node = open_node(&path).ok()?;
+            let src = node.create_source().ok()?;
+            let _ = src.attach(Some(ctx));
+            let card = detect_card(&path);
+            Some(NodeEntry { path, node, card })
+        })
+        .collect()
+}
+
+fn read_topology_map(req: &FwReq, node: &FwNode) -> Result<Vec<SelfId>, String> {
+    let mut frame = [0; 12];
+    req.transaction_sync(
+        node,
+        FwTcode::ReadBlockRequest,
+        CSR_TOPOLOGY_MAP_OFFSET,
+        frame.len(),
+        &mut frame,
+        TIMEOUT_MS,
+    )
+    .map_err(|e| format!("Fail to read topology map: {}", e))?;
+
+    let mut quadlet = [0; 4];
+    quadlet.copy_from_slice(&frame[8..12]);
+    let self_id_count = std::cmp::min(
+        (u32::from_be_bytes(quadlet) & 0xffff) as usize,
+        MAX_SELF_ID_COUNT,
+    );
+
+    let mut frame = vec![0; self_id_count * 4];
+    let mut offset = 0;
+    while offset < frame.len() {
+        let len = std::cmp::min(frame.len() - offset, 256);
+        req.transaction_sync(
+            node,
+            FwTcode::ReadBlockRequest,
+            CSR_TOPOLOGY_MAP_OFFSET + 12 + offset as u64,
+            len,
+            &mut frame[offset..(offset + len)],
+            TIMEOUT_MS,
+        )
+        .map_err(|e| format!("Fail to read self ID packets: {}", e))?;
+        offset += len;
+    }
+
+    Ok(frame
+        .chunks_exact(4)
+        .filter_map(|chunk| {
+            quadlet.copy_from_slice(chunk);
+            SelfId::parse(u32::from_be_bytes(quadlet))
+        })
+        .collect())
+}
+
+fn print_bus(target: &NodeEntry, entries: &[NodeEntry]) -> Result<(), String> {
+    let node = &target.node;
+    let raw = node.config_rom().map_err(|e| e.to_string())?;
+    let info = BusInfo::parse(&raw)
+        .ok_or_else(|| format!("Malformed configuration ROM in {}", target.path))?;
+
+    let local_node_id = node.local_node_id();
+    let root_node_id = node.root_node_id();
+    let bus_manager_node_id = node.bus_manager_node_id();
+    let ir_manager_node_id = node.ir_manager_node_id();
+
+    println!("unit: {}", target.path);
+    println!("  GUID: 0x{:016x}", info.guid);
+    println!("  node ID: 0x{:04x}", node.node_id());
+    println!("  link speed: {}", speed_label(info.link_speed));
+    println!("  max payload: {} bytes", info.max_payload());
+
+    println!("bus:");
+    println!("  generation: {}", node.generation());
+    println!("  local node ID: 0x{:04x}", local_node_id);
+    println!("  root node ID: 0x{:04x}", root_node_id);
+    println!("  bus manager node ID: 0x{:04x}", bus_manager_node_id);
+    println!(
+        "  isochronous resource manager node ID: 0x{:04x}",
+        ir_manager_node_id
+    );
+
+    let mut warnings = Vec::new();
+
+    let cards: Vec<&PathBuf> = entries.iter().filter_map(|entry| entry.card.as_ref()).fold(
+        Vec::new(),
+        |mut cards, card| {
+            if !cards.contains(&card) {
+                cards.push(card);
+            }
+            cards
+        },
+    );
+    if cards.len() > 1 {
+        warnings.push(format!(
+            "{} buses are detected. Ensure the unit is connected to expected host controller.",
+            cards.len()
+        ));
+    }
+
+    // The topology map is available in local node.
+    let local = entries.iter().find(|entry| {
+        entry.node.node_id() == entry.node.local_node_id()
+            && (entry.card.is_none() || entry.card == target.card)
+    });
+
+    match local {
+        Some(local) => {
+            let req = FwReq::new();
+            let self_ids = read_topology_map(&req, &local.node)?;
+
+            println!("  topology:");
+            self_ids.iter().for_each(|self_id| {
+                println!(
+                    "    phy {:2}: speed {}, gap count {}, link {}{}",
+                    self_id.phy_id,
+                    speed_label(self_id.speed),
+                    self_id.gap_count,
+                    if self_id.link_active {
+                        "active"
+                    } else {
+                        "inactive"
+                    },
+                    if self_id.contender { ", contender" } else { "" },
+                );
+            });
+
+            let gap_count = self_ids.first().map(|self_id| self_id.gap_count);
+            if self_ids
+                .iter()
+                .any(|self_id| Some(self_id.gap_count) != gap_count)
+            {
+                warnings.push("Gap count is inconsistent between nodes.".to_string());
+            }
+
+            if gap_count == Some(0x3f) && self_ids.len() > 2 {
+                warnings.push("Gap count is not optimized for the topology.".to_string());
+            }
+
+            let target_phy_id = node.node_id() & 0x3f;
+            let target_speed = self_ids
+                .iter()
+                .find(|self_id| self_id.phy_id == target_phy_id)
+                .map(|self_id| self_id.speed)
+                .unwrap_or(info.link_speed);
+            self_ids
+                .iter()
+                .filter(|self_id| self_id.speed < target_speed)
+                .for_each(|self_id| {
+                    warnings.push(format!(
+                        "phy {} is {} and may limit the {} unit when it is on the path.",
+                        self_id.phy_id,
+                        speed_label(self_id.speed),
+                        speed_label(target_speed)
+                    ));
+                });
+        }
+        None => {
+            warnings.push("Local node is not accessible to read topology map.".to_string());
+        }
+    }
+
+    if warnings.len() > 0 {
+        println!("warnings:");
+        warnings
+            .iter()
+            .for_each(|warning| println!("  {}", warning));
+    }
+
+    Ok(())
+}
+
+fn main() {
+    let code = std::env::args()
+        .nth(1)
+        .ok_or(
+            "At least one argument is required for path to special file of FireWire character device"
+                .to_string(),
+        )
+        .and_then(|path| {
+            let ctx = MainContext::new();
+            let entries = enumerate_nodes(&ctx);
+
+            let target = entries
+                .iter()
+                .position(|entry| entry.path == path)
+                .ok_or_else(|| {
+                    // Generate the error message with its cause.
+                    open_node(&path)
+                        .err()
+                        .unwrap_or_else(|| format!("Fail to access to {}", path))
+                })?;
+
+            let dispatcher = Arc::new(MainLoop::new(Some(&ctx), false));
+            let d = dispatcher.clone();
+            let th = thread::spawn(move || d.run());
+
+            let result = print_bus(&entries[target], &entries);
+
+            dispatcher.quit();
+            th.join().unwrap();
+            result
+        })
+        .map(|_| 0)
+        .unwrap_or_else(|msg| {
+            eprintln!("{}", msg);
+            print_help();
+            1
+        });
+
+    std::process::exit(code)
+}
+
+fn print_help() {
+    print!(
+        r###"
+Usage:
+  snd-firewire-bus-info CDEV
+
+  where:
+    CDEV:       The path to special file of firewire character device for the unit, typically
+                '/dev/fw1'.
+"###
+    );
+}
@@ -11,7 +11,8 @@ impl ServiceCmd<(String, u32), TascamRuntime> for TascamServiceCmd {
         ("SUBSYSTEM", "The name of subsystem; 'snd' or 'fw'"),
         (
             "SYSNUM",
-            "The numeric ID of sound card or fw character device",
+            "The numeric ID of sound card or fw character device. For sound card, '--guid' \
+             followed by GUID of node in hexadecimal is also available",
         ),
     ];
 
@@ -26,7 +27,14 @@ impl ServiceCmd<(String, u32), TascamRuntime> for TascamServiceCmd {
                 Err(msg)
             }
         }
-        .and_then(|subsystem| parse_arg_as_u32(&args[1]).map(|sysnum| (subsystem, sysnum)))
+        .and_then(|subsystem| {
+            let sysnum = if subsystem == "snd" {
+                parse_card_args(&args[1..])
+            } else {
+                parse_arg_as_u32(&args[1])
+            };
+            sysnum.map(|sysnum| (subsystem, sysnum))
+        })
     }
 }
 
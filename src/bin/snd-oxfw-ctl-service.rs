@@ -7,11 +7,10 @@ struct OxfwServiceCmd;
 
 impl ServiceCmd<u32, OxfwRuntime> for OxfwServiceCmd {
     const CMD_NAME: &'static str = "snd-oxfw-ctl-service";
-    const ARGS: &'static [(&'static str, &'static str)] =
-        &[("CARD_ID", "The numeric ID of sound card")];
+    const ARGS: &'static [(&'static str, &'static str)] = &[("CARD_ID", CARD_ARG_DESC)];
 
     fn parse_args(args: &[String]) -> Result<u32, String> {
-        parse_arg_as_u32(&args[0])
+        parse_card_args(args)
     }
 }
 
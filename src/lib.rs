@@ -2,8 +2,19 @@
 // Copyright (c) 2020 Takashi Sakamoto
 
 use {
-    alsactl::CardError, alsaseq::UserClientError, core::RuntimeOperation, glib::FileError,
-    hinawa::FwNodeError, hitaki::AlsaFirewireError, std::str::FromStr,
+    alsactl::CardError,
+    alsaseq::UserClientError,
+    core::{
+        card_guid::*, diagnostics::*, elem_batch::*, instance_lock::*, passed_fds::*, sandbox::*,
+        RuntimeOperation,
+    },
+    glib::{Error, FileError},
+    hinawa::{
+        prelude::{FwNodeExt, FwNodeExtManual},
+        FwNode, FwNodeError,
+    },
+    hitaki::AlsaFirewireError,
+    std::str::FromStr,
 };
 
 pub fn parse_arg_as_u32(arg: &str) -> Result<u32, String> {
@@ -15,9 +26,86 @@ pub fn parse_arg_as_u32(arg: &str) -> Result<u32, String> {
     })
 }
 
+/// The description of argument for sound card.
+pub const CARD_ARG_DESC: &str =
+    "The numeric ID of sound card, or '--guid' followed by GUID of node in hexadecimal";
+
+pub fn parse_card_args(args: &[String]) -> Result<u32, String> {
+    if args[0] != "--guid" {
+        return parse_arg_as_u32(&args[0]);
+    }
+
+    args.get(1)
+        .ok_or_else(|| "GUID should follow '--guid'".to_string())
+        .and_then(|arg| {
+            parse_guid(arg).ok_or_else(|| format!("GUID should be hexadecimal number: {}", arg))
+        })
+        .and_then(|guid| resolve_card_by_guid(guid).map_err(|e| e.to_string()))
+}
+
+/// The argument of runtime to identify the node.
+pub trait NodeArg {
+    /// Retrieve GUID of the node.
+    fn node_guid(&self) -> Result<u64, Error>;
+
+    /// The numeric ID of sound card, if available.
+    fn card_id(&self) -> Option<u32>;
+
+    /// The name of device for the node, like 'card1' or 'fw1'.
+    fn device_name(&self) -> String;
+}
+
+fn guid_from_raw(raw: &[u8]) -> Result<u64, Error> {
+    guid_from_config_rom(raw)
+        .ok_or_else(|| Error::new(FileError::Nxio, "Configuration ROM is too short"))
+}
+
+impl NodeArg for u32 {
+    fn node_guid(&self) -> Result<u64, Error> {
+        read_card_config_rom(*self).and_then(|raw| guid_from_raw(&raw))
+    }
+
+    fn card_id(&self) -> Option<u32> {
+        Some(*self)
+    }
+
+    fn device_name(&self) -> String {
+        format!("card{}", self)
+    }
+}
+
+impl NodeArg for (String, u32) {
+    fn node_guid(&self) -> Result<u64, Error> {
+        if self.0 == "fw" {
+            let node = FwNode::new();
+            node.open(&devnode_path(&format!("fw{}", self.1)))?;
+            node.config_rom().and_then(|raw| guid_from_raw(raw))
+        } else {
+            self.1.node_guid()
+        }
+    }
+
+    fn card_id(&self) -> Option<u32> {
+        if self.0 == "fw" {
+            None
+        } else {
+            Some(self.1)
+        }
+    }
+
+    fn device_name(&self) -> String {
+        if self.0 == "fw" {
+            format!("fw{}", self.1)
+        } else {
+            self.1.device_name()
+        }
+    }
+}
+
 pub trait ServiceCmd<T, R>: Sized
 where
-    R: RuntimeOperation<T>,
+    T: NodeArg,
+    R: RuntimeOperation<T> + DiagnosticsOperation<T>,
 {
     const CMD_NAME: &'static str;
     const ARGS: &'static [(&'static str, &'static str)];
@@ -38,11 +126,130 @@ Usage:
 
         Self::ARGS.iter().for_each(|entry| {
             println!("    {}: {}", entry.0, entry.1);
+        });
+
+        println!(
+            "
+  or
+  {} diagnostics{}
+
+  to collect diagnostics of the device into {}-diagnostics.tar for bug report.",
+            Self::CMD_NAME,
+            &Self::ARGS
+                .iter()
+                .fold(String::new(), |label, entry| label + " " + entry.0),
+            Self::CMD_NAME,
+        );
+
+        println!(
+            "
+  or
+  {} set{} PATTERN VALUE...
+
+  to write the values to the elements whose name matches the pattern. The asterisk in the
+  pattern matches any sequence of characters, and the question mark matches any character.
+  The single value is assigned to all of the values in the element.",
+            Self::CMD_NAME,
+            &Self::ARGS
+                .iter()
+                .fold(String::new(), |label, entry| label + " " + entry.0),
+        );
+    }
+
+    fn collect_diagnostics(args: &[String]) -> Result<String, String> {
+        if args.len() < Self::ARGS.len() {
+            return Err("Arguments for the device are required".to_string());
+        }
+
+        let arg = Self::parse_args(args)?;
+        let entries = R::collect_diagnostics(&arg)
+            .map_err(|e| format!("Fail to collect diagnostics: {}", e))?;
+
+        let dir = format!("{}-diagnostics", Self::CMD_NAME);
+        let path = format!("{}.tar", dir);
+        write_diagnostics(&path, &dir, &entries).map_err(|e| e.to_string())?;
+
+        Ok(path)
+    }
+
+    fn set_elems(args: &[String]) -> Result<Vec<String>, String> {
+        // The GUID of node follows '--guid'.
+        let mut count = Self::ARGS.len();
+        if args.iter().take(count).any(|arg| arg == "--guid") {
+            count += 1;
+        }
+
+        if args.len() < count + 2 {
+            let msg = "Arguments for the device, the pattern, and the values are required";
+            return Err(msg.to_string());
+        }
+
+        let (node_args, literals) = args.split_at(count);
+        let arg = Self::parse_args(node_args)?;
+        let card_id = arg
+            .card_id()
+            .ok_or_else(|| "No sound card is available for the device".to_string())?;
+
+        set_elems_by_pattern(card_id, &literals[0], &literals[1..])
+            .map(|elem_id_list| {
+                elem_id_list
+                    .iter()
+                    .map(|elem_id| format!("{}[{}]", elem_id.name(), elem_id.index()))
+                    .collect()
+            })
+            .map_err(|e| format!("Fail to set elements: {}", e))
+    }
+
+    // The lock is acquired for GUID of node. When it is not available, the lock is acquired for
+    // the sound card or the node given by arguments instead.
+    fn lock_instance(args: &T) -> Result<InstanceLock, String> {
+        let res = match args.node_guid() {
+            Ok(guid) => InstanceLock::acquire(guid),
+            Err(_) => InstanceLock::acquire_by_device(&args.device_name()),
+        };
+        res.map_err(|e| {
+            if e.kind::<FileError>() == Some(FileError::Again) {
+                format!("The other instance runs for the device: {}", e)
+            } else {
+                format!("Fail to acquire lock for instance: {}", e)
+            }
         })
     }
 
     fn run() {
         let args: Vec<String> = std::env::args().skip(1).collect();
+
+        if args.len() > 0 && args[0] == "diagnostics" {
+            let code = Self::collect_diagnostics(&args[1..])
+                .map(|path| {
+                    println!("Diagnostics are written to {}", path);
+                    libc::EXIT_SUCCESS
+                })
+                .unwrap_or_else(|msg| {
+                    eprintln!("{}", msg);
+                    Self::print_help();
+                    libc::EXIT_FAILURE
+                });
+            std::process::exit(code)
+        }
+
+        if args.len() > 0 && args[0] == "set" {
+            let code = Self::set_elems(&args[1..])
+                .map(|names| {
+                    names.iter().for_each(|name| println!("{}", name));
+                    libc::EXIT_SUCCESS
+                })
+                .unwrap_or_else(|msg| {
+                    eprintln!("{}", msg);
+                    Self::print_help();
+                    libc::EXIT_FAILURE
+                });
+            std::process::exit(code)
+        }
+
+        // Hold the lock till the process finishes.
+        let mut _instance_lock = None;
+
         let code = (if args.len() < Self::ARGS.len() {
             let msg = if Self::ARGS.len() == 1 {
                 format!("1 argument is required at least")
@@ -54,6 +261,7 @@ Usage:
             Self::parse_args(&args)
         })
         .and_then(|args| {
+            _instance_lock = Some(Self::lock_instance(&args)?);
             R::new(args).map_err(|e| {
                 let (domain, cause) = if let Some(error) = e.kind::<FileError>() {
                     (
@@ -99,6 +307,13 @@ Usage:
                 format!("{}: {}, {}", domain, cause, e)
             })
         })
+        .and_then(|runtime| {
+            // The character devices for the unit are opened at the point, and the threads for
+            // dispatchers are not launched yet.
+            engage_sandbox()
+                .map_err(|e| format!("Fail to engage sandbox: {}", e))
+                .map(|_| runtime)
+        })
         .and_then(|mut runtime| {
             runtime
                 .listen()